@@ -0,0 +1,629 @@
+//! Per-pair data-quality report, built once after loading: gaps, raw timestamp problems,
+//! zero-volume streaks, self-contradicting candles, outlier wicks and how much real history
+//! is left. Limits live in `config::DATA_QUALITY`.
+
+use serde::Serialize;
+
+use crate::config::{DATA_QUALITY, QualityLimits};
+use crate::data::timeseries::TimeSeriesCollection;
+use crate::models::resample::WEEK_ORIGIN_MS;
+use crate::models::{IngestIssues, OhlcvTimeSeries};
+use crate::utils::TimeUtils;
+
+const MS_IN_DAY: f64 = 86_400_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum QualityVerdict {
+    Good,
+    Warning,
+    Bad,
+}
+
+impl std::fmt::Display for QualityVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            QualityVerdict::Good => "good",
+            QualityVerdict::Warning => "warning",
+            QualityVerdict::Bad => "bad",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A run of consecutive candles: open time of the first, open time after the last
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CandleSpan {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub candles: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OhlcProblem {
+    /// NaN or infinite price or volume
+    NonFinite,
+    /// Zero or negative price
+    NonPositivePrice,
+    /// high < max(open, close)
+    HighBelowBody,
+    /// low > min(open, close)
+    LowAboveBody,
+    NegativeVolume,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct OhlcAnomaly {
+    pub timestamp_ms: i64,
+    pub problem: OhlcProblem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct OutlierWick {
+    pub timestamp_ms: i64,
+    /// Longest wick of the candle, as a percentage of its close
+    pub wick_pct: f64,
+    /// The pair's median high-low range, as a percentage of close
+    pub median_range_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataQualityReport {
+    pub pair: String,
+    pub interval_ms: i64,
+    pub first_kline_timestamp_ms: i64,
+    pub last_kline_timestamp_ms: i64,
+    pub candles: usize,
+
+    // Gaps
+    pub filled_candles: usize,
+    pub pct_gaps: f64,
+    /// Longest first
+    pub largest_gaps: Vec<CandleSpan>,
+
+    // Timestamps
+    pub ingest: IngestIssues,
+    /// The series does not start on the interval grid Binance uses
+    pub misaligned_start: bool,
+
+    // Candle contents
+    pub zero_volume_streaks: usize,
+    pub longest_zero_volume_streak: Option<CandleSpan>,
+    pub ohlc_inconsistencies: usize,
+    pub ohlc_examples: Vec<OhlcAnomaly>,
+    pub outlier_wicks: usize,
+    pub outlier_examples: Vec<OutlierWick>,
+
+    // History
+    pub span_days: f64,
+    /// Days covered by real (not filled) candles
+    pub effective_history_days: f64,
+
+    pub verdict: QualityVerdict,
+    /// One line per limit the pair broke
+    pub reasons: Vec<String>,
+}
+
+impl DataQualityReport {
+    pub fn assess(series: &OhlcvTimeSeries) -> Self {
+        let interval_ms = series.pair_interval.interval_ms;
+        let candles = series.klines();
+        let ts = |idx: usize| series.first_kline_timestamp_ms + idx as i64 * interval_ms;
+        let span = |start: usize, len: usize| CandleSpan {
+            start_ms: ts(start),
+            end_ms: ts(start + len),
+            candles: len,
+        };
+
+        // Gaps
+        let filled_candles = series.gaps.filled_count();
+        let mut gap_runs = series.gaps.runs().to_vec();
+        gap_runs.sort_by(|a, b| b.len.cmp(&a.len).then(a.start.cmp(&b.start)));
+        let largest_gaps = gap_runs
+            .iter()
+            .take(DATA_QUALITY.largest_gaps_listed)
+            .map(|run| span(run.start, run.len))
+            .collect();
+
+        // Zero-volume streaks among traded candles
+        let mut zero_volume_streaks = 0;
+        let mut longest_zero_volume_streak: Option<CandleSpan> = None;
+        let mut streak_start = None;
+        for idx in 0..=candles {
+            let zero =
+                idx < candles && !series.is_filled(idx) && series.base_asset_volumes[idx] == 0.0;
+            match (zero, streak_start) {
+                (true, None) => streak_start = Some(idx),
+                (false, Some(start)) => {
+                    let len = idx - start;
+                    if len >= DATA_QUALITY.min_zero_volume_streak {
+                        zero_volume_streaks += 1;
+                        if longest_zero_volume_streak.is_none_or(|s| len > s.candles) {
+                            longest_zero_volume_streak = Some(span(start, len));
+                        }
+                    }
+                    streak_start = None;
+                }
+                _ => {}
+            }
+        }
+
+        // OHLC consistency
+        let mut ohlc_inconsistencies = 0;
+        let mut ohlc_examples = Vec::new();
+        for idx in (0..candles).filter(|&idx| !series.is_filled(idx)) {
+            if let Some(problem) = ohlc_problem(series, idx) {
+                ohlc_inconsistencies += 1;
+                if ohlc_examples.len() < DATA_QUALITY.examples_listed {
+                    ohlc_examples.push(OhlcAnomaly {
+                        timestamp_ms: ts(idx),
+                        problem,
+                    });
+                }
+            }
+        }
+
+        // Outlier wicks, measured against the pair's own typical candle
+        let mut ranges: Vec<f64> = (0..candles)
+            .filter(|&idx| !series.is_filled(idx) && series.close_prices[idx] > 0.0)
+            .map(|idx| {
+                (series.high_prices[idx] - series.low_prices[idx]) / series.close_prices[idx]
+            })
+            .filter(|range| range.is_finite())
+            .collect();
+        let median_range = median(&mut ranges);
+        let mut outlier_wicks = 0;
+        let mut outlier_examples = Vec::new();
+        if median_range > 0.0 {
+            let limit = median_range * DATA_QUALITY.outlier_wick_multiple;
+            for idx in (0..candles).filter(|&idx| !series.is_filled(idx)) {
+                let close = series.close_prices[idx];
+                if close <= 0.0 {
+                    continue;
+                }
+                let body_top = series.open_prices[idx].max(close);
+                let body_bottom = series.open_prices[idx].min(close);
+                let wick =
+                    (series.high_prices[idx] - body_top).max(body_bottom - series.low_prices[idx]);
+                let wick = wick / close;
+                if wick > limit {
+                    outlier_wicks += 1;
+                    if outlier_examples.len() < DATA_QUALITY.examples_listed {
+                        outlier_examples.push(OutlierWick {
+                            timestamp_ms: ts(idx),
+                            wick_pct: wick * 100.0,
+                            median_range_pct: median_range * 100.0,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut report = DataQualityReport {
+            pair: series.pair_interval.name.clone(),
+            interval_ms,
+            first_kline_timestamp_ms: series.first_kline_timestamp_ms,
+            last_kline_timestamp_ms: ts(candles.saturating_sub(1)),
+            candles,
+            filled_candles,
+            pct_gaps: pct(filled_candles, candles),
+            largest_gaps,
+            ingest: series.ingest,
+            misaligned_start: !on_grid(series.first_kline_timestamp_ms, interval_ms),
+            zero_volume_streaks,
+            longest_zero_volume_streak,
+            ohlc_inconsistencies,
+            ohlc_examples,
+            outlier_wicks,
+            outlier_examples,
+            span_days: candles as f64 * interval_ms as f64 / MS_IN_DAY,
+            effective_history_days: (candles - filled_candles) as f64 * interval_ms as f64
+                / MS_IN_DAY,
+            verdict: QualityVerdict::Good,
+            reasons: Vec::new(),
+        };
+
+        report.reasons = report.broken_limits(&DATA_QUALITY.bad);
+        if !report.reasons.is_empty() {
+            report.verdict = QualityVerdict::Bad;
+        } else {
+            report.reasons = report.broken_limits(&DATA_QUALITY.warn);
+            if !report.reasons.is_empty() {
+                report.verdict = QualityVerdict::Warning;
+            }
+        }
+        report
+    }
+
+    /// Duplicate and off-grid timestamps, counting an off-grid start as one
+    pub fn timestamp_issues(&self) -> usize {
+        self.ingest.duplicate_timestamps
+            + self.ingest.misaligned_timestamps
+            + self.misaligned_start as usize
+    }
+
+    fn broken_limits(&self, limits: &QualityLimits) -> Vec<String> {
+        let traded = self.candles - self.filled_candles;
+        let pct_outlier_wicks = pct(self.outlier_wicks, traded);
+        let mut reasons = Vec::new();
+        if self.pct_gaps > limits.max_pct_gaps {
+            reasons.push(format!(
+                "{:.2}% gaps (limit {:.2}%)",
+                self.pct_gaps, limits.max_pct_gaps
+            ));
+        }
+        if self.ohlc_inconsistencies > limits.max_ohlc_inconsistencies {
+            reasons.push(format!(
+                "{} inconsistent candles (limit {})",
+                self.ohlc_inconsistencies, limits.max_ohlc_inconsistencies
+            ));
+        }
+        if self.timestamp_issues() > limits.max_timestamp_issues {
+            reasons.push(format!(
+                "{} duplicate / misaligned timestamps (limit {})",
+                self.timestamp_issues(),
+                limits.max_timestamp_issues
+            ));
+        }
+        if pct_outlier_wicks > limits.max_pct_outlier_wicks {
+            reasons.push(format!(
+                "{:.2}% outlier wicks (limit {:.2}%)",
+                pct_outlier_wicks, limits.max_pct_outlier_wicks
+            ));
+        }
+        if self.effective_history_days < limits.min_effective_history_days {
+            reasons.push(format!(
+                "{:.0} days of real history (minimum {:.0})",
+                self.effective_history_days, limits.min_effective_history_days
+            ));
+        }
+        reasons
+    }
+
+    /// One line for logs and lists, e.g. "BTCUSDT: warning (2.10% gaps (limit 1.00%))"
+    pub fn summary(&self) -> String {
+        if self.reasons.is_empty() {
+            format!("{}: {}", self.pair, self.verdict)
+        } else {
+            format!(
+                "{}: {} ({})",
+                self.pair,
+                self.verdict,
+                self.reasons.join("; ")
+            )
+        }
+    }
+}
+
+fn ohlc_problem(series: &OhlcvTimeSeries, idx: usize) -> Option<OhlcProblem> {
+    let open = series.open_prices[idx];
+    let high = series.high_prices[idx];
+    let low = series.low_prices[idx];
+    let close = series.close_prices[idx];
+    let base_volume = series.base_asset_volumes[idx];
+    let quote_volume = series.quote_asset_volumes[idx];
+
+    if [open, high, low, close, base_volume, quote_volume]
+        .iter()
+        .any(|v| !v.is_finite())
+    {
+        Some(OhlcProblem::NonFinite)
+    } else if open <= 0.0 || high <= 0.0 || low <= 0.0 || close <= 0.0 {
+        Some(OhlcProblem::NonPositivePrice)
+    } else if high < open.max(close) {
+        Some(OhlcProblem::HighBelowBody)
+    } else if low > open.min(close) {
+        Some(OhlcProblem::LowAboveBody)
+    } else if base_volume < 0.0 || quote_volume < 0.0 {
+        Some(OhlcProblem::NegativeVolume)
+    } else {
+        None
+    }
+}
+
+/// Whether `timestamp_ms` is a candle open time for `interval_ms` (UTC epoch grid,
+/// Monday-based for weeks). Month candles have no fixed grid, so always pass.
+fn on_grid(timestamp_ms: i64, interval_ms: i64) -> bool {
+    if interval_ms <= 0 || interval_ms == TimeUtils::MS_IN_1_M {
+        return true;
+    }
+    let origin_ms = if interval_ms % TimeUtils::MS_IN_W == 0 {
+        WEEK_ORIGIN_MS
+    } else {
+        0
+    };
+    (timestamp_ms - origin_ms).rem_euclid(interval_ms) == 0
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mid = values.len() / 2;
+    values.select_nth_unstable_by(mid, f64::total_cmp);
+    values[mid]
+}
+
+fn pct(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
+
+/// Reports for every series in the collection, in collection order.
+pub fn assess_collection(collection: &TimeSeriesCollection) -> Vec<DataQualityReport> {
+    collection
+        .series_data
+        .iter()
+        .map(|series| DataQualityReport::assess(series))
+        .collect()
+}
+
+/// Drops every series whose report is `Bad`; returns the pairs removed.
+pub fn exclude_bad_pairs(
+    collection: &mut TimeSeriesCollection,
+    reports: &[DataQualityReport],
+) -> Vec<String> {
+    let bad: Vec<String> = reports
+        .iter()
+        .filter(|r| r.verdict == QualityVerdict::Bad)
+        .map(|r| r.pair.clone())
+        .collect();
+    collection
+        .series_data
+        .retain(|series| !bad.contains(&series.pair_interval.name));
+    bad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GapMask;
+
+    const INTERVAL_MS: i64 = TimeUtils::MS_IN_30_MIN;
+    const FIRST_MS: i64 = 1_700_000_000_000 - 1_700_000_000_000 % INTERVAL_MS;
+    /// Range 2% of close, wicks 0.5%
+    const CANDLE: (f64, f64, f64, f64) = (100.0, 101.0, 99.0, 100.5);
+    /// 200 days of 30m candles, comfortably over every history limit
+    const LONG: usize = 200 * 48;
+
+    fn series(candles: usize) -> OhlcvTimeSeries {
+        OhlcvTimeSeries::from_test_candles("BTCUSDT", INTERVAL_MS, FIRST_MS, &vec![CANDLE; candles])
+    }
+
+    fn with_ohlc(candle: (f64, f64, f64, f64)) -> OhlcvTimeSeries {
+        let mut series = series(20);
+        series.open_prices[3] = candle.0;
+        series.high_prices[3] = candle.1;
+        series.low_prices[3] = candle.2;
+        series.close_prices[3] = candle.3;
+        series
+    }
+
+    fn with_outlier_wicks(candles: usize, outliers: usize) -> OhlcvTimeSeries {
+        let mut series = series(candles);
+        for idx in 0..outliers {
+            series.high_prices[idx * 10] = 200.0;
+        }
+        series
+    }
+
+    #[test]
+    fn a_clean_long_series_is_good() {
+        let report = DataQualityReport::assess(&series(LONG));
+        assert_eq!(report.verdict, QualityVerdict::Good);
+        assert!(report.reasons.is_empty());
+        assert_eq!(report.candles, LONG);
+        assert_eq!(
+            report.last_kline_timestamp_ms,
+            FIRST_MS + (LONG as i64 - 1) * INTERVAL_MS
+        );
+        assert_eq!(report.effective_history_days, 200.0);
+        assert_eq!(
+            (
+                report.zero_volume_streaks,
+                report.ohlc_inconsistencies,
+                report.outlier_wicks
+            ),
+            (0, 0, 0)
+        );
+        assert!(!report.misaligned_start);
+    }
+
+    #[test]
+    fn zero_volume_runs_count_from_the_minimum_streak_length() {
+        let min = DATA_QUALITY.min_zero_volume_streak;
+        let mut series = series(100);
+        // Too short, long enough, longest (running to the end)
+        let runs = [(10, min - 1), (30, min), (100 - min - 2, min + 2)];
+        for (start, len) in runs {
+            series.base_asset_volumes[start..start + len].fill(0.0);
+        }
+
+        let report = DataQualityReport::assess(&series);
+        assert_eq!(report.zero_volume_streaks, 2);
+        let start = 100 - min - 2;
+        assert_eq!(
+            report.longest_zero_volume_streak,
+            Some(CandleSpan {
+                start_ms: FIRST_MS + start as i64 * INTERVAL_MS,
+                end_ms: FIRST_MS + 100 * INTERVAL_MS,
+                candles: min + 2,
+            })
+        );
+    }
+
+    #[test]
+    fn filled_candles_break_a_zero_volume_streak() {
+        let min = DATA_QUALITY.min_zero_volume_streak;
+        let mut series = series(100);
+        series.base_asset_volumes[10..10 + 2 * min].fill(0.0);
+        series.gaps = GapMask::from_flags((0..100).map(|idx| idx == 10 + min - 1));
+
+        let report = DataQualityReport::assess(&series);
+        assert_eq!(report.zero_volume_streaks, 1);
+        assert_eq!(report.longest_zero_volume_streak.unwrap().candles, min);
+    }
+
+    #[test]
+    fn each_kind_of_contradictory_candle_is_named() {
+        let cases = [
+            ((100.0, f64::NAN, 99.0, 100.5), OhlcProblem::NonFinite),
+            ((0.0, 101.0, 99.0, 100.5), OhlcProblem::NonPositivePrice),
+            ((100.0, 100.2, 99.0, 100.5), OhlcProblem::HighBelowBody),
+            ((100.0, 101.0, 100.2, 100.5), OhlcProblem::LowAboveBody),
+        ];
+        for (candle, problem) in cases {
+            let series = with_ohlc(candle);
+            assert_eq!(ohlc_problem(&series, 3), Some(problem), "{:?}", candle);
+            let report = DataQualityReport::assess(&series);
+            assert_eq!(report.ohlc_inconsistencies, 1);
+            assert_eq!(
+                report.ohlc_examples,
+                [OhlcAnomaly {
+                    timestamp_ms: FIRST_MS + 3 * INTERVAL_MS,
+                    problem,
+                }]
+            );
+        }
+
+        let mut series = series(20);
+        series.base_asset_volumes[3] = -1.0;
+        assert_eq!(ohlc_problem(&series, 3), Some(OhlcProblem::NegativeVolume));
+        assert_eq!(ohlc_problem(&series, 4), None);
+    }
+
+    #[test]
+    fn contradictory_candles_are_counted_past_the_examples_but_not_when_filled() {
+        let mut series = series(20);
+        series.high_prices[..10].fill(50.0);
+        series.gaps = GapMask::from_flags((0..20).map(|idx| idx == 0));
+
+        let report = DataQualityReport::assess(&series);
+        assert_eq!(report.ohlc_inconsistencies, 9);
+        assert_eq!(report.ohlc_examples.len(), DATA_QUALITY.examples_listed);
+        assert_eq!(report.ohlc_examples[0].timestamp_ms, FIRST_MS + INTERVAL_MS);
+    }
+
+    #[test]
+    fn wicks_are_outliers_against_the_pairs_median_range() {
+        let report = DataQualityReport::assess(&with_outlier_wicks(100, 1));
+        assert_eq!(report.outlier_wicks, 1);
+        let wick = report.outlier_examples[0];
+        assert_eq!(wick.timestamp_ms, FIRST_MS);
+        assert!((wick.wick_pct - 99.5 / 100.5 * 100.0).abs() < 1e-9);
+        assert!((wick.median_range_pct - 2.0 / 100.5 * 100.0).abs() < 1e-9);
+
+        // Just inside the limit is not an outlier
+        let mut series = series(100);
+        let limit = 2.0 / 100.5 * DATA_QUALITY.outlier_wick_multiple;
+        series.high_prices[0] = 100.5 * (1.0 + limit) - 0.01;
+        assert_eq!(DataQualityReport::assess(&series).outlier_wicks, 0);
+
+        // A pair that never moves has no typical range to compare against
+        let flat = OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            INTERVAL_MS,
+            FIRST_MS,
+            &[(1.0, 1.0, 1.0, 1.0); 10],
+        );
+        assert_eq!(DataQualityReport::assess(&flat).outlier_wicks, 0);
+    }
+
+    #[test]
+    fn open_times_are_checked_against_the_interval_grid() {
+        assert!(on_grid(FIRST_MS, INTERVAL_MS));
+        assert!(!on_grid(FIRST_MS + 1, INTERVAL_MS));
+        assert!(on_grid(-INTERVAL_MS, INTERVAL_MS));
+        assert!(on_grid(0, TimeUtils::MS_IN_D));
+        // Weeks open on Mondays, not on the epoch (a Thursday)
+        assert!(!on_grid(0, TimeUtils::MS_IN_W));
+        assert!(on_grid(
+            WEEK_ORIGIN_MS + 3 * TimeUtils::MS_IN_W,
+            TimeUtils::MS_IN_W
+        ));
+        assert!(on_grid(12_345, TimeUtils::MS_IN_1_M));
+
+        let mut series = series(LONG);
+        series.first_kline_timestamp_ms += TimeUtils::MS_IN_MIN;
+        let report = DataQualityReport::assess(&series);
+        assert!(report.misaligned_start);
+        assert_eq!(report.timestamp_issues(), 1);
+        assert_eq!(report.verdict, QualityVerdict::Warning);
+    }
+
+    #[test]
+    fn verdicts_follow_the_warn_and_bad_limits() {
+        let verdict = |series: &OhlcvTimeSeries| DataQualityReport::assess(series).verdict;
+        let (warn, bad) = (&DATA_QUALITY.warn, &DATA_QUALITY.bad);
+
+        // Gaps: a percentage of all candles
+        let gapped = |pct: f64| {
+            let mut series = series(LONG);
+            let filled = (LONG as f64 * pct / 100.0).ceil() as usize;
+            series.gaps = GapMask::from_flags((0..LONG).map(|idx| idx < filled));
+            series
+        };
+        assert_eq!(verdict(&gapped(warn.max_pct_gaps)), QualityVerdict::Good);
+        assert_eq!(
+            verdict(&gapped(warn.max_pct_gaps + 0.1)),
+            QualityVerdict::Warning
+        );
+        assert_eq!(
+            verdict(&gapped(bad.max_pct_gaps + 0.1)),
+            QualityVerdict::Bad
+        );
+
+        // Contradictory candles: a count
+        let inconsistent = |count: usize| {
+            let mut series = series(LONG);
+            series.high_prices[..count].fill(50.0);
+            series
+        };
+        assert_eq!(
+            verdict(&inconsistent(warn.max_ohlc_inconsistencies + 1)),
+            QualityVerdict::Warning
+        );
+        assert_eq!(
+            verdict(&inconsistent(bad.max_ohlc_inconsistencies)),
+            QualityVerdict::Warning
+        );
+        assert_eq!(
+            verdict(&inconsistent(bad.max_ohlc_inconsistencies + 1)),
+            QualityVerdict::Bad
+        );
+
+        // Outlier wicks: a percentage of traded candles
+        let outliers = |pct: f64| (LONG as f64 * pct / 100.0).floor() as usize + 1;
+        let warn_wicks = with_outlier_wicks(LONG, outliers(warn.max_pct_outlier_wicks));
+        assert_eq!(verdict(&with_outlier_wicks(LONG, 1)), QualityVerdict::Good);
+        assert_eq!(verdict(&warn_wicks), QualityVerdict::Warning);
+        let bad_wicks = with_outlier_wicks(LONG, outliers(bad.max_pct_outlier_wicks));
+        assert_eq!(verdict(&bad_wicks), QualityVerdict::Bad);
+
+        // History: days of real candles
+        let days = |days: f64| series((days * 48.0) as usize);
+        assert_eq!(
+            verdict(&days(warn.min_effective_history_days)),
+            QualityVerdict::Good
+        );
+        assert_eq!(
+            verdict(&days(warn.min_effective_history_days - 1.0)),
+            QualityVerdict::Warning
+        );
+        assert_eq!(
+            verdict(&days(bad.min_effective_history_days - 1.0)),
+            QualityVerdict::Bad
+        );
+
+        // A bad pair lists only the limits it broke at the bad level
+        let report = DataQualityReport::assess(&days(bad.min_effective_history_days - 1.0));
+        assert_eq!(report.reasons.len(), 1);
+        assert!(
+            report
+                .summary()
+                .starts_with("BTCUSDT: bad (29 days of real history")
+        );
+    }
+}
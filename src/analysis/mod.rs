@@ -1,8 +1,10 @@
 // Analysis algorithms and zone scoring
+pub mod data_quality;
 pub mod multi_pair_monitor;
 pub mod pair_analysis;
 pub mod selection_criteria;
 pub mod zone_scoring;
 
 // Re-export commonly used types
+pub use data_quality::{DataQualityReport, QualityVerdict};
 pub use multi_pair_monitor::MultiPairMonitor;
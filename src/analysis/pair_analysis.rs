@@ -1,9 +1,9 @@
 use crate::config::ANALYSIS; // Use global config for defaults, or passed config
 use crate::data::timeseries::TimeSeriesCollection;
-use crate::domain::price_horizon::PriceHorizonConfig;
 use crate::domain::price_horizon;
+use crate::domain::price_horizon::PriceHorizonConfig;
 use crate::models::cva::CVACore;
-use crate::models::timeseries::{TimeSeriesSlice, find_matching_ohlcv};
+use crate::models::timeseries::{OhlcvTimeSeries, TimeSeriesSlice, find_matching_ohlcv};
 use anyhow::{Context, Result, bail};
 
 // --- NEW PURE FUNCTION FOR THE ENGINE ---
@@ -13,21 +13,35 @@ use anyhow::{Context, Result, bail};
 pub fn pair_analysis_pure(
     pair_name: String,
     timeseries_data: &TimeSeriesCollection,
+    interval_ms: i64,
     current_price: f64,
     price_horizon_config: &PriceHorizonConfig,
 ) -> Result<CVACore> {
-    // Use Constants from Config
-    let zone_count = ANALYSIS.zone_count;
-    let time_decay_factor = ANALYSIS.time_decay_factor;
-
     // 1. Find the Data
     // find_matching_ohlcv returns Result, so we use with_context to add the error message
-    let ohlcv_time_series = find_matching_ohlcv(
-        &timeseries_data.series_data,
-        &pair_name,
-        ANALYSIS.interval_width_ms,
+    let ohlcv_time_series =
+        find_matching_ohlcv(&timeseries_data.series_data, &pair_name, interval_ms)
+            .with_context(|| format!("No OHLCV data found for {}", pair_name))?;
+
+    cva_from_series(
+        pair_name,
+        ohlcv_time_series,
+        current_price,
+        price_horizon_config,
     )
-    .with_context(|| format!("No OHLCV data found for {}", pair_name))?;
+}
+
+/// Calculates CVA directly from a single series (e.g. a history truncated at a
+/// walk-forward cutoff), skipping the collection lookup.
+pub fn cva_from_series(
+    pair_name: String,
+    ohlcv_time_series: &OhlcvTimeSeries,
+    current_price: f64,
+    price_horizon_config: &PriceHorizonConfig,
+) -> Result<CVACore> {
+    // Use Constants from Config
+    let zone_count = ANALYSIS.zone_count;
+    let time_decay_factor = ANALYSIS.time_decay_factor;
 
     // 2. Price Horizon: Calculate relevant slices based on price
     // Note: The Engine calculates this fresh every time. No "Slice Caching".
@@ -35,6 +49,7 @@ pub fn pair_analysis_pure(
         ohlcv_time_series,
         current_price,
         price_horizon_config,
+        ANALYSIS.gaps.price_horizon_includes_filled,
     );
 
     // 3. Validation
@@ -53,7 +68,8 @@ pub fn pair_analysis_pure(
     let end_idx = slice_ranges.last().map(|r| r.1).unwrap_or(0);
 
     let duration_years = if end_idx > start_idx {
-        let duration_ms = (end_idx - start_idx) as f64 * ANALYSIS.interval_width_ms as f64;
+        let duration_ms =
+            (end_idx - start_idx) as f64 * ohlcv_time_series.pair_interval.interval_ms as f64;
         let millis_per_year = 31_536_000_000.0;
         duration_ms / millis_per_year
     } else {
@@ -77,17 +93,28 @@ pub fn pair_analysis_pure(
         pair_name.clone(),
         dynamic_decay_factor,
         price_range,
+        ANALYSIS.gaps.cva_weight,
+        ANALYSIS.trades.exact_volume_weight,
     );
 
     // 6. Add Metadata
     let first_kline_timestamp = ohlcv_time_series.first_kline_timestamp_ms;
-    if let (Some((first_start, _)), Some((_, last_end))) =
-        (slice_ranges.first(), slice_ranges.last())
-    {
-        cva_results.start_timestamp_ms =
-            first_kline_timestamp + (*first_start as i64 * ANALYSIS.interval_width_ms);
-        cva_results.end_timestamp_ms =
-            first_kline_timestamp + (*last_end as i64 * ANALYSIS.interval_width_ms);
+    let interval_ms = ohlcv_time_series.pair_interval.interval_ms;
+    cva_results.slice_ranges_ms = slice_ranges
+        .iter()
+        .map(|&(start, end)| {
+            (
+                first_kline_timestamp + start as i64 * interval_ms,
+                first_kline_timestamp + end as i64 * interval_ms,
+            )
+        })
+        .collect();
+    if let (Some(&(first_start, _)), Some(&(_, last_end))) = (
+        cva_results.slice_ranges_ms.first(),
+        cva_results.slice_ranges_ms.last(),
+    ) {
+        cva_results.start_timestamp_ms = first_start;
+        cva_results.end_timestamp_ms = last_end;
     }
 
     Ok(cva_results)
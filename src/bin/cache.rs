@@ -0,0 +1,127 @@
+use anyhow::{Context, Result, anyhow, bail};
+use zone_sniper::config::{ANALYSIS, PERSISTENCE};
+use zone_sniper::data::timeseries::cache_file::CacheFile;
+use zone_sniper::data::timeseries::cache_format::{MIGRATIONS, current_schema_hash};
+use zone_sniper::data::timeseries::chunked_cache::ChunkedCache;
+use zone_sniper::utils::TimeUtils;
+
+/// Usage: `cargo run --release --bin cache -- <verify|migrate> [INTERVAL]`
+///
+/// `verify` reads every chunk of the local kline cache (checksums, headers, contiguity)
+/// and reports pairs that are corrupt or stored in an older format.
+/// `migrate` upgrades the cache to the current format on disk: no network access.
+/// INTERVAL is Binance shorthand (e.g. `30m`, `1h`); defaults to the analysis interval.
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first().map(String::as_str).unwrap_or("");
+    let interval_ms = match args.get(1) {
+        Some(text) => TimeUtils::interval_from_string(text)
+            .ok_or_else(|| anyhow!("Unknown interval '{}'", text))?,
+        None => ANALYSIS.interval_width_ms,
+    };
+
+    match command {
+        "verify" => verify(interval_ms),
+        "migrate" => migrate(interval_ms),
+        _ => bail!("Usage: cache <verify|migrate> [INTERVAL]"),
+    }
+}
+
+fn verify(interval_ms: i64) -> Result<()> {
+    let dir = ChunkedCache::default_dir(interval_ms);
+    let cache = match ChunkedCache::open(&dir) {
+        Ok(cache) => cache,
+        Err(e) => {
+            let legacy_path = CacheFile::default_cache_path(interval_ms);
+            if legacy_path.exists() {
+                println!(
+                    "No chunked cache at {}, but found legacy cache {}: run `cache migrate`.",
+                    dir.display(),
+                    legacy_path.display()
+                );
+            }
+            return Err(e);
+        }
+    };
+
+    println!(
+        "{}: manifest format v{} (this build writes v{}, schema {:016x}), {} pairs",
+        dir.display(),
+        cache.manifest.format_version,
+        PERSISTENCE.kline.format_version,
+        current_schema_hash(),
+        cache.manifest.pairs.len()
+    );
+
+    let mut failed = 0;
+    let mut outdated = 0;
+    for pair in cache.verify() {
+        match &pair.error {
+            Some(e) => {
+                failed += 1;
+                println!("  ❌ {}: {}", pair.pair, e);
+            }
+            None if pair.outdated_chunks > 0 => {
+                outdated += 1;
+                println!(
+                    "  ⚠️  {}: ok, {} of {} chunks need migrating",
+                    pair.pair, pair.outdated_chunks, pair.chunks
+                );
+            }
+            None => println!(
+                "  ✅ {}: {} chunks, {} candles",
+                pair.pair, pair.chunks, pair.candles
+            ),
+        }
+    }
+
+    if outdated > 0 {
+        println!(
+            "{} pairs use an older format: run `cache migrate`.",
+            outdated
+        );
+    }
+    if failed > 0 {
+        bail!("{} pairs failed verification", failed);
+    }
+    println!("✅ Cache verified.");
+    Ok(())
+}
+
+fn migrate(interval_ms: i64) -> Result<()> {
+    println!("Known migrations:");
+    for migration in MIGRATIONS {
+        println!(
+            "  v{} -> v{}: {}",
+            migration.from, migration.to, migration.description
+        );
+    }
+
+    let report = ChunkedCache::upgrade(interval_ms).context("Migration failed")?;
+    if report.moved_legacy_dir {
+        println!("Moved the versioned cache directory to its unversioned name.");
+    }
+    if report.imported_legacy_file {
+        println!(
+            "Imported {} (it can be deleted once you're happy with the result).",
+            CacheFile::default_cache_path(interval_ms).display()
+        );
+    }
+    for (pair, e) in &report.failed {
+        println!("  ❌ {}: {}", pair, e);
+    }
+
+    if report.changed_anything() {
+        println!(
+            "✅ Cache at format v{}: {} chunks rewritten across {} pairs.",
+            PERSISTENCE.kline.format_version, report.chunks_rewritten, report.pairs_migrated
+        );
+    } else {
+        println!("Nothing to migrate.");
+    }
+
+    if !report.failed.is_empty() {
+        bail!("{} pairs could not be migrated", report.failed.len());
+    }
+    Ok(())
+}
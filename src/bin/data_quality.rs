@@ -0,0 +1,100 @@
+use anyhow::{Context, Result, anyhow};
+use zone_sniper::analysis::data_quality::{DataQualityReport, assess_collection};
+use zone_sniper::config::ANALYSIS;
+use zone_sniper::data::timeseries::serde_version::load_local_collection;
+use zone_sniper::utils::time_utils::epoch_ms_to_utc;
+
+/// Usage: `cargo run --release --bin data_quality -- [--json] [PAIR ...]`
+/// Reports on every pair in the local kline cache, or only the pairs given.
+/// `--json` prints the full reports as JSON instead of a summary.
+fn main() -> Result<()> {
+    let mut json = false;
+    let mut requested = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            pair => requested.push(pair.to_uppercase()),
+        }
+    }
+
+    let collection = load_local_collection(ANALYSIS.interval_width_ms)
+        .context("Failed to load the local kline cache")?;
+
+    let reports: Vec<DataQualityReport> = assess_collection(&collection)
+        .into_iter()
+        .filter(|r| requested.is_empty() || requested.contains(&r.pair))
+        .collect();
+
+    if reports.is_empty() {
+        return Err(anyhow!("No matching pairs in the local kline cache"));
+    }
+
+    if json {
+        let text = serde_json::to_string_pretty(&reports).context("Failed to serialize reports")?;
+        println!("{}", text);
+    } else {
+        reports.iter().for_each(print_report);
+    }
+    Ok(())
+}
+
+fn print_report(report: &DataQualityReport) {
+    println!("\n{}", report.summary());
+    println!(
+        "  {} candles, {} to {}: {:.0} days, {:.0} of them real",
+        report.candles,
+        epoch_ms_to_utc(report.first_kline_timestamp_ms),
+        epoch_ms_to_utc(report.last_kline_timestamp_ms),
+        report.span_days,
+        report.effective_history_days
+    );
+    println!(
+        "  gaps {:.2}% ({} candles)",
+        report.pct_gaps, report.filled_candles
+    );
+    for gap in &report.largest_gaps {
+        println!(
+            "    {} -> {}  {} candles",
+            epoch_ms_to_utc(gap.start_ms),
+            epoch_ms_to_utc(gap.end_ms),
+            gap.candles
+        );
+    }
+    println!(
+        "  timestamps: {} duplicate, {} misaligned, start {}, {} candles truncated",
+        report.ingest.duplicate_timestamps,
+        report.ingest.misaligned_timestamps,
+        if report.misaligned_start {
+            "off-grid"
+        } else {
+            "on grid"
+        },
+        report.ingest.truncated_candles
+    );
+    match &report.longest_zero_volume_streak {
+        Some(streak) => println!(
+            "  zero-volume streaks: {} (longest {} candles from {})",
+            report.zero_volume_streaks,
+            streak.candles,
+            epoch_ms_to_utc(streak.start_ms)
+        ),
+        None => println!("  zero-volume streaks: 0"),
+    }
+    println!("  inconsistent candles: {}", report.ohlc_inconsistencies);
+    for anomaly in &report.ohlc_examples {
+        println!(
+            "    {}  {:?}",
+            epoch_ms_to_utc(anomaly.timestamp_ms),
+            anomaly.problem
+        );
+    }
+    println!("  outlier wicks: {}", report.outlier_wicks);
+    for wick in &report.outlier_examples {
+        println!(
+            "    {}  wick {:.2}% (median range {:.3}%)",
+            epoch_ms_to_utc(wick.timestamp_ms),
+            wick.wick_pct,
+            wick.median_range_pct
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{Context, Result, anyhow};
+use zone_sniper::config::ANALYSIS;
+use zone_sniper::data::timeseries::serde_version::load_local_collection;
+use zone_sniper::data::timeseries::tabular_version::{export_path, export_series};
+use zone_sniper::models::EdgeBuckets;
+use zone_sniper::utils::TimeUtils;
+
+/// Usage: `cargo run --release --bin export_klines -- [--parquet] [--interval=4h] [PAIR ...]`
+///
+/// Writes pairs from the local kline cache to `ohlcv_exports/PAIR_INTERVAL.csv` (or
+/// `.parquet`, which needs `--features parquet`), including pair / interval / pct_gaps
+/// metadata. `--interval` resamples to a coarser interval first, keeping only full
+/// buckets. With no pairs given, every cached pair is exported. The files read back
+/// in with `zone-sniper --import`.
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let extension = if args.iter().any(|a| a == "--parquet") {
+        "parquet"
+    } else {
+        "csv"
+    };
+    let target_interval_ms = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--interval="))
+        .map(|text| {
+            TimeUtils::interval_from_string(text)
+                .ok_or_else(|| anyhow!("Unknown interval '{}'", text))
+        })
+        .transpose()?;
+    let requested: Vec<String> = args
+        .iter()
+        .filter(|a| !a.starts_with("--"))
+        .map(|p| p.to_uppercase())
+        .collect();
+
+    let collection = load_local_collection(ANALYSIS.interval_width_ms)
+        .context("Failed to load the local kline cache")?;
+
+    let mut exported = 0;
+    for ts in collection
+        .series_data
+        .iter()
+        .filter(|ts| requested.is_empty() || requested.contains(&ts.pair_interval.name))
+    {
+        let resampled;
+        let ts = match target_interval_ms {
+            Some(interval_ms) => {
+                resampled = ts.resample(interval_ms, EdgeBuckets::Drop)?;
+                &resampled
+            }
+            None => ts,
+        };
+        let path = export_path(ts, extension);
+        export_series(ts, &path)?;
+        println!("Wrote {} ({} candles)", path.display(), ts.klines());
+        exported += 1;
+    }
+
+    if exported == 0 {
+        return Err(anyhow!("No matching pairs in the local kline cache"));
+    }
+    Ok(())
+}
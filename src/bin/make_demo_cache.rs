@@ -1,6 +1,6 @@
 use anyhow::{Context, Result, anyhow};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -8,23 +8,30 @@ use zone_sniper::config::ANALYSIS;
 use zone_sniper::config::DEMO;
 use zone_sniper::config::{PERSISTENCE, kline_cache_filename};
 use zone_sniper::data::price_stream::PriceStreamManager;
+use zone_sniper::data::tick_tape::{TickRecorder, TickTape, spawn_recording};
 use zone_sniper::data::timeseries::TimeSeriesCollection;
 use zone_sniper::data::timeseries::cache_file::CacheFile;
+use zone_sniper::data::timeseries::serde_version::load_local_collection;
 
 fn main() -> Result<()> {
+    // Without a live feed to record, the demo tape can be synthesized from the saved prices
+    if std::env::args()
+        .skip(1)
+        .any(|arg| arg == "--synthetic-tape")
+    {
+        return write_synthetic_demo_tape();
+    }
     build_demo_cache()
 }
 
 fn build_demo_cache() -> Result<()> {
     let source_filename = kline_cache_filename(ANALYSIS.interval_width_ms);
-    let source_path = PathBuf::from(PERSISTENCE.kline.directory).join(&source_filename);
-    let cache = CacheFile::load_from_path(&source_path)
-        .with_context(|| format!("Failed to load source cache {:?}", source_path))?;
+    let source =
+        load_local_collection(ANALYSIS.interval_width_ms).context("Failed to load source cache")?;
 
     println!(
-        "Loaded {} pairs from {:?}",
-        cache.data.series_data.len(),
-        source_path
+        "Loaded {} pairs from the local kline cache",
+        source.series_data.len()
     );
 
     let demo_pairs: HashSet<String> = DEMO
@@ -34,7 +41,7 @@ fn build_demo_cache() -> Result<()> {
         .map(|p| p.to_uppercase())
         .collect();
 
-    let filtered = filter_pairs(cache.data.clone(), &demo_pairs);
+    let filtered = filter_pairs(source.clone(), &demo_pairs);
     let mut filtered_collection = filtered;
 
     if filtered_collection.series_data.len() > DEMO.max_pairs {
@@ -44,7 +51,7 @@ fn build_demo_cache() -> Result<()> {
     let output_cache = CacheFile::new(
         ANALYSIS.interval_width_ms,
         filtered_collection,
-        cache.version,
+        source.version,
     );
 
     let demo_filename = format!("demo_{}", source_filename);
@@ -62,6 +69,9 @@ fn build_demo_cache() -> Result<()> {
     let prices = fetch_current_prices_for_demo_pairs(&demo_pairs)?;
     write_demo_prices_json(&prices)?;
 
+    // Finally record a stretch of live ticks that the WASM build loops, so its prices move.
+    record_demo_tape(&demo_pairs)?;
+
     Ok(())
 }
 
@@ -151,3 +161,51 @@ fn write_demo_prices_json(prices: &HashMap<String, f64>) -> Result<()> {
 
     Ok(())
 }
+
+fn record_demo_tape(demo_pairs: &HashSet<String>) -> Result<()> {
+    let output_path = PathBuf::from(PERSISTENCE.kline.directory).join("demo_ticks.bin");
+    // Recorders append; the demo loops a single session, so start from an empty tape
+    if output_path.exists() {
+        std::fs::remove_file(&output_path)
+            .with_context(|| format!("Failed to replace {}", output_path.display()))?;
+    }
+
+    let stream = PriceStreamManager::new();
+    let recorder = TickRecorder::open(&output_path)?;
+    spawn_recording(recorder, stream.subscribe_updates());
+    // Prices only: bookTicker would make the bundled tape many times larger
+    stream.subscribe_all(demo_pairs.iter().cloned().collect());
+
+    let duration = Duration::from_secs(DEMO.tape_duration_sec);
+    println!(
+        "Recording {:?} of live ticks for the demo tape...",
+        duration
+    );
+    thread::sleep(duration);
+
+    println!("✅ Demo tick tape written to {:?}.", output_path);
+    Ok(())
+}
+
+/// Swings each price in `demo_prices.json` gently over a tape as long as a recorded one
+fn write_synthetic_demo_tape() -> Result<()> {
+    let directory = PathBuf::from(PERSISTENCE.kline.directory);
+    let prices_path = directory.join("demo_prices.json");
+    let prices: BTreeMap<String, f64> = serde_json::from_str(
+        &std::fs::read_to_string(&prices_path)
+            .with_context(|| format!("Failed to read {}", prices_path.display()))?,
+    )
+    .with_context(|| format!("Failed to parse {}", prices_path.display()))?;
+
+    let tape = TickTape::synthetic(&prices, DEMO.tape_duration_sec as i64 * 1000, 2_000, 0.3);
+    let output_path = directory.join("demo_ticks.bin");
+    std::fs::write(&output_path, tape.to_bytes()?)
+        .with_context(|| format!("Failed to write {}", output_path.display()))?;
+
+    println!(
+        "✅ Synthetic demo tick tape written to {:?} ({} ticks).",
+        output_path,
+        tape.records.len()
+    );
+    Ok(())
+}
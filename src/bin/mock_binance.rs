@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use zone_sniper::config::ANALYSIS;
+use zone_sniper::data::mock_binance::{
+    MockBinanceConfig, MockBinanceServer, WsEvent, write_fixture,
+};
+use zone_sniper::data::timeseries::serde_version::load_local_collection;
+
+/// Usage: `cargo run --bin mock_binance -- [FIXTURES_DIR] [--seed-from-cache]`
+///
+/// Serves the fixtures in FIXTURES_DIR (default `mock_fixtures`). With
+/// `--seed-from-cache`, the directory is first filled from the local kline cache.
+/// The live feed replays each pair's last close so the UI has prices to show.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let seed = args.iter().any(|a| a == "--seed-from-cache");
+    let fixtures_dir = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("mock_fixtures"));
+
+    if seed {
+        seed_fixtures(&fixtures_dir)?;
+    }
+
+    let server = MockBinanceServer::start(MockBinanceConfig::new(&fixtures_dir)).await?;
+
+    println!("Mock Binance serving {}", fixtures_dir.display());
+    for (var, value) in server.env_overrides() {
+        println!("  export {}={}", var, value);
+    }
+    println!("Press Ctrl+C to stop.");
+
+    // Keep the ticker feed alive: replay the latest closes every few seconds.
+    let ticks = latest_closes(&fixtures_dir);
+    loop {
+        server.push_ws_events(ticks.iter().cloned());
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(5)) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn seed_fixtures(fixtures_dir: &std::path::Path) -> Result<()> {
+    let collection = load_local_collection(ANALYSIS.interval_width_ms)
+        .context("Failed to load the local kline cache")?;
+
+    for ts in &collection.series_data {
+        let path = write_fixture(ts, fixtures_dir)?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn latest_closes(fixtures_dir: &std::path::Path) -> Vec<WsEvent> {
+    let Ok(entries) = std::fs::read_dir(fixtures_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let (symbol, _) = path.file_stem()?.to_str()?.rsplit_once('_')?;
+            let text = std::fs::read_to_string(&path).ok()?;
+            let rows: Vec<serde_json::Value> = serde_json::from_str(&text).ok()?;
+            let price = rows.last()?.get(4)?.as_str()?.parse::<f64>().ok()?;
+            Some(WsEvent::Tick {
+                symbol: symbol.to_string(),
+                price,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,74 @@
+use anyhow::{Context, Result, anyhow};
+use std::path::PathBuf;
+use zone_sniper::config::ANALYSIS;
+use zone_sniper::config::PERSISTENCE;
+use zone_sniper::data::timeseries::serde_version::load_local_collection;
+use zone_sniper::domain::venue::Venue;
+use zone_sniper::journeys::validation::{ValidationReport, walk_forward_validate};
+
+/// Usage: `cargo run --release --bin walk_forward -- [PAIR ...]`
+/// With no pairs given, every pair in the local kline cache is validated.
+fn main() -> Result<()> {
+    let requested: Vec<String> = std::env::args().skip(1).map(|p| p.to_uppercase()).collect();
+
+    let collection = load_local_collection(ANALYSIS.interval_width_ms)
+        .context("Failed to load the local kline cache")?;
+
+    let series: Vec<_> = collection
+        .series_data
+        .iter()
+        .filter(|ts| requested.is_empty() || requested.contains(&ts.pair_interval.name))
+        .collect();
+
+    if series.is_empty() {
+        return Err(anyhow!("No matching pairs in the local kline cache"));
+    }
+
+    let output_dir = PathBuf::from(PERSISTENCE.journey_export.directory);
+    std::fs::create_dir_all(&output_dir)
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+
+    for ts in series {
+        match walk_forward_validate(
+            ts,
+            &ANALYSIS.price_horizon,
+            ANALYSIS.journey.declustering,
+            &ANALYSIS.journey.validation,
+        ) {
+            Ok(report) => {
+                print_report(&report);
+                let path = output_dir.join(format!(
+                    "{}_walk_forward.json",
+                    Venue::path_safe(&report.pair)
+                ));
+                let json = serde_json::to_string_pretty(&report)
+                    .context("Failed to serialize validation report")?;
+                std::fs::write(&path, json)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+            Err(e) => println!("⚠️  {}: {}", ts.pair_interval.name, e),
+        }
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &ValidationReport) {
+    println!(
+        "\n{}: {} cutoffs, {} zone predictions",
+        report.pair,
+        report.cutoffs_evaluated,
+        report.predictions.len()
+    );
+    println!(
+        "  Brier {:.4} | reference {:.4} | skill {:+.3}",
+        report.brier_score, report.reference_brier_score, report.brier_skill_score
+    );
+    println!("  predicted     n   mean_pred  observed");
+    for bin in report.calibration.iter().filter(|b| b.count > 0) {
+        println!(
+            "  {:.1}-{:.1}  {:>6}   {:>8.3}  {:>8.3}",
+            bin.lower, bin.upper, bin.count, bin.mean_predicted, bin.observed_rate
+        );
+    }
+}
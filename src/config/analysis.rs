@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize}; // Add Import
 
 use crate::{
     domain::price_horizon::PriceHorizonConfig,
+    journeys::{
+        DeclusterStrategy, MonteCarloSettings, ObstructionSettings, PositionSizing,
+        WalkForwardSettings,
+    },
     utils::TimeUtils,
 };
 
@@ -22,6 +26,16 @@ pub struct JourneySettings {
     // Tolerance when matching historical prices for journey analysis (percentage)
     pub start_price_tolerance_pct: f64,
     pub stop_loss_pct: f64,
+    // How clustered historical matches around the start price are thinned out
+    pub declustering: DeclusterStrategy,
+    // Block-bootstrap settings for simulating sequences of journey outcomes
+    pub monte_carlo: MonteCarloSettings,
+    // How much equity each simulated trade commits in the journey panel's Monte Carlo
+    pub position_sizing: PositionSizing,
+    // How reversal zones between live price and target are detected in history
+    pub obstruction: ObstructionSettings,
+    // Walk-forward harness used to check Stage-0 probabilities against what happened
+    pub validation: WalkForwardSettings,
 }
 
 /// Settings for CVA (Cumulative Volume Analysis)
@@ -34,6 +48,27 @@ pub struct CvaSettings {
     pub min_candles_for_analysis: usize,
 }
 
+/// How candles forward-filled over exchange gaps (see `GapMask`) feed the analyses
+#[derive(Clone, Debug, Serialize, Deserialize)] // Add Serde
+pub struct GapSettings {
+    // Weight of a filled candle in the CVA histogram. 0.0 skips it, 1.0 counts it like a traded candle
+    pub cva_weight: f64,
+    // Whether a filled candle can make a stretch of history price-relevant
+    pub price_horizon_includes_filled: bool,
+    // Whether journeys may start on, or hit a target / stop on, a filled candle
+    pub journeys_include_filled: bool,
+}
+
+/// Exact volume-at-price from Binance aggTrades (loaded with `--agg-trades`)
+#[derive(Clone, Debug, Serialize, Deserialize)] // Add Serde
+pub struct TradeProfileSettings {
+    // How far back trades are fetched and kept
+    pub lookback_days: i64,
+    // Share of a covered candle's volume placed at its traded prices (the rest is spread
+    // over its range as usual). 1.0 uses the trades alone, 0.0 ignores them
+    pub exact_volume_weight: f64,
+}
+
 /// Parameters for a specific zone type (Sticky, Reversal, etc.)
 #[derive(Clone, Debug, Copy, Serialize, Deserialize)] // Add Serde
 pub struct ZoneParams {
@@ -71,6 +106,8 @@ pub struct AnalysisConfig {
     pub journey: JourneySettings,
     pub cva: CvaSettings,
     pub zones: ZoneClassificationConfig,
+    pub gaps: GapSettings,
+    pub trades: TradeProfileSettings,
 
     pub price_horizon: PriceHorizonConfig,
 }
@@ -112,6 +149,33 @@ pub const ANALYSIS: AnalysisConfig = AnalysisConfig {
         start_price_tolerance_pct: 0.5,
         // Stop-loss threshold (percentage move against position) for journey failures
         stop_loss_pct: 5.0,
+        // Keep every match by default so the headline stats don't shift silently;
+        // opt into e.g. MinSpacing { min_spacing_ms: 86_400_000 } for one attempt per day
+        declustering: DeclusterStrategy::AllMatches,
+        monte_carlo: MonteCarloSettings {
+            paths: 2_000,
+            trades_per_path: 50,
+            // Consecutive attempts are correlated, so resample short runs rather than single trades
+            block_len: 5,
+            ruin_drawdown_pct: 50.0,
+            seed: 0x5EED,
+        },
+        position_sizing: PositionSizing::FixedFraction(0.1),
+        obstruction: ObstructionSettings {
+            // One week of history before each historical attempt
+            lookback_ms: TimeUtils::MS_IN_W,
+            zone_width_pct: 0.5,
+            min_rejections: 2,
+            min_group_samples: 10,
+        },
+        validation: WalkForwardSettings {
+            cutoffs: 50,
+            min_history_days: 90,
+            horizon_days: 7,
+            start_price_tolerance_pct: 0.5,
+            min_samples: 10,
+            calibration_bins: 10,
+        },
     },
 
     cva: CvaSettings {
@@ -123,6 +187,20 @@ pub const ANALYSIS: AnalysisConfig = AnalysisConfig {
         min_candles_for_analysis: 100,
     },
 
+    // Filled candles are copies of the last real one, with its volume: counting them
+    // would pile phantom volume onto whatever price the market stopped at.
+    gaps: GapSettings {
+        cva_weight: 0.0,
+        price_horizon_includes_filled: false,
+        journeys_include_filled: false,
+    },
+
+    // A few days of a busy pair is already millions of trades
+    trades: TradeProfileSettings {
+        lookback_days: 3,
+        exact_volume_weight: 1.0,
+    },
+
     // NEW: Initialize Default AutoDuration
     price_horizon: PriceHorizonConfig {
         threshold_pct: 0.15,
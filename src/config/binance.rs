@@ -24,69 +24,177 @@ impl Default for BinanceApiConfig {
 pub struct RestLimits {
     /// Default limit for number of klines returned in a single request
     pub klines_limit: i32,
-    /// Maximum number of simultaneous Binance API calls allowed per batch
-    pub simultaneous_calls_ceiling: usize,
+    /// REST requests in flight at once across every venue (`data::fetch_scheduler`); the
+    /// weight budget paces them further
+    pub max_requests_in_flight: usize,
     /// Maximum total number of pair/interval combinations to query
     pub max_lookups_total: usize,
     /// Weight limit per minute as specified in Binance FAQ
     pub weight_limit_minute: u32,
     /// Weight cost for a single kline API call
     pub kline_call_weight: u32,
+    /// Weight cost of `exchangeInfo` for every symbol
+    pub exchange_info_call_weight: u32,
+    /// Weight cost of `ticker/price` for every symbol
+    pub ticker_price_call_weight: u32,
+    /// Weight cost of `ticker/24hr` for every symbol
+    pub ticker_24hr_call_weight: u32,
     /// Maximum age of cached kline data (seconds)
     pub kline_acceptable_age_sec: i64,
+    /// Number of aggregated trades returned in a single request (max 1000)
+    pub agg_trades_limit: i32,
+    /// Weight cost for a single aggTrades API call
+    pub agg_trades_call_weight: u32,
+}
+
+/// Configuration for REST Endpoints
+pub struct RestConfig {
+    /// REST API base URL (scheme + host, no trailing slash)
+    pub base_url: &'static str,
 }
 
 /// Configuration for WebSocket Connections
 pub struct WsConfig {
-    /// WebSocket base URL for Binance streaming API (single stream)
+    /// WebSocket base URL for Binance streaming API (scheme + host + port)
     pub base_url: &'static str,
-    /// WebSocket base URL for Binance combined streaming API
-    pub combined_base_url: &'static str,
+    /// Path (plus query prefix) of the combined streaming API, appended to `base_url`
+    pub combined_path: &'static str,
     /// Maximum reconnection delay (seconds)
     pub max_reconnect_delay_sec: u64,
     /// Initial reconnection delay (seconds)
     pub initial_reconnect_delay_sec: u64,
+    /// Streams carried per connection before another is opened. Binance allows 1024;
+    /// the initial URL lists them all, so stay well below that
+    pub max_streams_per_connection: usize,
+    /// A symbol with no frame for this long is `Stale` and its price isn't acted on.
+    /// Quiet pairs can raise it per pair with `stale_after=` in the watchlist
+    pub stale_after_sec: u64,
+    /// Updates buffered per `PriceStreamManager::subscribe_updates` receiver before the
+    /// oldest are dropped and the receiver is told it lagged
+    pub update_channel_capacity: usize,
+    /// Also stream best bid/ask (`@bookTicker`) for every pair
+    pub book_ticker: bool,
+    /// Order-book levels streamed per side (`@depth<N>`: 5, 10 or 20). 0 disables
+    pub depth_levels: u32,
 }
 
 /// Default values for the Rest Client
 pub struct ClientDefaults {
     pub timeout_ms: u64,
+    /// SDK retries of requests that never got an answer
     pub retries: u32,
     pub backoff_ms: u64,
+    /// Retries of requests turned away with 429 / 5xx (`data::fetch_scheduler`)
+    pub throttle_retries: u32,
+    /// First backoff after a 5xx (doubled per attempt, jittered) unless told otherwise
+    pub throttle_backoff_ms: u64,
+    pub max_throttle_backoff_ms: u64,
+    /// How long a 418 (IP ban) stops all requests to its venue when it doesn't say.
+    /// Binance bans last from 2 minutes to 3 days, and requests during one extend it.
+    pub ip_ban_min_secs: u64,
+}
+
+/// Environment variables that redirect Binance traffic, e.g. to the mock server
+/// in `data::mock_binance`. Unset means the production URLs above are used.
+pub struct EndpointOverrides {
+    pub rest_base_url_env: &'static str,
+    pub ws_base_url_env: &'static str,
+}
+
+/// Where the list of pairs to load comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairSource {
+    /// The watchlist file, validated against exchangeInfo
+    File,
+    /// Every trading symbol quoted in `PairDiscovery::quote_asset`
+    QuoteAsset,
+    /// The `max_pairs` symbols quoted in `quote_asset` with the highest 24h quote volume
+    VolumeRank,
+}
+
+pub struct PairDiscovery {
+    /// Watchlist file (groups, comments, per-pair overrides; see `domain::watchlist`)
+    pub watchlist_path: &'static str,
+    pub source: PairSource,
+    pub quote_asset: &'static str,
 }
 
 /// The Master Configuration Struct
 pub struct BinanceConfig {
     pub limits: RestLimits,
+    pub rest: RestConfig,
     pub ws: WsConfig,
+    pub overrides: EndpointOverrides,
     pub client: ClientDefaults,
-    /// Interval for debug prints in development
-    pub debug_print_interval: u32,
+    pub pairs: PairDiscovery,
     pub max_pairs: usize,
 }
 
 pub const BINANCE: BinanceConfig = BinanceConfig {
     limits: RestLimits {
         klines_limit: 1000,
-        // Theoretical limit is 1000, but 500 is safer for rate limiting
-        simultaneous_calls_ceiling: 500,
+        max_requests_in_flight: 10,
         max_lookups_total: 1000,
         weight_limit_minute: 6000,
         kline_call_weight: 2,
+        exchange_info_call_weight: 20,
+        ticker_price_call_weight: 4,
+        ticker_24hr_call_weight: 80,
         // 24 hours (60 * 60 * 24)
         kline_acceptable_age_sec: 86_400,
+        agg_trades_limit: 1000,
+        agg_trades_call_weight: 4,
+    },
+    rest: RestConfig {
+        base_url: "https://api.binance.com",
     },
     ws: WsConfig {
-        base_url: "wss://stream.binance.com:9443/ws",
-        combined_base_url: "wss://stream.binance.com:9443/stream?streams=",
+        base_url: "wss://stream.binance.com:9443",
+        combined_path: "/stream?streams=",
         max_reconnect_delay_sec: 300, // 5 minutes
         initial_reconnect_delay_sec: 1,
+        max_streams_per_connection: 200,
+        stale_after_sec: 60,
+        update_channel_capacity: 4096,
+        book_ticker: true,
+        depth_levels: 20,
+    },
+    overrides: EndpointOverrides {
+        rest_base_url_env: "ZONE_SNIPER_BINANCE_REST_URL",
+        ws_base_url_env: "ZONE_SNIPER_BINANCE_WS_URL",
     },
     client: ClientDefaults {
         timeout_ms: 5000,
         retries: 5,
         backoff_ms: 5000,
+        throttle_retries: 5,
+        throttle_backoff_ms: 500,
+        max_throttle_backoff_ms: 30_000,
+        ip_ban_min_secs: 120,
+    },
+    pairs: PairDiscovery {
+        watchlist_path: "pairs.txt",
+        source: PairSource::File,
+        quote_asset: "USDT",
     },
-    debug_print_interval: 10,
     max_pairs: 20,
 };
+
+impl BinanceConfig {
+    /// REST base URL, honouring the `rest_base_url_env` override.
+    pub fn rest_base_url(&self) -> String {
+        std::env::var(self.overrides.rest_base_url_env)
+            .unwrap_or_else(|_| self.rest.base_url.to_string())
+    }
+
+    /// WebSocket base URL, honouring the `ws_base_url_env` override.
+    pub fn ws_base_url(&self) -> String {
+        std::env::var(self.overrides.ws_base_url_env)
+            .unwrap_or_else(|_| self.ws.base_url.to_string())
+    }
+
+    /// Prefix of a combined-stream URL; stream names are appended to it.
+    pub fn ws_combined_url_prefix(&self) -> String {
+        format!("{}{}", self.ws_base_url(), self.ws.combined_path)
+    }
+}
@@ -20,6 +20,8 @@ pub struct DemoConfig {
     pub max_pairs: usize,
     /// Bundled resources
     pub resources: DemoResources,
+    /// Seconds of live ticks `make_demo_cache` records for the demo to loop
+    pub tape_duration_sec: u64,
 }
 
 pub const DEMO: DemoConfig = DemoConfig {
@@ -30,4 +32,5 @@ pub const DEMO: DemoConfig = DemoConfig {
         // cache_filename: "demo_kline_30m_v4.bin",
         pairs: &["BTCUSDT", "ETHUSDT", "SOLUSDT", "BNBUSDT", "PAXGUSDT"],
     },
+    tape_duration_sec: 300,
 };
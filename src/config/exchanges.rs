@@ -0,0 +1,71 @@
+//! Endpoints and limits of the venues besides Binance spot (which is configured in
+//! `BINANCE`). See `data::exchange` for the adapters that use them.
+
+use super::binance::EndpointOverrides;
+
+/// Where one venue is reached, and what its klines cost
+pub struct VenueConfig {
+    /// REST API base URL (scheme + host, no trailing slash)
+    pub rest_base_url: &'static str,
+    /// WebSocket base URL (scheme + host + port)
+    pub ws_base_url: &'static str,
+    pub overrides: EndpointOverrides,
+    /// Weight limit per minute for this venue's REST API
+    pub weight_limit_minute: u32,
+    /// Weight cost of one klines call of `BINANCE.limits.klines_limit` rows
+    pub kline_call_weight: u32,
+    /// Weight cost of listing every symbol
+    pub symbols_call_weight: u32,
+    /// Weight cost of every symbol's last price
+    pub prices_call_weight: u32,
+}
+
+impl VenueConfig {
+    /// REST base URL, honouring the `rest_base_url_env` override.
+    pub fn rest_base_url(&self) -> String {
+        std::env::var(self.overrides.rest_base_url_env)
+            .unwrap_or_else(|_| self.rest_base_url.to_string())
+    }
+
+    /// WebSocket base URL, honouring the `ws_base_url_env` override.
+    pub fn ws_base_url(&self) -> String {
+        std::env::var(self.overrides.ws_base_url_env)
+            .unwrap_or_else(|_| self.ws_base_url.to_string())
+    }
+}
+
+pub struct ExchangesConfig {
+    /// Binance USDⓈ-M futures. Same stream payloads as spot, served from its own hosts
+    pub binance_futures: VenueConfig,
+    /// Any exchange (or in-house gateway) speaking the protocol in
+    /// `data::exchange::generic`. There's no public default: set the override variables.
+    pub generic: VenueConfig,
+}
+
+pub const EXCHANGES: ExchangesConfig = ExchangesConfig {
+    binance_futures: VenueConfig {
+        rest_base_url: "https://fapi.binance.com",
+        ws_base_url: "wss://fstream.binance.com",
+        overrides: EndpointOverrides {
+            rest_base_url_env: "ZONE_SNIPER_BINANCE_FUTURES_REST_URL",
+            ws_base_url_env: "ZONE_SNIPER_BINANCE_FUTURES_WS_URL",
+        },
+        weight_limit_minute: 2400,
+        // limit 500..=1000
+        kline_call_weight: 5,
+        symbols_call_weight: 1,
+        prices_call_weight: 2,
+    },
+    generic: VenueConfig {
+        rest_base_url: "http://127.0.0.1:8380",
+        ws_base_url: "ws://127.0.0.1:8381",
+        overrides: EndpointOverrides {
+            rest_base_url_env: "ZONE_SNIPER_GENERIC_REST_URL",
+            ws_base_url_env: "ZONE_SNIPER_GENERIC_WS_URL",
+        },
+        weight_limit_minute: 1200,
+        kline_call_weight: 1,
+        symbols_call_weight: 1,
+        prices_call_weight: 1,
+    },
+};
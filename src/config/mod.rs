@@ -5,14 +5,26 @@ mod analysis;
 mod binance;
 mod debug;
 mod demo;
+mod exchanges;
 mod persistence;
+mod quality;
+mod tabular;
 
 // Can't be private because we don't re-export it
 pub mod plot;
 
 // Re-export commonly used items
-pub use analysis::{ANALYSIS, ZoneParams, AnalysisConfig};
-pub use binance::{BINANCE, BinanceApiConfig};
+pub use analysis::{
+    ANALYSIS, AnalysisConfig, GapSettings, TradeProfileSettings, ZoneClassificationConfig,
+    ZoneParams,
+};
+pub use binance::{BINANCE, BinanceApiConfig, PairSource};
 pub use debug::DEBUG_FLAGS;
 pub use demo::DEMO;
-pub use persistence::{PERSISTENCE, kline_cache_filename};
+pub use exchanges::{EXCHANGES, VenueConfig};
+pub use persistence::{
+    CacheFormatVersion, PERSISTENCE, kline_cache_dirname, kline_cache_filename,
+    legacy_kline_cache_dirname,
+};
+pub use quality::{DATA_QUALITY, DataQualityConfig, QualityLimits};
+pub use tabular::{ColumnMapping, ColumnRef, TABULAR, TabularFormat, TimestampUnit};
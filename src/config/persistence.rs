@@ -27,16 +27,63 @@
 // /// Path for saving/loading application UI state
 // pub const APP_STATE_PATH: &str = ".states.json";
 
+use serde::{Deserialize, Serialize};
+
 use crate::utils::TimeUtils;
 
+/// Semantic version of the on-disk chunk format.
+/// Bump `major` when old chunks need a migration (see `data::timeseries::cache_format`),
+/// `minor` for additive changes old readers can ignore, `patch` for fixes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CacheFormatVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+impl CacheFormatVersion {
+    /// Headerless bincode chunks written before versioned headers existed.
+    pub const LEGACY: CacheFormatVersion = CacheFormatVersion {
+        major: 4,
+        minor: 0,
+        patch: 0,
+    };
+
+    /// Parses "5.0.0" (or "5.0" / "5").
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().trim_start_matches('v').split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+        let patch = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl std::fmt::Display for CacheFormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
 /// Configuration for Kline Data Persistence
 pub struct KlinePersistenceConfig {
     /// Directory path for storing kline data
     pub directory: &'static str,
     /// Base filename for kline data files (without extension)
     pub filename_base: &'static str,
-    /// Current version of the kline data serialization format
+    /// Version of the legacy single-file cache (and the WASM demo cache built from it)
     pub version: f64,
+    /// Version written into every chunk header and the manifest of the chunked cache
+    pub format_version: CacheFormatVersion,
+    /// Index file inside each per-interval chunked cache directory
+    pub manifest_filename: &'static str,
 }
 
 /// Configuration for Application State Persistence
@@ -45,10 +92,34 @@ pub struct AppPersistenceConfig {
     pub state_path: &'static str,
 }
 
+/// Configuration for Journey Result Exports
+pub struct JourneyExportConfig {
+    /// Directory that CSV/JSON journey exports are written to
+    pub directory: &'static str,
+}
+
+/// Configuration for the cached Binance exchangeInfo symbol registry
+pub struct ExchangeInfoPersistenceConfig {
+    /// Directory the registry file is stored in
+    pub directory: &'static str,
+    pub filename: &'static str,
+    /// Refetch from Binance once the cached copy is older than this
+    pub max_age_sec: i64,
+}
+
+/// Configuration for the cached aggTrades volume profiles
+pub struct TradeProfilePersistenceConfig {
+    /// Directory holding one profile file per pair and interval
+    pub directory: &'static str,
+}
+
 /// The Master Persistence Configuration
 pub struct PersistenceConfig {
     pub kline: KlinePersistenceConfig,
     pub app: AppPersistenceConfig,
+    pub journey_export: JourneyExportConfig,
+    pub exchange_info: ExchangeInfoPersistenceConfig,
+    pub trade_profile: TradeProfilePersistenceConfig,
 }
 
 pub const PERSISTENCE: PersistenceConfig = PersistenceConfig {
@@ -56,10 +127,28 @@ pub const PERSISTENCE: PersistenceConfig = PersistenceConfig {
         directory: "kline_data",
         filename_base: "kline",
         version: 4.0,
+        format_version: CacheFormatVersion {
+            major: 6,
+            minor: 0,
+            patch: 0,
+        },
+        manifest_filename: "manifest.json",
     },
     app: AppPersistenceConfig {
         state_path: ".states.json",
     },
+    journey_export: JourneyExportConfig {
+        directory: "journey_exports",
+    },
+    exchange_info: ExchangeInfoPersistenceConfig {
+        directory: "kline_data",
+        filename: "exchange_info.json",
+        // 24 hours (60 * 60 * 24)
+        max_age_sec: 86_400,
+    },
+    trade_profile: TradeProfilePersistenceConfig {
+        directory: "kline_data/trade_profiles",
+    },
 };
 
 /// Generate interval-specific cache filename
@@ -74,3 +163,22 @@ pub fn kline_cache_filename(interval_ms: i64) -> String {
         PERSISTENCE.kline.filename_base, interval_str, PERSISTENCE.kline.version
     )
 }
+
+/// Generate interval-specific chunked cache directory name
+/// Example: "kline_30m" (holds manifest.json and one folder per pair). Unversioned:
+/// the format version lives in the manifest and chunk headers, so upgrades migrate in place.
+pub fn kline_cache_dirname(interval_ms: i64) -> String {
+    let interval_str = TimeUtils::interval_to_string(interval_ms);
+
+    format!("{}_{}", PERSISTENCE.kline.filename_base, interval_str)
+}
+
+/// Directory name the chunked cache used before it was unversioned ("kline_30m_v4").
+/// Only read by the migration that moves it to `kline_cache_dirname`.
+pub fn legacy_kline_cache_dirname(interval_ms: i64) -> String {
+    format!(
+        "{}_v{}",
+        kline_cache_dirname(interval_ms),
+        PERSISTENCE.kline.version
+    )
+}
@@ -32,6 +32,27 @@ pub struct PlotConfig {
     pub background_bar_intensity_pct: f32,
     pub active_zone_stroke_color: Color32,
     pub active_zone_stroke_width: f32,
+    /// Depth ladder (live order book drawn against the zones)
+    pub depth_bid_color: Color32,
+    pub depth_ask_color: Color32,
+    pub depth_line_width: f32,
+    /// Share of the plot width taken by the largest resting quantity
+    pub depth_ladder_width_pct: f64,
+    /// Candlestick chart (drawn left of the histogram, sharing its price axis)
+    pub candle_up_color: Color32,
+    pub candle_down_color: Color32,
+    /// Shading of the candle slices the histogram was built from
+    pub cva_slice_color: Color32,
+    /// Share of the plot area's width taken by the candlestick chart
+    pub candle_chart_width_pct: f32,
+    /// Body width as a share of the candle interval
+    pub candle_body_width_pct: f64,
+    /// More candles than this in view are merged, so zooming out stays cheap
+    pub max_drawn_candles: usize,
+    /// Shading of a historical journey attempt picked in the journey panel
+    pub journey_attempt_color: Color32,
+    /// Its start and target price lines
+    pub journey_attempt_line_color: Color32,
 }
 
 pub const PLOT_CONFIG: PlotConfig = PlotConfig {
@@ -64,4 +85,18 @@ pub const PLOT_CONFIG: PlotConfig = PlotConfig {
     // I recommend Gold/Yellow for visibility against dark backgrounds
     active_zone_stroke_color: Color32::from_rgb(200, 185, 0), // Gold
     active_zone_stroke_width: 1.5, 
+
+    depth_bid_color: Color32::from_rgb(0, 200, 120),
+    depth_ask_color: Color32::from_rgb(230, 60, 60),
+    depth_line_width: 2.0,
+    depth_ladder_width_pct: 0.25,
+
+    candle_up_color: Color32::from_rgb(38, 166, 154),
+    candle_down_color: Color32::from_rgb(239, 83, 80),
+    cva_slice_color: Color32::from_rgba_unmultiplied_const(120, 140, 220, 28),
+    journey_attempt_color: Color32::from_rgba_unmultiplied_const(255, 215, 0, 40),
+    journey_attempt_line_color: Color32::from_rgb(255, 215, 0),
+    candle_chart_width_pct: 0.65,
+    candle_body_width_pct: 0.7,
+    max_drawn_candles: 600,
 };
@@ -0,0 +1,53 @@
+//! config/quality.rs Data-quality checks run on every pair after loading.
+
+/// Limits a pair's data must stay within to earn a given verdict
+pub struct QualityLimits {
+    /// Percentage of candles that were filled over gaps
+    pub max_pct_gaps: f64,
+    /// Candles whose open / high / low / close contradict each other
+    pub max_ohlc_inconsistencies: usize,
+    /// Duplicate or off-grid kline timestamps in the raw data
+    pub max_timestamp_issues: usize,
+    /// Percentage of traded candles with an outlier wick
+    pub max_pct_outlier_wicks: f64,
+    /// Days of real (not filled) candles
+    pub min_effective_history_days: f64,
+}
+
+/// Configuration for Data Quality Reports
+pub struct DataQualityConfig {
+    /// How many of the longest gaps each report lists
+    pub largest_gaps_listed: usize,
+    /// How many example candles each report lists per kind of problem
+    pub examples_listed: usize,
+    /// Consecutive zero-volume candles needed before a run is reported as a streak
+    pub min_zero_volume_streak: usize,
+    /// A wick longer than this many median candle ranges (of the same pair) is an outlier
+    pub outlier_wick_multiple: f64,
+    /// Beyond these a pair is flagged as a warning
+    pub warn: QualityLimits,
+    /// Beyond these a pair is flagged as bad (and dropped with --exclude-bad-data)
+    pub bad: QualityLimits,
+}
+
+pub const DATA_QUALITY: DataQualityConfig = DataQualityConfig {
+    largest_gaps_listed: 5,
+    examples_listed: 5,
+    // Three hours of 30m candles
+    min_zero_volume_streak: 6,
+    outlier_wick_multiple: 25.0,
+    warn: QualityLimits {
+        max_pct_gaps: 1.0,
+        max_ohlc_inconsistencies: 0,
+        max_timestamp_issues: 0,
+        max_pct_outlier_wicks: 0.05,
+        min_effective_history_days: 180.0,
+    },
+    bad: QualityLimits {
+        max_pct_gaps: 10.0,
+        max_ohlc_inconsistencies: 10,
+        max_timestamp_issues: 100,
+        max_pct_outlier_wicks: 1.0,
+        min_effective_history_days: 30.0,
+    },
+};
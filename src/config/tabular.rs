@@ -0,0 +1,110 @@
+//! config/tabular.rs CSV / Parquet import and export of candle data.
+
+/// How a timestamp column is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampUnit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+    /// Text such as "2025-01-31T12:00:00Z" or "2025-01-31 12:00:00" (read as UTC)
+    DateTime,
+    /// Guess per value: text is parsed as a date, numbers by magnitude (s / ms / us / ns)
+    Auto,
+}
+
+/// A column picked by header name (case-insensitive) or by zero-based position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnRef {
+    Name(&'static str),
+    Index(usize),
+}
+
+/// Which source columns hold which candle fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnMapping {
+    /// Candle open time
+    pub timestamp: ColumnRef,
+    pub open: ColumnRef,
+    pub high: ColumnRef,
+    pub low: ColumnRef,
+    pub close: ColumnRef,
+    pub base_volume: ColumnRef,
+    /// Filled with close * base volume when absent
+    pub quote_volume: Option<ColumnRef>,
+    /// Lets one file hold several pairs; otherwise the pair comes from the file name
+    pub symbol: Option<ColumnRef>,
+}
+
+/// One way of reading candle files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TabularFormat {
+    pub columns: ColumnMapping,
+    pub timestamp_unit: TimestampUnit,
+    /// CSV only: whether the first non-comment row is a header
+    pub has_headers: bool,
+    /// CSV only
+    pub delimiter: u8,
+}
+
+/// Configuration for Tabular Import
+pub struct TabularImportConfig {
+    /// Used when `--import` is given a directory: every .csv / .parquet file inside is read
+    pub directory: &'static str,
+    /// Matches the columns `export_klines` writes
+    pub default_format: TabularFormat,
+    /// Binance public data dumps (data.binance.vision): headerless, microsecond open times
+    pub binance_vision_format: TabularFormat,
+}
+
+/// Configuration for Tabular Export
+pub struct TabularExportConfig {
+    /// Directory that CSV/Parquet candle exports are written to
+    pub directory: &'static str,
+}
+
+/// The Master Tabular Configuration
+pub struct TabularConfig {
+    pub import: TabularImportConfig,
+    pub export: TabularExportConfig,
+}
+
+pub const TABULAR: TabularConfig = TabularConfig {
+    import: TabularImportConfig {
+        directory: "import_data",
+        default_format: TabularFormat {
+            columns: ColumnMapping {
+                timestamp: ColumnRef::Name("open_time_ms"),
+                open: ColumnRef::Name("open"),
+                high: ColumnRef::Name("high"),
+                low: ColumnRef::Name("low"),
+                close: ColumnRef::Name("close"),
+                base_volume: ColumnRef::Name("base_volume"),
+                quote_volume: Some(ColumnRef::Name("quote_volume")),
+                symbol: None,
+            },
+            timestamp_unit: TimestampUnit::Auto,
+            has_headers: true,
+            delimiter: b',',
+        },
+        binance_vision_format: TabularFormat {
+            columns: ColumnMapping {
+                timestamp: ColumnRef::Index(0),
+                open: ColumnRef::Index(1),
+                high: ColumnRef::Index(2),
+                low: ColumnRef::Index(3),
+                close: ColumnRef::Index(4),
+                base_volume: ColumnRef::Index(5),
+                quote_volume: Some(ColumnRef::Index(7)),
+                symbol: None,
+            },
+            // Spot dumps switched from ms to us in 2025; Auto copes with both.
+            timestamp_unit: TimestampUnit::Auto,
+            has_headers: false,
+            delimiter: b',',
+        },
+    },
+    export: TabularExportConfig {
+        directory: "ohlcv_exports",
+    },
+};
@@ -0,0 +1,91 @@
+//! Exchange adapters: historical candles, live prices and symbol metadata for one venue,
+//! behind the `Exchange` trait so loading, the price stream and pair validation don't
+//! care where a pair trades.
+//!
+//! - `binance_spot`: Binance spot, through the SDK
+//! - `binance_futures`: Binance USDⓈ-M futures, through the SDK
+//! - `generic`: a small REST/WS protocol, for venues (or gateways) without an SDK here
+//!
+//! Every REST request goes through `data::fetch_scheduler`, which paces it against the
+//! venue's weight budget and retries it when turned away, over one client per venue.
+//!
+//! Adapters deal in venue symbols (`BTCUSDT`); the venue prefix of app-wide pair names
+//! (`binance-futures:BTCUSDT`, see `domain::venue`) is added and removed by the callers.
+
+pub mod binance;
+pub mod binance_futures;
+pub mod binance_spot;
+pub mod generic;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use crate::data::price_stream::{ClosedKline, PriceQuote, StreamSelection};
+use crate::data::timeseries::bnapi_version::bn_kline::AllValidKlines4Pair;
+use crate::domain::order_book::{BookTicker, DepthSnapshot};
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::symbol_registry::SymbolInfo;
+use crate::domain::venue::Venue;
+
+/// Live data carried by one stream frame
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    Price(PriceQuote),
+    Book(BookTicker),
+    Depth(DepthSnapshot),
+    /// `symbol` is the venue symbol, lowercase
+    ClosedKline(ClosedKline),
+}
+
+/// One text frame from a price stream connection, as read by `Exchange::parse_frame`
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamFrame {
+    /// Data for `symbol` (venue symbol, lowercase) on `stream`. `event` is `None` for frames
+    /// that only show the stream is alive (e.g. a candle that hasn't closed yet) or that
+    /// couldn't be read.
+    Data {
+        stream: String,
+        symbol: String,
+        event: Option<StreamEvent>,
+    },
+    /// Answer to a `stream_request`
+    Reply { id: u64, error: Option<String> },
+    /// Not a frame we know
+    Unknown,
+}
+
+#[async_trait]
+pub trait Exchange: Send + Sync {
+    fn venue(&self) -> Venue;
+
+    /// Every symbol listed on the venue, with its trading rules
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolInfo>>;
+
+    /// Whole kline history of `pair_interval.venue_symbol()`
+    async fn fetch_klines(&self, pair_interval: PairInterval) -> Result<AllValidKlines4Pair>;
+
+    /// Last price of every symbol (venue symbol, uppercase), to warm up the price stream
+    async fn fetch_prices(&self) -> Result<HashMap<String, f64>>;
+
+    /// Stream names carried for one lowercase venue symbol, each `<symbol>@<kind>`
+    fn streams_for(&self, symbol: &str, selection: &StreamSelection) -> Vec<String>;
+
+    /// URL of a connection carrying `streams` from the start
+    fn stream_url(&self, streams: &BTreeSet<String>) -> String;
+
+    /// Text frame asking a live connection to add (or drop) `streams`
+    fn stream_request(&self, subscribe: bool, streams: &[String], id: u64) -> String;
+
+    fn parse_frame(&self, text: &str) -> StreamFrame;
+}
+
+/// The adapter for `venue`
+pub fn exchange_for(venue: Venue) -> Arc<dyn Exchange> {
+    match venue {
+        Venue::BinanceSpot => Arc::new(binance_spot::BinanceSpot),
+        Venue::BinanceFutures => Arc::new(binance_futures::BinanceFutures),
+        Venue::Generic => Arc::new(generic::GenericExchange),
+    }
+}
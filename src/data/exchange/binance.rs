@@ -0,0 +1,453 @@
+//! What Binance spot and USDⓈ-M futures have in common: weight headers, error kinds,
+//! the `exchangeInfo` layout and the combined-stream protocol (same stream names and
+//! payloads on both, apart from the futures depth field names).
+//!
+//! SDK responses are re-read through `serde_json::Value` (Binance's own field names) so
+//! both venues share one parser and we only depend on the endpoints, not on the SDK's
+//! per-venue model types.
+
+use anyhow::{Result, anyhow};
+use binance_sdk::common::models::Interval as binance_interval;
+use binance_sdk::models::RestApiRateLimit;
+use binance_sdk::{errors, errors::ConnectorError as connection_error};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+
+#[cfg(debug_assertions)]
+use crate::config::DEBUG_FLAGS;
+use crate::data::exchange::{StreamEvent, StreamFrame};
+use crate::data::fetch_scheduler::{FetchScheduler, Throttled};
+use crate::data::price_stream::{ClosedKline, PriceQuote, StreamSelection};
+use crate::data::timeseries::bnapi_version::bn_kline::BNKlineError;
+use crate::domain::order_book::{BookLevel, BookTicker, DepthSnapshot};
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::symbol_registry::SymbolInfo;
+use crate::domain::venue::Venue;
+use crate::utils::TimeUtils;
+
+/// Tells the scheduler how much of the minute's weight Binance says we've used
+pub(crate) fn observe_rate_limits(venue: Venue, rate_limits: &Option<Vec<RestApiRateLimit>>) {
+    for rate_limit in rate_limits.iter().flatten() {
+        if rate_limit.interval_num == 1 && rate_limit.interval == binance_interval::Minute {
+            #[cfg(debug_assertions)]
+            if DEBUG_FLAGS.print_binance {
+                log::info!("{} min-weight: {}", venue, rate_limit.count);
+            }
+            FetchScheduler::shared().observe_used_weight(venue, rate_limit.count);
+        }
+    }
+}
+
+/// 429 / 5xx, which the scheduler retries, and 418, which stops the venue. Binance's
+/// `Retry-After` doesn't survive the SDK, so the scheduler falls back to the next weight
+/// window (or the shortest ban).
+fn throttled(conn_err: &errors::ConnectorError) -> Option<Throttled> {
+    let (status, message) = match conn_err {
+        connection_error::TooManyRequestsError(msg) => (429, msg.clone()),
+        connection_error::RateLimitBanError(msg) => (418, msg.clone()),
+        connection_error::ServerError { msg, status_code } => {
+            (status_code.unwrap_or(500), msg.clone())
+        }
+        _ => return None,
+    };
+    Some(Throttled {
+        status,
+        retry_after: None,
+        message,
+    })
+}
+
+/// An SDK error for `what` (e.g. "exchangeInfo request"), kept retryable if it is
+pub(crate) fn request_error(e: anyhow::Error, what: &str) -> anyhow::Error {
+    match e
+        .downcast_ref::<errors::ConnectorError>()
+        .and_then(throttled)
+    {
+        Some(throttled) => anyhow::Error::new(throttled).context(format!("{} failed", what)),
+        None => anyhow!("{} failed: {:?}", what, e),
+    }
+}
+
+/// Logs a failed klines call by kind and turns it into the error `fetch_klines` returns
+/// (`Throttled` for the kinds worth retrying)
+pub(crate) fn klines_call_error(e: anyhow::Error, pair_interval: &PairInterval) -> anyhow::Error {
+    if let Some(conn_err) = e.downcast_ref::<errors::ConnectorError>() {
+        match conn_err {
+            connection_error::ConnectorClientError(msg) => {
+                log::error!(
+                    "{} Client error: Check your request parameters. {}",
+                    pair_interval,
+                    msg
+                );
+            }
+            connection_error::TooManyRequestsError(msg) => {
+                log::warn!(
+                    "{} Rate limit exceeded. Please wait and try again. {}",
+                    pair_interval,
+                    msg
+                );
+            }
+            connection_error::RateLimitBanError(msg) => {
+                log::error!(
+                    "{} IP address banned due to excessive rate limits. {}",
+                    pair_interval,
+                    msg
+                );
+            }
+            errors::ConnectorError::ServerError { msg, status_code } => {
+                log::error!(
+                    "{} Server error: {} (status code: {:?})",
+                    pair_interval,
+                    msg,
+                    status_code
+                );
+            }
+            errors::ConnectorError::NetworkError(msg) => {
+                log::error!(
+                    "{} Network error: Check your internet connection. {}",
+                    pair_interval,
+                    msg
+                );
+            }
+            errors::ConnectorError::NotFoundError(msg) => {
+                log::error!("Resource not found. {}", msg);
+            }
+            connection_error::BadRequestError(msg) => {
+                log::warn!(
+                    "{} Bad request: Verify your input parameters. {}",
+                    pair_interval,
+                    msg
+                );
+            }
+            other => {
+                log::error!("Unexpected ConnectionError variant: {:?}", other);
+            }
+        }
+        let context = format!("Binance API call failed for {}", pair_interval);
+        match throttled(conn_err) {
+            Some(throttled) => anyhow::Error::new(throttled).context(context),
+            None => anyhow::Error::new(BNKlineError::ConnectionFailed(conn_err.to_string()))
+                .context(context),
+        }
+    } else {
+        log::error!(
+            "An unexpected error occurred for {}: {:#}",
+            pair_interval,
+            e
+        );
+        anyhow::Error::new(BNKlineError::ConnectionFailed(e.to_string())).context(format!(
+            "Unexpected error during API call for {}",
+            pair_interval
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSymbol {
+    symbol: String,
+    #[serde(default)]
+    status: String,
+    base_asset: String,
+    quote_asset: String,
+    #[serde(default)]
+    filters: Vec<Value>,
+}
+
+impl From<RawSymbol> for SymbolInfo {
+    fn from(raw: RawSymbol) -> Self {
+        let filter_value = |filter_type: &str, field: &str| {
+            raw.filters
+                .iter()
+                .find(|f| f.get("filterType").and_then(Value::as_str) == Some(filter_type))
+                .and_then(|f| f.get(field))
+                .and_then(Value::as_str)
+                .and_then(|v| v.parse::<f64>().ok())
+        };
+
+        SymbolInfo {
+            tick_size: filter_value("PRICE_FILTER", "tickSize"),
+            step_size: filter_value("LOT_SIZE", "stepSize"),
+            min_qty: filter_value("LOT_SIZE", "minQty"),
+            symbol: raw.symbol.to_uppercase(),
+            base_asset: raw.base_asset.to_uppercase(),
+            quote_asset: raw.quote_asset.to_uppercase(),
+            status: raw.status,
+        }
+    }
+}
+
+/// The `symbols` of an `exchangeInfo` response
+pub(crate) fn parse_exchange_info(data: &Value) -> Result<Vec<SymbolInfo>> {
+    let raw_symbols = data
+        .get("symbols")
+        .cloned()
+        .ok_or_else(|| anyhow!("exchangeInfo response has no symbols"))?;
+    let raw_symbols: Vec<RawSymbol> = serde_json::from_value(raw_symbols)?;
+    Ok(raw_symbols.into_iter().map(SymbolInfo::from).collect())
+}
+
+/// A `ticker/price` response for every symbol: `[{"symbol": .., "price": ".."}, ..]`
+pub(crate) fn parse_ticker_prices(data: &Value) -> Result<HashMap<String, f64>> {
+    let tickers = data
+        .as_array()
+        .ok_or_else(|| anyhow!("ticker/price response is not a list"))?;
+    Ok(tickers
+        .iter()
+        .filter_map(|t| {
+            let symbol = t.get("symbol")?.as_str()?.to_uppercase();
+            let price = t.get("price")?.as_str()?.parse::<f64>().ok()?;
+            (price > 0.0).then_some((symbol, price))
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct MiniTickerData {
+    /// Event time (ms); 0 if the payload left it out
+    #[serde(rename = "E", default)]
+    event_time_ms: i64,
+    #[serde(rename = "c")]
+    close_price: String,
+    #[serde(rename = "s")]
+    symbol: String,
+}
+
+/// Kline event payload; only closed candles (`x`) are kept
+#[derive(Debug, Deserialize)]
+struct KlineData {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "k")]
+    kline: KlinePayload,
+}
+
+#[derive(Debug, Deserialize)]
+struct KlinePayload {
+    #[serde(rename = "t")]
+    open_time_ms: i64,
+    #[serde(rename = "i")]
+    interval: String,
+    #[serde(rename = "o")]
+    open_price: String,
+    #[serde(rename = "h")]
+    high_price: String,
+    #[serde(rename = "l")]
+    low_price: String,
+    #[serde(rename = "c")]
+    close_price: String,
+    #[serde(rename = "v")]
+    base_volume: String,
+    #[serde(rename = "q")]
+    quote_volume: String,
+    #[serde(rename = "x")]
+    is_closed: bool,
+}
+
+/// `@bookTicker` payload (it has no event type field on spot)
+#[derive(Debug, Deserialize)]
+struct BookTickerData {
+    #[serde(rename = "b")]
+    bid_price: String,
+    #[serde(rename = "B")]
+    bid_qty: String,
+    #[serde(rename = "a")]
+    ask_price: String,
+    #[serde(rename = "A")]
+    ask_qty: String,
+}
+
+/// `@depth<N>` payload; the symbol is only in the stream name. Futures names the
+/// fields `u` / `b` / `a`.
+#[derive(Debug, Deserialize)]
+struct PartialDepthData {
+    #[serde(rename = "lastUpdateId", alias = "u")]
+    last_update_id: i64,
+    #[serde(alias = "b")]
+    bids: Vec<[String; 2]>,
+    #[serde(alias = "a")]
+    asks: Vec<[String; 2]>,
+}
+
+/// The combined stream carries every subscribed stream type, and only some payloads
+/// say what they are, so `stream` (e.g. `btcusdt@depth20`) decides how `data` is read
+#[derive(Debug, Deserialize)]
+struct CombinedStreamMessage {
+    stream: String,
+    data: Value,
+}
+
+/// Anything Binance sends on a combined stream: data, or the reply to one of our requests
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ServerFrame {
+    Data(CombinedStreamMessage),
+    Reply {
+        id: u64,
+        #[serde(default)]
+        error: Option<Value>,
+    },
+}
+
+impl BookTickerData {
+    fn into_book_ticker(self) -> Option<BookTicker> {
+        Some(BookTicker {
+            bid: BookLevel {
+                price: self.bid_price.parse().ok()?,
+                quantity: self.bid_qty.parse().ok()?,
+            },
+            ask: BookLevel {
+                price: self.ask_price.parse().ok()?,
+                quantity: self.ask_qty.parse().ok()?,
+            },
+        })
+    }
+}
+
+impl PartialDepthData {
+    fn into_depth_snapshot(self) -> Option<DepthSnapshot> {
+        let parse_side = |levels: Vec<[String; 2]>| {
+            levels
+                .into_iter()
+                .map(|[price, quantity]| {
+                    Some(BookLevel {
+                        price: price.parse().ok()?,
+                        quantity: quantity.parse().ok()?,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+        };
+        Some(DepthSnapshot {
+            last_update_id: self.last_update_id,
+            bids: parse_side(self.bids)?,
+            asks: parse_side(self.asks)?,
+        })
+    }
+}
+
+impl KlineData {
+    fn into_closed_kline(self) -> Option<ClosedKline> {
+        let k = self.kline;
+        if !k.is_closed {
+            return None;
+        }
+        Some(ClosedKline {
+            symbol: self.symbol.to_lowercase(),
+            interval_ms: TimeUtils::interval_from_string(&k.interval)?,
+            open_time_ms: k.open_time_ms,
+            open_price: k.open_price.parse().ok()?,
+            high_price: k.high_price.parse().ok()?,
+            low_price: k.low_price.parse().ok()?,
+            close_price: k.close_price.parse().ok()?,
+            base_volume: k.base_volume.parse().ok()?,
+            quote_volume: k.quote_volume.parse().ok()?,
+        })
+    }
+}
+
+/// Stream names carried for one (lowercase) symbol
+pub(crate) fn streams_for(symbol: &str, selection: &StreamSelection) -> Vec<String> {
+    let mut streams = vec![format!("{}@miniTicker", symbol)];
+    streams.extend(selection.kline_intervals.iter().map(|&interval_ms| {
+        format!(
+            "{}@kline_{}",
+            symbol,
+            TimeUtils::interval_to_string(interval_ms)
+        )
+    }));
+    if selection.book_ticker {
+        streams.push(format!("{}@bookTicker", symbol));
+    }
+    if selection.depth_levels > 0 {
+        streams.push(format!("{}@depth{}", symbol, selection.depth_levels));
+    }
+    streams
+}
+
+/// `prefix` is everything before the stream list (`wss://host/stream?streams=`)
+pub(crate) fn combined_stream_url(prefix: &str, streams: &BTreeSet<String>) -> String {
+    let stream_descriptor = streams.iter().cloned().collect::<Vec<_>>().join("/");
+    format!("{}{}", prefix, stream_descriptor)
+}
+
+/// Binance's SUBSCRIBE / UNSUBSCRIBE methods
+pub(crate) fn stream_request(subscribe: bool, streams: &[String], id: u64) -> String {
+    let method = if subscribe {
+        "SUBSCRIBE"
+    } else {
+        "UNSUBSCRIBE"
+    };
+    json!({ "method": method, "params": streams, "id": id }).to_string()
+}
+
+pub(crate) fn parse_frame(text: &str) -> StreamFrame {
+    match serde_json::from_str::<ServerFrame>(text) {
+        Ok(ServerFrame::Data(message)) => parse_stream_message(message),
+        Ok(ServerFrame::Reply { id, error }) => StreamFrame::Reply {
+            id,
+            error: error.map(|e| e.to_string()),
+        },
+        Err(_) => StreamFrame::Unknown,
+    }
+}
+
+fn parse_stream_message(message: CombinedStreamMessage) -> StreamFrame {
+    let Some((stream_symbol, stream_type)) = message.stream.split_once('@') else {
+        return StreamFrame::Unknown;
+    };
+    let symbol = stream_symbol.to_string();
+
+    let event = if stream_type.starts_with("kline_") {
+        serde_json::from_value::<KlineData>(message.data)
+            .ok()
+            .and_then(KlineData::into_closed_kline)
+            .map(StreamEvent::ClosedKline)
+    } else if stream_type == "bookTicker" {
+        let book = serde_json::from_value::<BookTickerData>(message.data)
+            .ok()
+            .and_then(BookTickerData::into_book_ticker);
+        if book.is_none() {
+            log::error!("⚠️ Failed to parse bookTicker for {}", stream_symbol);
+        }
+        book.map(StreamEvent::Book)
+    } else if stream_type.starts_with("depth") {
+        let depth = serde_json::from_value::<PartialDepthData>(message.data)
+            .ok()
+            .and_then(PartialDepthData::into_depth_snapshot);
+        if depth.is_none() {
+            log::error!("⚠️ Failed to parse depth snapshot for {}", stream_symbol);
+        }
+        depth.map(StreamEvent::Depth)
+    } else if stream_type == "miniTicker" {
+        match serde_json::from_value::<MiniTickerData>(message.data) {
+            Ok(ticker) => match ticker.close_price.parse::<f64>() {
+                Ok(price) => Some(StreamEvent::Price(PriceQuote::received_now(
+                    price,
+                    ticker.event_time_ms,
+                ))),
+                Err(parse_err) => {
+                    log::error!(
+                        "⚠️ Failed to parse miniTicker price '{}' for {}: {}",
+                        ticker.close_price,
+                        ticker.symbol,
+                        parse_err
+                    );
+                    None
+                }
+            },
+            Err(_) => {
+                log::error!("⚠️ Failed to parse miniTicker for {}", stream_symbol);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    StreamFrame::Data {
+        stream: message.stream.clone(),
+        symbol,
+        event,
+    }
+}
@@ -0,0 +1,162 @@
+//! Binance USDⓈ-M futures through the SDK. The REST and stream payloads match spot's,
+//! so everything after the request itself is shared with `binance_spot` via `binance`.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use binance_sdk::config::ConfigurationRestApi;
+use binance_sdk::derivatives_trading_usds_futures::{
+    DerivativesTradingUsdsFuturesRestApi,
+    rest_api::{
+        KlineCandlestickDataIntervalEnum, KlineCandlestickDataParams, RestApi,
+        SymbolPriceTickerV2Params,
+    },
+};
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+use super::binance::{self, klines_call_error, observe_rate_limits, request_error};
+use super::{Exchange, StreamFrame};
+use crate::config::{BINANCE, BinanceApiConfig, EXCHANGES};
+use crate::data::fetch_scheduler::FetchScheduler;
+use crate::data::price_stream::StreamSelection;
+use crate::data::timeseries::bnapi_version::bn_kline::{
+    AllValidKlines4Pair, BNKline, convert_klines, load_klines_paged,
+};
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::symbol_registry::SymbolInfo;
+use crate::domain::venue::Venue;
+use crate::utils::TimeUtils;
+
+/// The futures REST client every request shares, built on first use
+fn futures_client() -> Result<&'static RestApi> {
+    static CLIENT: OnceLock<RestApi> = OnceLock::new();
+    if let Some(rest_client) = CLIENT.get() {
+        return Ok(rest_client);
+    }
+    let config = BinanceApiConfig::default();
+    let rest_conf = ConfigurationRestApi::builder()
+        .timeout(config.timeout_ms)
+        .retries(config.retries)
+        .backoff(config.backoff_ms)
+        .base_path(EXCHANGES.binance_futures.rest_base_url())
+        .build()?;
+    Ok(CLIENT.get_or_init(|| DerivativesTradingUsdsFuturesRestApi::from_config(rest_conf)))
+}
+
+/// Futures has no 1s candles; every other Binance interval is named as on spot
+fn interval_from_ms(ms: i64) -> Result<KlineCandlestickDataIntervalEnum> {
+    serde_json::from_value(json!(TimeUtils::interval_to_string(ms)))
+        .map_err(|_| anyhow!("Unsupported futures interval: {}ms", ms))
+}
+
+pub struct BinanceFutures;
+
+impl BinanceFutures {
+    async fn fetch_klines_page(
+        rest_client: &RestApi,
+        pair_interval: &PairInterval,
+        end_time: Option<i64>,
+    ) -> Result<Vec<BNKline>> {
+        let response = FetchScheduler::shared()
+            .run(
+                Venue::BinanceFutures,
+                EXCHANGES.binance_futures.kline_call_weight,
+                || async move {
+                    let params = KlineCandlestickDataParams::builder(
+                        pair_interval.venue_symbol().to_string(),
+                        interval_from_ms(pair_interval.interval_ms)?,
+                    )
+                    .limit(Some(BINANCE.limits.klines_limit as i64))
+                    .end_time(end_time)
+                    .build()?;
+                    rest_client
+                        .kline_candlestick_data(params)
+                        .await
+                        .map_err(|e| klines_call_error(e, pair_interval))
+                },
+            )
+            .await?;
+        observe_rate_limits(Venue::BinanceFutures, &response.rate_limits);
+        let data = serde_json::to_value(response.data().await?)?;
+
+        let rows: Vec<Vec<Value>> = serde_json::from_value(data)?;
+        convert_klines(&rows).map_err(|e| {
+            anyhow::Error::new(e).context(format!("{} convert_klines failed", pair_interval))
+        })
+    }
+}
+
+#[async_trait]
+impl Exchange for BinanceFutures {
+    fn venue(&self) -> Venue {
+        Venue::BinanceFutures
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolInfo>> {
+        let rest_client = futures_client()?;
+        let response = FetchScheduler::shared()
+            .run(
+                Venue::BinanceFutures,
+                EXCHANGES.binance_futures.symbols_call_weight,
+                || async {
+                    rest_client
+                        .exchange_information()
+                        .await
+                        .map_err(|e| request_error(e, "futures exchangeInfo request"))
+                },
+            )
+            .await?;
+        observe_rate_limits(Venue::BinanceFutures, &response.rate_limits);
+        binance::parse_exchange_info(&serde_json::to_value(response.data().await?)?)
+    }
+
+    async fn fetch_klines(&self, pair_interval: PairInterval) -> Result<AllValidKlines4Pair> {
+        let rest_client = futures_client()?;
+        load_klines_paged(
+            pair_interval.clone(),
+            BINANCE.limits.klines_limit,
+            |end_time| Self::fetch_klines_page(rest_client, &pair_interval, end_time),
+        )
+        .await
+    }
+
+    async fn fetch_prices(&self) -> Result<HashMap<String, f64>> {
+        let rest_client = futures_client()?;
+        let response = FetchScheduler::shared()
+            .run(
+                Venue::BinanceFutures,
+                EXCHANGES.binance_futures.prices_call_weight,
+                || async {
+                    rest_client
+                        .symbol_price_ticker_v2(SymbolPriceTickerV2Params::default())
+                        .await
+                        .map_err(|e| request_error(e, "futures ticker/price request"))
+                },
+            )
+            .await?;
+        observe_rate_limits(Venue::BinanceFutures, &response.rate_limits);
+        binance::parse_ticker_prices(&serde_json::to_value(response.data().await?)?)
+    }
+
+    fn streams_for(&self, symbol: &str, selection: &StreamSelection) -> Vec<String> {
+        binance::streams_for(symbol, selection)
+    }
+
+    fn stream_url(&self, streams: &BTreeSet<String>) -> String {
+        let prefix = format!(
+            "{}{}",
+            EXCHANGES.binance_futures.ws_base_url(),
+            BINANCE.ws.combined_path
+        );
+        binance::combined_stream_url(&prefix, streams)
+    }
+
+    fn stream_request(&self, subscribe: bool, streams: &[String], id: u64) -> String {
+        binance::stream_request(subscribe, streams, id)
+    }
+
+    fn parse_frame(&self, text: &str) -> StreamFrame {
+        binance::parse_frame(text)
+    }
+}
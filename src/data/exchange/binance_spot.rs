@@ -0,0 +1,255 @@
+//! Binance spot through the SDK: klines, `exchangeInfo`, `ticker/price` and the
+//! combined price streams.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use binance_sdk::config::ConfigurationRestApi;
+use binance_sdk::spot::{
+    SpotRestApi,
+    rest_api::{
+        ExchangeInfoParams, KlinesIntervalEnum, KlinesParams, RestApi, Ticker24hrParams,
+        TickerPriceParams,
+    },
+};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+use super::binance::{self, klines_call_error, observe_rate_limits, request_error};
+use super::{Exchange, StreamFrame};
+use crate::config::{BINANCE, BinanceApiConfig};
+use crate::data::fetch_scheduler::FetchScheduler;
+use crate::data::price_stream::StreamSelection;
+use crate::data::timeseries::bnapi_version::bn_kline::{
+    AllValidKlines4Pair, BNKline, convert_klines, load_klines_paged,
+};
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::symbol_registry::{SymbolInfo, SymbolRegistry};
+use crate::domain::venue::Venue;
+use crate::utils::TimeUtils;
+
+pub trait IntervalToMs {
+    fn to_ms(&self) -> i64;
+}
+
+// 2. Implement it for the external type
+impl IntervalToMs for KlinesIntervalEnum {
+    fn to_ms(&self) -> i64 {
+        match self {
+            KlinesIntervalEnum::Interval1s => TimeUtils::MS_IN_S,
+            KlinesIntervalEnum::Interval1m => TimeUtils::MS_IN_MIN,
+            KlinesIntervalEnum::Interval3m => TimeUtils::MS_IN_3_MIN,
+            KlinesIntervalEnum::Interval5m => TimeUtils::MS_IN_5_MIN,
+            KlinesIntervalEnum::Interval15m => TimeUtils::MS_IN_15_MIN,
+            KlinesIntervalEnum::Interval30m => TimeUtils::MS_IN_30_MIN,
+            KlinesIntervalEnum::Interval1h => TimeUtils::MS_IN_H,
+            KlinesIntervalEnum::Interval2h => TimeUtils::MS_IN_2_H,
+            KlinesIntervalEnum::Interval4h => TimeUtils::MS_IN_4_H,
+            KlinesIntervalEnum::Interval6h => TimeUtils::MS_IN_6_H,
+            KlinesIntervalEnum::Interval8h => TimeUtils::MS_IN_8_H,
+            KlinesIntervalEnum::Interval12h => TimeUtils::MS_IN_12_H,
+            KlinesIntervalEnum::Interval1d => TimeUtils::MS_IN_D,
+            KlinesIntervalEnum::Interval3d => TimeUtils::MS_IN_3_D,
+            KlinesIntervalEnum::Interval1w => TimeUtils::MS_IN_W,
+            KlinesIntervalEnum::Interval1M => TimeUtils::MS_IN_1_M,
+        }
+    }
+}
+
+// 3. For "MS -> Enum", a static helper is still best,
+//    but we return Result instead of panicking.
+pub fn try_interval_from_ms(ms: i64) -> Result<KlinesIntervalEnum, String> {
+    match ms {
+        TimeUtils::MS_IN_S => Ok(KlinesIntervalEnum::Interval1s),
+        TimeUtils::MS_IN_MIN => Ok(KlinesIntervalEnum::Interval1m),
+        TimeUtils::MS_IN_3_MIN => Ok(KlinesIntervalEnum::Interval3m),
+        TimeUtils::MS_IN_5_MIN => Ok(KlinesIntervalEnum::Interval5m),
+        TimeUtils::MS_IN_15_MIN => Ok(KlinesIntervalEnum::Interval15m),
+        TimeUtils::MS_IN_30_MIN => Ok(KlinesIntervalEnum::Interval30m),
+        TimeUtils::MS_IN_H => Ok(KlinesIntervalEnum::Interval1h),
+        TimeUtils::MS_IN_2_H => Ok(KlinesIntervalEnum::Interval2h),
+        TimeUtils::MS_IN_4_H => Ok(KlinesIntervalEnum::Interval4h),
+        TimeUtils::MS_IN_6_H => Ok(KlinesIntervalEnum::Interval6h),
+        TimeUtils::MS_IN_8_H => Ok(KlinesIntervalEnum::Interval8h),
+        TimeUtils::MS_IN_12_H => Ok(KlinesIntervalEnum::Interval12h),
+        TimeUtils::MS_IN_D => Ok(KlinesIntervalEnum::Interval1d),
+        TimeUtils::MS_IN_3_D => Ok(KlinesIntervalEnum::Interval3d),
+        TimeUtils::MS_IN_W => Ok(KlinesIntervalEnum::Interval1w),
+        TimeUtils::MS_IN_1_M => Ok(KlinesIntervalEnum::Interval1M),
+        _ => Err(format!("Unsupported interval: {}ms", ms)),
+    }
+}
+
+/// The spot REST client every request shares, built on first use
+pub(crate) fn spot_client() -> Result<&'static RestApi> {
+    static CLIENT: OnceLock<RestApi> = OnceLock::new();
+    if let Some(rest_client) = CLIENT.get() {
+        return Ok(rest_client);
+    }
+    let config = BinanceApiConfig::default();
+    let rest_conf = ConfigurationRestApi::builder()
+        .timeout(config.timeout_ms)
+        .retries(config.retries)
+        .backoff(config.backoff_ms)
+        .base_path(BINANCE.rest_base_url())
+        .build()?;
+    // Create the Spot REST API client (production unless redirected via BINANCE.overrides)
+    Ok(CLIENT.get_or_init(|| SpotRestApi::from_config(rest_conf)))
+}
+
+pub struct BinanceSpot;
+
+impl BinanceSpot {
+    /// One page of klines, sent when the scheduler's weight budget allows
+    async fn fetch_klines_page(
+        rest_client: &RestApi,
+        pair_interval: &PairInterval,
+        end_time: Option<i64>,
+    ) -> Result<Vec<BNKline>> {
+        let response = FetchScheduler::shared()
+            .run(
+                Venue::BinanceSpot,
+                BINANCE.limits.kline_call_weight,
+                || async move {
+                    let params = KlinesParams::builder(
+                        pair_interval.venue_symbol().to_string(),
+                        // We use .expect() here to replicate the old behavior
+                        // (crashing if the interval is invalid), but now it's explicit.
+                        try_interval_from_ms(pair_interval.interval_ms)
+                            .expect("Invalid Binance interval configuration"),
+                    )
+                    .limit(BINANCE.limits.klines_limit) // If not passed in, 500 is used as `limit`
+                    .end_time(end_time)
+                    .build()?;
+                    rest_client
+                        .klines(params)
+                        .await
+                        .map_err(|e| klines_call_error(e, pair_interval))
+                },
+            )
+            .await?;
+        observe_rate_limits(Venue::BinanceSpot, &response.rate_limits);
+        let data = serde_json::to_value(response.data().await?)?;
+
+        let rows: Vec<Vec<Value>> = serde_json::from_value(data)?;
+        convert_klines(&rows).map_err(|e| {
+            anyhow::Error::new(e).context(format!("{} convert_klines failed", pair_interval))
+        })
+    }
+
+    /// Symbols quoted in `quote_asset`, ranked by 24h quote volume (highest first).
+    pub async fn rank_by_24h_volume(
+        registry: &SymbolRegistry,
+        quote_asset: &str,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let rest_client = spot_client()?;
+        let response = FetchScheduler::shared()
+            .run(
+                Venue::BinanceSpot,
+                BINANCE.limits.ticker_24hr_call_weight,
+                || async {
+                    rest_client
+                        .ticker24hr(Ticker24hrParams::default())
+                        .await
+                        .map_err(|e| request_error(e, "24hr ticker request"))
+                },
+            )
+            .await?;
+        observe_rate_limits(Venue::BinanceSpot, &response.rate_limits);
+        let data = serde_json::to_value(response.data().await?)?;
+
+        let mut ranked: Vec<(String, f64)> = data
+            .as_array()
+            .ok_or_else(|| anyhow!("24hr ticker response is not a list"))?
+            .iter()
+            .filter_map(|t| {
+                let symbol = t.get("symbol")?.as_str()?.to_uppercase();
+                let volume = t.get("quoteVolume")?.as_str()?.parse::<f64>().ok()?;
+                Some((symbol, volume))
+            })
+            .filter(|(symbol, _)| {
+                registry
+                    .get(symbol)
+                    .is_some_and(|info| info.is_trading() && info.quote_asset == quote_asset)
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(ranked.into_iter().take(limit).map(|(s, _)| s).collect())
+    }
+}
+
+#[async_trait]
+impl Exchange for BinanceSpot {
+    fn venue(&self) -> Venue {
+        Venue::BinanceSpot
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolInfo>> {
+        let rest_client = spot_client()?;
+        let response = FetchScheduler::shared()
+            .run(
+                Venue::BinanceSpot,
+                BINANCE.limits.exchange_info_call_weight,
+                || async {
+                    rest_client
+                        .exchange_info(ExchangeInfoParams::default())
+                        .await
+                        .map_err(|e| request_error(e, "exchangeInfo request"))
+                },
+            )
+            .await?;
+        observe_rate_limits(Venue::BinanceSpot, &response.rate_limits);
+        binance::parse_exchange_info(&serde_json::to_value(response.data().await?)?)
+    }
+
+    async fn fetch_klines(&self, pair_interval: PairInterval) -> Result<AllValidKlines4Pair> {
+        let rest_client = spot_client()?;
+        load_klines_paged(
+            pair_interval.clone(),
+            BINANCE.limits.klines_limit,
+            |end_time| Self::fetch_klines_page(rest_client, &pair_interval, end_time),
+        )
+        .await
+    }
+
+    async fn fetch_prices(&self) -> Result<HashMap<String, f64>> {
+        let rest_client = spot_client()?;
+        let response = FetchScheduler::shared()
+            .run(
+                Venue::BinanceSpot,
+                BINANCE.limits.ticker_price_call_weight,
+                || async {
+                    let params = TickerPriceParams {
+                        symbol: None,
+                        symbols: None,
+                        symbol_status: None,
+                    };
+                    rest_client
+                        .ticker_price(params)
+                        .await
+                        .map_err(|e| request_error(e, "ticker/price request"))
+                },
+            )
+            .await?;
+        observe_rate_limits(Venue::BinanceSpot, &response.rate_limits);
+        binance::parse_ticker_prices(&serde_json::to_value(response.data().await?)?)
+    }
+
+    fn streams_for(&self, symbol: &str, selection: &StreamSelection) -> Vec<String> {
+        binance::streams_for(symbol, selection)
+    }
+
+    fn stream_url(&self, streams: &BTreeSet<String>) -> String {
+        binance::combined_stream_url(&BINANCE.ws_combined_url_prefix(), streams)
+    }
+
+    fn stream_request(&self, subscribe: bool, streams: &[String], id: u64) -> String {
+        binance::stream_request(subscribe, streams, id)
+    }
+
+    fn parse_frame(&self, text: &str) -> StreamFrame {
+        binance::parse_frame(text)
+    }
+}
@@ -0,0 +1,352 @@
+//! A deliberately small REST/WS protocol, so a venue without an adapter here can be
+//! added with a thin gateway in front of it. `data::mock_binance` serves it for testing.
+//!
+//! REST (JSON, `GET`, base URL from `EXCHANGES.generic`):
+//!
+//! ```text
+//! /v1/symbols   [{"symbol": "BTCUSD", "base": "BTC", "quote": "USD",
+//!                 "status": "TRADING", "tick_size": 0.01, "step_size": 0.0001, "min_qty": 0.0001}]
+//!               (status defaults to TRADING; the three rules are optional)
+//! /v1/candles?symbol=BTCUSD&interval=30m&limit=1000[&end_time=<ms>]
+//!               [{"t": <open ms>, "o": .., "h": .., "l": .., "c": .., "v": .., "q": ..}]
+//!               oldest first: the `limit` newest candles opening at or before end_time
+//!               (`q`, quote volume, is optional and estimated as v * c when missing)
+//! /v1/prices    [{"symbol": "BTCUSD", "price": 101.5}]
+//! ```
+//!
+//! A 429 or 5xx is retried (after `Retry-After` seconds, if given).
+//!
+//! WebSocket: connect to `{ws}/v1/stream?streams=btcusd@ticker/btcusd@book/btcusd@candle_30m`.
+//!
+//! ```text
+//! server  {"stream": "btcusd@ticker", "time": <ms>, "price": 101.5}
+//!         {"stream": "btcusd@book", "bid": [price, qty], "ask": [price, qty]}
+//!         {"stream": "btcusd@candle_30m", "closed": true, "candle": {"t": .., "o": .., ..}}
+//!         {"id": 1, "error": null}                      (reply to a request)
+//! client  {"op": "subscribe" | "unsubscribe", "id": 1, "streams": ["btcusd@ticker"]}
+//! ```
+//!
+//! There is no depth stream; `StreamSelection::depth_levels` is ignored.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::{Exchange, StreamEvent, StreamFrame};
+use crate::config::{BINANCE, EXCHANGES};
+use crate::data::fetch_scheduler::{FetchScheduler, Throttled};
+use crate::data::price_stream::{ClosedKline, PriceQuote, StreamSelection};
+use crate::data::timeseries::bnapi_version::bn_kline::{
+    AllValidKlines4Pair, BNKline, load_klines_paged,
+};
+use crate::domain::order_book::{BookLevel, BookTicker};
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::symbol_registry::SymbolInfo;
+use crate::domain::venue::Venue;
+use crate::utils::TimeUtils;
+
+#[derive(Deserialize)]
+struct GenericSymbol {
+    symbol: String,
+    base: String,
+    quote: String,
+    #[serde(default = "trading")]
+    status: String,
+    tick_size: Option<f64>,
+    step_size: Option<f64>,
+    min_qty: Option<f64>,
+}
+
+fn trading() -> String {
+    "TRADING".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct GenericCandle {
+    t: i64,
+    o: f64,
+    h: f64,
+    l: f64,
+    c: f64,
+    v: f64,
+    q: Option<f64>,
+}
+
+impl GenericCandle {
+    fn quote_volume(&self) -> f64 {
+        self.q.unwrap_or(self.v * self.c)
+    }
+}
+
+impl From<GenericCandle> for BNKline {
+    fn from(candle: GenericCandle) -> Self {
+        BNKline {
+            open_timestamp_ms: candle.t,
+            open_price: Some(candle.o),
+            high_price: Some(candle.h),
+            low_price: Some(candle.l),
+            close_price: Some(candle.c),
+            base_asset_volume: Some(candle.v),
+            quote_asset_volume: Some(candle.quote_volume()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GenericPrice {
+    symbol: String,
+    price: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GenericFrame {
+    Data {
+        stream: String,
+        #[serde(default)]
+        time: i64,
+        price: Option<f64>,
+        bid: Option<[f64; 2]>,
+        ask: Option<[f64; 2]>,
+        candle: Option<GenericCandle>,
+        #[serde(default)]
+        closed: bool,
+    },
+    Reply {
+        id: u64,
+        #[serde(default)]
+        error: Option<Value>,
+    },
+}
+
+pub struct GenericExchange;
+
+impl GenericExchange {
+    /// The HTTP client every request shares, built on first use
+    fn client() -> Result<&'static reqwest::Client> {
+        static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+        if let Some(client) = CLIENT.get() {
+            return Ok(client);
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(BINANCE.client.timeout_ms))
+            .build()?;
+        Ok(CLIENT.get_or_init(|| client))
+    }
+
+    /// `GET path?query` through the scheduler. 429 / 418 / 5xx come back as `Throttled`,
+    /// with the venue's `Retry-After` (in seconds) if it sent one.
+    async fn get<T: serde::de::DeserializeOwned>(
+        path: &str,
+        query: &[(&str, String)],
+        weight: u32,
+    ) -> Result<T> {
+        let client = Self::client()?;
+        let url = format!("{}{}", EXCHANGES.generic.rest_base_url(), path);
+        let response = FetchScheduler::shared()
+            .run(Venue::Generic, weight, || async {
+                let response = client
+                    .get(&url)
+                    .query(query)
+                    .send()
+                    .await
+                    .with_context(|| format!("Request to {} failed", url))?;
+                let status = response.status();
+                if matches!(status.as_u16(), 418 | 429) || status.is_server_error() {
+                    let retry_after = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok()?.trim().parse::<u64>().ok())
+                        .map(Duration::from_secs);
+                    let throttled = Throttled {
+                        status: status.as_u16(),
+                        retry_after,
+                        message: response.text().await.unwrap_or_default(),
+                    };
+                    return Err(
+                        anyhow::Error::new(throttled).context(format!("Request to {} failed", url))
+                    );
+                }
+                response
+                    .error_for_status()
+                    .with_context(|| format!("Request to {} failed", url))
+            })
+            .await?;
+        response
+            .json::<T>()
+            .await
+            .with_context(|| format!("Unexpected response from {}", url))
+    }
+
+    async fn fetch_klines_page(
+        pair_interval: &PairInterval,
+        end_time: Option<i64>,
+    ) -> Result<Vec<BNKline>> {
+        let mut query = vec![
+            ("symbol", pair_interval.venue_symbol().to_string()),
+            (
+                "interval",
+                TimeUtils::interval_to_string(pair_interval.interval_ms).to_string(),
+            ),
+            ("limit", BINANCE.limits.klines_limit.to_string()),
+        ];
+        if let Some(end_time) = end_time {
+            query.push(("end_time", end_time.to_string()));
+        }
+        let candles: Vec<GenericCandle> =
+            Self::get("/v1/candles", &query, EXCHANGES.generic.kline_call_weight)
+                .await
+                .with_context(|| format!("Candles request failed for {}", pair_interval))?;
+        Ok(candles.into_iter().map(BNKline::from).collect())
+    }
+}
+
+#[async_trait]
+impl Exchange for GenericExchange {
+    fn venue(&self) -> Venue {
+        Venue::Generic
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolInfo>> {
+        let symbols: Vec<GenericSymbol> =
+            Self::get("/v1/symbols", &[], EXCHANGES.generic.symbols_call_weight).await?;
+        Ok(symbols
+            .into_iter()
+            .map(|s| SymbolInfo {
+                symbol: s.symbol.to_uppercase(),
+                base_asset: s.base.to_uppercase(),
+                quote_asset: s.quote.to_uppercase(),
+                status: s.status,
+                tick_size: s.tick_size,
+                step_size: s.step_size,
+                min_qty: s.min_qty,
+            })
+            .collect())
+    }
+
+    async fn fetch_klines(&self, pair_interval: PairInterval) -> Result<AllValidKlines4Pair> {
+        load_klines_paged(
+            pair_interval.clone(),
+            BINANCE.limits.klines_limit,
+            |end_time| Self::fetch_klines_page(&pair_interval, end_time),
+        )
+        .await
+    }
+
+    async fn fetch_prices(&self) -> Result<HashMap<String, f64>> {
+        let prices: Vec<GenericPrice> =
+            Self::get("/v1/prices", &[], EXCHANGES.generic.prices_call_weight).await?;
+        Ok(prices
+            .into_iter()
+            .filter(|p| p.price > 0.0)
+            .map(|p| (p.symbol.to_uppercase(), p.price))
+            .collect())
+    }
+
+    fn streams_for(&self, symbol: &str, selection: &StreamSelection) -> Vec<String> {
+        let mut streams = vec![format!("{}@ticker", symbol)];
+        streams.extend(selection.kline_intervals.iter().map(|&interval_ms| {
+            format!(
+                "{}@candle_{}",
+                symbol,
+                TimeUtils::interval_to_string(interval_ms)
+            )
+        }));
+        if selection.book_ticker {
+            streams.push(format!("{}@book", symbol));
+        }
+        streams
+    }
+
+    fn stream_url(&self, streams: &BTreeSet<String>) -> String {
+        let stream_list = streams.iter().cloned().collect::<Vec<_>>().join("/");
+        format!(
+            "{}/v1/stream?streams={}",
+            EXCHANGES.generic.ws_base_url(),
+            stream_list
+        )
+    }
+
+    fn stream_request(&self, subscribe: bool, streams: &[String], id: u64) -> String {
+        let op = if subscribe {
+            "subscribe"
+        } else {
+            "unsubscribe"
+        };
+        json!({ "op": op, "id": id, "streams": streams }).to_string()
+    }
+
+    fn parse_frame(&self, text: &str) -> StreamFrame {
+        let frame = match serde_json::from_str::<GenericFrame>(text) {
+            Ok(frame) => frame,
+            Err(_) => return StreamFrame::Unknown,
+        };
+        let (stream, time, price, bid, ask, candle, closed) = match frame {
+            GenericFrame::Reply { id, error } => {
+                return StreamFrame::Reply {
+                    id,
+                    error: error.filter(|e| !e.is_null()).map(|e| e.to_string()),
+                };
+            }
+            GenericFrame::Data {
+                stream,
+                time,
+                price,
+                bid,
+                ask,
+                candle,
+                closed,
+            } => (stream, time, price, bid, ask, candle, closed),
+        };
+        let Some((symbol, kind)) = stream.split_once('@') else {
+            return StreamFrame::Unknown;
+        };
+        let symbol = symbol.to_lowercase();
+
+        let event = if kind == "ticker" {
+            price.map(|price| StreamEvent::Price(PriceQuote::received_now(price, time)))
+        } else if kind == "book" {
+            bid.zip(ask).map(|([bid, bid_qty], [ask, ask_qty])| {
+                StreamEvent::Book(BookTicker {
+                    bid: BookLevel {
+                        price: bid,
+                        quantity: bid_qty,
+                    },
+                    ask: BookLevel {
+                        price: ask,
+                        quantity: ask_qty,
+                    },
+                })
+            })
+        } else if let Some(interval) = kind.strip_prefix("candle_") {
+            candle
+                .filter(|_| closed)
+                .zip(TimeUtils::interval_from_string(interval))
+                .map(|(candle, interval_ms)| {
+                    StreamEvent::ClosedKline(ClosedKline {
+                        symbol: symbol.clone(),
+                        interval_ms,
+                        open_time_ms: candle.t,
+                        open_price: candle.o,
+                        high_price: candle.h,
+                        low_price: candle.l,
+                        close_price: candle.c,
+                        base_volume: candle.v,
+                        quote_volume: candle.quote_volume(),
+                    })
+                })
+        } else {
+            None
+        };
+
+        StreamFrame::Data {
+            stream: stream.clone(),
+            symbol,
+            event,
+        }
+    }
+}
@@ -0,0 +1,562 @@
+//! The one place REST requests wait for their turn, shared by every load.
+//!
+//! Each venue has a token bucket holding its per-minute weight budget
+//! (`BINANCE.limits.weight_limit_minute`, `EXCHANGES.*.weight_limit_minute`), refilled
+//! continuously. A request takes its weight before it is sent, so concurrent loads share
+//! the budget instead of each finding the limit after the fact; the venue's own count
+//! (`X-MBX-USED-WEIGHT-1M`) is folded back in with `observe_used_weight`.
+//!
+//! Requests turned away for now (`Throttled`: 429 and 5xx) are retried with jittered
+//! exponential backoff. A `Retry-After` pauses the whole venue, not just the request that
+//! got it. The Binance SDK drops that header from error responses, so a Binance 429
+//! waits for the next weight window instead, which is what the header would say.
+//!
+//! A 418 means the venue has banned our IP, and every request sent during the ban makes
+//! it longer. It isn't retried: the venue fails every request, without sending it, until
+//! the ban's `Retry-After` (or `BINANCE.client.ip_ban_min_secs`) is over.
+//!
+//! Kline loads also report how far they've got (`FetchProgress`).
+
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::Semaphore;
+use tokio::time::{Duration, Instant, sleep};
+
+use crate::config::{BINANCE, EXCHANGES};
+use crate::data::pair_loading::FetchProgress;
+use crate::domain::venue::Venue;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
+
+/// A request the venue turned away for now; worth sending again later
+#[derive(Debug, Clone, PartialEq)]
+pub struct Throttled {
+    pub status: u16,
+    /// How long the venue asked us to wait, if it said
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+impl Throttled {
+    /// 429 (too many requests): the budget is spent
+    fn is_rate_limit(&self) -> bool {
+        self.status == 429
+    }
+
+    /// 418: the venue has banned the IP for sending on after 429s
+    fn is_ip_ban(&self) -> bool {
+        self.status == 418
+    }
+}
+
+impl fmt::Display for Throttled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP {}: {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for Throttled {}
+
+/// One venue's weight budget
+struct WeightBucket {
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Instant,
+    /// Nothing is sent before this (set by `Retry-After`)
+    paused_until: Option<Instant>,
+    /// Requests fail unsent before this (set by a 418)
+    banned_until: Option<Instant>,
+}
+
+impl WeightBucket {
+    fn new(weight_limit_minute: u32, now: Instant) -> Self {
+        Self {
+            capacity: weight_limit_minute as f64,
+            tokens: weight_limit_minute as f64,
+            refilled_at: now,
+            paused_until: None,
+            banned_until: None,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.refilled_at = now;
+    }
+
+    /// Takes `weight` if it's there, otherwise says how long until it will be
+    fn try_take(&mut self, weight: u32, now: Instant) -> Result<(), Duration> {
+        if let Some(until) = self.paused_until {
+            if until > now {
+                return Err(until - now);
+            }
+            self.paused_until = None;
+        }
+        self.refill(now);
+        // A request dearer than the whole budget still goes, once the bucket is full
+        let weight = (weight as f64).min(self.capacity);
+        if self.tokens >= weight {
+            self.tokens -= weight;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (weight - self.tokens) * 60.0 / self.capacity,
+            ))
+        }
+    }
+
+    /// The venue counts more used than we do (other clients on the IP, or its window
+    /// started earlier): trust it
+    fn observe_used(&mut self, used: u32, now: Instant) {
+        self.refill(now);
+        self.tokens = self.tokens.min((self.capacity - used as f64).max(0.0));
+    }
+
+    /// Sends nothing until `until`, and restarts from an empty budget after that
+    fn pause(&mut self, until: Instant) {
+        let until = self.paused_until.map_or(until, |paused| paused.max(until));
+        self.paused_until = Some(until);
+        self.tokens = 0.0;
+        self.refilled_at = until;
+    }
+
+    /// Fails everything until `until`, and restarts from an empty budget after that
+    fn ban(&mut self, until: Instant) {
+        let until = self.banned_until.map_or(until, |banned| banned.max(until));
+        self.banned_until = Some(until);
+        self.pause(until);
+    }
+
+    /// What is left of the ban, if there is one
+    fn ban_left(&self, now: Instant) -> Option<Duration> {
+        self.banned_until
+            .filter(|&until| until > now)
+            .map(|until| until - now)
+    }
+}
+
+fn weight_limit_minute(venue: Venue) -> u32 {
+    match venue {
+        Venue::BinanceSpot => BINANCE.limits.weight_limit_minute,
+        Venue::BinanceFutures => EXCHANGES.binance_futures.weight_limit_minute,
+        Venue::Generic => EXCHANGES.generic.weight_limit_minute,
+    }
+}
+
+/// Until the venue's next one-minute weight window
+fn until_next_minute() -> Duration {
+    let ms_into_minute = local_now_as_timestamp_ms().rem_euclid(60_000) as u64;
+    Duration::from_millis(60_000 - ms_into_minute)
+}
+
+/// `throttle_backoff_ms` doubled per attempt (capped), then a random 50..100% of that
+fn jittered_backoff(attempt: u32) -> Duration {
+    let client = &BINANCE.client;
+    let ceiling_ms = client
+        .throttle_backoff_ms
+        .saturating_mul(1 << attempt.saturating_sub(1).min(20))
+        .min(client.max_throttle_backoff_ms);
+    let jitter = getrandom::u64().unwrap_or(0) % (ceiling_ms / 2 + 1);
+    Duration::from_millis(ceiling_ms / 2 + jitter)
+}
+
+pub struct FetchScheduler {
+    buckets: Mutex<HashMap<Venue, WeightBucket>>,
+    in_flight: Semaphore,
+    progress: Mutex<FetchProgress>,
+}
+
+impl FetchScheduler {
+    fn new() -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            in_flight: Semaphore::new(BINANCE.limits.max_requests_in_flight),
+            progress: Mutex::new(FetchProgress::default()),
+        }
+    }
+
+    /// The scheduler every REST request goes through
+    pub fn shared() -> &'static FetchScheduler {
+        static SHARED: OnceLock<FetchScheduler> = OnceLock::new();
+        SHARED.get_or_init(FetchScheduler::new)
+    }
+
+    fn with_bucket<T>(&self, venue: Venue, f: impl FnOnce(&mut WeightBucket, Instant) -> T) -> T {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets
+            .entry(venue)
+            .or_insert_with(|| WeightBucket::new(weight_limit_minute(venue), now));
+        f(bucket, now)
+    }
+
+    /// Waits until `venue`'s budget has `weight` to spare, and takes it. Fails once the
+    /// venue has banned us, rather than wait out the ban.
+    async fn take_weight(&self, venue: Venue, weight: u32) -> Result<()> {
+        loop {
+            let taken = self.with_bucket(venue, |bucket, now| {
+                if let Some(left) = bucket.ban_left(now) {
+                    bail!(
+                        "{} has banned this IP for another {}s; not sending",
+                        venue,
+                        left.as_secs().max(1)
+                    );
+                }
+                Ok(bucket.try_take(weight, now))
+            })?;
+            match taken {
+                Ok(()) => return Ok(()),
+                // In steps, so a ban that starts meanwhile is noticed
+                Err(wait) => sleep(wait.min(Duration::from_secs(1))).await,
+            }
+        }
+    }
+
+    /// Sends `request`, which costs `weight` on `venue`, once the budget allows and a
+    /// request slot is free. `Throttled` failures are retried up to
+    /// `BINANCE.client.throttle_retries` times, except a 418, which bans the venue;
+    /// anything else is returned as is. While `venue` is banned nothing is sent.
+    pub async fn run<T, F, Fut>(&self, venue: Venue, weight: u32, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            self.take_weight(venue, weight).await?;
+            let result = {
+                let _slot = self.in_flight.acquire().await.expect("never closed");
+                request().await
+            };
+            let error = match result {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            let Some(throttled) = error
+                .chain()
+                .find_map(|cause| cause.downcast_ref::<Throttled>())
+                .cloned()
+            else {
+                return Err(error);
+            };
+            if throttled.is_ip_ban() {
+                let ban = throttled
+                    .retry_after
+                    .unwrap_or(Duration::from_secs(BINANCE.client.ip_ban_min_secs));
+                self.with_bucket(venue, |bucket, now| bucket.ban(now + ban));
+                log::error!(
+                    "{} banned this IP ({}); no requests to it for {}s",
+                    venue,
+                    throttled,
+                    ban.as_secs()
+                );
+                return Err(error);
+            }
+            if attempt >= BINANCE.client.throttle_retries {
+                return Err(error);
+            }
+            attempt += 1;
+
+            let wait = match throttled
+                .retry_after
+                .or_else(|| throttled.is_rate_limit().then(until_next_minute))
+            {
+                Some(retry_after) => {
+                    self.with_bucket(venue, |bucket, now| bucket.pause(now + retry_after));
+                    retry_after
+                }
+                None => jittered_backoff(attempt),
+            };
+            log::warn!(
+                "{} turned a request away ({}), retry {}/{} in {:.1}s",
+                venue,
+                throttled,
+                attempt,
+                BINANCE.client.throttle_retries,
+                wait.as_secs_f64()
+            );
+            sleep(wait).await;
+        }
+    }
+
+    /// `used` weight of the current window, as reported by the venue
+    pub fn observe_used_weight(&self, venue: Venue, used: u32) {
+        self.with_bucket(venue, |bucket, now| bucket.observe_used(used, now));
+    }
+
+    /// Starts counting a load of `total` pairs
+    pub fn begin_load(&self, total: usize) {
+        *self.progress.lock().unwrap() = FetchProgress {
+            total,
+            started_ms: local_now_as_timestamp_ms(),
+            ..Default::default()
+        };
+    }
+
+    /// Counts one pair of the current load as loaded (or failed); returns the new totals
+    pub fn pair_finished(&self, ok: bool) -> FetchProgress {
+        let mut progress = self.progress.lock().unwrap();
+        if ok {
+            progress.done += 1;
+        } else {
+            progress.failed += 1;
+        }
+        *progress
+    }
+
+    pub fn progress(&self) -> FetchProgress {
+        *self.progress.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn throttled(status: u16, retry_after: Option<Duration>) -> anyhow::Error {
+        Throttled {
+            status,
+            retry_after,
+            message: "slow down".to_string(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn the_bucket_spends_refills_and_says_how_long_to_wait() {
+        let start = Instant::now();
+        // One weight a second
+        let mut bucket = WeightBucket::new(60, start);
+
+        assert_eq!(bucket.try_take(60, start), Ok(()));
+        assert_eq!(bucket.try_take(2, start), Err(Duration::from_secs(2)));
+        assert_eq!(bucket.try_take(30, start + Duration::from_secs(30)), Ok(()));
+        assert!(bucket.try_take(1, start + Duration::from_secs(30)).is_err());
+
+        // Never more than the budget, and a dearer request goes once it's full
+        let later = start + Duration::from_secs(600);
+        assert_eq!(bucket.try_take(500, later), Ok(()));
+        assert!(bucket.try_take(1, later).is_err());
+    }
+
+    #[test]
+    fn the_venue_count_wins_when_it_is_higher() {
+        let start = Instant::now();
+        let mut bucket = WeightBucket::new(60, start);
+
+        bucket.observe_used(50, start);
+        assert_eq!(bucket.try_take(10, start), Ok(()));
+        assert!(bucket.try_take(1, start).is_err());
+
+        // A lower count than ours changes nothing
+        bucket.observe_used(0, start);
+        assert!(bucket.try_take(1, start).is_err());
+    }
+
+    #[test]
+    fn a_pause_holds_everything_then_restarts_from_an_empty_budget() {
+        let start = Instant::now();
+        let mut bucket = WeightBucket::new(60, start);
+
+        bucket.pause(start + Duration::from_secs(5));
+        // An earlier pause doesn't shorten a later one
+        bucket.pause(start + Duration::from_secs(2));
+        assert_eq!(
+            bucket.try_take(1, start + Duration::from_secs(1)),
+            Err(Duration::from_secs(4))
+        );
+        assert_eq!(
+            bucket.try_take(1, start + Duration::from_secs(5)),
+            Err(Duration::from_secs(1))
+        );
+        assert_eq!(bucket.try_take(3, start + Duration::from_secs(8)), Ok(()));
+    }
+
+    #[test]
+    fn a_ban_only_ever_grows_and_leaves_an_empty_budget() {
+        let start = Instant::now();
+        let mut bucket = WeightBucket::new(60, start);
+
+        bucket.ban(start + Duration::from_secs(10));
+        bucket.ban(start + Duration::from_secs(3));
+        assert_eq!(bucket.ban_left(start), Some(Duration::from_secs(10)));
+        assert_eq!(bucket.ban_left(start + Duration::from_secs(10)), None);
+        assert_eq!(
+            bucket.try_take(2, start + Duration::from_secs(10)),
+            Err(Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_per_attempt_with_jitter_up_to_the_cap() {
+        let client = &BINANCE.client;
+        for (attempt, ceiling_ms) in [
+            (1, client.throttle_backoff_ms),
+            (3, client.throttle_backoff_ms * 4),
+            (40, client.max_throttle_backoff_ms),
+        ] {
+            for _ in 0..20 {
+                let wait = jittered_backoff(attempt).as_millis() as u64;
+                assert!(
+                    (ceiling_ms / 2..=ceiling_ms).contains(&wait),
+                    "attempt {}: {}ms",
+                    attempt,
+                    wait
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn a_retry_after_pauses_the_whole_venue() {
+        let scheduler = FetchScheduler::new();
+        let retry_after = Duration::from_millis(300);
+        let started = Instant::now();
+        let attempts = AtomicU32::new(0);
+
+        let throttled_once = scheduler.run(Venue::BinanceFutures, 1, || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    Err(throttled(429, Some(retry_after)))
+                } else {
+                    Ok(started.elapsed())
+                }
+            }
+        });
+        // Sent while the venue is paused, so it waits out the Retry-After too
+        let bystander = async {
+            sleep(Duration::from_millis(50)).await;
+            scheduler
+                .run(Venue::BinanceFutures, 1, || async { Ok(started.elapsed()) })
+                .await
+        };
+        // Other venues carry on
+        let other_venue = async {
+            sleep(Duration::from_millis(50)).await;
+            scheduler
+                .run(Venue::Generic, 1, || async { Ok(started.elapsed()) })
+                .await
+        };
+
+        let (retried, bystander, other_venue) =
+            tokio::join!(throttled_once, bystander, other_venue);
+        assert!(retried.unwrap() >= retry_after);
+        assert!(bystander.unwrap() >= retry_after);
+        assert!(other_venue.unwrap() < retry_after);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn a_server_error_without_retry_after_backs_off() {
+        let scheduler = FetchScheduler::new();
+        let started = Instant::now();
+        let attempts = AtomicU32::new(0);
+
+        let result = scheduler
+            .run(Venue::Generic, 1, || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt == 0 {
+                        // Found under added context too
+                        Err(throttled(503, None).context("candles request"))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 1);
+        assert!(started.elapsed() >= Duration::from_millis(BINANCE.client.throttle_backoff_ms / 2));
+    }
+
+    #[tokio::test]
+    async fn only_throttled_errors_are_retried_and_only_so_often() {
+        let scheduler = FetchScheduler::new();
+        let attempts = AtomicU32::new(0);
+
+        let error = scheduler
+            .run(Venue::Generic, 1, || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(anyhow::anyhow!("HTTP 400: bad symbol")) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "HTTP 400: bad symbol");
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 1);
+
+        let error = scheduler
+            .run(Venue::Generic, 1, || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(throttled(429, Some(Duration::from_millis(1)))) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.downcast_ref::<Throttled>().unwrap().status, 429);
+        assert_eq!(
+            attempts.load(Ordering::SeqCst),
+            BINANCE.client.throttle_retries + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn an_ip_ban_is_not_retried_and_nothing_is_sent_until_it_ends() {
+        let scheduler = FetchScheduler::new();
+        let ban = Duration::from_millis(300);
+        let attempts = AtomicU32::new(0);
+        let send = || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        };
+
+        let error = scheduler
+            .run(Venue::BinanceSpot, 1, || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(throttled(418, Some(ban))) }
+            })
+            .await
+            .unwrap_err();
+        let banned_at = Instant::now();
+        assert_eq!(error.downcast_ref::<Throttled>().unwrap().status, 418);
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 1);
+
+        // Refused unsent for the rest of the ban; other venues carry on
+        let refused = scheduler
+            .run(Venue::BinanceSpot, 1, send)
+            .await
+            .unwrap_err();
+        assert!(refused.to_string().contains("banned"), "{}", refused);
+        assert!(refused.downcast_ref::<Throttled>().is_none());
+        assert_eq!(attempts.load(Ordering::SeqCst), 0);
+        scheduler.run(Venue::Generic, 1, send).await.unwrap();
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 1);
+
+        sleep(ban.saturating_sub(banned_at.elapsed())).await;
+        scheduler.run(Venue::BinanceSpot, 1, send).await.unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn a_ban_without_retry_after_lasts_the_minimum() {
+        let scheduler = FetchScheduler::new();
+        scheduler
+            .run(Venue::BinanceFutures, 1, || async {
+                Err::<(), _>(throttled(418, None))
+            })
+            .await
+            .unwrap_err();
+
+        let left = scheduler
+            .with_bucket(Venue::BinanceFutures, |bucket, now| bucket.ban_left(now))
+            .unwrap();
+        let minimum = Duration::from_secs(BINANCE.client.ip_ban_min_secs);
+        assert!(left <= minimum && left > minimum - Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,882 @@
+//! Local mock of the Binance REST + WebSocket endpoints the app uses, which also speaks
+//! the Binance USDⓈ-M futures paths and the generic protocol (`data::exchange::generic`)
+//! so every exchange adapter can be pointed at it.
+//!
+//! Point the app (or an integration test) at it through the environment variables in
+//! `BINANCE.overrides` and `EXCHANGES`; `MockBinanceServer::env_overrides` returns the
+//! exact pairs.
+//!
+//! REST (plain HTTP/1.1, one request per connection):
+//! - `GET /api/v3/klines` (and `/fapi/v1/klines`) serves fixture klines, honouring
+//!   `limit`, `startTime` and `endTime` so pagination behaves as it does against Binance.
+//! - `GET /api/v3/ticker/price` (and `/fapi/v2/ticker/price`) returns the latest fixture
+//!   close for every symbol.
+//! - `GET /api/v3/exchangeInfo` (and `/fapi/v1/exchangeInfo`) lists every fixture symbol
+//!   as trading.
+//! - `GET /v1/symbols`, `/v1/candles` and `/v1/prices` serve the same data in the
+//!   generic protocol.
+//! - Every response carries `X-MBX-USED-WEIGHT-1M`; exceeding `weight_limit_minute`
+//!   returns 429 automatically, and `inject_rest_fault` queues 429 / 418 / 5xx /
+//!   dropped-connection responses for the next requests.
+//!
+//! WebSocket: each connection plays its own script of `WsEvent`s in order (miniTicker,
+//! closed-kline, bookTicker and depth frames in the combined-stream envelope, pauses and
+//! abrupt disconnects). `script_ws_connection` sets the script a connection starts with,
+//! one per connection in accept order, so a reconnect can be given a different script
+//! than the connection it replaces; `push_ws_events` appends to every open connection's.
+//! Connections to `/v1/...` get generic frames instead, which have no depth.
+//!
+//! Fixtures are JSON files named `{SYMBOL}_{interval}.json` (e.g. `BTCUSDT_30m.json`)
+//! holding the raw Binance kline arrays, oldest first.
+
+use anyhow::{Context, Result, anyhow};
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::config::{BINANCE, EXCHANGES};
+use crate::data::price_stream::ClosedKline;
+use crate::domain::order_book::{BookLevel, BookTicker, DepthSnapshot};
+use crate::models::OhlcvTimeSeries;
+use crate::utils::TimeUtils;
+
+/// Behaviour knobs for the mock server.
+#[derive(Debug, Clone)]
+pub struct MockBinanceConfig {
+    /// Directory holding `{SYMBOL}_{interval}.json` kline fixtures.
+    pub fixtures_dir: PathBuf,
+    /// Weight charged per klines request (Binance charges 2 for limit <= 1000).
+    pub kline_weight: u32,
+    /// Weight charged per all-symbols ticker request.
+    pub ticker_weight: u32,
+    /// Used weight per minute above which requests are answered with 429.
+    pub weight_limit_minute: u32,
+    /// Delay between scripted WebSocket frames.
+    pub ws_frame_interval: Duration,
+    /// Quote assets fixture symbols end in, longest first. The mock is the exchange here,
+    /// so its exchangeInfo splits `BTCUSDT` by these.
+    pub quote_assets: Vec<&'static str>,
+}
+
+impl MockBinanceConfig {
+    pub fn new(fixtures_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            fixtures_dir: fixtures_dir.into(),
+            kline_weight: BINANCE.limits.kline_call_weight,
+            ticker_weight: 4,
+            weight_limit_minute: BINANCE.limits.weight_limit_minute,
+            ws_frame_interval: Duration::from_millis(100),
+            quote_assets: vec!["FDUSD", "USDT", "USDC", "BTC", "ETH"],
+        }
+    }
+}
+
+/// Failure injected into the next REST request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestFault {
+    /// HTTP 429 with a `Retry-After` header.
+    TooManyRequests { retry_after_secs: u64 },
+    /// HTTP 418: the IP is banned for `retry_after_secs`.
+    IpBan { retry_after_secs: u64 },
+    /// Any 5xx status.
+    ServerError(u16),
+    /// Close the socket without writing a response.
+    Disconnect,
+}
+
+/// One step of the scripted WebSocket feed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsEvent {
+    /// Push a miniTicker frame for `symbol` with close price `price`.
+    Tick { symbol: String, price: f64 },
+    /// Push a closed-candle frame on `{symbol}@kline_<interval>`.
+    Kline(ClosedKline),
+    /// Push a `{symbol}@bookTicker` frame.
+    Book { symbol: String, book: BookTicker },
+    /// Push a `{symbol}@depth<N>` frame, N being the number of levels given per side.
+    Depth {
+        symbol: String,
+        depth: DepthSnapshot,
+    },
+    /// Wait before the next event.
+    Pause(Duration),
+    /// Drop the connection abruptly (no close frame), forcing a reconnect.
+    Disconnect,
+}
+
+#[derive(Default)]
+struct MockState {
+    /// (SYMBOL, interval string) -> raw kline rows, oldest first.
+    klines: HashMap<(String, String), Vec<Value>>,
+    rest_faults: VecDeque<RestFault>,
+    /// Scripts for connections not accepted yet, in accept order
+    ws_pending_scripts: VecDeque<Vec<WsEvent>>,
+    /// Remaining script of each open connection, by connection number
+    ws_scripts: HashMap<usize, VecDeque<WsEvent>>,
+    used_weight: u32,
+    weight_minute: u64,
+    request_log: Vec<String>,
+    ws_requests: Vec<Value>,
+    ws_connections: usize,
+}
+
+/// Running mock server; both listeners are stopped when this is dropped.
+pub struct MockBinanceServer {
+    rest_addr: SocketAddr,
+    ws_addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockBinanceServer {
+    /// Loads fixtures and binds REST and WebSocket listeners on ephemeral localhost ports.
+    /// Must be called from inside a tokio runtime.
+    pub async fn start(config: MockBinanceConfig) -> Result<Self> {
+        let state = Arc::new(Mutex::new(MockState {
+            klines: load_fixtures(&config.fixtures_dir)?,
+            ..Default::default()
+        }));
+
+        let rest_listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_listener = TcpListener::bind("127.0.0.1:0").await?;
+        let rest_addr = rest_listener.local_addr()?;
+        let ws_addr = ws_listener.local_addr()?;
+
+        let config = Arc::new(config);
+        let rest_task = tokio::spawn(accept_loop(
+            rest_listener,
+            state.clone(),
+            config.clone(),
+            Protocol::Rest,
+        ));
+        let ws_task = tokio::spawn(accept_loop(
+            ws_listener,
+            state.clone(),
+            config,
+            Protocol::Ws,
+        ));
+
+        Ok(Self {
+            rest_addr,
+            ws_addr,
+            state,
+            tasks: vec![rest_task, ws_task],
+        })
+    }
+
+    pub fn rest_base_url(&self) -> String {
+        format!("http://{}", self.rest_addr)
+    }
+
+    pub fn ws_base_url(&self) -> String {
+        format!("ws://{}", self.ws_addr)
+    }
+
+    /// Environment variable / value pairs that redirect the app (every venue) to this
+    /// server.
+    pub fn env_overrides(&self) -> Vec<(&'static str, String)> {
+        [
+            BINANCE.overrides,
+            EXCHANGES.binance_futures.overrides,
+            EXCHANGES.generic.overrides,
+        ]
+        .into_iter()
+        .flat_map(|overrides| {
+            [
+                (overrides.rest_base_url_env, self.rest_base_url()),
+                (overrides.ws_base_url_env, self.ws_base_url()),
+            ]
+        })
+        .collect()
+    }
+
+    /// Queues a failure for the next REST request (faults are consumed in order).
+    pub fn inject_rest_fault(&self, fault: RestFault) {
+        self.state.lock().unwrap().rest_faults.push_back(fault);
+    }
+
+    /// Queues the script the next WebSocket connection starts with. Each call scripts
+    /// one connection, in accept order; connections beyond those start with nothing.
+    pub fn script_ws_connection(&self, events: impl IntoIterator<Item = WsEvent>) {
+        self.state
+            .lock()
+            .unwrap()
+            .ws_pending_scripts
+            .push_back(events.into_iter().collect());
+    }
+
+    /// Appends events to the script of every open WebSocket connection.
+    pub fn push_ws_events(&self, events: impl IntoIterator<Item = WsEvent>) {
+        let events: Vec<WsEvent> = events.into_iter().collect();
+        for script in self.state.lock().unwrap().ws_scripts.values_mut() {
+            script.extend(events.iter().cloned());
+        }
+    }
+
+    /// Request lines (`GET /api/v3/klines?...`) received so far.
+    pub fn request_log(&self) -> Vec<String> {
+        self.state.lock().unwrap().request_log.clone()
+    }
+
+    /// SUBSCRIBE / UNSUBSCRIBE requests received over WebSocket so far.
+    pub fn ws_requests(&self) -> Vec<Value> {
+        self.state.lock().unwrap().ws_requests.clone()
+    }
+
+    /// Number of WebSocket connections accepted so far (reconnects included).
+    pub fn ws_connections(&self) -> usize {
+        self.state.lock().unwrap().ws_connections
+    }
+
+    /// Number of WebSocket connections open right now.
+    pub fn open_ws_connections(&self) -> usize {
+        self.state.lock().unwrap().ws_scripts.len()
+    }
+
+    /// Weight used in the current minute, as reported in `X-MBX-USED-WEIGHT-1M`.
+    pub fn used_weight(&self) -> u32 {
+        self.state.lock().unwrap().used_weight
+    }
+}
+
+impl Drop for MockBinanceServer {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Writes a series as a `{SYMBOL}_{interval}.json` fixture the mock server can serve.
+pub fn write_fixture(series: &OhlcvTimeSeries, dir: &Path) -> Result<PathBuf> {
+    let interval_ms = series.pair_interval.interval_ms;
+    let rows: Vec<Value> = (0..series.klines())
+        .map(|i| {
+            let open_time = series.first_kline_timestamp_ms + i as i64 * interval_ms;
+            json!([
+                open_time,
+                series.open_prices[i].to_string(),
+                series.high_prices[i].to_string(),
+                series.low_prices[i].to_string(),
+                series.close_prices[i].to_string(),
+                series.base_asset_volumes[i].to_string(),
+                open_time + interval_ms - 1,
+                series.quote_asset_volumes[i].to_string(),
+                0,
+                "0",
+                "0",
+                "0"
+            ])
+        })
+        .collect();
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!(
+        "{}_{}.json",
+        series.pair_interval.venue_symbol(),
+        TimeUtils::interval_to_string(interval_ms)
+    ));
+    std::fs::write(&path, serde_json::to_string(&rows)?)
+        .with_context(|| format!("Failed to write fixture {}", path.display()))?;
+    Ok(path)
+}
+
+fn load_fixtures(dir: &Path) -> Result<HashMap<(String, String), Vec<Value>>> {
+    let mut klines = HashMap::new();
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some((symbol, interval)) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.rsplit_once('_'))
+        else {
+            continue;
+        };
+
+        let text = std::fs::read_to_string(&path)?;
+        let rows: Vec<Value> = serde_json::from_str(&text)
+            .with_context(|| format!("Fixture {} is not a kline array", path.display()))?;
+        klines.insert((symbol.to_uppercase(), interval.to_string()), rows);
+    }
+
+    if klines.is_empty() {
+        return Err(anyhow!("No kline fixtures found in {}", dir.display()));
+    }
+    Ok(klines)
+}
+
+#[derive(Clone, Copy)]
+enum Protocol {
+    Rest,
+    Ws,
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    state: Arc<Mutex<MockState>>,
+    config: Arc<MockBinanceConfig>,
+    protocol: Protocol,
+) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let state = state.clone();
+        let config = config.clone();
+        tokio::spawn(async move {
+            let result = match protocol {
+                Protocol::Rest => serve_rest(stream, state, &config).await,
+                Protocol::Ws => serve_ws(stream, state, &config).await,
+            };
+            if let Err(e) = result {
+                log::warn!("Mock Binance connection ended with error: {:#}", e);
+            }
+        });
+    }
+}
+
+struct HttpResponse {
+    status: u16,
+    body: String,
+    retry_after_secs: Option<u64>,
+}
+
+async fn serve_rest(
+    mut stream: TcpStream,
+    state: Arc<Mutex<MockState>>,
+    config: &MockBinanceConfig,
+) -> Result<()> {
+    let request_line = read_request_line(&mut stream).await?;
+    let target = request_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| anyhow!("Malformed request line: {}", request_line))?
+        .to_string();
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let params = parse_query(query);
+
+    let (response, used_weight) = {
+        let mut state = state.lock().unwrap();
+        state.request_log.push(request_line.clone());
+
+        match state.rest_faults.pop_front() {
+            Some(RestFault::Disconnect) => return Ok(()),
+            Some(fault) => (fault_response(&fault), state.used_weight),
+            None => {
+                let weight = match path {
+                    "/api/v3/klines" | "/fapi/v1/klines" => config.kline_weight,
+                    "/api/v3/ticker/price" | "/fapi/v2/ticker/price" => config.ticker_weight,
+                    _ => 1,
+                };
+                let used = charge_weight(&mut state, weight);
+                let response = if used > config.weight_limit_minute {
+                    fault_response(&RestFault::TooManyRequests {
+                        retry_after_secs: seconds_to_next_minute(),
+                    })
+                } else {
+                    route(&state, config, path, &params)
+                };
+                (response, used)
+            }
+        }
+    };
+
+    write_response(&mut stream, &response, used_weight).await
+}
+
+fn route(
+    state: &MockState,
+    config: &MockBinanceConfig,
+    path: &str,
+    params: &HashMap<String, String>,
+) -> HttpResponse {
+    match path {
+        "/api/v3/klines" | "/fapi/v1/klines" => klines_response(state, params),
+        "/api/v3/ticker/price" | "/fapi/v2/ticker/price" => {
+            let tickers: Vec<Value> = latest_closes(state)
+                .into_iter()
+                .map(|(symbol, close)| json!({ "symbol": symbol, "price": close }))
+                .collect();
+            ok(Value::Array(tickers).to_string())
+        }
+        "/api/v3/exchangeInfo" | "/fapi/v1/exchangeInfo" => {
+            let symbols: Vec<Value> = fixture_symbols(state, config)
+                .into_iter()
+                .map(|(symbol, base, quote)| {
+                    json!({
+                        "symbol": symbol,
+                        "status": "TRADING",
+                        "baseAsset": base,
+                        "quoteAsset": quote,
+                        "filters": [
+                            { "filterType": "PRICE_FILTER", "tickSize": "0.00000100" },
+                            {
+                                "filterType": "LOT_SIZE",
+                                "stepSize": "0.00100000",
+                                "minQty": "0.00100000"
+                            }
+                        ]
+                    })
+                })
+                .collect();
+            ok(
+                json!({ "timezone": "UTC", "serverTime": unix_secs() * 1000, "symbols": symbols })
+                    .to_string(),
+            )
+        }
+        "/api/v3/ping" | "/fapi/v1/ping" => ok("{}".to_string()),
+        "/v1/symbols" => {
+            let symbols: Vec<Value> = fixture_symbols(state, config)
+                .into_iter()
+                .map(|(symbol, base, quote)| {
+                    json!({ "symbol": symbol, "base": base, "quote": quote })
+                })
+                .collect();
+            ok(Value::Array(symbols).to_string())
+        }
+        "/v1/candles" => generic_candles_response(state, params),
+        "/v1/prices" => {
+            let prices: Vec<Value> = latest_closes(state)
+                .into_iter()
+                .filter_map(|(symbol, close)| {
+                    Some(json!({ "symbol": symbol, "price": close.parse::<f64>().ok()? }))
+                })
+                .collect();
+            ok(Value::Array(prices).to_string())
+        }
+        _ => error_response(404, -1, "Unknown endpoint"),
+    }
+}
+
+/// Latest fixture close per symbol
+fn latest_closes(state: &MockState) -> Vec<(String, String)> {
+    state
+        .klines
+        .iter()
+        .filter_map(|((symbol, _), rows)| {
+            let close = rows.last()?.get(4)?.as_str()?;
+            Some((symbol.clone(), close.to_string()))
+        })
+        .collect()
+}
+
+/// (symbol, base, quote) of every fixture symbol ending in a `quote_assets` entry, sorted
+fn fixture_symbols(state: &MockState, config: &MockBinanceConfig) -> Vec<(String, String, String)> {
+    let mut symbols: Vec<(String, String, String)> = state
+        .klines
+        .keys()
+        .filter_map(|(symbol, _)| {
+            let quote = config
+                .quote_assets
+                .iter()
+                .find(|quote| symbol.len() > quote.len() && symbol.ends_with(*quote))?;
+            let base = symbol.strip_suffix(quote)?;
+            Some((symbol.clone(), base.to_string(), quote.to_string()))
+        })
+        .collect();
+    symbols.sort();
+    symbols.dedup();
+    symbols
+}
+
+/// A raw Binance kline row as a generic candle object
+fn generic_candle(row: &Value) -> Option<Value> {
+    let number = |i: usize| row.get(i)?.as_str()?.parse::<f64>().ok();
+    Some(json!({
+        "t": row.get(0)?.as_i64()?,
+        "o": number(1)?,
+        "h": number(2)?,
+        "l": number(3)?,
+        "c": number(4)?,
+        "v": number(5)?,
+        "q": number(7)?,
+    }))
+}
+
+fn generic_candles_response(state: &MockState, params: &HashMap<String, String>) -> HttpResponse {
+    let (Some(symbol), Some(interval)) = (params.get("symbol"), params.get("interval")) else {
+        return error_response(400, -1, "symbol and interval are required");
+    };
+    let Some(rows) = state
+        .klines
+        .get(&(symbol.to_uppercase(), interval.to_string()))
+    else {
+        return error_response(404, -1, "Unknown symbol");
+    };
+    let limit = params
+        .get("limit")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(1000)
+        .max(1);
+    let end_time = params.get("end_time").and_then(|t| t.parse::<i64>().ok());
+
+    let in_range: Vec<Value> = rows
+        .iter()
+        .filter(|row| {
+            let open_time = row.get(0).and_then(Value::as_i64).unwrap_or(0);
+            end_time.is_none_or(|end| open_time <= end)
+        })
+        .filter_map(generic_candle)
+        .collect();
+    let skip = in_range.len().saturating_sub(limit);
+    ok(json!(in_range[skip..]).to_string())
+}
+
+fn klines_response(state: &MockState, params: &HashMap<String, String>) -> HttpResponse {
+    let (Some(symbol), Some(interval)) = (params.get("symbol"), params.get("interval")) else {
+        return error_response(
+            400,
+            -1102,
+            "Mandatory parameter 'symbol' or 'interval' missing",
+        );
+    };
+    let Some(rows) = state
+        .klines
+        .get(&(symbol.to_uppercase(), interval.to_string()))
+    else {
+        return error_response(400, -1121, "Invalid symbol.");
+    };
+
+    let limit = params
+        .get("limit")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(500)
+        .clamp(1, 1000);
+    let start_time = params.get("startTime").and_then(|t| t.parse::<i64>().ok());
+    let end_time = params.get("endTime").and_then(|t| t.parse::<i64>().ok());
+
+    let in_range: Vec<&Value> = rows
+        .iter()
+        .filter(|row| {
+            let open_time = row.get(0).and_then(Value::as_i64).unwrap_or(0);
+            start_time.is_none_or(|start| open_time >= start)
+                && end_time.is_none_or(|end| open_time <= end)
+        })
+        .collect();
+
+    // Binance returns the oldest `limit` rows after startTime, otherwise the newest.
+    let selected: Vec<&Value> = if start_time.is_some() {
+        in_range.into_iter().take(limit).collect()
+    } else {
+        let skip = in_range.len().saturating_sub(limit);
+        in_range.into_iter().skip(skip).collect()
+    };
+
+    ok(json!(selected).to_string())
+}
+
+fn fault_response(fault: &RestFault) -> HttpResponse {
+    match fault {
+        RestFault::TooManyRequests { retry_after_secs } => HttpResponse {
+            retry_after_secs: Some(*retry_after_secs),
+            ..error_response(429, -1003, "Too many requests; current limit is exceeded.")
+        },
+        RestFault::IpBan { retry_after_secs } => HttpResponse {
+            retry_after_secs: Some(*retry_after_secs),
+            ..error_response(418, -1003, "Way too many requests; IP banned.")
+        },
+        RestFault::ServerError(status) => error_response(
+            *status,
+            -1000,
+            "An unknown error occurred while processing the request.",
+        ),
+        RestFault::Disconnect => error_response(500, -1000, "Disconnect"),
+    }
+}
+
+fn ok(body: String) -> HttpResponse {
+    HttpResponse {
+        status: 200,
+        body,
+        retry_after_secs: None,
+    }
+}
+
+fn error_response(status: u16, code: i32, msg: &str) -> HttpResponse {
+    HttpResponse {
+        status,
+        body: json!({ "code": code, "msg": msg }).to_string(),
+        retry_after_secs: None,
+    }
+}
+
+fn charge_weight(state: &mut MockState, weight: u32) -> u32 {
+    let minute = unix_secs() / 60;
+    if minute != state.weight_minute {
+        state.weight_minute = minute;
+        state.used_weight = 0;
+    }
+    state.used_weight += weight;
+    state.used_weight
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn seconds_to_next_minute() -> u64 {
+    60 - unix_secs() % 60
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Reads the request head and returns its first line. Bodies are ignored (GET only).
+async fn read_request_line(stream: &mut TcpStream) -> Result<String> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.len() > 64 * 1024 {
+            return Err(anyhow!("Request head too large"));
+        }
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    head.lines()
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Empty request"))
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    response: &HttpResponse,
+    used_weight: u32,
+) -> Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        418 => "I'm a teapot",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Error",
+    };
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json;charset=UTF-8\r\nContent-Length: {}\r\nX-MBX-USED-WEIGHT-1M: {}\r\nConnection: close\r\n",
+        response.status,
+        reason,
+        response.body.len(),
+        used_weight
+    );
+    if let Some(secs) = response.retry_after_secs {
+        head.push_str(&format!("Retry-After: {}\r\n", secs));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn serve_ws(
+    stream: TcpStream,
+    state: Arc<Mutex<MockState>>,
+    config: &MockBinanceConfig,
+) -> Result<()> {
+    // The request line says which protocol the client speaks
+    let mut head = [0u8; 64];
+    let peeked = stream.peek(&mut head).await?;
+    let generic = head[..peeked].starts_with(b"GET /v1/");
+    let ws_stream = tokio_tungstenite::accept_async(stream).await?;
+    let connection = {
+        let mut state = state.lock().unwrap();
+        let connection = state.ws_connections;
+        state.ws_connections += 1;
+        let script = state.ws_pending_scripts.pop_front().unwrap_or_default();
+        state.ws_scripts.insert(connection, script.into());
+        connection
+    };
+
+    let result = play_ws_script(ws_stream, &state, connection, generic, config).await;
+    state.lock().unwrap().ws_scripts.remove(&connection);
+    result
+}
+
+async fn play_ws_script(
+    ws_stream: tokio_tungstenite::WebSocketStream<TcpStream>,
+    state: &Mutex<MockState>,
+    connection: usize,
+    generic: bool,
+    config: &MockBinanceConfig,
+) -> Result<()> {
+    let (mut write, mut read) = ws_stream.split();
+
+    loop {
+        let next_event = state
+            .lock()
+            .unwrap()
+            .ws_scripts
+            .get_mut(&connection)
+            .and_then(VecDeque::pop_front);
+        match next_event {
+            Some(WsEvent::Pause(duration)) => tokio::time::sleep(duration).await,
+            // Dropping both halves closes the TCP stream without a close frame.
+            Some(WsEvent::Disconnect) => return Ok(()),
+            Some(event) if generic => {
+                if let Some(frame) = generic_frame(&event) {
+                    write.send(Message::Text(frame.to_string().into())).await?;
+                }
+            }
+            Some(WsEvent::Tick { symbol, price }) => {
+                let symbol_upper = symbol.to_uppercase();
+                let frame = json!({
+                    "stream": format!("{}@miniTicker", symbol.to_lowercase()),
+                    "data": {
+                        "e": "24hrMiniTicker",
+                        "E": unix_secs() * 1000,
+                        "s": symbol_upper,
+                        "c": price.to_string(),
+                    }
+                });
+                write.send(Message::Text(frame.to_string().into())).await?;
+            }
+            Some(WsEvent::Kline(kline)) => {
+                let interval = TimeUtils::interval_to_string(kline.interval_ms);
+                let frame = json!({
+                    "stream": format!("{}@kline_{}", kline.symbol.to_lowercase(), interval),
+                    "data": {
+                        "e": "kline",
+                        "E": unix_secs() * 1000,
+                        "s": kline.symbol.to_uppercase(),
+                        "k": {
+                            "t": kline.open_time_ms,
+                            "T": kline.open_time_ms + kline.interval_ms - 1,
+                            "s": kline.symbol.to_uppercase(),
+                            "i": interval,
+                            "o": kline.open_price.to_string(),
+                            "h": kline.high_price.to_string(),
+                            "l": kline.low_price.to_string(),
+                            "c": kline.close_price.to_string(),
+                            "v": kline.base_volume.to_string(),
+                            "q": kline.quote_volume.to_string(),
+                            "x": true,
+                        }
+                    }
+                });
+                write.send(Message::Text(frame.to_string().into())).await?;
+            }
+            Some(WsEvent::Book { symbol, book }) => {
+                let frame = json!({
+                    "stream": format!("{}@bookTicker", symbol.to_lowercase()),
+                    "data": {
+                        "u": unix_secs(),
+                        "s": symbol.to_uppercase(),
+                        "b": book.bid.price.to_string(),
+                        "B": book.bid.quantity.to_string(),
+                        "a": book.ask.price.to_string(),
+                        "A": book.ask.quantity.to_string(),
+                    }
+                });
+                write.send(Message::Text(frame.to_string().into())).await?;
+            }
+            Some(WsEvent::Depth { symbol, depth }) => {
+                let levels = |side: &[BookLevel]| {
+                    side.iter()
+                        .map(|l| json!([l.price.to_string(), l.quantity.to_string()]))
+                        .collect::<Vec<_>>()
+                };
+                let frame = json!({
+                    "stream": format!(
+                        "{}@depth{}",
+                        symbol.to_lowercase(),
+                        depth.bids.len().max(depth.asks.len())
+                    ),
+                    "data": {
+                        "lastUpdateId": depth.last_update_id,
+                        "bids": levels(&depth.bids),
+                        "asks": levels(&depth.asks),
+                    }
+                });
+                write.send(Message::Text(frame.to_string().into())).await?;
+            }
+            None => {}
+        }
+
+        // Drain client frames (pings, subscribe requests) without blocking the script.
+        tokio::select! {
+            incoming = read.next() => match incoming {
+                Some(Ok(Message::Ping(payload))) => write.send(Message::Pong(payload)).await?,
+                Some(Ok(Message::Text(text))) => {
+                    // Acknowledge SUBSCRIBE / UNSUBSCRIBE like Binance does
+                    if let Ok(request) = serde_json::from_str::<Value>(&text) {
+                        let reply = if generic {
+                            json!({ "id": request["id"], "error": null })
+                        } else {
+                            json!({ "result": null, "id": request["id"] })
+                        };
+                        state.lock().unwrap().ws_requests.push(request);
+                        write.send(Message::Text(reply.to_string().into())).await?;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Err(e)) => return Err(e.into()),
+                Some(Ok(_)) => {}
+            },
+            _ = tokio::time::sleep(config.ws_frame_interval) => {}
+        }
+    }
+}
+
+/// A scripted event as a generic-protocol frame; `None` for depth, which it doesn't have
+fn generic_frame(event: &WsEvent) -> Option<Value> {
+    match event {
+        WsEvent::Tick { symbol, price } => Some(json!({
+            "stream": format!("{}@ticker", symbol.to_lowercase()),
+            "time": unix_secs() * 1000,
+            "price": price,
+        })),
+        WsEvent::Kline(kline) => Some(json!({
+            "stream": format!(
+                "{}@candle_{}",
+                kline.symbol.to_lowercase(),
+                TimeUtils::interval_to_string(kline.interval_ms)
+            ),
+            "closed": true,
+            "candle": {
+                "t": kline.open_time_ms,
+                "o": kline.open_price,
+                "h": kline.high_price,
+                "l": kline.low_price,
+                "c": kline.close_price,
+                "v": kline.base_volume,
+                "q": kline.quote_volume,
+            }
+        })),
+        WsEvent::Book { symbol, book } => Some(json!({
+            "stream": format!("{}@book", symbol.to_lowercase()),
+            "bid": [book.bid.price, book.bid.quantity],
+            "ask": [book.ask.price, book.ask.quantity],
+        })),
+        WsEvent::Depth { .. } | WsEvent::Pause(_) | WsEvent::Disconnect => None,
+    }
+}
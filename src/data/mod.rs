@@ -1,11 +1,22 @@
 // Data loading, caching, and streaming
+#[cfg(not(target_arch = "wasm32"))]
+pub mod exchange;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fetch_scheduler;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mock_binance;
+pub mod pair_loading;
 pub mod pre_main_async;
 pub mod price_stream;
+pub mod tick_tape;
 pub mod timeseries;
 
 // Re-export commonly used types
 pub use pre_main_async::fetch_pair_data;
-pub use price_stream::PriceStreamManager;
+pub use price_stream::{
+    ClosedKline, ConnectionStatus, PriceQuote, PriceStreamManager, PriceUpdate, PriceUpdates,
+    StreamSelection, UpdateNotifier,
+};
 pub use timeseries::TimeSeriesCollection;
 // Only re-export this for non-WASM targets
 #[cfg(not(target_arch = "wasm32"))]
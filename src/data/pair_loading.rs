@@ -0,0 +1,325 @@
+//! Pairs load in the background while the UI is already up. The loader sends each pair
+//! as soon as it's ready (`PairLoadEvent`) and `SniperEngine` takes it in; a pair that
+//! fails is reported with its reason and the rest load regardless.
+//!
+//! Pairs from the API stream in one by one as their klines arrive; a cache or an import
+//! is read whole, so its pairs all arrive together.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::OnceLock;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::Cli;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config::BINANCE;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::fetch_scheduler::FetchScheduler;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::pre_main_async::{prepare_collection, timeseries_providers};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::timeseries::{TimeSeriesCollection, get_timeseries_data_async};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::write_timeseries_data_async;
+use crate::domain::symbol_registry::SymbolRegistry;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::domain::watchlist::Watchlist;
+use crate::models::OhlcvTimeSeries;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
+
+/// One step of the background load
+#[derive(Debug)]
+pub enum PairLoadEvent {
+    /// A pair ready for analysis (quality-checked, with its aggTrades if asked for)
+    Loaded(Arc<OhlcvTimeSeries>),
+    /// A pair that couldn't be loaded, and why
+    Failed { pair: String, reason: String },
+    /// How far the kline download has got (API loads only)
+    Progress(FetchProgress),
+    /// exchangeInfo of the venues being loaded, fetched (or refreshed) by an API load
+    Symbols(Arc<SymbolRegistry>),
+    /// Nothing more is coming: the source the pairs came from, or why none had any
+    Finished(Result<&'static str, String>),
+}
+
+/// The load so far, as the UI shows it
+#[derive(Debug, Clone, Default)]
+pub struct PairLoadStatus {
+    pub loaded: usize,
+    /// (pair, reason)
+    pub failures: Vec<(String, String)>,
+    pub progress: Option<FetchProgress>,
+    pub finished: bool,
+    /// Why no source had any data, if none had
+    pub error: Option<String>,
+}
+
+impl PairLoadStatus {
+    /// A load that was over before the engine started (data handed over up front)
+    pub fn complete(loaded: usize) -> Self {
+        Self {
+            loaded,
+            finished: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        !self.finished
+    }
+
+    /// Folds `event` in (the pairs themselves are the engine's business)
+    pub fn record(&mut self, event: &PairLoadEvent) {
+        match event {
+            PairLoadEvent::Loaded(_) => self.loaded += 1,
+            PairLoadEvent::Failed { pair, reason } => {
+                self.failures.push((pair.clone(), reason.clone()))
+            }
+            PairLoadEvent::Progress(progress) => self.progress = Some(*progress),
+            PairLoadEvent::Symbols(_) => {}
+            PairLoadEvent::Finished(result) => {
+                self.finished = true;
+                self.error = result.as_ref().err().cloned();
+            }
+        }
+    }
+
+    /// Why `pair` didn't load, if it failed
+    pub fn failure(&self, pair: &str) -> Option<&str> {
+        self.failures
+            .iter()
+            .find(|(failed, _)| failed == pair)
+            .map(|(_, reason)| reason.as_str())
+    }
+}
+
+/// How far the current kline load has got, in pairs
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FetchProgress {
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+    /// When the load started (ms)
+    pub started_ms: i64,
+}
+
+impl FetchProgress {
+    pub fn finished(&self) -> usize {
+        self.done + self.failed
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.finished() >= self.total
+    }
+
+    /// 0.0 ..= 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.finished() as f32 / self.total as f32
+    }
+
+    /// Time left at the pace so far; `None` until a pair has finished
+    pub fn eta_ms(&self, now_ms: i64) -> Option<i64> {
+        let finished = self.finished();
+        if finished == 0 {
+            return None;
+        }
+        let per_pair_ms = (now_ms - self.started_ms).max(0) / finished as i64;
+        Some(per_pair_ms * self.total.saturating_sub(finished) as i64)
+    }
+}
+
+impl fmt::Display for FetchProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} pairs", self.finished(), self.total)?;
+        if self.failed > 0 {
+            write!(f, " ({} failed)", self.failed)?;
+        }
+        if !self.is_complete() {
+            if let Some(eta_ms) = self.eta_ms(local_now_as_timestamp_ms()) {
+                write!(f, ", ETA {}s", (eta_ms + 999) / 1000)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The runtime every background load runs on, started by the first one. Connections
+/// pooled by the shared REST clients (`data::exchange`) live on it, so it stays up for
+/// the price stream to go on using them; later loads reuse it rather than start their own.
+#[cfg(not(target_arch = "wasm32"))]
+fn loading_runtime() -> Result<&'static tokio::runtime::Runtime, String> {
+    static RUNTIME: OnceLock<Result<tokio::runtime::Runtime, String>> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .thread_name("pair-loading")
+                .enable_all()
+                .build()
+                .map_err(|e| format!("Failed to create Tokio runtime: {}", e))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Loads pairs on a thread of its own, sending them over the returned channel as they're
+/// ready. The cache is written once everything is in, and the thread ends with the load.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_pair_loading(args: Cli, klines_acceptable_age_secs: i64) -> Receiver<PairLoadEvent> {
+    let (events, receiver) = channel();
+    std::thread::spawn(move || match loading_runtime() {
+        Ok(runtime) => runtime.block_on(load_pairs(args, klines_acceptable_age_secs, events)),
+        Err(e) => {
+            let _ = events.send(PairLoadEvent::Finished(Err(e)));
+        }
+    });
+    receiver
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn load_pairs(args: Cli, klines_acceptable_age_secs: i64, events: Sender<PairLoadEvent>) {
+    // The API loader reports pairs here as they arrive; others only return the lot
+    let (streamed_tx, mut streamed_rx) = tokio::sync::mpsc::unbounded_channel();
+    let providers = timeseries_providers(klines_acceptable_age_secs, &args, Some(streamed_tx));
+    let fetch = async move {
+        let result = get_timeseries_data_async(&providers).await;
+        // Closes `streamed_rx`
+        drop(providers);
+        result
+    };
+
+    let mut sent: HashSet<String> = HashSet::new();
+    let forward = async {
+        while let Some(event) = streamed_rx.recv().await {
+            match event {
+                PairLoadEvent::Loaded(series) => {
+                    let collection = TimeSeriesCollection {
+                        series_data: vec![series],
+                        ..Default::default()
+                    };
+                    send_prepared(collection, &args, &events, &mut sent).await;
+                }
+                other => {
+                    let _ = events.send(other);
+                }
+            }
+            let _ = events.send(PairLoadEvent::Progress(FetchScheduler::shared().progress()));
+        }
+    };
+    let (result, ()) = tokio::join!(fetch, forward);
+
+    let (collection, signature) = match result {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            log::error!("⚠️  No pair data could be loaded: {:#}", e);
+            let _ = events.send(PairLoadEvent::Finished(Err(format!("{:#}", e))));
+            return;
+        }
+    };
+
+    // Whatever didn't stream in (a cache or an import) goes now
+    let rest = TimeSeriesCollection {
+        series_data: collection
+            .series_data
+            .iter()
+            .filter(|series| !sent.contains(series.pair_interval.name()))
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+    send_prepared(rest, &args, &events, &mut sent).await;
+    log::info!("Loaded {} pairs using: {}", sent.len(), signature);
+    let _ = events.send(PairLoadEvent::Finished(Ok(signature)));
+
+    // Each pair is cached at its own interval, which the watchlist may override
+    let watchlist = Watchlist::load(BINANCE.pairs.watchlist_path).unwrap_or_default();
+    if let Err(e) = write_timeseries_data_async(signature, collection, watchlist).await {
+        log::warn!("⚠️  Failed to write cache: {}", e);
+    }
+}
+
+/// Quality checks (and aggTrades) for `collection`, then sends what's left of it
+#[cfg(not(target_arch = "wasm32"))]
+async fn send_prepared(
+    mut collection: TimeSeriesCollection,
+    args: &Cli,
+    events: &Sender<PairLoadEvent>,
+    sent: &mut HashSet<String>,
+) {
+    for (pair, reason) in prepare_collection(&mut collection, args).await {
+        let _ = events.send(PairLoadEvent::Failed { pair, reason });
+    }
+    for series in collection.series_data {
+        sent.insert(series.pair_interval.name().to_string());
+        let _ = events.send(PairLoadEvent::Loaded(series));
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::data::timeseries::tabular_version::export_series;
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    #[test]
+    fn each_load_ends_and_every_load_shares_one_runtime() {
+        let dir =
+            std::env::temp_dir().join(format!("zone_sniper_pair_loading_{}", std::process::id()));
+        let candles: Vec<_> = (0..48)
+            .map(|i| {
+                let close = 100.0 + i as f64;
+                (close, close + 1.0, close - 1.0, close)
+            })
+            .collect();
+        let series =
+            OhlcvTimeSeries::from_test_candles("BTCUSDT", 1_800_000, 1_736_121_600_000, &candles);
+        export_series(&series, &dir.join("BTCUSDT_30m.csv")).unwrap();
+        let args = Cli {
+            prefer_api: false,
+            import: Some(dir.clone()),
+            import_format: crate::ImportPreset::Default,
+            exclude_bad_data: false,
+            agg_trades: false,
+            record_ticks: None,
+            replay: None,
+            replay_speed: crate::data::tick_tape::ReplaySpeed::REAL_TIME,
+        };
+
+        for _ in 0..2 {
+            let events = spawn_pair_loading(args.clone(), 0);
+            let mut loaded = Vec::new();
+            loop {
+                match events.recv_timeout(Duration::from_secs(30)) {
+                    Ok(PairLoadEvent::Loaded(series)) => loaded.push(series),
+                    Ok(PairLoadEvent::Finished(result)) => {
+                        assert_eq!(result, Ok("File Import"));
+                        break;
+                    }
+                    Ok(_) => {}
+                    Err(e) => panic!("load never finished: {:?}", e),
+                }
+            }
+            assert_eq!(loaded.len(), 1);
+            assert_eq!(loaded[0].pair_interval.name(), "BTCUSDT");
+            // The loader lets go of the channel once it's done, rather than living on
+            assert_eq!(
+                events.recv_timeout(Duration::from_secs(30)).err(),
+                Some(RecvTimeoutError::Disconnected)
+            );
+        }
+        assert!(std::ptr::eq(
+            loading_runtime().unwrap(),
+            loading_runtime().unwrap()
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,10 @@
-// Async code to run in main before egui starts up
+// Async code that loads the pair data the app analyses
+
+use anyhow::Result;
 
 use crate::Cli;
+use crate::analysis::QualityVerdict;
+use crate::analysis::data_quality::{assess_collection, exclude_bad_pairs};
 use crate::data::timeseries::{
     CreateTimeSeriesData, TimeSeriesCollection, get_timeseries_data_async,
 };
@@ -10,69 +14,126 @@ use crate::config::DEMO;
 #[cfg(target_arch = "wasm32")]
 use crate::data::timeseries::wasm_demo::WasmDemoData;
 
-#[cfg(not(target_arch = "wasm32"))]
-use crate::config::ANALYSIS;
 #[cfg(debug_assertions)]
 use crate::config::DEBUG_FLAGS;
 #[cfg(not(target_arch = "wasm32"))]
-use crate::config::PERSISTENCE;
+use crate::config::{ANALYSIS, BINANCE};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::pair_loading::PairLoadEvent;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::data::timeseries::bnapi_version::BNAPIVersion;
 #[cfg(not(target_arch = "wasm32"))]
+use crate::data::timeseries::bnapi_version::agg_trades::attach_trade_profiles;
+#[cfg(not(target_arch = "wasm32"))]
 use crate::data::timeseries::serde_version::{SerdeVersion, check_local_data_validity};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::timeseries::tabular_version::TabularVersion;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::domain::watchlist::Watchlist;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::mpsc::UnboundedSender;
 
-// The async function to load  to run before the GUI starts at all (so can't rely on gui app state)
-pub async fn fetch_pair_data(
+/// The sources to try, in order: an import on its own, otherwise the cache and the API
+/// (whichever the user prefers first). The API and cache loaders report each pair on
+/// `streamed` as it arrives, if given.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn timeseries_providers(
     klines_acceptable_age_secs: i64,
     args: &Cli,
-) -> (TimeSeriesCollection, &'static str) {
+    streamed: Option<UnboundedSender<PairLoadEvent>>,
+) -> Vec<Box<dyn CreateTimeSeriesData>> {
+    if let Some(path) = &args.import {
+        // Imported files are analysed on their own; they never fall back to (or overwrite) the cache.
+        return vec![Box::new(TabularVersion {
+            path: path.clone(),
+            format: args.import_format.format(),
+        })];
+    }
+
     // Klines loading logic: If `check_local_data_validity` fails, then only choice is to read from API.
     // else if `check_local_data_validity` succeeds, both methods become available so we prioritize whatever the user wants (set to prioritize_local_disk_read via cli)
+    let watchlist = Watchlist::load(BINANCE.pairs.watchlist_path).unwrap_or_default();
+    let validity = check_local_data_validity(
+        klines_acceptable_age_secs,
+        ANALYSIS.interval_width_ms,
+        &watchlist,
+    );
+    let local = Box::new(SerdeVersion {
+        interval_ms: ANALYSIS.interval_width_ms,
+        watchlist,
+        streamed: streamed.clone(),
+    });
+    let api = Box::new(BNAPIVersion { streamed });
+    let api_first = args.prefer_api;
+    match (api_first, validity) {
+        (false, Ok(_)) => vec![local, api], // local first
+        (true, Ok(_)) => vec![api, local],  // API first
+        (_, Err(e)) => {
+            log::warn!("⚠️  Local cache validation failed: {:#}", e);
+            log::warn!("⚠️  Falling back to Binance API...");
+            vec![api] // API only
+        }
+    }
+}
 
-    #[cfg(target_arch = "wasm32")]
-    {
-        let _ = args;
-        let _ = klines_acceptable_age_secs;
+/// Logs each pair's data quality, drops the bad ones if asked to and attaches aggTrades
+/// profiles if asked to. Returns the pairs dropped, with why.
+pub async fn prepare_collection(
+    timeseries_data: &mut TimeSeriesCollection,
+    args: &Cli,
+) -> Vec<(String, String)> {
+    let reports = assess_collection(timeseries_data);
+    for report in &reports {
+        match report.verdict {
+            QualityVerdict::Good => {}
+            QualityVerdict::Warning => log::info!("Data quality: {}", report.summary()),
+            QualityVerdict::Bad => log::warn!("⚠️  Data quality: {}", report.summary()),
+        }
+    }
+    let mut dropped = Vec::new();
+    if args.exclude_bad_data {
+        let excluded = exclude_bad_pairs(timeseries_data, &reports);
+        if !excluded.is_empty() {
+            log::warn!(
+                "⚠️  Excluded {} pair(s) with bad data: {}",
+                excluded.len(),
+                excluded.join(", ")
+            );
+        }
+        dropped.extend(excluded.into_iter().map(|pair| {
+            let reason = reports
+                .iter()
+                .find(|report| report.pair == pair)
+                .map_or_else(String::new, |report| report.summary());
+            (pair, format!("Excluded for bad data: {}", reason))
+        }));
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    let providers: Vec<Box<dyn CreateTimeSeriesData>> = {
-        let api_first = args.prefer_api;
-        match (
-            api_first,
-            check_local_data_validity(
-                klines_acceptable_age_secs,
-                PERSISTENCE.kline.version,
-                ANALYSIS.interval_width_ms,
-            ),
-        ) {
-            (false, Ok(_)) => vec![
-                Box::new(SerdeVersion {
-                    interval_ms: ANALYSIS.interval_width_ms,
-                }),
-                Box::new(BNAPIVersion),
-            ], // local first
-            (true, Ok(_)) => vec![
-                Box::new(BNAPIVersion),
-                Box::new(SerdeVersion {
-                    interval_ms: ANALYSIS.interval_width_ms,
-                }),
-            ], // API first
-            (_, Err(e)) => {
-                log::warn!("⚠️  Local cache validation failed: {:#}", e);
-                log::warn!("⚠️  Falling back to Binance API...");
-                vec![Box::new(BNAPIVersion)] // API only
-            }
+    if args.agg_trades {
+        if let Err(e) = attach_trade_profiles(timeseries_data).await {
+            log::warn!("⚠️  Skipping aggTrades volume profiles: {:#}", e);
         }
-    };
+    }
+    dropped
+}
+
+/// Loads every pair in one go (the web demo, which has its data bundled). Natively the
+/// app loads in the background instead, see `data::pair_loading`.
+pub async fn fetch_pair_data(
+    klines_acceptable_age_secs: i64,
+    args: &Cli,
+) -> Result<(TimeSeriesCollection, &'static str)> {
+    #[cfg(target_arch = "wasm32")]
+    let _ = klines_acceptable_age_secs;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let providers = timeseries_providers(klines_acceptable_age_secs, args, None);
 
     #[cfg(target_arch = "wasm32")]
     let providers: Vec<Box<dyn CreateTimeSeriesData>> = vec![Box::new(WasmDemoData)];
 
-    #[cfg_attr(not(target_arch = "wasm32"), allow(unused_mut))]
-    let (mut timeseries_data, timeseries_signature) = get_timeseries_data_async(&providers)
-        .await
-        .expect("failed to retrieve time series data so exiting main function!");
+    let (mut timeseries_data, timeseries_signature) = get_timeseries_data_async(&providers).await?;
 
     #[cfg(target_arch = "wasm32")]
     {
@@ -88,6 +149,8 @@ pub async fn fetch_pair_data(
         }
     }
 
+    prepare_collection(&mut timeseries_data, args).await;
+
     #[cfg(debug_assertions)]
     if DEBUG_FLAGS.print_serde {
         log::info!(
@@ -96,5 +159,5 @@ pub async fn fetch_pair_data(
         );
         log::info!("Data fetch complete.");
     }
-    (timeseries_data, timeseries_signature)
+    Ok((timeseries_data, timeseries_signature))
 }
@@ -1,87 +1,416 @@
 #[cfg(not(target_arch = "wasm32"))]
+mod shards;
+
+#[cfg(target_arch = "wasm32")]
+use super::tick_tape::TapeSnapshot;
+#[cfg(not(target_arch = "wasm32"))]
+use super::tick_tape::TickRecord;
+use super::tick_tape::{ReplaySpeed, TickTape};
+use super::tick_tape::{TapeCursor, Tick};
+#[cfg(not(target_arch = "wasm32"))]
 use crate::config::BINANCE;
 #[cfg(all(debug_assertions, not(target_arch = "wasm32")))] // Not needed for WASM
 use crate::config::DEBUG_FLAGS;
 #[cfg(not(target_arch = "wasm32"))]
-use futures::StreamExt;
-#[cfg(not(target_arch = "wasm32"))]
-use serde::Deserialize;
+use crate::data::exchange::{StreamEvent, exchange_for};
+use crate::domain::order_book::{BookTicker, DepthSnapshot};
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+use crate::utils::TimeUtils;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
 #[cfg(target_arch = "wasm32")]
 use serde_json;
+#[cfg(not(target_arch = "wasm32"))]
+use shards::ShardSet;
 #[cfg(target_arch = "wasm32")]
 use std::collections::HashMap;
 #[cfg(not(target_arch = "wasm32"))]
-use std::collections::HashMap;
+use crate::domain::venue::Venue;
 #[cfg(not(target_arch = "wasm32"))]
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 #[cfg(not(target_arch = "wasm32"))]
-use std::time::Duration;
+use std::sync::Mutex;
 #[cfg(not(target_arch = "wasm32"))]
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
 #[cfg(target_arch = "wasm32")]
 const DEMO_PRICES_JSON: &str = include_str!(concat!(
     env!("CARGO_MANIFEST_DIR"),
     "/kline_data/demo_prices.json"
 ));
+/// Live ticks recorded by `make_demo_cache`, looped so the demo's prices move
+#[cfg(target_arch = "wasm32")]
+const DEMO_TICKS_BYTES: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/kline_data/demo_ticks.bin"
+));
 
-#[cfg(not(target_arch = "wasm32"))]
-#[derive(Debug, Deserialize)]
-struct MiniTickerData {
-    #[serde(rename = "c")]
-    close_price: String,
-    #[serde(rename = "s")]
-    symbol: String,
+/// Which streams to open per symbol on top of `@miniTicker`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamSelection {
+    /// A `@kline_<interval>` stream per interval, feeding `take_closed_klines`
+    pub kline_intervals: Vec<i64>,
+    /// `@bookTicker`, feeding `get_book`
+    pub book_ticker: bool,
+    /// `@depth<levels>` (5, 10 or 20) feeding `get_depth`; 0 opens no depth stream
+    pub depth_levels: u32,
+}
+
+/// A finished candle from a `@kline_<interval>` stream, waiting to be appended
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedKline {
+    /// Lowercase, like the price map keys
+    pub symbol: String,
+    pub interval_ms: i64,
+    pub open_time_ms: i64,
+    pub open_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub close_price: f64,
+    pub base_volume: f64,
+    pub quote_volume: f64,
+}
+
+/// A live price and when it was made: `event_time_ms` is Binance's clock, `received_ms`
+/// ours. The gap between them is feed lag; the age of `received_ms` is how long the
+/// symbol has been silent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceQuote {
+    pub price: f64,
+    pub event_time_ms: i64,
+    pub received_ms: i64,
 }
 
+impl PriceQuote {
+    /// A quote received now, stamped with `event_time_ms` (or now, if unknown)
+    pub fn received_now(price: f64, event_time_ms: i64) -> Self {
+        let received_ms = local_now_as_timestamp_ms();
+        Self {
+            price,
+            event_time_ms: if event_time_ms > 0 {
+                event_time_ms
+            } else {
+                received_ms
+            },
+            received_ms,
+        }
+    }
+
+    pub fn age_ms(&self, now_ms: i64) -> i64 {
+        (now_ms - self.received_ms).max(0)
+    }
+}
+
+/// Something in the live feeds changed. Symbols are lowercase, like the price map keys;
+/// the data itself is read back with the getters.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceUpdate {
+    Price {
+        symbol: String,
+        quote: PriceQuote,
+    },
+    Book {
+        symbol: String,
+        book: BookTicker,
+    },
+    Depth {
+        symbol: String,
+    },
+    /// Drain `take_closed_klines`
+    ClosedKline {
+        symbol: String,
+    },
+    /// The receiver fell behind and updates were dropped; re-read everything
+    Lagged,
+}
+
+/// Called after every published update, from the stream's own threads (e.g. to wake a
+/// UI that only repaints when something changed)
+pub type UpdateNotifier = Arc<dyn Fn() + Send + Sync>;
+
+/// One subscriber's view of the update channel (see `subscribe_updates`)
 #[cfg(not(target_arch = "wasm32"))]
-#[derive(Debug, Deserialize)]
-struct CombinedStreamMessage {
-    #[serde(rename = "stream")]
-    _stream: String,
-    data: MiniTickerData,
+pub struct PriceUpdates {
+    rx: broadcast::Receiver<PriceUpdate>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
+impl PriceUpdates {
+    /// Next pending update without waiting; `None` once drained
+    pub fn try_next(&mut self) -> Option<PriceUpdate> {
+        match self.rx.try_recv() {
+            Ok(update) => Some(update),
+            Err(TryRecvError::Lagged(_)) => Some(PriceUpdate::Lagged),
+            Err(TryRecvError::Empty | TryRecvError::Closed) => None,
+        }
+    }
+
+    /// Waits for the next update, for consumers that don't run in a render loop.
+    /// `None` once the manager is gone.
+    pub async fn next(&mut self) -> Option<PriceUpdate> {
+        match self.rx.recv().await {
+            Ok(update) => Some(update),
+            Err(RecvError::Lagged(_)) => Some(PriceUpdate::Lagged),
+            Err(RecvError::Closed) => None,
+        }
+    }
+
+    /// Blocking `next`, for a plain thread. Must not be called from async code.
+    pub fn blocking_next(&mut self) -> Option<PriceUpdate> {
+        match self.rx.blocking_recv() {
+            Ok(update) => Some(update),
+            Err(RecvError::Lagged(_)) => Some(PriceUpdate::Lagged),
+            Err(RecvError::Closed) => None,
+        }
+    }
+}
+
+/// Per-symbol feed state. The socket being open isn't enough to be `Connected`: a symbol
+/// whose frames stop arriving for longer than its staleness threshold becomes `Stale`.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ConnectionStatus {
     Connected,
     Connecting,
     Disconnected,
+    /// Socket open, but nothing received for this symbol within its threshold
+    Stale,
 }
 
-/// Manages WebSocket connections to Binance for live price updates
-/// Subscribes to all pairs upfront with automatic reconnection
+/// Everything the stream writes and the app reads, keyed by lowercase pair name
 #[cfg(not(target_arch = "wasm32"))]
-pub struct PriceStreamManager {
-    // Map of symbol -> current price
-    prices: Arc<Mutex<HashMap<String, f64>>>,
-    // Map of symbol -> connection status
-    connection_status: Arc<Mutex<HashMap<String, ConnectionStatus>>>,
-    subscribed_symbols: Arc<Mutex<Vec<String>>>,
+#[derive(Clone)]
+struct LiveFeeds {
+    prices: Arc<Mutex<HashMap<String, PriceQuote>>>,
+    books: Arc<Mutex<HashMap<String, BookTicker>>>,
+    depths: Arc<Mutex<HashMap<String, DepthSnapshot>>>,
+    // Closed candles received since the engine last drained them
+    closed_klines: Arc<Mutex<Vec<ClosedKline>>>,
+    // Map of symbol -> socket status and when it was set (ms)
+    connection_status: Arc<Mutex<HashMap<String, (ConnectionStatus, i64)>>>,
+    // Map of symbol -> when any frame for it last arrived (ms)
+    last_seen: Arc<Mutex<HashMap<String, i64>>>,
+    // Per-symbol staleness thresholds (ms); `BINANCE.ws.stale_after_sec` otherwise
+    stale_after_ms: Arc<Mutex<HashMap<String, i64>>>,
     // Suspension flag - when true, price updates are ignored
     suspended: Arc<Mutex<bool>>,
+    // Every change is published here for the engine (and anyone else) to drain
+    updates: broadcast::Sender<PriceUpdate>,
+    notifier: Arc<Mutex<Option<UpdateNotifier>>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for LiveFeeds {
+    fn default() -> Self {
+        let (updates, _) = broadcast::channel(BINANCE.ws.update_channel_capacity);
+        Self {
+            prices: Default::default(),
+            books: Default::default(),
+            depths: Default::default(),
+            closed_klines: Default::default(),
+            connection_status: Default::default(),
+            last_seen: Default::default(),
+            stale_after_ms: Default::default(),
+            suspended: Default::default(),
+            updates,
+            notifier: Default::default(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LiveFeeds {
+    fn set_status(&self, symbols: impl IntoIterator<Item = String>, status: ConnectionStatus) {
+        let now_ms = local_now_as_timestamp_ms();
+        let mut status_map = self.connection_status.lock().unwrap();
+        for symbol in symbols {
+            status_map.insert(symbol, (status, now_ms));
+        }
+    }
+
+    fn stale_after_ms(&self, symbol: &str) -> i64 {
+        self.stale_after_ms
+            .lock()
+            .unwrap()
+            .get(symbol)
+            .copied()
+            .unwrap_or(BINANCE.ws.stale_after_sec as i64 * 1000)
+    }
+
+    /// Socket status, downgraded to `Stale` when an open socket has carried nothing for
+    /// the symbol (since it connected) for longer than the symbol's threshold
+    fn status_of(&self, symbol: &str, now_ms: i64) -> Option<ConnectionStatus> {
+        let (status, since_ms) = *self.connection_status.lock().unwrap().get(symbol)?;
+        if status != ConnectionStatus::Connected {
+            return Some(status);
+        }
+        let last_seen = self.last_seen.lock().unwrap().get(symbol).copied();
+        let last_activity = last_seen.unwrap_or(since_ms).max(since_ms);
+        if now_ms - last_activity > self.stale_after_ms(symbol) {
+            Some(ConnectionStatus::Stale)
+        } else {
+            Some(status)
+        }
+    }
+
+    fn publish(&self, update: PriceUpdate) {
+        // Nobody subscribed yet is fine; the maps still hold the latest values
+        let _ = self.updates.send(update);
+        let notifier = self.notifier.lock().unwrap().clone();
+        if let Some(notify) = notifier {
+            notify();
+        }
+    }
+
+    fn set_price(&self, symbol: String, quote: PriceQuote) {
+        self.prices.lock().unwrap().insert(symbol.clone(), quote);
+        self.publish(PriceUpdate::Price { symbol, quote });
+    }
+
+    fn set_book(&self, symbol: String, book: BookTicker) {
+        self.books.lock().unwrap().insert(symbol.clone(), book);
+        self.publish(PriceUpdate::Book { symbol, book });
+    }
+
+    fn mark_seen(&self, symbol: &str) {
+        self.last_seen
+            .lock()
+            .unwrap()
+            .insert(symbol.to_string(), local_now_as_timestamp_ms());
+    }
+
+    /// Drops everything known about a symbol that is no longer streamed
+    fn forget(&self, symbol: &str) {
+        self.prices.lock().unwrap().remove(symbol);
+        self.books.lock().unwrap().remove(symbol);
+        self.depths.lock().unwrap().remove(symbol);
+        self.connection_status.lock().unwrap().remove(symbol);
+        self.last_seen.lock().unwrap().remove(symbol);
+    }
+}
+
+/// Manages WebSocket connections to each pair's venue for live price updates
+/// Symbols are sharded over several connections with automatic reconnection, and can be
+/// added or removed at any time by subscribing again.
+/// Built with `replaying`, it plays a recorded tape instead and opens no connections.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct PriceStreamManager {
+    feeds: LiveFeeds,
+    shards: Mutex<ShardSet>,
+    replay: Option<Mutex<Option<(TickTape, ReplaySpeed)>>>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl PriceStreamManager {
     pub fn new() -> Self {
         Self {
-            prices: Arc::new(Mutex::new(HashMap::new())),
-            connection_status: Arc::new(Mutex::new(HashMap::new())),
-            subscribed_symbols: Arc::new(Mutex::new(Vec::new())),
-            suspended: Arc::new(Mutex::new(false)),
+            feeds: LiveFeeds::default(),
+            shards: Mutex::new(ShardSet::default()),
+            replay: None,
         }
     }
 
-    /// Get the current live price for a symbol
+    /// A manager fed from a recorded tape (see `data::tick_tape`) instead of Binance.
+    /// Playback starts with the first subscription, so subscribers created before it
+    /// see every tick.
+    pub fn replaying(tape: TickTape, speed: ReplaySpeed) -> Self {
+        Self {
+            replay: Some(Mutex::new(Some((tape, speed)))),
+            ..Self::new()
+        }
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Get the current live price for a symbol, however old (see `get_fresh_price`)
     pub fn get_price(&self, symbol: &str) -> Option<f64> {
+        self.get_quote(symbol).map(|quote| quote.price)
+    }
+
+    /// The current live price with its event and receipt times
+    pub fn get_quote(&self, symbol: &str) -> Option<PriceQuote> {
         let symbol_lower = symbol.to_lowercase();
-        self.prices.lock().unwrap().get(&symbol_lower).copied()
+        self.feeds
+            .prices
+            .lock()
+            .unwrap()
+            .get(&symbol_lower)
+            .copied()
+    }
+
+    /// The live price, unless it is older than the symbol's staleness threshold
+    pub fn get_fresh_price(&self, symbol: &str) -> Option<f64> {
+        self.get_quote(symbol)
+            .filter(|quote| !self.is_quote_stale(symbol, quote, local_now_as_timestamp_ms()))
+            .map(|quote| quote.price)
+    }
+
+    /// True if there is no price for the symbol, or it hasn't changed hands (or been
+    /// re-sent) within its staleness threshold
+    pub fn is_price_stale(&self, symbol: &str) -> bool {
+        self.get_quote(symbol)
+            .is_none_or(|quote| self.is_quote_stale(symbol, &quote, local_now_as_timestamp_ms()))
+    }
+
+    fn is_quote_stale(&self, symbol: &str, quote: &PriceQuote, now_ms: i64) -> bool {
+        let threshold_ms = self.feeds.stale_after_ms(&symbol.to_lowercase());
+        quote.age_ms(now_ms) > threshold_ms
+    }
+
+    /// Replaces the per-symbol staleness thresholds. Symbols left out use
+    /// `BINANCE.ws.stale_after_sec`.
+    pub fn set_stale_thresholds(&self, thresholds_sec: HashMap<String, u64>) {
+        *self.feeds.stale_after_ms.lock().unwrap() = thresholds_sec
+            .into_iter()
+            .map(|(symbol, sec)| (symbol.to_lowercase(), sec as i64 * 1000))
+            .collect();
+    }
+
+    /// Data-flow status of one symbol; `None` if it isn't streamed
+    pub fn connection_status(&self, symbol: &str) -> Option<ConnectionStatus> {
+        self.feeds
+            .status_of(&symbol.to_lowercase(), local_now_as_timestamp_ms())
+    }
+
+    /// Streamed symbols that are currently `Stale`, sorted
+    pub fn stale_symbols(&self) -> Vec<String> {
+        let now_ms = local_now_as_timestamp_ms();
+        let symbols: Vec<String> = self
+            .feeds
+            .connection_status
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        let mut stale: Vec<String> = symbols
+            .into_iter()
+            .filter(|symbol| self.feeds.status_of(symbol, now_ms) == Some(ConnectionStatus::Stale))
+            .collect();
+        stale.sort();
+        stale
+    }
+
+    /// Best bid and ask, if `@bookTicker` is streamed for this symbol
+    pub fn get_book(&self, symbol: &str) -> Option<BookTicker> {
+        let symbol_lower = symbol.to_lowercase();
+        self.feeds.books.lock().unwrap().get(&symbol_lower).copied()
+    }
+
+    /// Latest order-book snapshot, if `@depth<N>` is streamed for this symbol
+    pub fn get_depth(&self, symbol: &str) -> Option<DepthSnapshot> {
+        let symbol_lower = symbol.to_lowercase();
+        self.feeds
+            .depths
+            .lock()
+            .unwrap()
+            .get(&symbol_lower)
+            .cloned()
     }
 
     /// Suspend price updates (for simulation mode)
     pub fn suspend(&self) {
-        *self.suspended.lock().unwrap() = true;
+        *self.feeds.suspended.lock().unwrap() = true;
         #[cfg(debug_assertions)]
         if DEBUG_FLAGS.print_simulation_events {
             log::info!("🔇 WebSocket price updates suspended");
@@ -90,7 +419,7 @@ impl PriceStreamManager {
 
     /// Resume price updates (exit simulation mode)
     pub fn resume(&self) {
-        *self.suspended.lock().unwrap() = false;
+        *self.feeds.suspended.lock().unwrap() = false;
         #[cfg(debug_assertions)]
         if DEBUG_FLAGS.print_simulation_events {
             log::info!("🔊 WebSocket price updates resumed");
@@ -99,60 +428,121 @@ impl PriceStreamManager {
 
     /// Check if price updates are suspended
     pub fn is_suspended(&self) -> bool {
-        *self.suspended.lock().unwrap()
+        *self.feeds.suspended.lock().unwrap()
     }
 
-    /// Get overall connection health (percentage of connected streams)
+    /// Get overall connection health (percentage of symbols connected and receiving data)
     pub fn connection_health(&self) -> f64 {
-        let status_map = self.connection_status.lock().unwrap();
-        if status_map.is_empty() {
+        let now_ms = local_now_as_timestamp_ms();
+        let symbols: Vec<String> = self
+            .feeds
+            .connection_status
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        if symbols.is_empty() {
             return 0.0;
         }
-        let connected = status_map
-            .values()
-            .filter(|&&s| s == ConnectionStatus::Connected)
+        let connected = symbols
+            .iter()
+            .filter(|symbol| {
+                self.feeds.status_of(symbol, now_ms) == Some(ConnectionStatus::Connected)
+            })
             .count();
-        (connected as f64 / status_map.len() as f64) * 100.0
+        (connected as f64 / symbols.len() as f64) * 100.0
+    }
+
+    /// Number of WebSocket connections the symbols are spread over
+    pub fn connection_count(&self) -> usize {
+        self.shards.lock().unwrap().len()
+    }
+
+    /// A receiver for every change from now on. Each subscriber gets its own copy of
+    /// the stream; one that falls `BINANCE.ws.update_channel_capacity` behind gets
+    /// `PriceUpdate::Lagged` instead of the dropped updates.
+    pub fn subscribe_updates(&self) -> PriceUpdates {
+        PriceUpdates {
+            rx: self.feeds.updates.subscribe(),
+        }
+    }
+
+    /// Replaces the callback run after each published update
+    pub fn set_update_notifier(&self, notify: UpdateNotifier) {
+        *self.feeds.notifier.lock().unwrap() = Some(notify);
+    }
+
+    /// Closed candles received since the last call, oldest first
+    pub fn take_closed_klines(&self) -> Vec<ClosedKline> {
+        std::mem::take(&mut *self.feeds.closed_klines.lock().unwrap())
     }
 
     pub fn subscribe_all(&self, symbols: Vec<String>) {
-        let symbols_lower: Vec<String> = symbols.iter().map(|s| s.to_lowercase()).collect();
-        
-        let mut subscribed = self.subscribed_symbols.lock().unwrap();
-        if *subscribed == symbols_lower {
-            return; 
-        }
-        
-        log::info!(">>> PriceStream: Requesting {} pairs: {:?}", symbols_lower.len(), symbols_lower);
-
-        *subscribed = symbols_lower.clone();
-        
-        // Clone Arcs to move into the background thread
-        let prices_arc = self.prices.clone();
-        let status_arc = self.connection_status.clone();
-        let suspended_arc = self.suspended.clone();
-        
-        // Clone symbol list for the warmup call
-        let symbols_for_warmup = symbols_lower.clone();
-
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
-            rt.block_on(async move {
-                // 1. PULL (Batch Snapshot)
-                // This runs ONCE at startup to populate the cache immediately
-                warm_up_prices(prices_arc.clone(), &symbols_for_warmup).await;
-
-                // 2. PUSH (Live Updates)
-                // Then we enter the infinite WebSocket loop to keep prices fresh
-                run_combined_price_stream_with_reconnect(
-                    symbols_lower,
-                    prices_arc,
-                    status_arc,
-                    suspended_arc,
-                )
-                .await;
+        self.subscribe_all_with(symbols, StreamSelection::default());
+    }
+
+    /// Streams exactly `symbols`, each with the extra streams in `selection`
+    pub fn subscribe_all_with(&self, symbols: Vec<String>, selection: StreamSelection) {
+        self.subscribe_each(
+            symbols
+                .into_iter()
+                .map(|symbol| (symbol, selection.clone()))
+                .collect(),
+        );
+    }
+
+    /// Streams exactly the symbols in `selections`, each with its own extra streams (e.g.
+    /// the kline interval it is analysed at). Can be called again at any time: only the
+    /// difference is subscribed or unsubscribed, and dropped symbols are forgotten.
+    pub fn subscribe_each(&self, selections: Vec<(String, StreamSelection)>) {
+        if let Some(replay) = &self.replay {
+            // The tape decides what is streamed
+            if let Some((tape, speed)) = replay.lock().unwrap().take() {
+                let feeds = self.feeds.clone();
+                self.shards
+                    .lock()
+                    .unwrap()
+                    .runtime()
+                    .spawn(run_replay(feeds, tape, speed));
+            }
+            return;
+        }
+
+        let selections: Vec<(String, StreamSelection)> = selections
+            .into_iter()
+            .map(|(symbol, selection)| (symbol.to_lowercase(), selection))
+            .collect();
+
+        let mut shards = self.shards.lock().unwrap();
+        let changes = shards.update(&selections);
+        shards.connect_new(&self.feeds);
+        if changes.added.is_empty() && changes.removed.is_empty() && changes.restreamed.is_empty() {
+            return;
+        }
+
+        log::info!(
+            ">>> PriceStream: {} pairs over {} connections (+{:?} -{:?} ~{:?})",
+            selections.len(),
+            shards.len(),
+            changes.added,
+            changes.removed,
+            changes.restreamed
+        );
+
+        for symbol in &changes.removed {
+            self.feeds.forget(symbol);
+        }
+
+        // PULL (Batch Snapshot) so new pairs have a price before their first tick.
+        // The shards PUSH live updates from then on.
+        if !changes.added.is_empty() {
+            let feeds = self.feeds.clone();
+            let added = changes.added;
+            shards.runtime().spawn(async move {
+                warm_up_prices(feeds, &added).await;
             });
-        });
+        }
     }
 }
 
@@ -163,10 +553,33 @@ impl Default for PriceStreamManager {
     }
 }
 
+/// A tape played on a loop from when it was loaded. The browser has no background
+/// tasks, so readers work out where the tape is from the clock whenever they look.
+#[cfg(target_arch = "wasm32")]
+struct LoopedTape {
+    tape: TickTape,
+    speed: ReplaySpeed,
+    started: crate::utils::app_time::AppInstant,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LoopedTape {
+    fn tape_ms(&self) -> i64 {
+        let first_ms = self.tape.first_ms().unwrap_or(0);
+        let span_ms = self.tape.duration_ms() + 1;
+        match self.speed.tape_elapsed_ms(self.started.elapsed()) {
+            Some(elapsed_ms) => first_ms + elapsed_ms % span_ms,
+            None => first_ms + span_ms,
+        }
+    }
+}
+
+/// Static demo prices, moved by the bundled demo tape where it has ticks
 #[cfg(target_arch = "wasm32")]
-#[derive(Clone)]
 pub struct PriceStreamManager {
     prices: HashMap<String, f64>,
+    tape: Option<Arc<LoopedTape>>,
+    cursor: std::sync::Mutex<TapeCursor>,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -179,18 +592,76 @@ impl Default for PriceStreamManager {
 #[cfg(target_arch = "wasm32")]
 impl PriceStreamManager {
     pub fn new() -> Self {
+        match TickTape::from_bytes(DEMO_TICKS_BYTES) {
+            Ok(tape) => Self::replaying(tape, ReplaySpeed::REAL_TIME),
+            Err(e) => {
+                log::warn!("Demo tick tape unavailable, prices stay static: {:#}", e);
+                Self::replaying(TickTape::default(), ReplaySpeed::REAL_TIME)
+            }
+        }
+    }
+
+    pub fn replaying(tape: TickTape, speed: ReplaySpeed) -> Self {
         let parsed: HashMap<String, f64> =
             serde_json::from_str(DEMO_PRICES_JSON).unwrap_or_default();
         let mut prices = HashMap::new();
         for (symbol, price) in parsed {
             prices.insert(symbol.to_lowercase(), price);
         }
-        Self { prices }
+        let tape = (!tape.records.is_empty()).then(|| {
+            Arc::new(LoopedTape {
+                tape,
+                speed,
+                started: crate::utils::app_time::now(),
+            })
+        });
+        Self {
+            prices,
+            tape,
+            cursor: std::sync::Mutex::new(TapeCursor::default()),
+        }
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.tape.is_some()
+    }
+
+    /// The tape's latest values at the current loop position
+    fn sampled<T>(&self, read: impl FnOnce(&TapeSnapshot) -> Option<T>) -> Option<T> {
+        let looped = self.tape.as_ref()?;
+        let mut cursor = self.cursor.lock().unwrap();
+        cursor.advance(&looped.tape, looped.tape_ms());
+        read(&cursor.latest)
     }
 
     pub fn get_price(&self, symbol: &str) -> Option<f64> {
         let symbol_lower = symbol.to_lowercase();
-        self.prices.get(&symbol_lower).copied()
+        self.sampled(|latest| latest.prices.get(&symbol_lower).copied())
+            .or_else(|| self.prices.get(&symbol_lower).copied())
+    }
+
+    pub fn get_quote(&self, symbol: &str) -> Option<PriceQuote> {
+        self.get_price(symbol)
+            .map(|price| PriceQuote::received_now(price, 0))
+    }
+
+    // Demo prices are static by design, so they never go stale
+    pub fn get_fresh_price(&self, symbol: &str) -> Option<f64> {
+        self.get_price(symbol)
+    }
+
+    pub fn is_price_stale(&self, _symbol: &str) -> bool {
+        false
+    }
+
+    pub fn set_stale_thresholds(&self, _thresholds_sec: HashMap<String, u64>) {}
+
+    pub fn connection_status(&self, symbol: &str) -> Option<ConnectionStatus> {
+        self.get_price(symbol).map(|_| ConnectionStatus::Connected)
+    }
+
+    pub fn stale_symbols(&self) -> Vec<String> {
+        Vec::new()
     }
 
     pub fn suspend(&self) {}
@@ -205,288 +676,357 @@ impl PriceStreamManager {
         100.0
     }
 
+    pub fn connection_count(&self) -> usize {
+        0
+    }
+
     pub fn subscribe_all(&self, _symbols: Vec<String>) {}
-}
 
-/// Wrapper that handles reconnection logic with exponential backoff for a combined stream
-#[cfg(not(target_arch = "wasm32"))]
-async fn run_combined_price_stream_with_reconnect(
-    symbols: Vec<String>,
-    prices_arc: Arc<Mutex<HashMap<String, f64>>>,
-    status_arc: Arc<Mutex<HashMap<String, ConnectionStatus>>>,
-    suspended_arc: Arc<Mutex<bool>>,
-) {
-    let mut reconnect_delay = BINANCE.ws.initial_reconnect_delay_sec;
-
-    loop {
-        // Update status to connecting for every tracked symbol
-        {
-            let mut status_map = status_arc.lock().unwrap();
-            for symbol in &symbols {
-                status_map.insert(symbol.clone(), ConnectionStatus::Connecting);
-            }
+    pub fn subscribe_all_with(&self, _symbols: Vec<String>, _selection: StreamSelection) {}
+
+    pub fn subscribe_each(&self, _selections: Vec<(String, StreamSelection)>) {}
+
+    pub fn get_book(&self, symbol: &str) -> Option<BookTicker> {
+        let symbol_lower = symbol.to_lowercase();
+        self.sampled(|latest| latest.books.get(&symbol_lower).copied())
+    }
+
+    pub fn get_depth(&self, _symbol: &str) -> Option<DepthSnapshot> {
+        None
+    }
+
+    pub fn take_closed_klines(&self) -> Vec<ClosedKline> {
+        Vec::new()
+    }
+
+    pub fn subscribe_updates(&self) -> PriceUpdates {
+        PriceUpdates {
+            tape: self.tape.clone(),
+            cursor: TapeCursor::default(),
+            pending: std::collections::VecDeque::new(),
         }
+    }
 
-        let url = build_combined_stream_url(&symbols);
-
-        // Attempt connection
-        match run_combined_price_stream(
-            &symbols,
-            &url,
-            prices_arc.clone(),
-            status_arc.clone(),
-            suspended_arc.clone(),
-        )
-        .await
-        {
-            Ok(_) => {
-                // Connection closed normally (24-hour timeout or server close)
-                #[cfg(debug_assertions)]
-                if DEBUG_FLAGS.print_price_stream_updates {
-                    log::info!("Connection closed for combined stream, reconnecting...");
-                }
-
-                // Reset delay on successful connection that later closes
-                reconnect_delay = BINANCE.ws.initial_reconnect_delay_sec;
-            }
-            Err(e) => {
-                // Connection failed
-                log::error!("Price stream error: {}", e);
-
-                // Update status for every symbol
-                {
-                    let mut status_map = status_arc.lock().unwrap();
-                    for symbol in &symbols {
-                        status_map.insert(symbol.clone(), ConnectionStatus::Disconnected);
-                    }
-                }
-
-                // Exponential backoff
-                #[cfg(debug_assertions)]
-                if DEBUG_FLAGS.print_price_stream_updates {
-                    log::info!(
-                        "Reconnecting combined price stream in {} seconds...",
-                        reconnect_delay
-                    );
-                }
-                tokio::time::sleep(Duration::from_secs(reconnect_delay)).await;
-
-                // Increase delay for next attempt (capped at max)
-                reconnect_delay = (reconnect_delay * 2).min(BINANCE.ws.max_reconnect_delay_sec);
+    pub fn set_update_notifier(&self, _notify: UpdateNotifier) {}
+}
+
+/// The demo tape's ticks, as they come due on the loop (nothing without a tape)
+#[cfg(target_arch = "wasm32")]
+pub struct PriceUpdates {
+    tape: Option<Arc<LoopedTape>>,
+    cursor: TapeCursor,
+    pending: std::collections::VecDeque<PriceUpdate>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl PriceUpdates {
+    pub fn try_next(&mut self) -> Option<PriceUpdate> {
+        if self.pending.is_empty() {
+            let looped = self.tape.as_ref()?;
+            for record in self.cursor.advance(&looped.tape, looped.tape_ms()) {
+                self.pending.push_back(match record.tick {
+                    Tick::Price { price } => PriceUpdate::Price {
+                        symbol: record.symbol.clone(),
+                        quote: PriceQuote::received_now(price, record.event_time_ms),
+                    },
+                    Tick::Book { book } => PriceUpdate::Book {
+                        symbol: record.symbol.clone(),
+                        book,
+                    },
+                });
             }
         }
+        self.pending.pop_front()
+    }
 
-        // Small delay before reconnecting even on normal close
-        tokio::time::sleep(Duration::from_secs(1)).await;
+    pub async fn next(&mut self) -> Option<PriceUpdate> {
+        self.try_next()
     }
 }
 
+/// Routes one parsed stream frame from `venue` to its feed. Live quotes are dropped while
+/// suspended; closed candles are history, so they are kept either way.
 #[cfg(not(target_arch = "wasm32"))]
-async fn run_combined_price_stream(
-    symbols: &[String],
-    url: &str,
-    prices_arc: Arc<Mutex<HashMap<String, f64>>>,
-    status_arc: Arc<Mutex<HashMap<String, ConnectionStatus>>>,
-    suspended_arc: Arc<Mutex<bool>>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    #[cfg(debug_assertions)]
-    if DEBUG_FLAGS.print_price_stream_updates {
-        log::info!("Connecting to Binance combined WebSocket: {}", url);
-    }
-
-    let (ws_stream, _) = connect_async(url).await?;
-
-    // Update status to connected
-    {
-        let mut status_map = status_arc.lock().unwrap();
-        for symbol in symbols {
-            status_map.insert(symbol.clone(), ConnectionStatus::Connected);
+fn apply_stream_data(venue: Venue, symbol: &str, event: Option<StreamEvent>, feeds: &LiveFeeds) {
+    let symbol = venue.qualify(symbol);
+    // Any frame shows the symbol's feed is alive, suspended or not
+    feeds.mark_seen(&symbol);
+
+    let Some(event) = event else {
+        return;
+    };
+    if let StreamEvent::ClosedKline(mut kline) = event {
+        kline.symbol = symbol.clone();
+        #[cfg(debug_assertions)]
+        if DEBUG_FLAGS.print_price_stream_updates {
+            log::info!(
+                "[price-stream] {} closed {} candle at {:.6}",
+                kline.symbol,
+                TimeUtils::interval_to_string(kline.interval_ms),
+                kline.close_price
+            );
         }
+        feeds.closed_klines.lock().unwrap().push(kline);
+        feeds.publish(PriceUpdate::ClosedKline { symbol });
+        return;
     }
-
-    #[cfg(debug_assertions)]
-    if DEBUG_FLAGS.print_price_stream_updates {
-        log::info!(
-            "✓ Connected to combined price stream for {} symbols",
-            symbols.len()
-        );
+    if *feeds.suspended.lock().unwrap() {
+        return;
     }
-    let (_write, mut read) = ws_stream.split();
-
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Ok(wrapper) = serde_json::from_str::<CombinedStreamMessage>(&text) {
-                    match wrapper.data.close_price.parse::<f64>() {
-                        Ok(price) => {
-                            // Only update prices if not suspended
-                            let is_suspended = *suspended_arc.lock().unwrap();
-                            if !is_suspended {
-                                let symbol_lower = wrapper.data.symbol.to_lowercase();
-                                if symbols.contains(&symbol_lower) {
-                                    prices_arc
-                                        .lock()
-                                        .unwrap()
-                                        .insert(symbol_lower.clone(), price);
-
-                                    #[cfg(debug_assertions)]
-                                    if DEBUG_FLAGS.print_price_stream_updates {
-                                        log::info!(
-                                            "[price-stream] {} -> {:.6}",
-                                            wrapper.data.symbol,
-                                            price
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                        Err(parse_err) => {
-                            log::error!(
-                                "⚠️ Failed to parse miniTicker price '{}' for {}: {}",
-                                wrapper.data.close_price,
-                                wrapper.data.symbol,
-                                parse_err
-                            );
-                            #[cfg(debug_assertions)]
-                            if DEBUG_FLAGS.print_price_stream_updates {
-                                log::info!("[price-stream] raw payload: {}", text);
-                            }
-                        }
-                    }
-                } else {
-                    #[cfg(debug_assertions)]
-                    if DEBUG_FLAGS.print_price_stream_updates {
-                        log::error!("⚠️ Unexpected combined stream payload: {}", text);
-                    }
-                }
-            }
-            Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {
-                // WebSocket keepalive - handled automatically
-            }
-            Ok(Message::Close(_)) => {
-                #[cfg(debug_assertions)]
-                if DEBUG_FLAGS.print_price_stream_updates {
-                    log::info!("Combined WebSocket closed (likely 24hr timeout)");
-                }
-                break;
-            }
-            Err(e) => {
-                log::error!("WebSocket error: {}", e);
-                return Err(e.into());
+
+    match event {
+        StreamEvent::Price(quote) => {
+            #[cfg(debug_assertions)]
+            if DEBUG_FLAGS.print_price_stream_updates {
+                log::info!("[price-stream] {} -> {:.6}", symbol, quote.price);
             }
-            _ => {}
+            feeds.set_price(symbol, quote);
         }
+        StreamEvent::Book(book) => feeds.set_book(symbol, book),
+        StreamEvent::Depth(depth) => {
+            feeds.depths.lock().unwrap().insert(symbol.clone(), depth);
+            feeds.publish(PriceUpdate::Depth { symbol });
+        }
+        StreamEvent::ClosedKline(_) => unreachable!("handled above"),
     }
+}
 
-    // Update status on disconnect
-    {
-        let mut status_map = status_arc.lock().unwrap();
-        for symbol in symbols {
-            status_map.insert(symbol.clone(), ConnectionStatus::Disconnected);
+/// Plays a tape into the feeds as if it were arriving now: received times are rewritten,
+/// event times kept. Like live quotes, ticks are dropped while suspended.
+#[cfg(not(target_arch = "wasm32"))]
+async fn run_replay(feeds: LiveFeeds, tape: TickTape, speed: ReplaySpeed) {
+    let Some(first_ms) = tape.first_ms() else {
+        log::warn!(">>> PriceStream: Tick tape is empty, nothing to replay");
+        return;
+    };
+    let symbols: std::collections::BTreeSet<String> =
+        tape.records.iter().map(|r| r.symbol.clone()).collect();
+    log::info!(
+        ">>> PriceStream: Replaying {} ticks for {} pairs ({}s recorded) at {}",
+        tape.records.len(),
+        symbols.len(),
+        tape.duration_ms() / 1000,
+        speed
+    );
+    feeds.set_status(symbols, ConnectionStatus::Connected);
+
+    let started = tokio::time::Instant::now();
+    let mut cursor = TapeCursor::default();
+    while let Some(due_ms) = cursor.next_due_ms(&tape) {
+        tokio::time::sleep_until(started + speed.wall_duration(due_ms - first_ms)).await;
+        let tape_ms = speed
+            .tape_elapsed_ms(started.elapsed())
+            .map_or(due_ms, |elapsed| (first_ms + elapsed).max(due_ms));
+        for record in cursor.advance(&tape, tape_ms) {
+            apply_tick(&feeds, record);
+        }
+        if speed == ReplaySpeed::Max {
+            // Let the receivers keep up
+            tokio::task::yield_now().await;
         }
     }
+    log::info!(">>> PriceStream: Replay finished");
+}
 
-    Ok(())
+#[cfg(not(target_arch = "wasm32"))]
+fn apply_tick(feeds: &LiveFeeds, record: &TickRecord) {
+    feeds.mark_seen(&record.symbol);
+    if *feeds.suspended.lock().unwrap() {
+        return;
+    }
+    match record.tick {
+        Tick::Price { price } => feeds.set_price(
+            record.symbol.clone(),
+            PriceQuote {
+                price,
+                event_time_ms: record.event_time_ms,
+                received_ms: local_now_as_timestamp_ms(),
+            },
+        ),
+        Tick::Book { book } => feeds.set_book(record.symbol.clone(), book),
+    }
 }
 
+/// PULL (batch snapshot) of the last price of `symbols` (lowercase pair names), one
+/// request per venue
 #[cfg(not(target_arch = "wasm32"))]
-fn build_combined_stream_url(symbols: &[String]) -> String {
-    let stream_descriptor = symbols
-        .iter()
-        .map(|symbol| format!("{}@miniTicker", symbol))
-        .collect::<Vec<_>>()
-        .join("/");
-
-    format!("{}{}", BINANCE.ws.combined_base_url, stream_descriptor)
+async fn warm_up_prices(feeds: LiveFeeds, symbols: &[String]) {
+    log::info!(">>> PriceStream: Warming up price cache via REST API...");
+
+    let mut by_venue: BTreeMap<Venue, Vec<&str>> = BTreeMap::new();
+    for symbol in symbols {
+        let (venue, local) = Venue::split(symbol);
+        by_venue.entry(venue).or_default().push(local);
+    }
+
+    let mut updated_count = 0;
+    for (venue, wanted) in by_venue {
+        let prices = match exchange_for(venue).fetch_prices().await {
+            Ok(prices) => prices,
+            Err(e) => {
+                log::error!(">>> PriceStream: {} warmup request failed: {:#}", venue, e);
+                continue;
+            }
+        };
+        for local in wanted {
+            if let Some(&price) = prices.get(&local.to_uppercase()) {
+                // No event time in the REST ticker
+                feeds.set_price(venue.qualify(local), PriceQuote::received_now(price, 0));
+                updated_count += 1;
+            }
+        }
+    }
+    log::info!(
+        ">>> PriceStream: Warmup complete. Updated {}/{} pairs.",
+        updated_count,
+        symbols.len()
+    );
 }
 
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
 
-#[cfg(not(target_arch = "wasm32"))]
-use binance_sdk::spot::SpotRestApi;
-#[cfg(not(target_arch = "wasm32"))]
-use binance_sdk::config::ConfigurationRestApi;
-#[cfg(not(target_arch = "wasm32"))]
-use std::collections::HashSet;
-#[cfg(not(target_arch = "wasm32"))]
-use crate::config::{BinanceApiConfig};
-#[cfg(not(target_arch = "wasm32"))]
-use binance_sdk::spot::rest_api::{TickerPriceParams, TickerPriceResponse};
+    const NOW_MS: i64 = 1_700_000_000_000;
 
+    fn default_threshold_ms() -> i64 {
+        BINANCE.ws.stale_after_sec as i64 * 1000
+    }
 
-#[cfg(not(target_arch = "wasm32"))]
-async fn warm_up_prices(prices_arc: Arc<Mutex<HashMap<String, f64>>>, symbols: &[String]) {
-    
-    log::info!(">>> PriceStream: Warming up price cache via REST API...");
+    fn connected(feeds: &LiveFeeds, symbol: &str, since_ms: i64) {
+        feeds
+            .connection_status
+            .lock()
+            .unwrap()
+            .insert(symbol.to_string(), (ConnectionStatus::Connected, since_ms));
+    }
 
-    let config = BinanceApiConfig::default();
-    
-    let rest_conf = ConfigurationRestApi::builder()
-        .timeout(config.timeout_ms)
-        .retries(config.retries)
-        .backoff(config.backoff_ms)
-        .build()
-        .expect("Failed to build Binance REST config");
-
-    let client = SpotRestApi::production(rest_conf);
-
-    let params = TickerPriceParams {
-        symbol: None,
-        symbols: None,
-        symbol_status: None,
-    };
+    fn seen(feeds: &LiveFeeds, symbol: &str, at_ms: i64) {
+        feeds
+            .last_seen
+            .lock()
+            .unwrap()
+            .insert(symbol.to_string(), at_ms);
+    }
 
-    // 1. Make the Request
-    match client.ticker_price(params).await {
-        Ok(response) => {
-            // 2. Await the data extraction (It returns a Result<TickerPriceResponse>)
-            match response.data().await {
-                Ok(ticker_data) => {
-                    match ticker_data {
-                        // 3. Match the Vector Variant
-                        TickerPriceResponse::TickerPriceResponse2(all_tickers) => {
-                            let mut p_lock = prices_arc.lock().unwrap();
-                            let mut updated_count = 0;
-                            
-                            let wanted_set: HashSet<String> = symbols.iter()
-                                .map(|s| s.to_lowercase())
-                                .collect();
-
-                            for ticker in all_tickers {
-                                // 4. Safely handle Option fields (symbol/price might be None)
-                                if let (Some(s), Some(p)) = (&ticker.symbol, &ticker.price) {
-                                    let symbol_lower = s.to_lowercase();
-                                    
-                                    if wanted_set.contains(&symbol_lower) {
-                                        let price = p.parse::<f64>().unwrap_or(0.0);
-                                        if price > 0.0 {
-                                            p_lock.insert(symbol_lower, price);
-                                            updated_count += 1;
-                                        }
-                                    }
-                                }
-                            }
-                            log::info!(
-                                ">>> PriceStream: Warmup complete. Updated {}/{} pairs.",
-                                updated_count,
-                                symbols.len()
-                            );
-                        },
-                        TickerPriceResponse::TickerPriceResponse1(_) => {
-                            log::warn!(">>> PriceStream: Unexpected 'Single' response type during batch warmup.");
-                        },
-                        _ => {
-                            log::warn!(">>> PriceStream: Unexpected 'Other' response type.");
-                        }
-                    }
-                },
-                Err(e) => {
-                    log::error!(">>> PriceStream: Failed to parse response data: {:?}", e);
-                }
-            }
+    fn quote_received(at_ms: i64) -> PriceQuote {
+        PriceQuote {
+            price: 100.0,
+            event_time_ms: at_ms,
+            received_ms: at_ms,
         }
-        Err(e) => {
-            log::error!(">>> PriceStream: Warmup request failed: {:?}", e);
+    }
+
+    #[test]
+    fn thresholds_default_to_the_config_and_can_be_set_per_symbol() {
+        let manager = PriceStreamManager::new();
+        let default_ms = default_threshold_ms();
+        assert_eq!(manager.feeds.stale_after_ms("btcusdt"), default_ms);
+
+        manager.set_stale_thresholds(HashMap::from([("ETHUSDT".to_string(), 5)]));
+        assert_eq!(manager.feeds.stale_after_ms("ethusdt"), 5_000);
+        assert_eq!(manager.feeds.stale_after_ms("btcusdt"), default_ms);
+    }
+
+    #[test]
+    fn a_connected_symbol_goes_stale_once_frames_stop_for_longer_than_its_threshold() {
+        let feeds = LiveFeeds::default();
+        let threshold_ms = default_threshold_ms();
+        connected(&feeds, "btcusdt", NOW_MS - 10 * threshold_ms);
+        seen(&feeds, "btcusdt", NOW_MS - threshold_ms);
+
+        // Fresh: the last frame is exactly at the threshold
+        assert_eq!(
+            feeds.status_of("btcusdt", NOW_MS),
+            Some(ConnectionStatus::Connected)
+        );
+        assert_eq!(
+            feeds.status_of("btcusdt", NOW_MS + 1),
+            Some(ConnectionStatus::Stale)
+        );
+
+        // A shorter threshold of its own makes it stale sooner
+        feeds
+            .stale_after_ms
+            .lock()
+            .unwrap()
+            .insert("btcusdt".to_string(), 1_000);
+        assert_eq!(
+            feeds.status_of("btcusdt", NOW_MS - threshold_ms + 1_000),
+            Some(ConnectionStatus::Connected)
+        );
+        assert_eq!(
+            feeds.status_of("btcusdt", NOW_MS - threshold_ms + 1_001),
+            Some(ConnectionStatus::Stale)
+        );
+    }
+
+    #[test]
+    fn a_symbol_never_received_is_timed_from_when_it_connected() {
+        let feeds = LiveFeeds::default();
+        let threshold_ms = default_threshold_ms();
+        assert_eq!(feeds.status_of("btcusdt", NOW_MS), None);
+
+        connected(&feeds, "btcusdt", NOW_MS);
+        assert_eq!(
+            feeds.status_of("btcusdt", NOW_MS + threshold_ms),
+            Some(ConnectionStatus::Connected)
+        );
+        assert_eq!(
+            feeds.status_of("btcusdt", NOW_MS + threshold_ms + 1),
+            Some(ConnectionStatus::Stale)
+        );
+
+        // Frames from before a reconnect don't count against the new connection
+        seen(&feeds, "btcusdt", NOW_MS - 10 * threshold_ms);
+        assert_eq!(
+            feeds.status_of("btcusdt", NOW_MS + 1),
+            Some(ConnectionStatus::Connected)
+        );
+    }
+
+    #[test]
+    fn only_an_open_socket_is_reported_stale() {
+        let feeds = LiveFeeds::default();
+        for status in [ConnectionStatus::Connecting, ConnectionStatus::Disconnected] {
+            feeds
+                .connection_status
+                .lock()
+                .unwrap()
+                .insert("btcusdt".to_string(), (status, 0));
+            assert_eq!(feeds.status_of("btcusdt", NOW_MS), Some(status));
         }
     }
-}
 
+    #[test]
+    fn quotes_are_stale_by_their_receipt_time() {
+        let manager = PriceStreamManager::new();
+        manager.set_stale_thresholds(HashMap::from([("ethusdt".to_string(), 5)]));
+        let threshold_ms = default_threshold_ms();
+
+        let fresh = quote_received(NOW_MS - threshold_ms);
+        assert!(!manager.is_quote_stale("BTCUSDT", &fresh, NOW_MS));
+        let stale = quote_received(NOW_MS - threshold_ms - 1);
+        assert!(manager.is_quote_stale("BTCUSDT", &stale, NOW_MS));
+
+        let quote = quote_received(NOW_MS - 5_001);
+        assert!(manager.is_quote_stale("ETHUSDT", &quote, NOW_MS));
+        assert!(!manager.is_quote_stale("BTCUSDT", &quote, NOW_MS));
+
+        // A quote stamped after `now` (clock skew) is not stale
+        assert!(!manager.is_quote_stale("BTCUSDT", &quote_received(NOW_MS + 1), NOW_MS));
+    }
+
+    #[test]
+    fn a_symbol_with_no_price_is_stale() {
+        let manager = PriceStreamManager::new();
+        assert!(manager.is_price_stale("btcusdt"));
+        assert_eq!(manager.get_fresh_price("btcusdt"), None);
+
+        manager
+            .feeds
+            .set_price("btcusdt".to_string(), PriceQuote::received_now(100.0, 0));
+        assert!(!manager.is_price_stale("BTCUSDT"));
+        assert_eq!(manager.get_fresh_price("BTCUSDT"), Some(100.0));
+    }
+}
@@ -0,0 +1,634 @@
+//! Spreads the per-symbol streams over as many combined-stream connections as
+//! `BINANCE.ws.max_streams_per_connection` needs, and changes what a live connection
+//! carries with the venue's subscribe / unsubscribe requests instead of reconnecting.
+//! A connection only carries streams of one venue (`Exchange`).
+//!
+//! Each shard keeps the set of streams it should carry. The manager edits that set and
+//! then tells the shard what changed; a shard that is (re)connecting simply builds its
+//! URL from the current set, so nothing is lost if the two race.
+
+use futures::{SinkExt, StreamExt};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use super::{ConnectionStatus, LiveFeeds, StreamSelection, apply_stream_data};
+use crate::config::BINANCE;
+#[cfg(debug_assertions)]
+use crate::config::DEBUG_FLAGS;
+use crate::data::exchange::{Exchange, StreamFrame, exchange_for};
+use crate::domain::venue::Venue;
+
+/// Streams to add to or drop from a running connection
+#[derive(Debug, PartialEq)]
+enum ShardCommand {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+struct Shard {
+    venue: Venue,
+    /// Streams this connection should carry; re-read on every reconnect
+    streams: Arc<Mutex<BTreeSet<String>>>,
+    commands: UnboundedSender<ShardCommand>,
+    /// The connection's end of `commands`, until `ShardSet::connect_new` starts it
+    idle_commands: Option<UnboundedReceiver<ShardCommand>>,
+}
+
+/// The shard carrying a pair and the streams it carries for it
+struct Assignment {
+    shard: usize,
+    streams: Vec<String>,
+}
+
+/// What a call to `ShardSet::update` changed
+pub(super) struct ShardChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Kept pairs whose streams changed with their selection
+    pub restreamed: Vec<String>,
+}
+
+#[derive(Default)]
+pub(super) struct ShardSet {
+    /// Background runtime the shards run on, started with the first subscription
+    runtime: Option<Handle>,
+    shards: Vec<Shard>,
+    /// Pair name -> where its streams are carried
+    assignment: HashMap<String, Assignment>,
+}
+
+impl ShardSet {
+    pub fn len(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn runtime(&mut self) -> Handle {
+        self.runtime
+            .get_or_insert_with(|| {
+                let (handle_tx, handle_rx) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+                    let _ = handle_tx.send(rt.handle().clone());
+                    // Keep the runtime alive for the shard tasks
+                    rt.block_on(std::future::pending::<()>());
+                });
+                handle_rx
+                    .recv()
+                    .expect("Price stream runtime failed to start")
+            })
+            .clone()
+    }
+
+    /// Makes the shards carry exactly `selections` (lowercase pair names, each with the
+    /// streams it wants). Pairs stay on the shard they were first given to while it has
+    /// room for their streams; new ones (and ones whose streams outgrew their shard) fill
+    /// the first shard of their venue with room, and a new shard is opened when none has
+    /// any. New shards connect on `connect_new`.
+    pub fn update(&mut self, selections: &[(String, StreamSelection)]) -> ShardChanges {
+        let mut subscribe: HashMap<usize, Vec<String>> = HashMap::new();
+        let mut unsubscribe: HashMap<usize, Vec<String>> = HashMap::new();
+        let wanted: HashMap<&String, &StreamSelection> = selections
+            .iter()
+            .map(|(symbol, selection)| (symbol, selection))
+            .collect();
+
+        // 1. Dropped pairs
+        let mut removed: Vec<String> = self
+            .assignment
+            .keys()
+            .filter(|symbol| !wanted.contains_key(symbol))
+            .cloned()
+            .collect();
+        removed.sort();
+        for symbol in &removed {
+            let assignment = self.assignment.remove(symbol).expect("assigned symbol");
+            self.drop_streams(assignment.shard, &assignment.streams);
+            unsubscribe
+                .entry(assignment.shard)
+                .or_default()
+                .extend(assignment.streams);
+        }
+
+        // 2. Kept pairs whose streams changed: moved whole when their shard lacks room
+        let mut restreamed: Vec<String> = Vec::new();
+        let mut moved: Vec<String> = Vec::new();
+        for (symbol, selection) in selections {
+            let Some(assignment) = self.assignment.get(symbol) else {
+                continue;
+            };
+            let (_, streams) = streams_for(symbol, selection);
+            if streams == assignment.streams {
+                continue;
+            }
+            restreamed.push(symbol.clone());
+            let idx = assignment.shard;
+            let old: BTreeSet<&String> = assignment.streams.iter().collect();
+            let new: BTreeSet<&String> = streams.iter().collect();
+            let dropped: Vec<String> = old.difference(&new).map(|s| s.to_string()).collect();
+            let gained: Vec<String> = new.difference(&old).map(|s| s.to_string()).collect();
+
+            self.drop_streams(idx, &dropped);
+            unsubscribe.entry(idx).or_default().extend(dropped);
+            if self.has_room(idx, gained.len()) {
+                self.shards[idx]
+                    .streams
+                    .lock()
+                    .unwrap()
+                    .extend(gained.iter().cloned());
+                subscribe.entry(idx).or_default().extend(gained);
+                self.assignment.get_mut(symbol).expect("assigned").streams = streams;
+            } else {
+                let kept: Vec<String> = old.intersection(&new).map(|s| s.to_string()).collect();
+                self.drop_streams(idx, &kept);
+                unsubscribe.entry(idx).or_default().extend(kept);
+                self.assignment.remove(symbol);
+                moved.push(symbol.clone());
+            }
+        }
+
+        // 3. New pairs, and the ones moving
+        let mut added = Vec::new();
+        for (symbol, selection) in selections {
+            if self.assignment.contains_key(symbol) {
+                continue;
+            }
+            let (venue, streams) = streams_for(symbol, selection);
+            let idx = match self.shard_with_room(venue, streams.len()) {
+                Some(idx) => idx,
+                None => self.open_shard(venue),
+            };
+            self.shards[idx]
+                .streams
+                .lock()
+                .unwrap()
+                .extend(streams.iter().cloned());
+            subscribe
+                .entry(idx)
+                .or_default()
+                .extend(streams.iter().cloned());
+            self.assignment.insert(
+                symbol.clone(),
+                Assignment {
+                    shard: idx,
+                    streams,
+                },
+            );
+            if !moved.contains(symbol) {
+                added.push(symbol.clone());
+            }
+        }
+
+        // 4. Tell running connections (unsubscribing first, so a shard never carries
+        // more than it has room for)
+        let mut unsubscribe: Vec<(usize, Vec<String>)> = unsubscribe.into_iter().collect();
+        unsubscribe.sort();
+        for (idx, streams) in unsubscribe {
+            if !streams.is_empty() {
+                let _ = self.shards[idx]
+                    .commands
+                    .send(ShardCommand::Unsubscribe(streams));
+            }
+        }
+        let mut subscribe: Vec<(usize, Vec<String>)> = subscribe.into_iter().collect();
+        subscribe.sort();
+        for (idx, streams) in subscribe {
+            if !streams.is_empty() {
+                let _ = self.shards[idx]
+                    .commands
+                    .send(ShardCommand::Subscribe(streams));
+            }
+        }
+
+        ShardChanges {
+            added,
+            removed,
+            restreamed,
+        }
+    }
+
+    /// Starts the connections of shards opened since the last call
+    pub fn connect_new(&mut self, feeds: &LiveFeeds) {
+        for idx in 0..self.shards.len() {
+            let Some(command_rx) = self.shards[idx].idle_commands.take() else {
+                continue;
+            };
+            let shard = &self.shards[idx];
+            let task = run_shard_with_reconnect(
+                idx,
+                exchange_for(shard.venue),
+                shard.streams.clone(),
+                command_rx,
+                feeds.clone(),
+            );
+            self.runtime().spawn(task);
+        }
+    }
+
+    fn drop_streams(&self, idx: usize, streams: &[String]) {
+        let mut carried = self.shards[idx].streams.lock().unwrap();
+        for stream in streams {
+            carried.remove(stream);
+        }
+    }
+
+    fn has_room(&self, idx: usize, stream_count: usize) -> bool {
+        self.shards[idx].streams.lock().unwrap().len() + stream_count
+            <= BINANCE.ws.max_streams_per_connection
+    }
+
+    fn shard_with_room(&self, venue: Venue, stream_count: usize) -> Option<usize> {
+        (0..self.shards.len())
+            .find(|&idx| self.shards[idx].venue == venue && self.has_room(idx, stream_count))
+    }
+
+    fn open_shard(&mut self, venue: Venue) -> usize {
+        let (commands, command_rx) = unbounded_channel();
+        self.shards.push(Shard {
+            venue,
+            streams: Arc::new(Mutex::new(BTreeSet::new())),
+            commands,
+            idle_commands: Some(command_rx),
+        });
+        self.shards.len() - 1
+    }
+}
+
+/// The venue of a pair name and the stream names its venue carries it on
+fn streams_for(symbol: &str, selection: &StreamSelection) -> (Venue, Vec<String>) {
+    let (venue, local) = Venue::split(symbol);
+    (venue, exchange_for(venue).streams_for(local, selection))
+}
+
+/// Pair names behind a set of `venue` stream names (`btcusdt@miniTicker` -> `btcusdt`)
+fn symbols_of<'a>(venue: Venue, streams: impl IntoIterator<Item = &'a String>) -> BTreeSet<String> {
+    streams
+        .into_iter()
+        .filter_map(|stream| {
+            stream
+                .split_once('@')
+                .map(|(symbol, _)| venue.qualify(symbol))
+        })
+        .collect()
+}
+
+/// Why a connection ended without an error
+enum ShardExit {
+    /// Server closed it (24-hour limit or maintenance); reconnect
+    Closed,
+    /// The manager is gone; stop for good
+    Shutdown,
+}
+
+/// Keeps one shard connected, with exponential backoff between failed attempts
+async fn run_shard_with_reconnect(
+    shard_idx: usize,
+    exchange: Arc<dyn Exchange>,
+    streams: Arc<Mutex<BTreeSet<String>>>,
+    mut commands: UnboundedReceiver<ShardCommand>,
+    feeds: LiveFeeds,
+) {
+    let venue = exchange.venue();
+    let mut reconnect_delay = BINANCE.ws.initial_reconnect_delay_sec;
+
+    loop {
+        let carried = streams.lock().unwrap().clone();
+        if carried.is_empty() {
+            // Nothing to carry: wait for a subscription instead of holding an idle socket
+            match commands.recv().await {
+                Some(_) => continue,
+                None => return,
+            }
+        }
+
+        feeds.set_status(symbols_of(venue, &carried), ConnectionStatus::Connecting);
+        let url = exchange.stream_url(&carried);
+
+        match run_shard(
+            shard_idx,
+            exchange.as_ref(),
+            &url,
+            &streams,
+            &mut commands,
+            &feeds,
+        )
+        .await
+        {
+            Ok(ShardExit::Shutdown) => return,
+            Ok(ShardExit::Closed) => {
+                #[cfg(debug_assertions)]
+                if DEBUG_FLAGS.print_price_stream_updates {
+                    log::info!("Connection {} closed, reconnecting...", shard_idx);
+                }
+
+                // Reset delay on successful connection that later closes
+                reconnect_delay = BINANCE.ws.initial_reconnect_delay_sec;
+            }
+            Err(e) => {
+                log::error!("Price stream error on connection {}: {}", shard_idx, e);
+
+                let carried = streams.lock().unwrap().clone();
+                feeds.set_status(symbols_of(venue, &carried), ConnectionStatus::Disconnected);
+
+                // Exponential backoff
+                #[cfg(debug_assertions)]
+                if DEBUG_FLAGS.print_price_stream_updates {
+                    log::info!(
+                        "Reconnecting connection {} in {} seconds...",
+                        shard_idx,
+                        reconnect_delay
+                    );
+                }
+                tokio::time::sleep(Duration::from_secs(reconnect_delay)).await;
+
+                // Increase delay for next attempt (capped at max)
+                reconnect_delay = (reconnect_delay * 2).min(BINANCE.ws.max_reconnect_delay_sec);
+            }
+        }
+
+        // Small delay before reconnecting even on normal close
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+async fn run_shard(
+    shard_idx: usize,
+    exchange: &dyn Exchange,
+    url: &str,
+    streams: &Mutex<BTreeSet<String>>,
+    commands: &mut UnboundedReceiver<ShardCommand>,
+    feeds: &LiveFeeds,
+) -> Result<ShardExit, Box<dyn std::error::Error + Send + Sync>> {
+    #[cfg(debug_assertions)]
+    if DEBUG_FLAGS.print_price_stream_updates {
+        log::info!(
+            "Connecting to {} combined WebSocket: {}",
+            exchange.venue(),
+            url
+        );
+    }
+
+    let venue = exchange.venue();
+    let (ws_stream, _) = connect_async(url).await?;
+
+    let connected = streams.lock().unwrap().clone();
+    feeds.set_status(symbols_of(venue, &connected), ConnectionStatus::Connected);
+
+    #[cfg(debug_assertions)]
+    if DEBUG_FLAGS.print_price_stream_updates {
+        log::info!(
+            "✓ Connection {} carrying {} streams",
+            shard_idx,
+            connected.len()
+        );
+    }
+    let (mut write, mut read) = ws_stream.split();
+    let mut request_id: u64 = 0;
+
+    let exit = loop {
+        tokio::select! {
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    match exchange.parse_frame(&text) {
+                        StreamFrame::Data { stream, symbol, event } => {
+                            // Frames can still arrive for a stream we just unsubscribed
+                            if streams.lock().unwrap().contains(&stream) {
+                                apply_stream_data(venue, &symbol, event, feeds);
+                            }
+                        }
+                        StreamFrame::Reply { id, error: Some(error) } => {
+                            log::error!(
+                                "⚠️ Connection {} rejected request {}: {}",
+                                shard_idx,
+                                id,
+                                error
+                            );
+                        }
+                        StreamFrame::Reply { .. } => {}
+                        StreamFrame::Unknown => {
+                            #[cfg(debug_assertions)]
+                            if DEBUG_FLAGS.print_price_stream_updates {
+                                log::error!("⚠️ Unexpected combined stream payload: {}", text);
+                            }
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    #[cfg(debug_assertions)]
+                    if DEBUG_FLAGS.print_price_stream_updates {
+                        log::info!("Connection {} closed (likely 24hr timeout)", shard_idx);
+                    }
+                    break ShardExit::Closed;
+                }
+                Some(Err(e)) => {
+                    log::error!("WebSocket error: {}", e);
+                    return Err(e.into());
+                }
+                // WebSocket keepalive - handled automatically
+                Some(Ok(_)) => {}
+            },
+            command = commands.recv() => {
+                let (subscribe, params) = match command {
+                    Some(ShardCommand::Subscribe(params)) => {
+                        feeds.set_status(symbols_of(venue, &params), ConnectionStatus::Connected);
+                        (true, params)
+                    }
+                    Some(ShardCommand::Unsubscribe(params)) => (false, params),
+                    None => break ShardExit::Shutdown,
+                };
+                request_id += 1;
+                let request = exchange.stream_request(subscribe, &params, request_id);
+                write.send(Message::Text(request.into())).await?;
+            }
+        }
+    };
+
+    // Update status on disconnect
+    let carried = streams.lock().unwrap().clone();
+    feeds.set_status(symbols_of(venue, &carried), ConnectionStatus::Disconnected);
+
+    Ok(exit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M30: i64 = 30 * 60 * 1000;
+    const H1: i64 = 60 * 60 * 1000;
+
+    fn klines(intervals: &[i64]) -> StreamSelection {
+        StreamSelection {
+            kline_intervals: intervals.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn each(symbols: &[&str], selection: &StreamSelection) -> Vec<(String, StreamSelection)> {
+        symbols
+            .iter()
+            .map(|symbol| (symbol.to_string(), selection.clone()))
+            .collect()
+    }
+
+    /// Commands sent to shard `idx` since the last call
+    fn sent(set: &mut ShardSet, idx: usize) -> Vec<ShardCommand> {
+        let commands = set.shards[idx].idle_commands.as_mut().unwrap();
+        std::iter::from_fn(|| commands.try_recv().ok()).collect()
+    }
+
+    fn carried(set: &ShardSet, idx: usize) -> BTreeSet<String> {
+        set.shards[idx].streams.lock().unwrap().clone()
+    }
+
+    fn strings(streams: &[&str]) -> Vec<String> {
+        streams.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn new_pairs_fill_shards_of_their_venue_up_to_capacity() {
+        let max = BINANCE.ws.max_streams_per_connection;
+        let mut selections: Vec<(String, StreamSelection)> = (0..=max)
+            .map(|i| (format!("pair{}usdt", i), StreamSelection::default()))
+            .collect();
+        selections.push((
+            "binance-futures:btcusdt".to_string(),
+            StreamSelection::default(),
+        ));
+        let mut set = ShardSet::default();
+
+        let changes = set.update(&selections);
+
+        assert_eq!(changes.added.len(), max + 2);
+        assert!(changes.removed.is_empty() && changes.restreamed.is_empty());
+        assert_eq!(set.len(), 3);
+        assert_eq!(carried(&set, 0).len(), max);
+        assert_eq!(carried(&set, 1).len(), 1);
+        assert_eq!(set.shards[2].venue, Venue::BinanceFutures);
+        assert_eq!(
+            carried(&set, 2),
+            BTreeSet::from(["btcusdt@miniTicker".to_string()])
+        );
+        assert!(matches!(&sent(&mut set, 0)[..], [ShardCommand::Subscribe(s)] if s.len() == max));
+    }
+
+    #[test]
+    fn each_pair_streams_only_its_own_intervals() {
+        let mut set = ShardSet::default();
+        set.update(&[
+            ("btcusdt".to_string(), klines(&[M30])),
+            ("ethusdt".to_string(), klines(&[H1])),
+        ]);
+
+        assert_eq!(
+            carried(&set, 0),
+            BTreeSet::from_iter(strings(&[
+                "btcusdt@kline_30m",
+                "btcusdt@miniTicker",
+                "ethusdt@kline_1h",
+                "ethusdt@miniTicker",
+            ]))
+        );
+    }
+
+    #[test]
+    fn only_the_difference_is_sent() {
+        let selection = klines(&[M30]);
+        let mut set = ShardSet::default();
+        set.update(&each(&["btcusdt", "ethusdt"], &selection));
+        sent(&mut set, 0);
+
+        let changes = set.update(&each(&["btcusdt", "solusdt"], &selection));
+        assert_eq!(changes.added, ["solusdt"]);
+        assert_eq!(changes.removed, ["ethusdt"]);
+        assert_eq!(
+            sent(&mut set, 0),
+            [
+                ShardCommand::Unsubscribe(strings(&["ethusdt@miniTicker", "ethusdt@kline_30m"])),
+                ShardCommand::Subscribe(strings(&["solusdt@miniTicker", "solusdt@kline_30m"])),
+            ]
+        );
+
+        // Nothing changed: nothing sent
+        let changes = set.update(&each(&["btcusdt", "solusdt"], &selection));
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+        assert!(changes.restreamed.is_empty());
+        assert!(sent(&mut set, 0).is_empty());
+    }
+
+    #[test]
+    fn a_selection_change_is_applied_in_place_while_the_shard_has_room() {
+        let mut set = ShardSet::default();
+        set.update(&each(&["btcusdt", "ethusdt"], &klines(&[M30])));
+        sent(&mut set, 0);
+
+        let changes = set.update(&[
+            ("btcusdt".to_string(), klines(&[H1])),
+            ("ethusdt".to_string(), klines(&[M30])),
+        ]);
+
+        assert_eq!(changes.restreamed, ["btcusdt"]);
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+        assert_eq!(set.len(), 1);
+        assert_eq!(
+            sent(&mut set, 0),
+            [
+                ShardCommand::Unsubscribe(strings(&["btcusdt@kline_30m"])),
+                ShardCommand::Subscribe(strings(&["btcusdt@kline_1h"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_pair_that_outgrows_its_shard_moves_to_one_with_room() {
+        // Two streams each fill the shard exactly
+        let max = BINANCE.ws.max_streams_per_connection;
+        let symbols: Vec<String> = (0..max / 2).map(|i| format!("pair{}usdt", i)).collect();
+        let mut selections: Vec<(String, StreamSelection)> = symbols
+            .iter()
+            .map(|symbol| (symbol.clone(), klines(&[M30])))
+            .collect();
+        let mut set = ShardSet::default();
+        set.update(&selections);
+        assert_eq!((set.len(), carried(&set, 0).len()), (1, max));
+        sent(&mut set, 0);
+
+        selections[0].1 = klines(&[M30, H1]);
+        let changes = set.update(&selections);
+
+        assert_eq!(changes.restreamed, ["pair0usdt"]);
+        assert!(changes.added.is_empty());
+        assert_eq!(set.len(), 2);
+        assert_eq!(carried(&set, 0).len(), max - 2);
+        assert!(
+            carried(&set, 0)
+                .iter()
+                .all(|s| !s.starts_with("pair0usdt@"))
+        );
+        assert_eq!(
+            carried(&set, 1),
+            BTreeSet::from_iter(strings(&[
+                "pair0usdt@kline_1h",
+                "pair0usdt@kline_30m",
+                "pair0usdt@miniTicker",
+            ]))
+        );
+        assert_eq!(
+            sent(&mut set, 0),
+            [ShardCommand::Unsubscribe(strings(&[
+                "pair0usdt@kline_30m",
+                "pair0usdt@miniTicker",
+            ]))]
+        );
+        assert_eq!(set.assignment["pair0usdt"].shard, 1);
+        assert!(
+            set.shards
+                .iter()
+                .enumerate()
+                .all(|(idx, _)| carried(&set, idx).len() <= max)
+        );
+    }
+}
@@ -0,0 +1,565 @@
+//! Recorded live prices ("tapes") and the clock that plays them back.
+//!
+//! A tape is an append-only file: an 8-byte header, then one length-prefixed bincode
+//! record per miniTicker price or bookTicker quote, in the order they arrived.
+//!
+//! ```text
+//! magic "ZSTT" | version u16 | reserved u16
+//! record length u32 | bincode TickRecord      (repeated)
+//! ```
+//!
+//! Records are only ever appended, so a recording cut short (crash, kill) loses at most
+//! its last, partly written record: `TickTape::from_bytes` drops it with a warning, and
+//! `TickRecorder::open` truncates it before appending more.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::price_stream::{PriceUpdate, PriceUpdates};
+use crate::domain::order_book::BookTicker;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::utils::time_utils::local_now_as_timestamp_ms;
+
+pub const TAPE_MAGIC: [u8; 4] = *b"ZSTT";
+pub const TAPE_VERSION: u16 = 1;
+pub const TAPE_HEADER_LEN: usize = 8;
+
+/// What was received
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Tick {
+    /// Last price (`@miniTicker` close)
+    Price { price: f64 },
+    /// Best bid and ask (`@bookTicker`)
+    Book { book: BookTicker },
+}
+
+/// One received update. Times are ms since the epoch: `event_time_ms` from Binance
+/// (equal to `received_ms` where the stream doesn't send one), `received_ms` ours.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickRecord {
+    pub received_ms: i64,
+    pub event_time_ms: i64,
+    /// Lowercase, like the price map keys
+    pub symbol: String,
+    pub tick: Tick,
+}
+
+pub fn tape_header() -> [u8; TAPE_HEADER_LEN] {
+    let mut out = [0u8; TAPE_HEADER_LEN];
+    out[0..4].copy_from_slice(&TAPE_MAGIC);
+    out[4..6].copy_from_slice(&TAPE_VERSION.to_le_bytes());
+    // 6..8 reserved
+    out
+}
+
+/// Checks a tape header, returning the format version
+fn check_header(bytes: &[u8]) -> Result<u16> {
+    if bytes.len() < TAPE_HEADER_LEN || bytes[0..4] != TAPE_MAGIC {
+        bail!("Not a tick tape (missing ZSTT header)");
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version != TAPE_VERSION {
+        bail!(
+            "Tick tape format v{} is not supported (expected v{})",
+            version,
+            TAPE_VERSION
+        );
+    }
+    Ok(version)
+}
+
+/// A record as written to the file: length prefix, then the bincode payload
+pub fn encode_record(record: &TickRecord) -> Result<Vec<u8>> {
+    let payload = bincode::serialize(record)?;
+    let mut out = Vec::with_capacity(4 + payload.len());
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+/// The length-prefixed record payloads after the header, and the offset where the last
+/// complete one ends. A torn final record (prefix or payload cut short) is left out.
+fn complete_records(bytes: &[u8]) -> (Vec<&[u8]>, usize) {
+    let mut payloads = Vec::new();
+    let mut pos = TAPE_HEADER_LEN;
+    while let Some(len_bytes) = bytes.get(pos..pos + 4) {
+        let len =
+            u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
+        let Some(payload) = bytes.get(pos + 4..pos + 4 + len) else {
+            break;
+        };
+        payloads.push(payload);
+        pos += 4 + len;
+    }
+    (payloads, pos.min(bytes.len()))
+}
+
+/// Playback rate relative to how the ticks were recorded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// As fast as the ticks can be applied
+    Max,
+    /// `1.0` is real time, `10.0` ten times faster
+    Times(f64),
+}
+
+impl ReplaySpeed {
+    pub const REAL_TIME: ReplaySpeed = ReplaySpeed::Times(1.0);
+
+    /// Tape time covered after `elapsed` of wall time (`None` at max speed: all of it)
+    pub fn tape_elapsed_ms(&self, elapsed: Duration) -> Option<i64> {
+        match self {
+            ReplaySpeed::Max => None,
+            ReplaySpeed::Times(factor) => Some((elapsed.as_secs_f64() * 1000.0 * factor) as i64),
+        }
+    }
+
+    /// Wall time it takes to play `tape_ms` of the tape
+    pub fn wall_duration(&self, tape_ms: i64) -> Duration {
+        match self {
+            ReplaySpeed::Max => Duration::ZERO,
+            ReplaySpeed::Times(factor) => {
+                Duration::from_secs_f64((tape_ms.max(0) as f64 / 1000.0) / factor)
+            }
+        }
+    }
+}
+
+/// Parses `max`, `1x`, `10x` or a bare factor like `2.5`
+impl std::str::FromStr for ReplaySpeed {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim().to_lowercase();
+        if text == "max" {
+            return Ok(ReplaySpeed::Max);
+        }
+        match text.trim_end_matches('x').parse::<f64>() {
+            Ok(factor) if factor > 0.0 && factor.is_finite() => Ok(ReplaySpeed::Times(factor)),
+            _ => Err(format!(
+                "'{}' is not a replay speed (use e.g. 1x, 10x or max)",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ReplaySpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplaySpeed::Max => write!(f, "max"),
+            ReplaySpeed::Times(factor) => write!(f, "{}x", factor),
+        }
+    }
+}
+
+/// A whole tape in memory, in recording order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickTape {
+    pub records: Vec<TickRecord>,
+}
+
+impl TickTape {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_header(bytes)?;
+
+        let (payloads, end) = complete_records(bytes);
+        if end < bytes.len() {
+            log::warn!("Tick tape ends in a partial record; ignoring the tail");
+        }
+        let records = payloads
+            .into_iter()
+            .map(bincode::deserialize)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { records })
+    }
+
+    /// The tape as a file: header, then every record
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = tape_header().to_vec();
+        for record in &self.records {
+            out.extend_from_slice(&encode_record(record)?);
+        }
+        Ok(out)
+    }
+
+    /// A stand-in for a recorded session, for building the demo without a live feed:
+    /// every `step_ms` each symbol's price swings up to `swing_pct` around its entry in
+    /// `prices`, in a sine wave that ends where it started so the tape loops smoothly.
+    pub fn synthetic(
+        prices: &BTreeMap<String, f64>,
+        duration_ms: i64,
+        step_ms: i64,
+        swing_pct: f64,
+    ) -> Self {
+        let steps = (duration_ms / step_ms.max(1)).max(1);
+        let mut records = Vec::new();
+        for step in 0..=steps {
+            let received_ms = step * step_ms;
+            for (offset, (symbol, price)) in prices.iter().enumerate() {
+                // Each symbol a different phase, so they don't all move in lockstep
+                let angle =
+                    std::f64::consts::TAU * (step as f64 / steps as f64) + offset as f64 * 1.3;
+                records.push(TickRecord {
+                    received_ms,
+                    event_time_ms: received_ms,
+                    symbol: symbol.to_lowercase(),
+                    tick: Tick::Price {
+                        price: price * (1.0 + swing_pct / 100.0 * angle.sin()),
+                    },
+                });
+            }
+        }
+        Self { records }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        use anyhow::Context;
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read tick tape {}", path.display()))?;
+        Self::from_bytes(&bytes).with_context(|| format!("Bad tick tape {}", path.display()))
+    }
+
+    pub fn first_ms(&self) -> Option<i64> {
+        self.records.first().map(|r| r.received_ms)
+    }
+
+    /// Recorded span, first to last record
+    pub fn duration_ms(&self) -> i64 {
+        match (self.records.first(), self.records.last()) {
+            (Some(first), Some(last)) => last.received_ms - first.received_ms,
+            _ => 0,
+        }
+    }
+}
+
+/// Latest values per (lowercase) symbol at one point of a tape
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TapeSnapshot {
+    pub prices: HashMap<String, f64>,
+    pub books: HashMap<String, BookTicker>,
+}
+
+/// A position on a tape and the latest values the records before it leave
+#[derive(Debug, Clone, Default)]
+pub struct TapeCursor {
+    next: usize,
+    pub latest: TapeSnapshot,
+}
+
+impl TapeCursor {
+    /// Moves to `tape_ms` (a `received_ms` on the tape) and returns the records passed on
+    /// the way. Moving backwards, as a looping player does, starts over from the top.
+    pub fn advance<'a>(&mut self, tape: &'a TickTape, tape_ms: i64) -> &'a [TickRecord] {
+        let went_back = self
+            .next
+            .checked_sub(1)
+            .and_then(|last| tape.records.get(last))
+            .is_some_and(|last| last.received_ms > tape_ms);
+        if went_back {
+            *self = Self::default();
+        }
+
+        let start = self.next.min(tape.records.len());
+        let end =
+            start + tape.records[start..].partition_point(|record| record.received_ms <= tape_ms);
+        for record in &tape.records[start..end] {
+            match record.tick {
+                Tick::Price { price } => {
+                    self.latest.prices.insert(record.symbol.clone(), price);
+                }
+                Tick::Book { book } => {
+                    self.latest.books.insert(record.symbol.clone(), book);
+                }
+            }
+        }
+        self.next = end;
+        &tape.records[start..end]
+    }
+
+    /// When the next record is due, or `None` at the end of the tape
+    pub fn next_due_ms(&self, tape: &TickTape) -> Option<i64> {
+        tape.records.get(self.next).map(|record| record.received_ms)
+    }
+}
+
+/// Appends records to a tape file, writing the header first if the file is new
+#[cfg(not(target_arch = "wasm32"))]
+pub struct TickRecorder {
+    writer: std::io::BufWriter<std::fs::File>,
+    pub records_written: usize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TickRecorder {
+    /// Opens `path` for appending. An existing file must be a tape of this version; a
+    /// partly written last record (the recording was killed mid-write) is cut off first,
+    /// so new records don't land behind it.
+    pub fn open(path: &std::path::Path) -> Result<Self> {
+        use anyhow::Context;
+        use std::io::{Read, Write};
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open tick tape {}", path.display()))?;
+
+        if file.metadata()?.len() == 0 {
+            file.write_all(&tape_header())?;
+        } else {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            check_header(&bytes).with_context(|| format!("Cannot append to {}", path.display()))?;
+
+            let (_, end) = complete_records(&bytes);
+            if end < bytes.len() {
+                log::warn!(
+                    "{} ends in a partly written record; truncating {} bytes",
+                    path.display(),
+                    bytes.len() - end
+                );
+                file.set_len(end as u64)
+                    .with_context(|| format!("Failed to truncate {}", path.display()))?;
+            }
+        }
+
+        Ok(Self {
+            writer: std::io::BufWriter::new(file),
+            records_written: 0,
+        })
+    }
+
+    pub fn append(&mut self, record: &TickRecord) -> Result<()> {
+        use std::io::Write;
+        self.writer.write_all(&encode_record(record)?)?;
+        self.records_written += 1;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        use std::io::Write;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Appends every price and book update from `updates` to `recorder` on a background
+/// thread, flushing whenever it has caught up. Runs until the price stream is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_recording(
+    mut recorder: TickRecorder,
+    mut updates: PriceUpdates,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        while let Some(update) = updates.blocking_next() {
+            let mut pending = Some(update);
+            while let Some(update) = pending {
+                if let Some(record) = record_for(update) {
+                    if let Err(e) = recorder.append(&record) {
+                        log::error!("Tick recording stopped: {:#}", e);
+                        return;
+                    }
+                }
+                pending = updates.try_next();
+            }
+            if let Err(e) = recorder.flush() {
+                log::error!("Tick recording stopped: {:#}", e);
+                return;
+            }
+        }
+        log::info!(
+            "Tick recording finished ({} records)",
+            recorder.records_written
+        );
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn record_for(update: PriceUpdate) -> Option<TickRecord> {
+    match update {
+        PriceUpdate::Price { symbol, quote } => Some(TickRecord {
+            received_ms: quote.received_ms,
+            event_time_ms: quote.event_time_ms,
+            symbol,
+            tick: Tick::Price { price: quote.price },
+        }),
+        PriceUpdate::Book { symbol, book } => {
+            // bookTicker carries no event time
+            let now_ms = local_now_as_timestamp_ms();
+            Some(TickRecord {
+                received_ms: now_ms,
+                event_time_ms: now_ms,
+                symbol,
+                tick: Tick::Book { book },
+            })
+        }
+        PriceUpdate::Lagged => {
+            log::warn!("Tick recorder fell behind; some ticks were not recorded");
+            None
+        }
+        PriceUpdate::Depth { .. } | PriceUpdate::ClosedKline { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::order_book::BookLevel;
+
+    fn record(received_ms: i64, price: f64) -> TickRecord {
+        TickRecord {
+            received_ms,
+            event_time_ms: received_ms - 3,
+            symbol: "btcusdt".to_string(),
+            tick: Tick::Price { price },
+        }
+    }
+
+    fn temp_tape(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "zone_sniper_tick_tape_{}_{}.bin",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn tapes_round_trip_through_bytes_and_the_recorder() {
+        let book = BookTicker {
+            bid: BookLevel {
+                price: 99.5,
+                quantity: 2.0,
+            },
+            ask: BookLevel {
+                price: 100.5,
+                quantity: 1.0,
+            },
+        };
+        let tape = TickTape {
+            records: vec![
+                record(1_000, 100.0),
+                TickRecord {
+                    received_ms: 1_500,
+                    event_time_ms: 1_500,
+                    symbol: "ethusdt".to_string(),
+                    tick: Tick::Book { book },
+                },
+                record(2_000, 101.25),
+            ],
+        };
+        assert_eq!(
+            TickTape::from_bytes(&tape.to_bytes().unwrap()).unwrap(),
+            tape
+        );
+
+        let path = temp_tape("round_trip");
+        let mut recorder = TickRecorder::open(&path).unwrap();
+        for record in &tape.records {
+            recorder.append(record).unwrap();
+        }
+        recorder.flush().unwrap();
+        assert_eq!(recorder.records_written, 3);
+        drop(recorder);
+        assert_eq!(TickTape::load(&path).unwrap(), tape);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reopening_a_torn_tape_truncates_the_partial_record() {
+        let path = temp_tape("torn");
+        let mut recorder = TickRecorder::open(&path).unwrap();
+        recorder.append(&record(1_000, 100.0)).unwrap();
+        recorder.append(&record(2_000, 101.0)).unwrap();
+        recorder.flush().unwrap();
+        drop(recorder);
+        let complete_len = std::fs::metadata(&path).unwrap().len();
+
+        // Killed halfway through writing a third record
+        let torn = encode_record(&record(3_000, 102.0)).unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.extend_from_slice(&torn[..torn.len() / 2]);
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(TickTape::load(&path).unwrap().records.len(), 2);
+
+        let mut recorder = TickRecorder::open(&path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), complete_len);
+        recorder.append(&record(4_000, 103.0)).unwrap();
+        recorder.flush().unwrap();
+        drop(recorder);
+
+        let prices: Vec<i64> = TickTape::load(&path)
+            .unwrap()
+            .records
+            .iter()
+            .map(|r| r.received_ms)
+            .collect();
+        assert_eq!(prices, vec![1_000, 2_000, 4_000]);
+
+        // A tail too short to even hold a length prefix goes the same way
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.extend_from_slice(&[7, 0]);
+        std::fs::write(&path, &bytes).unwrap();
+        drop(TickRecorder::open(&path).unwrap());
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len() as usize,
+            bytes.len() - 2
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn appending_to_something_else_is_refused() {
+        let path = temp_tape("not_a_tape");
+        std::fs::write(&path, b"definitely not a tick tape").unwrap();
+        assert!(TickRecorder::open(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn synthetic_tapes_loop_back_to_where_they_started() {
+        let prices = BTreeMap::from([
+            ("BTCUSDT".to_string(), 100.0),
+            ("ETHUSDT".to_string(), 10.0),
+        ]);
+        let tape = TickTape::synthetic(&prices, 60_000, 1_000, 0.5);
+
+        assert_eq!(tape.records.len(), 61 * 2);
+        assert_eq!(tape.duration_ms(), 60_000);
+        let btc: Vec<f64> = tape
+            .records
+            .iter()
+            .filter(|r| r.symbol == "btcusdt")
+            .map(|r| match r.tick {
+                Tick::Price { price } => price,
+                Tick::Book { .. } => unreachable!(),
+            })
+            .collect();
+        assert!(btc.iter().all(|price| (99.5..=100.5).contains(price)));
+        assert!((btc[0] - btc[60]).abs() < 1e-9);
+        assert!(btc.iter().any(|price| (price - btc[0]).abs() > 0.1));
+    }
+
+    #[test]
+    fn the_bundled_demo_tape_parses() {
+        // The WASM build includes this file, so it has to exist and be a valid tape
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/kline_data/demo_ticks.bin"
+        ));
+        let tape = TickTape::from_bytes(bytes).unwrap();
+        assert!(!tape.records.is_empty());
+        assert_eq!(tape.to_bytes().unwrap(), bytes);
+    }
+}
@@ -1,29 +1,40 @@
+pub mod agg_trades;
 pub mod bn_kline;
+pub mod exchange_info;
 pub mod raw_ohlcv;
 
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, bail};
 use async_trait::async_trait;
 use bn_kline::AllValidKlines4Pair;
-use futures::future::join_all;
-use itertools::iproduct;
-use rayon::prelude::*;
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
 
-use tokio::{fs, task::JoinError, task::JoinHandle,};
+use tokio::fs;
+use tokio::sync::mpsc::UnboundedSender;
 #[cfg(debug_assertions)]
 use tokio::{time::Instant};
 
 #[cfg(debug_assertions)]
 use crate::config::DEBUG_FLAGS;
 use crate::config::{ANALYSIS, BINANCE, PERSISTENCE};
+use crate::data::exchange::exchange_for;
+use crate::data::fetch_scheduler::FetchScheduler;
+use crate::data::pair_loading::PairLoadEvent;
 use crate::data::timeseries::{CreateTimeSeriesData, TimeSeriesCollection};
 use crate::domain::pair_interval::PairInterval;
+use crate::domain::venue::Venue;
+use crate::domain::watchlist::Watchlist;
 use crate::models::OhlcvTimeSeries;
 pub use raw_ohlcv::OhlcvTimeSeriesTemp;
 
 #[cfg(debug_assertions)]
 use crate::utils::time_utils;
 
-pub struct BNAPIVersion;
+pub struct BNAPIVersion {
+    /// Each pair is also sent here as soon as it's loaded (or has failed)
+    pub streamed: Option<UnboundedSender<PairLoadEvent>>,
+}
+
 #[async_trait]
 impl CreateTimeSeriesData for BNAPIVersion {
     fn signature(&self) -> &'static str {
@@ -38,7 +49,7 @@ impl CreateTimeSeriesData for BNAPIVersion {
         #[cfg(debug_assertions)]
         let start_time = Instant::now();
 
-        let series_data = timeseries_data_load(&supply_interval_asset).await?;
+        let series_data = timeseries_data_load(&supply_interval_asset, self.streamed.as_ref()).await?;
 
         #[cfg(debug_assertions)]
         if DEBUG_FLAGS.print_binance {
@@ -83,42 +94,76 @@ pub async fn timeseries_data_load(
     // supply_quote_asset: &[&str],
     // supply_name: &[&str],
     supply_interval_asset: &[i64],
-) -> Result<Vec<OhlcvTimeSeries>> {
-    let mut all_valid_klines_4_pairs: Vec<AllValidKlines4Pair> = Vec::new();
-
-    let pairs_file_content = fs::read_to_string("pairs.txt").await?; // On fail, return Err from this func.
-    // Create the Vec<String> from the file content - TEMP  - what if this fails?
-    let supply_pairs: Vec<String> = pairs_file_content
-        .lines()
-        .map(|s| s.trim().to_uppercase()) // Trim whitespace and make uppercase
-        .filter(|s| !s.is_empty()) // Filter out empty lines
-        .take(BINANCE.max_pairs)
-        .collect();
-
-    let all_permutations = iproduct!(supply_pairs, supply_interval_asset)
-        .take(BINANCE.limits.max_lookups_total)
-        .map(|(pair_name, interval_ms)| PairInterval {
-            name: pair_name,
-            interval_ms: *interval_ms,
-        });
-
-    // Collect all_permutations into an owned collection so data can be safely sent to other threads
-    let all_permutations_vec: Vec<_> = all_permutations.collect();
-    for batch in all_permutations_vec.chunks(BINANCE.limits.simultaneous_calls_ceiling) {
-        // `batch` is a new iterator for each chunk.
-        let batch_vec: Vec<_> = batch.iter().collect();
-        let batch_size: u32 = batch_vec.len() as u32;
+    streamed: Option<&UnboundedSender<PairLoadEvent>>,
+) -> Result<Vec<Arc<OhlcvTimeSeries>>> {
+    let mut ohlcv_time_series: Vec<Arc<OhlcvTimeSeries>> = Vec::new();
+
+    let watchlist_text = fs::read_to_string(BINANCE.pairs.watchlist_path).await?; // On fail, return Err from this func.
+    let watchlist = Watchlist::parse(&watchlist_text, BINANCE.pairs.watchlist_path)?;
+    let file_pairs: Vec<String> = watchlist.enabled().map(|e| e.symbol.clone()).collect();
+
+    // Binance spot (for discovery) plus every venue the watchlist names
+    let mut venues = vec![Venue::BinanceSpot];
+    for pair in &file_pairs {
+        let venue = Venue::split(pair).0;
+        if !venues.contains(&venue) {
+            venues.push(venue);
+        }
+    }
 
-        // Process the current batch (i.e., make API calls)
-        #[cfg(debug_assertions)]
-        if DEBUG_FLAGS.print_binance {
-            log::info!("--- Processing batch of size {} ---", batch_vec.len());
+    // Validate (or discover) pairs up front so delisted / mistyped symbols never get fetched.
+    let supply_pairs: Vec<String> = match exchange_info::load_symbol_registry(&venues).await {
+        Ok(registry) => {
+            let (pairs, rejected) = exchange_info::resolve_pairs(&registry, file_pairs).await;
+            if let Some(streamed) = streamed {
+                let _ = streamed.send(PairLoadEvent::Symbols(registry));
+                for rejection in rejected {
+                    let _ = streamed.send(PairLoadEvent::Failed {
+                        pair: rejection.symbol().to_string(),
+                        reason: rejection.to_string(),
+                    });
+                }
+            }
+            pairs
         }
-        #[cfg(debug_assertions)]
-        let start_tasks_time = Instant::now(); // Record the start time
+        Err(e) => {
+            log::warn!("exchangeInfo unavailable, loading watchlist unvalidated: {:#}", e);
+            file_pairs.into_iter().take(BINANCE.max_pairs).collect()
+        }
+    };
+    if supply_pairs.is_empty() {
+        bail!("No valid pairs to load");
+    }
+
+    // A per-pair interval override replaces the supplied intervals for that pair.
+    let all_permutations = supply_pairs
+        .iter()
+        .flat_map(|pair_name| {
+            let intervals: Vec<i64> = match watchlist
+                .get(pair_name)
+                .and_then(|e| e.overrides.interval_ms)
+            {
+                Some(interval_ms) => vec![interval_ms],
+                None => supply_interval_asset.to_vec(),
+            };
+            intervals.into_iter().map(move |interval_ms| PairInterval {
+                name: pair_name.clone(),
+                interval_ms,
+            })
+        })
+        .take(BINANCE.limits.max_lookups_total);
+
+    // Every page goes through the shared scheduler, which paces the requests against each
+    // venue's weight budget; at most `max_requests_in_flight` pairs load at once.
+    let all_permutations_vec: Vec<PairInterval> = all_permutations.collect();
+    let scheduler = FetchScheduler::shared();
+    scheduler.begin_load(all_permutations_vec.len());
+
+    #[cfg(debug_assertions)]
+    let start_tasks_time = Instant::now(); // Record the start time
 
-        let mut handles: Vec<JoinHandle<Result<AllValidKlines4Pair>>> = Vec::new();
-        for pair_interval in batch_vec {
+    let mut loads = stream::iter(all_permutations_vec)
+        .map(|pair_interval| async move {
             #[cfg(debug_assertions)]
             if DEBUG_FLAGS.print_binance {
                 log::info!(
@@ -127,43 +172,15 @@ pub async fn timeseries_data_load(
                     pair_interval.interval_ms
                 );
             }
-            // Here you can make your API call for each item in the batch
-            let handle = tokio::spawn(bn_kline::load_klines(pair_interval.clone(), batch_size));
-            handles.push(handle);
-        }
-
-        let results: Vec<Result<Result<AllValidKlines4Pair>, JoinError>> = join_all(handles).await;
-        #[cfg(debug_assertions)]
-        {
-            let duration = start_tasks_time.elapsed(); // Calculate the elapsed time
-            if DEBUG_FLAGS.print_binance {
-                log::info!("\n...Time to complete all async tasks: {:?}", duration);
-                log::info!(
-                    "Number of results (successful + failed) returned is {}",
-                    results.len(),
-                );
-            }
-        }
-
-        let mut errors = Vec::new();
-
-        for result in results {
-            let pair_kline = match result {
-                Ok(inner_result) => inner_result,
-                Err(e) => {
-                    errors.push(format!("Request failed: {:?}", e));
-                    continue;
-                }
-            };
-
-            let pair_kline = match pair_kline {
-                Ok(data) => data,
-                Err(e) => {
-                    log::error!("Binance API error for pair: {:?}", e);
-                    continue;
-                }
-            };
+            let result = exchange_for(pair_interval.venue())
+                .fetch_klines(pair_interval.clone())
+                .await;
+            (pair_interval, result)
+        })
+        .buffer_unordered(BINANCE.limits.max_requests_in_flight);
 
+    while let Some((pair_interval, result)) = loads.next().await {
+        let series = result.and_then(|pair_kline| {
             #[cfg(debug_assertions)]
             if DEBUG_FLAGS.print_binance {
                 log::info!(
@@ -172,41 +189,42 @@ pub async fn timeseries_data_load(
                     pair_kline.klines.len()
                 );
             }
-            all_valid_klines_4_pairs.push(pair_kline);
-        }
-
-        // Return error if any critical failures occurred
-        if !errors.is_empty() {
-            return Err(anyhow!("Failed to fetch data: {}", errors.join(", ")));
+            Ok(OhlcvTimeSeries::from(OhlcvTimeSeriesTemp::try_from(pair_kline)?))
+        });
+        let progress = scheduler.pair_finished(series.is_ok());
+        log::info!("Loaded {}", progress);
+
+        let event = match series {
+            Ok(series) => {
+                let series = Arc::new(series);
+                let event = PairLoadEvent::Loaded(series.clone());
+                ohlcv_time_series.push(series);
+                event
+            }
+            Err(e) => {
+                log::error!("Exchange API error for {}: {:?}", pair_interval, e);
+                PairLoadEvent::Failed {
+                    pair: pair_interval.name().to_string(),
+                    reason: format!("{:#}", e),
+                }
+            }
+        };
+        if let Some(streamed) = streamed {
+            let _ = streamed.send(event);
         }
     }
 
-    if all_valid_klines_4_pairs.is_empty() {
-        bail!("Gotta bail because all_valid_klines_4_pairs is empty");
+    #[cfg(debug_assertions)]
+    if DEBUG_FLAGS.print_binance {
+        log::info!(
+            "\n...Time to complete all loads: {:?}",
+            start_tasks_time.elapsed()
+        );
     }
 
-    // Convert Vec<AllValidKlines4Pair> to Vec<OhlcvTimeSeriesTemp>
-    let ohlcv_time_series: Vec<OhlcvTimeSeriesTemp> = all_valid_klines_4_pairs
-        .into_par_iter()
-        // Map each item to a Result<OhlcvTimeSeriesTemp, E>
-        .map(OhlcvTimeSeriesTemp::try_from)
-        .filter_map(|result| match result {
-            Ok(ohlcv) => Some(ohlcv),
-            // For Err results, log the error and then return None to filter it out
-            Err(e) => {
-                log::error!("Error converting item: {}", e);
-                None
-            }
-        })
-        .collect();
-
     if ohlcv_time_series.is_empty() {
-        bail!("Gotta get out of app because ohlcv_time_series is empty.")
+        bail!("None of the {} pairs could be loaded", supply_pairs.len())
     }
-    let ohlcv_time_series: Vec<OhlcvTimeSeries> = ohlcv_time_series
-        .into_par_iter()
-        .map(|s| s.into())
-        .collect();
 
     Ok(ohlcv_time_series)
 }
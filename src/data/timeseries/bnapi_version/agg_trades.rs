@@ -0,0 +1,234 @@
+//! Binance `aggTrades` -> `TradeProfile`, cached on disk per pair and interval.
+//!
+//! SDK responses are re-read through `serde_json::Value` (Binance's short field names:
+//! `a` id, `p` price, `q` quantity, `T` time), as in `exchange_info`. A cached profile is
+//! topped up from the trade after its newest one, so only the first load pays for the
+//! whole lookback.
+
+use anyhow::{Context, Result, anyhow};
+use binance_sdk::spot::rest_api::{AggTradesParams, RestApi};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::config::{ANALYSIS, BINANCE, PERSISTENCE};
+use crate::data::exchange::binance::{observe_rate_limits, request_error};
+use crate::data::exchange::binance_spot::spot_client;
+use crate::data::fetch_scheduler::FetchScheduler;
+use crate::data::timeseries::TimeSeriesCollection;
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::venue::Venue;
+use crate::models::TradeProfile;
+use crate::utils::TimeUtils;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
+
+struct AggTrade {
+    id: i64,
+    timestamp_ms: i64,
+    price: f64,
+    quantity: f64,
+}
+
+fn parse_agg_trades(data: &Value) -> Result<Vec<AggTrade>> {
+    data.as_array()
+        .ok_or_else(|| anyhow!("aggTrades response is not a list"))?
+        .iter()
+        .map(|t| {
+            Some(AggTrade {
+                id: t.get("a")?.as_i64()?,
+                timestamp_ms: t.get("T")?.as_i64()?,
+                price: t.get("p")?.as_str()?.parse().ok()?,
+                quantity: t.get("q")?.as_str()?.parse().ok()?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| anyhow!("aggTrades response has a malformed trade"))
+}
+
+async fn fetch_agg_trades(
+    rest_client: &RestApi,
+    params: AggTradesParams,
+    pair_interval: &PairInterval,
+) -> Result<Vec<AggTrade>> {
+    let what = format!("aggTrades request for {}", pair_interval);
+    let response = FetchScheduler::shared()
+        .run(
+            Venue::BinanceSpot,
+            BINANCE.limits.agg_trades_call_weight,
+            || async {
+                rest_client
+                    .agg_trades(params.clone())
+                    .await
+                    .map_err(|e| request_error(e, &what))
+            },
+        )
+        .await?;
+    observe_rate_limits(Venue::BinanceSpot, &response.rate_limits);
+    let data = serde_json::to_value(response.data().await?)?;
+
+    parse_agg_trades(&data)
+}
+
+/// Adds every trade since the profile's newest one (or since its start, if it's empty)
+/// up to now. Returns how many trades were added.
+pub async fn update_trade_profile(
+    rest_client: &RestApi,
+    profile: &mut TradeProfile,
+    pair_interval: &PairInterval,
+    now_ms: i64,
+) -> Result<usize> {
+    let limit = BINANCE.limits.agg_trades_limit;
+    let symbol = pair_interval.venue_symbol().to_string();
+
+    // With no trade id to resume from, look for the first trade an hour at a time:
+    // Binance rejects startTime..endTime windows longer than that.
+    let mut from_id = profile.last_trade_id.map(|id| id + 1);
+    let mut window_start_ms = profile.last_trade_ms;
+    while from_id.is_none() && window_start_ms < now_ms {
+        let params = AggTradesParams::builder(symbol.clone())
+            .start_time(window_start_ms)
+            .end_time(window_start_ms + TimeUtils::MS_IN_H - 1)
+            .limit(limit)
+            .build()?;
+        let batch = fetch_agg_trades(rest_client, params, pair_interval).await?;
+        from_id = batch.first().map(|t| t.id);
+        window_start_ms += TimeUtils::MS_IN_H;
+    }
+
+    let mut added = 0;
+    while let Some(id) = from_id {
+        let params = AggTradesParams::builder(symbol.clone())
+            .from_id(id)
+            .limit(limit)
+            .build()?;
+        let batch = fetch_agg_trades(rest_client, params, pair_interval).await?;
+        for trade in &batch {
+            profile.add_trade(trade.id, trade.timestamp_ms, trade.price, trade.quantity);
+        }
+        added += batch.len();
+
+        // A short batch means we've caught up with the live market
+        from_id = match batch.last() {
+            Some(last) if batch.len() == limit as usize => Some(last.id + 1),
+            _ => None,
+        };
+    }
+
+    Ok(added)
+}
+
+fn profile_path(pair_interval: &PairInterval) -> PathBuf {
+    PathBuf::from(PERSISTENCE.trade_profile.directory).join(format!(
+        "{}_{}.bin",
+        Venue::path_safe(pair_interval.name()),
+        TimeUtils::interval_to_string(pair_interval.interval_ms)
+    ))
+}
+
+/// Reads the on-disk profile regardless of age. No network access.
+pub fn load_cached_trade_profile(pair_interval: &PairInterval) -> Result<TradeProfile> {
+    let path = profile_path(pair_interval);
+    let bytes =
+        std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    bincode::deserialize(&bytes).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save_trade_profile(pair_interval: &PairInterval, profile: &TradeProfile) -> Result<()> {
+    let path = profile_path(pair_interval);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, bincode::serialize(profile)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Gives every series a `TradeProfile` of its last `ANALYSIS.trades.lookback_days`, so
+/// the CVA knows the exact prices recent volume traded at. A pair whose trades can't be
+/// fetched keeps whatever its cached profile covers, or goes without.
+pub async fn attach_trade_profiles(collection: &mut TimeSeriesCollection) -> Result<()> {
+    let rest_client = spot_client()?;
+    let now_ms = local_now_as_timestamp_ms();
+    let window_start_ms = now_ms - ANALYSIS.trades.lookback_days * TimeUtils::MS_IN_D;
+
+    for series in &mut collection.series_data {
+        let pair_interval = series.pair_interval.clone();
+        // aggTrades are only fetched from Binance spot
+        if pair_interval.venue() != Venue::BinanceSpot {
+            continue;
+        }
+        let mut profile = match load_cached_trade_profile(&pair_interval) {
+            // A cache that stops before the window would resume from long-gone trades
+            Ok(cached)
+                if cached.interval_ms == pair_interval.interval_ms
+                    && cached.last_trade_ms >= window_start_ms =>
+            {
+                cached
+            }
+            _ => TradeProfile::new(
+                pair_interval.name().to_string(),
+                pair_interval.interval_ms,
+                window_start_ms,
+            ),
+        };
+        profile.prune_before(window_start_ms);
+
+        match update_trade_profile(rest_client, &mut profile, &pair_interval, now_ms).await {
+            Ok(added) => log::info!(
+                "{}: {} new aggTrades, {} candles with exact volume",
+                pair_interval,
+                added,
+                profile.candle_count()
+            ),
+            Err(e) => log::warn!("⚠️  aggTrades update failed for {}: {:#}", pair_interval, e),
+        }
+        // Trades arrive in id order, so even a failed update leaves a gap-free profile
+        // that the next run can resume from
+        if let Err(e) = save_trade_profile(&pair_interval, &profile) {
+            log::warn!("Could not cache aggTrades for {}: {:#}", pair_interval, e);
+        }
+
+        if !profile.is_empty() {
+            Arc::make_mut(series).trades = Some(Arc::new(profile));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two trades as `GET /api/v3/aggTrades` returns them
+    const AGG_TRADES_JSON: &str = r#"[
+        {"a":26129,"p":"0.01633102","q":"4.70443515","f":27781,"l":27781,"T":1498793709153,"m":true,"M":true},
+        {"a":26130,"p":"0.01633200","q":"0.10000000","f":27782,"l":27783,"T":1498793709286,"m":false,"M":true}
+    ]"#;
+
+    #[test]
+    fn agg_trades_are_read_from_binance_short_field_names() {
+        let data: Value = serde_json::from_str(AGG_TRADES_JSON).unwrap();
+        let trades = parse_agg_trades(&data).unwrap();
+
+        assert_eq!(trades.len(), 2);
+        let first = &trades[0];
+        assert_eq!(
+            (first.id, first.timestamp_ms, first.price, first.quantity),
+            (26129, 1498793709153, 0.01633102, 4.70443515)
+        );
+        assert_eq!((trades[1].id, trades[1].quantity), (26130, 0.1));
+        assert!(parse_agg_trades(&serde_json::json!([])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_agg_trades_are_an_error() {
+        for bad in [
+            r#"{"code":-1121,"msg":"Invalid symbol."}"#,
+            r#"[{"a":1,"q":"1.0","T":1}]"#,
+            r#"[{"a":1,"p":0.5,"q":"1.0","T":1}]"#,
+            r#"[{"a":1,"p":"abc","q":"1.0","T":1}]"#,
+        ] {
+            let data: Value = serde_json::from_str(bad).unwrap();
+            assert!(parse_agg_trades(&data).is_err(), "{}", bad);
+        }
+    }
+}
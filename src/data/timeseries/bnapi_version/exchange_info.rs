@@ -0,0 +1,178 @@
+//! Symbol metadata (`Exchange::fetch_symbols`) -> `SymbolRegistry`, cached on disk per
+//! venue. Registry keys are pair names (`BTCUSDT`, `binance-futures:BTCUSDT`), so one
+//! merged registry validates pairs from every venue.
+
+use anyhow::{Context, Result, anyhow};
+use std::path::PathBuf;
+
+use crate::config::{BINANCE, PERSISTENCE, PairSource};
+use crate::data::exchange::{Exchange, binance_spot::BinanceSpot, exchange_for};
+use crate::domain::symbol_registry::{PairRejection, SymbolInfo, SymbolRegistry};
+use crate::domain::venue::Venue;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
+
+/// `exchange_info.json` for Binance spot, `exchange_info_<venue>.json` for the others
+fn registry_path(venue: Venue) -> PathBuf {
+    let filename = match venue {
+        Venue::BinanceSpot => PERSISTENCE.exchange_info.filename.to_string(),
+        other => PERSISTENCE.exchange_info.filename.replacen(
+            ".json",
+            &format!("_{}.json", other.id()),
+            1,
+        ),
+    };
+    PathBuf::from(PERSISTENCE.exchange_info.directory).join(filename)
+}
+
+fn load_cached_venue_registry(venue: Venue) -> Result<SymbolRegistry> {
+    let path = registry_path(venue);
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Reads every venue's on-disk registry regardless of age, merged. No network access.
+pub fn load_cached_symbol_registry() -> Result<SymbolRegistry> {
+    let mut merged: Option<SymbolRegistry> = None;
+    for venue in Venue::ALL {
+        if let Ok(registry) = load_cached_venue_registry(venue) {
+            match merged.as_mut() {
+                Some(merged) => merged.merge(registry),
+                None => merged = Some(registry),
+            }
+        }
+    }
+    merged.ok_or_else(|| anyhow!("No cached exchangeInfo"))
+}
+
+fn save_symbol_registry(venue: Venue, registry: &SymbolRegistry) -> Result<()> {
+    let path = registry_path(venue);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string(registry)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Fetches every symbol listed on `exchange`, keyed by pair name.
+pub async fn fetch_symbol_registry(exchange: &dyn Exchange) -> Result<SymbolRegistry> {
+    let venue = exchange.venue();
+    let symbols = exchange.fetch_symbols().await?;
+
+    Ok(SymbolRegistry::new(
+        local_now_as_timestamp_ms(),
+        symbols.into_iter().map(|info| SymbolInfo {
+            symbol: venue.qualify(&info.symbol),
+            ..info
+        }),
+    ))
+}
+
+/// Fresh cache if we have one, otherwise refetch (falling back to a stale cache if
+/// the venue is unreachable).
+async fn load_venue_registry(venue: Venue) -> Result<SymbolRegistry> {
+    let cached = load_cached_venue_registry(venue).ok();
+    let now_ms = local_now_as_timestamp_ms();
+
+    if let Some(registry) = cached
+        .as_ref()
+        .filter(|r| !r.is_empty() && !r.is_stale(now_ms, PERSISTENCE.exchange_info.max_age_sec))
+    {
+        return Ok(registry.clone());
+    }
+
+    match fetch_symbol_registry(exchange_for(venue).as_ref()).await {
+        Ok(registry) => {
+            if let Err(e) = save_symbol_registry(venue, &registry) {
+                log::warn!("Could not cache {} exchangeInfo: {:#}", venue, e);
+            }
+            Ok(registry)
+        }
+        Err(e) => match cached {
+            Some(stale) if !stale.is_empty() => {
+                log::warn!(
+                    "{} exchangeInfo refresh failed, using stale cache: {:#}",
+                    venue,
+                    e
+                );
+                Ok(stale)
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+/// Registries of `venues`, merged into the one the engine splits pair names with. A venue
+/// that can't be loaded is left out (its pairs go unvalidated); it's an error only if
+/// none can be.
+pub async fn load_symbol_registry(venues: &[Venue]) -> Result<std::sync::Arc<SymbolRegistry>> {
+    let mut merged: Option<SymbolRegistry> = None;
+    let mut last_error = None;
+    for &venue in venues {
+        match load_venue_registry(venue).await {
+            Ok(registry) => match merged.as_mut() {
+                Some(merged) => merged.merge(registry),
+                None => merged = Some(registry),
+            },
+            Err(e) => {
+                log::warn!("{} exchangeInfo unavailable: {:#}", venue, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match merged {
+        Some(registry) => Ok(std::sync::Arc::new(registry)),
+        None => Err(last_error.unwrap_or_else(|| anyhow!("No venues to load exchangeInfo for"))),
+    }
+}
+
+/// Resolves the configured pair source into a validated list of pair names, and the
+/// watchlist pairs turned away. `file_pairs` are the enabled entries from the watchlist
+/// file. Discovery only looks at Binance spot; watchlist pairs on a venue missing from
+/// `registry` pass unvalidated.
+pub async fn resolve_pairs(
+    registry: &SymbolRegistry,
+    file_pairs: Vec<String>,
+) -> (Vec<String>, Vec<PairRejection>) {
+    let quote_asset = BINANCE.pairs.quote_asset.to_uppercase();
+
+    let discovered = match BINANCE.pairs.source {
+        PairSource::File => None,
+        PairSource::QuoteAsset => Some(Ok(registry
+            .trading_symbols_for_quote(&quote_asset)
+            .into_iter()
+            .filter(|pair| Venue::split(pair).0 == Venue::BinanceSpot)
+            .collect())),
+        PairSource::VolumeRank => {
+            Some(BinanceSpot::rank_by_24h_volume(registry, &quote_asset, BINANCE.max_pairs).await)
+        }
+    };
+
+    match discovered {
+        Some(Ok(pairs)) if !pairs.is_empty() => (
+            pairs.into_iter().take(BINANCE.max_pairs).collect(),
+            Vec::new(),
+        ),
+        other => {
+            if let Some(Err(e)) = other {
+                log::warn!("Pair discovery failed, using the watchlist: {:#}", e);
+            }
+            let (unchecked, checked): (Vec<String>, Vec<String>) = file_pairs
+                .into_iter()
+                .partition(|pair| !registry.has_venue(Venue::split(pair).0));
+            if !unchecked.is_empty() {
+                log::warn!("No exchangeInfo to validate {:?} against", unchecked);
+            }
+            let (valid, rejected) = registry.validate(&checked);
+            for rejection in &rejected {
+                log::warn!("Skipping watchlist pair: {}", rejection);
+            }
+            let pairs = valid
+                .into_iter()
+                .chain(unchecked)
+                .take(BINANCE.max_pairs)
+                .collect();
+            (pairs, rejected)
+        }
+    }
+}
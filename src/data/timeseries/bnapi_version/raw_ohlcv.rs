@@ -2,6 +2,7 @@ use anyhow::Result;
 use std::fmt;
 
 use crate::data::timeseries::bnapi_version::{AllValidKlines4Pair, PairInterval};
+use crate::models::{GapMask, IngestIssues};
 use crate::utils::{maths_utils, vec_utils};
 
 // MAX_PCT_MISSING_KLINES_ALLOWED is a delimiter. If BN klines data has < % of missing klines than this, we simply forward-fill the missing data.
@@ -24,6 +25,9 @@ pub struct OhlcvTimeSeriesTemp {
 
     // Stats
     pub pct_gaps: Option<f64>,
+    /// Which candles the forward fill below had to invent
+    pub gaps: GapMask,
+    pub ingest: IngestIssues,
 }
 
 impl OhlcvTimeSeriesTemp {}
@@ -80,10 +84,17 @@ impl TryFrom<AllValidKlines4Pair> for OhlcvTimeSeriesTemp {
             base_asset_volumes: vec![None; number_klines_needed],
             quote_asset_volumes: vec![None; number_klines_needed],
             pct_gaps: None,
+            gaps: GapMask::default(),
+            ingest: IngestIssues::default(),
         };
 
         // Loop through original klines data and map source data to destination data
         for source_kline in klines.klines {
+            let offset_ms = source_kline.open_timestamp_ms - time_series.first_kline_timestamp_ms;
+            if offset_ms % time_series.pair_interval.interval_ms != 0 {
+                time_series.ingest.misaligned_timestamps += 1;
+                continue;
+            }
             let kline_index = maths_utils::index_into_range(
                 time_series.first_kline_timestamp_ms,
                 source_kline.open_timestamp_ms,
@@ -100,6 +111,9 @@ impl TryFrom<AllValidKlines4Pair> for OhlcvTimeSeriesTemp {
                 );
             }
 
+            if time_series.open_prices[kline_index].is_some() {
+                time_series.ingest.duplicate_timestamps += 1;
+            }
             time_series.open_prices[kline_index] = source_kline.open_price;
             time_series.high_prices[kline_index] = source_kline.high_price;
             time_series.close_prices[kline_index] = source_kline.close_price;
@@ -114,28 +128,11 @@ impl TryFrom<AllValidKlines4Pair> for OhlcvTimeSeriesTemp {
         // log::info!("% of open price is {}", open_price_none_pct);
         time_series.pct_gaps = Some(open_price_none_pct);
         if open_price_none_pct > MAX_PCT_MISSING_KLINES_ALLOWED {
-            #[cfg(debug_assertions)]
-            log::info!(
-                "{} has {:.2}% gaps, which is above our limit ({:.2}%), so serious surgery required. ",
-                time_series.pair_interval,
-                vec_utils::count_pct_none_elements(&time_series.open_prices),
-                MAX_PCT_MISSING_KLINES_ALLOWED,
-            );
             // Find last None index, we will cut from this + 1
             let last_none_index = vec_utils::find_last_none_index(&time_series.open_prices);
-            log::info!("We have found last none index at {}", last_none_index);
-            log::info!(
-                "Before draining, open_prices was of size: {}",
-                time_series.open_prices.len()
-            );
             // Drain all vectors so they cut off up to this index
             let removed_count = time_series.open_prices.drain(..last_none_index).count();
-            #[cfg(debug_assertions)]
-            log::info!(
-                "After draining, open_prices is of size: {} and we removed {} items",
-                time_series.open_prices.len(),
-                removed_count
-            );
+            time_series.ingest.truncated_candles = removed_count;
             time_series.high_prices.drain(..last_none_index);
             time_series.low_prices.drain(..last_none_index);
             time_series.close_prices.drain(..last_none_index);
@@ -147,6 +144,17 @@ impl TryFrom<AllValidKlines4Pair> for OhlcvTimeSeriesTemp {
                 removed_count as i64 * time_series.pair_interval.interval_ms;
             time_series.pct_gaps = None;
         }
+        // Remember which candles are about to be invented, so analyses can tell them apart.
+        // A kline counts as filled if Binance was missing any of its fields.
+        time_series.gaps = GapMask::from_flags((0..time_series.open_prices.len()).map(|i| {
+            time_series.open_prices[i].is_none()
+                || time_series.high_prices[i].is_none()
+                || time_series.low_prices[i].is_none()
+                || time_series.close_prices[i].is_none()
+                || time_series.base_asset_volumes[i].is_none()
+                || time_series.quote_asset_volumes[i].is_none()
+        }));
+
         // Now go through each vector and forward fill (with default as 0?) any None values
         let default_price = 9999.99999; // Should never get filled in...
         let default_volume = 0.0; // Might happen occassionally
@@ -214,3 +222,47 @@ impl TryFrom<AllValidKlines4Pair> for OhlcvTimeSeriesTemp {
         Ok(time_series) // This is interrim time_series structure.....
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::timeseries::bnapi_version::bn_kline::BNKline;
+    use crate::models::GapRun;
+
+    const INTERVAL_MS: i64 = 60_000;
+
+    fn kline(idx: i64) -> BNKline {
+        let price = 100.0 + idx as f64;
+        BNKline {
+            open_timestamp_ms: idx * INTERVAL_MS,
+            open_price: Some(price),
+            high_price: Some(price + 1.0),
+            low_price: Some(price - 1.0),
+            close_price: Some(price),
+            base_asset_volume: Some(1.0),
+            quote_asset_volume: Some(price),
+        }
+    }
+
+    #[test]
+    fn forward_filled_klines_are_masked() {
+        // 20 minutes with 7 and 8 missing: 10% gaps, just under the fill limit
+        let klines = (0..20)
+            .filter(|idx| *idx != 7 && *idx != 8)
+            .map(kline)
+            .collect();
+        let pair_interval = PairInterval {
+            name: "BTCUSDT".to_string(),
+            interval_ms: INTERVAL_MS,
+        };
+
+        let series =
+            OhlcvTimeSeriesTemp::try_from(AllValidKlines4Pair::new(klines, pair_interval)).unwrap();
+
+        assert_eq!(series.open_prices.len(), 20);
+        assert_eq!(series.gaps.runs(), &[GapRun { start: 7, len: 2 }]);
+        assert_eq!(series.close_prices[7], Some(106.0));
+        assert_eq!(series.close_prices[8], Some(106.0));
+        assert_eq!(series.close_prices[9], Some(109.0));
+    }
+}
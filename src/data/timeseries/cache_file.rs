@@ -1,6 +1,7 @@
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use chrono::Utc;
@@ -8,9 +9,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::{PERSISTENCE, kline_cache_filename};
 use crate::data::timeseries::TimeSeriesCollection;
+use crate::domain::pair_interval::PairInterval;
+use crate::models::{GapMask, IngestIssues, OhlcvTimeSeries};
 
 /// Serialized cache wrapper used for both native and WASM demo builds.
+/// The on-disk layout is frozen at v4 (no gap masks); masks are re-detected on load.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "CacheFileV4", into = "CacheFileV4")]
 pub struct CacheFile {
     pub version: f64,
     pub timestamp_ms: i64,
@@ -18,6 +23,105 @@ pub struct CacheFile {
     pub data: TimeSeriesCollection,
 }
 
+/// `OhlcvTimeSeries` as serialized before it carried a `GapMask`: the v4 single-file
+/// cache, the WASM demo cache and v4/v5 chunk payloads.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnmaskedOhlcvTimeSeries {
+    pub pair_interval: PairInterval,
+    pub first_kline_timestamp_ms: i64,
+    pub open_prices: Vec<f64>,
+    pub high_prices: Vec<f64>,
+    pub low_prices: Vec<f64>,
+    pub close_prices: Vec<f64>,
+    pub base_asset_volumes: Vec<f64>,
+    pub quote_asset_volumes: Vec<f64>,
+    pub pct_gaps: f64,
+}
+
+impl From<UnmaskedOhlcvTimeSeries> for OhlcvTimeSeries {
+    fn from(old: UnmaskedOhlcvTimeSeries) -> Self {
+        let mut series = OhlcvTimeSeries {
+            pair_interval: old.pair_interval,
+            first_kline_timestamp_ms: old.first_kline_timestamp_ms,
+            open_prices: old.open_prices,
+            high_prices: old.high_prices,
+            low_prices: old.low_prices,
+            close_prices: old.close_prices,
+            base_asset_volumes: old.base_asset_volumes,
+            quote_asset_volumes: old.quote_asset_volumes,
+            pct_gaps: old.pct_gaps,
+            gaps: GapMask::default(),
+            ingest: IngestIssues::default(),
+            trades: None,
+        };
+        series.gaps = GapMask::detect_forward_filled(&series);
+        series
+    }
+}
+
+impl From<OhlcvTimeSeries> for UnmaskedOhlcvTimeSeries {
+    fn from(series: OhlcvTimeSeries) -> Self {
+        UnmaskedOhlcvTimeSeries {
+            pair_interval: series.pair_interval,
+            first_kline_timestamp_ms: series.first_kline_timestamp_ms,
+            open_prices: series.open_prices,
+            high_prices: series.high_prices,
+            low_prices: series.low_prices,
+            close_prices: series.close_prices,
+            base_asset_volumes: series.base_asset_volumes,
+            quote_asset_volumes: series.quote_asset_volumes,
+            pct_gaps: series.pct_gaps,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFileV4 {
+    version: f64,
+    timestamp_ms: i64,
+    interval_ms: i64,
+    name: String,
+    collection_version: f64,
+    series_data: Vec<UnmaskedOhlcvTimeSeries>,
+}
+
+impl From<CacheFileV4> for CacheFile {
+    fn from(file: CacheFileV4) -> Self {
+        Self {
+            version: file.version,
+            timestamp_ms: file.timestamp_ms,
+            interval_ms: file.interval_ms,
+            data: TimeSeriesCollection {
+                name: file.name,
+                version: file.collection_version,
+                series_data: file
+                    .series_data
+                    .into_iter()
+                    .map(|series| Arc::new(series.into()))
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl From<CacheFile> for CacheFileV4 {
+    fn from(file: CacheFile) -> Self {
+        Self {
+            version: file.version,
+            timestamp_ms: file.timestamp_ms,
+            interval_ms: file.interval_ms,
+            name: file.data.name,
+            collection_version: file.data.version,
+            series_data: file
+                .data
+                .series_data
+                .into_iter()
+                .map(|series| Arc::unwrap_or_clone(series).into())
+                .collect(),
+        }
+    }
+}
+
 impl CacheFile {
     pub fn new(interval_ms: i64, data: TimeSeriesCollection, version: f64) -> Self {
         Self {
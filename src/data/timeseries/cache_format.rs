@@ -0,0 +1,398 @@
+//! Versioned chunk encoding and the migrations between chunk format versions.
+//!
+//! Every chunk file is a fixed 36-byte little-endian header followed by a bincode payload:
+//!
+//! ```text
+//! magic "ZSKC" | major u16 | minor u16 | patch u16 | reserved u16
+//! schema hash u64 | payload checksum u64 | payload length u64
+//! ```
+//!
+//! Files without the magic are legacy (v4.0.0) headerless bincode. A chunk from an older
+//! version is run through `MIGRATIONS` in memory on load; `cache migrate` rewrites it so
+//! that only happens once. A format change therefore costs a local rewrite, not a refetch.
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::config::{CacheFormatVersion, PERSISTENCE};
+use crate::data::timeseries::cache_file::UnmaskedOhlcvTimeSeries;
+use crate::models::OhlcvTimeSeries;
+use crate::utils::checksum::Fnv1a64;
+
+pub const CACHE_MAGIC: [u8; 4] = *b"ZSKC";
+pub const HEADER_LEN: usize = 36;
+
+/// The serialized shape of a chunk payload. Edit this whenever `OhlcvTimeSeries` (or
+/// `PairInterval`) gains, loses or reorders a field: the changed hash is how a chunk
+/// written by an older build is told apart, and it must come with a version bump and
+/// a migration.
+const CHUNK_SCHEMA: &str = "OhlcvTimeSeries{pair_interval:PairInterval{name:String,interval_ms:i64},\
+first_kline_timestamp_ms:i64,open_prices:Vec<f64>,high_prices:Vec<f64>,low_prices:Vec<f64>,\
+close_prices:Vec<f64>,base_asset_volumes:Vec<f64>,quote_asset_volumes:Vec<f64>,pct_gaps:f64,\
+gaps:GapMask{runs:Vec<GapRun{start:usize,len:usize}>}}";
+
+pub fn current_schema_hash() -> u64 {
+    let mut hasher = Fnv1a64::new();
+    hasher.update(CHUNK_SCHEMA.as_bytes());
+    hasher.finish()
+}
+
+fn payload_checksum(payload: &[u8]) -> u64 {
+    let mut hasher = Fnv1a64::new();
+    hasher.update(payload);
+    hasher.finish()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkHeader {
+    pub version: CacheFormatVersion,
+    pub schema_hash: u64,
+    pub checksum: u64,
+    pub payload_len: u64,
+}
+
+impl ChunkHeader {
+    fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[0..4].copy_from_slice(&CACHE_MAGIC);
+        out[4..6].copy_from_slice(&self.version.major.to_le_bytes());
+        out[6..8].copy_from_slice(&self.version.minor.to_le_bytes());
+        out[8..10].copy_from_slice(&self.version.patch.to_le_bytes());
+        // 10..12 reserved
+        out[12..20].copy_from_slice(&self.schema_hash.to_le_bytes());
+        out[20..28].copy_from_slice(&self.checksum.to_le_bytes());
+        out[28..36].copy_from_slice(&self.payload_len.to_le_bytes());
+        out
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Self {
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let u64_at = |i: usize| {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[i..i + 8]);
+            u64::from_le_bytes(word)
+        };
+        Self {
+            version: CacheFormatVersion {
+                major: u16_at(4),
+                minor: u16_at(6),
+                patch: u16_at(8),
+            },
+            schema_hash: u64_at(12),
+            checksum: u64_at(20),
+            payload_len: u64_at(28),
+        }
+    }
+}
+
+/// Splits a chunk file into its header and payload, verifying length and checksum.
+/// Legacy headerless files come back with a synthesized `LEGACY` header.
+pub fn read_header(bytes: &[u8]) -> Result<(ChunkHeader, &[u8])> {
+    if bytes.len() < HEADER_LEN || bytes[0..4] != CACHE_MAGIC {
+        return Ok((
+            ChunkHeader {
+                version: CacheFormatVersion::LEGACY,
+                schema_hash: 0,
+                checksum: payload_checksum(bytes),
+                payload_len: bytes.len() as u64,
+            },
+            bytes,
+        ));
+    }
+
+    let mut raw = [0u8; HEADER_LEN];
+    raw.copy_from_slice(&bytes[..HEADER_LEN]);
+    let header = ChunkHeader::from_bytes(&raw);
+    let payload = &bytes[HEADER_LEN..];
+
+    if payload.len() as u64 != header.payload_len {
+        bail!(
+            "Truncated chunk: header says {} payload bytes, found {}",
+            header.payload_len,
+            payload.len()
+        );
+    }
+    if payload_checksum(payload) != header.checksum {
+        bail!("Payload checksum mismatch");
+    }
+    Ok((header, payload))
+}
+
+/// Serializes a series as a current-version chunk.
+pub fn encode_chunk(series: &OhlcvTimeSeries) -> Result<Vec<u8>> {
+    let payload = bincode::serialize(series)?;
+    Ok(wrap_payload(&payload))
+}
+
+fn wrap_payload(payload: &[u8]) -> Vec<u8> {
+    let header = ChunkHeader {
+        version: PERSISTENCE.kline.format_version,
+        schema_hash: current_schema_hash(),
+        checksum: payload_checksum(payload),
+        payload_len: payload.len() as u64,
+    };
+    let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+    out.extend_from_slice(&header.to_bytes());
+    out.extend_from_slice(payload);
+    out
+}
+
+/// A chunk read back from disk, plus the version it was stored as.
+#[derive(Debug, Clone)]
+pub struct DecodedChunk {
+    pub series: OhlcvTimeSeries,
+    pub stored_version: CacheFormatVersion,
+}
+
+impl DecodedChunk {
+    /// Older than this build writes; `cache migrate` rewrites these.
+    pub fn needs_migration(&self) -> bool {
+        self.stored_version < PERSISTENCE.kline.format_version
+    }
+}
+
+/// Decodes a chunk of any supported version, migrating older payloads in memory.
+/// Chunks sharing this build's major version are read directly (minor and patch
+/// changes are backwards compatible by definition).
+pub fn decode_chunk(bytes: &[u8]) -> Result<DecodedChunk> {
+    let (header, payload) = read_header(bytes)?;
+    let current = PERSISTENCE.kline.format_version;
+
+    let series = if header.version.major == current.major {
+        if header.schema_hash != current_schema_hash() {
+            bail!(
+                "Schema hash {:016x} does not match this build's {:016x} for v{} \
+                 (OhlcvTimeSeries changed without a format version bump?)",
+                header.schema_hash,
+                current_schema_hash(),
+                header.version
+            );
+        }
+        bincode::deserialize(payload)?
+    } else {
+        let mut payload = payload.to_vec();
+        for migration in migration_path(header.version)? {
+            payload = (migration.migrate)(&payload).map_err(|e| {
+                anyhow!(
+                    "Migration v{} -> v{} failed: {:#}",
+                    migration.from,
+                    migration.to,
+                    e
+                )
+            })?;
+        }
+        bincode::deserialize(&payload)?
+    };
+
+    Ok(DecodedChunk {
+        series,
+        stored_version: header.version,
+    })
+}
+
+/// One step between major chunk format versions. `migrate` turns a payload written under
+/// `from` into the payload `to` expects (headers are rebuilt by the caller).
+pub struct Migration {
+    pub from: CacheFormatVersion,
+    pub to: CacheFormatVersion,
+    pub description: &'static str,
+    pub migrate: fn(&[u8]) -> Result<Vec<u8>>,
+}
+
+/// Every supported upgrade, oldest first. Add a step here with each breaking format change.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: CacheFormatVersion::LEGACY,
+        to: CacheFormatVersion {
+            major: 5,
+            minor: 0,
+            patch: 0,
+        },
+        description: "Wrap headerless bincode chunks in a versioned, checksummed header",
+        // The payload layout is unchanged; only the header is new.
+        migrate: |payload| Ok(payload.to_vec()),
+    },
+    Migration {
+        from: CacheFormatVersion {
+            major: 5,
+            minor: 0,
+            patch: 0,
+        },
+        to: CacheFormatVersion {
+            major: 6,
+            minor: 0,
+            patch: 0,
+        },
+        description: "Add a gap mask, re-detected from forward-filled duplicate candles",
+        migrate: |payload| {
+            let old: UnmaskedOhlcvTimeSeries = bincode::deserialize(payload)?;
+            Ok(bincode::serialize(&OhlcvTimeSeries::from(old))?)
+        },
+    },
+];
+
+/// The chain of migrations taking `from` to the current major format version.
+pub fn migration_path(from: CacheFormatVersion) -> Result<Vec<&'static Migration>> {
+    let current = PERSISTENCE.kline.format_version;
+    if from.major > current.major {
+        bail!(
+            "Chunk format v{} is newer than this build supports (v{})",
+            from,
+            current
+        );
+    }
+
+    let mut path = Vec::new();
+    let mut version = from;
+    while version.major != current.major {
+        let step = MIGRATIONS
+            .iter()
+            .find(|m| m.from.major == version.major)
+            .ok_or_else(|| anyhow!("No migration from chunk format v{}", version))?;
+        path.push(step);
+        version = step.to;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GapMask, GapRun};
+    use crate::utils::TimeUtils;
+
+    const V5: CacheFormatVersion = CacheFormatVersion {
+        major: 5,
+        minor: 0,
+        patch: 0,
+    };
+
+    /// Candle 2 repeats candle 1: the forward fill the 5 -> 6 migration should find
+    fn series() -> OhlcvTimeSeries {
+        OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            TimeUtils::MS_IN_30_MIN,
+            1_738_281_600_000,
+            &[
+                (10.0, 12.0, 9.0, 11.0),
+                (11.0, 13.0, 10.0, 12.0),
+                (11.0, 13.0, 10.0, 12.0),
+                (12.0, 14.0, 11.0, 13.0),
+            ],
+        )
+    }
+
+    fn unmasked_payload(series: &OhlcvTimeSeries) -> Vec<u8> {
+        bincode::serialize(&UnmaskedOhlcvTimeSeries::from(series.clone())).unwrap()
+    }
+
+    fn with_header(version: CacheFormatVersion, schema_hash: u64, payload: &[u8]) -> Vec<u8> {
+        let header = ChunkHeader {
+            version,
+            schema_hash,
+            checksum: payload_checksum(payload),
+            payload_len: payload.len() as u64,
+        };
+        [header.to_bytes().as_slice(), payload].concat()
+    }
+
+    fn assert_same_candles(a: &OhlcvTimeSeries, b: &OhlcvTimeSeries) {
+        assert_eq!(a.pair_interval, b.pair_interval);
+        assert_eq!(a.first_kline_timestamp_ms, b.first_kline_timestamp_ms);
+        assert_eq!(a.open_prices, b.open_prices);
+        assert_eq!(a.high_prices, b.high_prices);
+        assert_eq!(a.low_prices, b.low_prices);
+        assert_eq!(a.close_prices, b.close_prices);
+        assert_eq!(a.base_asset_volumes, b.base_asset_volumes);
+        assert_eq!(a.quote_asset_volumes, b.quote_asset_volumes);
+    }
+
+    #[test]
+    fn current_chunks_round_trip() {
+        let mut original = series();
+        original.gaps = GapMask::detect_forward_filled(&original);
+        let bytes = encode_chunk(&original).unwrap();
+
+        let (header, payload) = read_header(&bytes).unwrap();
+        assert_eq!(&bytes[..4], &CACHE_MAGIC);
+        assert_eq!(header.version, PERSISTENCE.kline.format_version);
+        assert_eq!(header.schema_hash, current_schema_hash());
+        assert_eq!(payload.len(), bytes.len() - HEADER_LEN);
+
+        let decoded = decode_chunk(&bytes).unwrap();
+        assert!(!decoded.needs_migration());
+        assert_same_candles(&decoded.series, &original);
+        assert_eq!(decoded.series.gaps, original.gaps);
+    }
+
+    #[test]
+    fn legacy_chunks_migrate_through_5_to_6() {
+        let original = series();
+        let legacy = unmasked_payload(&original);
+
+        let path: Vec<_> = migration_path(CacheFormatVersion::LEGACY)
+            .unwrap()
+            .iter()
+            .map(|m| (m.from, m.to))
+            .collect();
+        assert_eq!(
+            path,
+            vec![
+                (CacheFormatVersion::LEGACY, V5),
+                (V5, PERSISTENCE.kline.format_version)
+            ]
+        );
+
+        let decoded = decode_chunk(&legacy).unwrap();
+        assert_eq!(decoded.stored_version, CacheFormatVersion::LEGACY);
+        assert!(decoded.needs_migration());
+        assert_same_candles(&decoded.series, &original);
+        // The gap mask didn't exist before 6: it is re-detected from the repeat
+        assert_eq!(decoded.series.gaps.runs(), &[GapRun { start: 2, len: 1 }]);
+
+        // Re-encoding is what `cache migrate` writes; it reads back without migrating
+        let rewritten = decode_chunk(&encode_chunk(&decoded.series).unwrap()).unwrap();
+        assert!(!rewritten.needs_migration());
+        assert_eq!(rewritten.series.gaps, decoded.series.gaps);
+    }
+
+    #[test]
+    fn v5_chunks_migrate_to_6() {
+        let original = series();
+        let bytes = with_header(V5, 0, &unmasked_payload(&original));
+
+        let decoded = decode_chunk(&bytes).unwrap();
+        assert_eq!(decoded.stored_version, V5);
+        assert!(decoded.needs_migration());
+        assert_same_candles(&decoded.series, &original);
+        assert_eq!(decoded.series.gaps.filled_count(), 1);
+    }
+
+    #[test]
+    fn corrupt_truncated_and_unknown_chunks_are_refused() {
+        let mut bytes = encode_chunk(&series()).unwrap();
+
+        let truncated = &bytes[..bytes.len() - 1];
+        let error = format!("{:#}", decode_chunk(truncated).unwrap_err());
+        assert!(error.contains("Truncated chunk"), "{}", error);
+
+        *bytes.last_mut().unwrap() ^= 0xff;
+        let error = format!("{:#}", decode_chunk(&bytes).unwrap_err());
+        assert!(error.contains("Payload checksum mismatch"), "{}", error);
+
+        // Same major version but another schema: OhlcvTimeSeries changed without a bump
+        let payload = bincode::serialize(&series()).unwrap();
+        let current = PERSISTENCE.kline.format_version;
+        let error = format!(
+            "{:#}",
+            decode_chunk(&with_header(current, 1, &payload)).unwrap_err()
+        );
+        assert!(error.contains("Schema hash"), "{}", error);
+
+        let newer = CacheFormatVersion {
+            major: current.major + 1,
+            ..current
+        };
+        assert!(decode_chunk(&with_header(newer, 0, &payload)).is_err());
+        assert!(migration_path(newer).is_err());
+    }
+}
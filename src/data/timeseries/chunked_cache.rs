@@ -0,0 +1,822 @@
+//! Per-pair, month-chunked kline cache.
+//!
+//! ```text
+//! kline_data/kline_30m/
+//!     manifest.json            <- format version + one entry per pair: chunks, timestamps, checksums
+//!     BTCUSDT/2025-10.bin      <- versioned header + bincode OhlcvTimeSeries for that month
+//!     BTCUSDT/2025-11.bin
+//!     ETHUSDT/...
+//! ```
+//!
+//! Pairs load independently (a corrupt chunk only loses its own pair), in parallel, and
+//! each is handed over as soon as it's decoded (`load_pairs_with`). Writing a series only
+//! rewrites the months whose checksum changed, so topping up a pair touches its latest
+//! chunk; the manifest is written once per batch. Each pair's entry carries its own
+//! `updated_at_ms`, which is what freshness checks go by. Chunk encoding and format
+//! migrations live in `cache_format`.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Datelike, Utc};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    CacheFormatVersion, PERSISTENCE, kline_cache_dirname, legacy_kline_cache_dirname,
+};
+use crate::data::timeseries::TimeSeriesCollection;
+use crate::data::timeseries::cache_file::CacheFile;
+use crate::data::timeseries::cache_format::{
+    DecodedChunk, current_schema_hash, decode_chunk, encode_chunk,
+};
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::venue::Venue;
+use crate::models::{GapMask, IngestIssues, OhlcvTimeSeries};
+use crate::utils::checksum::fnv1a_hex;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
+
+/// One month of candles for one pair.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChunkEntry {
+    /// "YYYY-MM" (UTC) of the candles' open times; also the file stem.
+    pub month: String,
+    pub first_kline_timestamp_ms: i64,
+    pub candle_count: usize,
+    /// FNV-1a of the whole chunk file (header included).
+    pub checksum: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PairManifestEntry {
+    pub pair_interval: PairInterval,
+    pub first_kline_timestamp_ms: i64,
+    pub candle_count: usize,
+    pub pct_gaps: f64,
+    pub updated_at_ms: i64,
+    /// Raw kline issues from when the series was built. Entries written before this was
+    /// recorded read as none.
+    #[serde(default)]
+    pub ingest: IngestIssues,
+    /// Oldest first; contiguous.
+    pub chunks: Vec<ChunkEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CacheManifest {
+    /// Oldest chunk format still present. Manifests from before versioned headers
+    /// carried a bare `version: 4.0` instead, which reads as `LEGACY`.
+    #[serde(default = "legacy_format_version")]
+    pub format_version: CacheFormatVersion,
+    /// `cache_format::current_schema_hash` of the build that last migrated the cache.
+    #[serde(default)]
+    pub schema_hash: String,
+    pub interval_ms: i64,
+    /// Last time any pair was written. Freshness is per pair
+    /// (`PairManifestEntry::updated_at_ms`); this only says when the cache last changed.
+    pub updated_at_ms: i64,
+    pub pairs: BTreeMap<String, PairManifestEntry>,
+}
+
+fn legacy_format_version() -> CacheFormatVersion {
+    CacheFormatVersion::LEGACY
+}
+
+/// Result of `ChunkedCache::verify` for one pair.
+#[derive(Debug, Clone)]
+pub struct PairVerification {
+    pub pair: String,
+    pub chunks: usize,
+    pub candles: usize,
+    /// Chunks stored in an older format (readable, but migrated on every load).
+    pub outdated_chunks: usize,
+    pub error: Option<String>,
+}
+
+/// What `ChunkedCache::upgrade` changed.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    /// A `kline_30m_v4`-style directory was renamed to the unversioned name.
+    pub moved_legacy_dir: bool,
+    /// The pre-chunking single-file cache was imported.
+    pub imported_legacy_file: bool,
+    pub pairs_migrated: usize,
+    pub chunks_rewritten: usize,
+    pub failed: Vec<(String, String)>,
+}
+
+impl MigrationReport {
+    pub fn changed_anything(&self) -> bool {
+        self.moved_legacy_dir || self.imported_legacy_file || self.chunks_rewritten > 0
+    }
+}
+
+/// Handle on a chunked cache directory. Nothing is read besides the manifest until
+/// a pair is requested.
+#[derive(Debug, Clone)]
+pub struct ChunkedCache {
+    pub dir: PathBuf,
+    pub manifest: CacheManifest,
+}
+
+impl ChunkedCache {
+    pub fn default_dir(interval_ms: i64) -> PathBuf {
+        PathBuf::from(PERSISTENCE.kline.directory).join(kline_cache_dirname(interval_ms))
+    }
+
+    /// Opens an existing cache. Fails if there is no readable manifest.
+    pub fn open(dir: &Path) -> Result<Self> {
+        let manifest_path = dir.join(PERSISTENCE.kline.manifest_filename);
+        let text = std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read cache manifest {:?}", manifest_path))?;
+        let manifest = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse cache manifest {:?}", manifest_path))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// Opens the cache at `dir`, or starts an empty one if it doesn't exist yet (or the
+    /// manifest is for another interval, in which case it is rebuilt). Older format
+    /// versions are kept: their chunks are migrated as they are read or rewritten.
+    pub fn open_or_create(dir: &Path, interval_ms: i64) -> Self {
+        match Self::open(dir) {
+            Ok(cache) if cache.manifest.interval_ms == interval_ms => cache,
+            _ => Self {
+                dir: dir.to_path_buf(),
+                manifest: CacheManifest {
+                    format_version: PERSISTENCE.kline.format_version,
+                    schema_hash: format!("{:016x}", current_schema_hash()),
+                    interval_ms,
+                    updated_at_ms: 0,
+                    pairs: BTreeMap::new(),
+                },
+            },
+        }
+    }
+
+    pub fn pair_names(&self) -> Vec<String> {
+        self.manifest.pairs.keys().cloned().collect()
+    }
+
+    pub fn entry(&self, pair: &str) -> Option<&PairManifestEntry> {
+        self.manifest.pairs.get(pair)
+    }
+
+    fn chunk_path(&self, pair: &str, month: &str) -> PathBuf {
+        self.dir
+            .join(Venue::path_safe(pair))
+            .join(format!("{}.bin", month))
+    }
+
+    /// Reads and decodes every chunk of one pair, verifying checksums and contiguity.
+    fn read_chunks(&self, pair: &str) -> Result<(&PairManifestEntry, Vec<DecodedChunk>)> {
+        let entry = self
+            .entry(pair)
+            .ok_or_else(|| anyhow!("{} is not in the cache manifest", pair))?;
+        let interval_ms = entry.pair_interval.interval_ms;
+
+        let mut decoded = Vec::with_capacity(entry.chunks.len());
+        let mut expected_start = entry.first_kline_timestamp_ms;
+        for chunk in &entry.chunks {
+            let path = self.chunk_path(pair, &chunk.month);
+            let bytes =
+                std::fs::read(&path).with_context(|| format!("Failed to read chunk {:?}", path))?;
+            if fnv1a_hex(&bytes) != chunk.checksum {
+                bail!("Checksum mismatch in {:?}", path);
+            }
+            let part =
+                decode_chunk(&bytes).with_context(|| format!("Failed to decode {:?}", path))?;
+
+            if part.series.first_kline_timestamp_ms != expected_start
+                || part.series.klines() != chunk.candle_count
+            {
+                bail!("Chunk {:?} does not continue the series", path);
+            }
+            expected_start += chunk.candle_count as i64 * interval_ms;
+            decoded.push(part);
+        }
+        Ok((entry, decoded))
+    }
+
+    /// Loads one pair, verifying every chunk's checksum and contiguity.
+    pub fn load_pair(&self, pair: &str) -> Result<OhlcvTimeSeries> {
+        let (entry, chunks) = self.read_chunks(pair)?;
+
+        let mut series = OhlcvTimeSeries {
+            pair_interval: entry.pair_interval.clone(),
+            first_kline_timestamp_ms: entry.first_kline_timestamp_ms,
+            open_prices: Vec::with_capacity(entry.candle_count),
+            high_prices: Vec::with_capacity(entry.candle_count),
+            low_prices: Vec::with_capacity(entry.candle_count),
+            close_prices: Vec::with_capacity(entry.candle_count),
+            base_asset_volumes: Vec::with_capacity(entry.candle_count),
+            quote_asset_volumes: Vec::with_capacity(entry.candle_count),
+            pct_gaps: entry.pct_gaps,
+            gaps: GapMask::default(),
+            ingest: entry.ingest,
+            trades: None,
+        };
+
+        for DecodedChunk { series: part, .. } in chunks {
+            series.gaps.append(&part.gaps, series.klines());
+            series.open_prices.extend(part.open_prices);
+            series.high_prices.extend(part.high_prices);
+            series.low_prices.extend(part.low_prices);
+            series.close_prices.extend(part.close_prices);
+            series.base_asset_volumes.extend(part.base_asset_volumes);
+            series.quote_asset_volumes.extend(part.quote_asset_volumes);
+        }
+
+        if series.klines() != entry.candle_count {
+            bail!(
+                "{}: loaded {} candles, manifest says {}",
+                pair,
+                series.klines(),
+                entry.candle_count
+            );
+        }
+        Ok(series)
+    }
+
+    /// Loads the given pairs in parallel. Failures are returned per pair rather than
+    /// aborting the whole load.
+    pub fn load_pairs(
+        &self,
+        pairs: &[String],
+    ) -> (Vec<Arc<OhlcvTimeSeries>>, Vec<(String, anyhow::Error)>) {
+        self.load_pairs_with(pairs, |_| {})
+    }
+
+    /// As `load_pairs`, handing each pair to `on_loaded` as soon as it is decoded, so
+    /// callers can use the first pairs while the rest are still being read.
+    pub fn load_pairs_with(
+        &self,
+        pairs: &[String],
+        on_loaded: impl Fn(&Arc<OhlcvTimeSeries>) + Sync,
+    ) -> (Vec<Arc<OhlcvTimeSeries>>, Vec<(String, anyhow::Error)>) {
+        let results: Vec<(String, Result<Arc<OhlcvTimeSeries>>)> = pairs
+            .par_iter()
+            .map(|pair| {
+                let result = self.load_pair(pair).map(Arc::new);
+                if let Ok(series) = &result {
+                    on_loaded(series);
+                }
+                (pair.clone(), result)
+            })
+            .collect();
+
+        let mut loaded = Vec::new();
+        let mut failed = Vec::new();
+        for (pair, result) in results {
+            match result {
+                Ok(series) => loaded.push(series),
+                Err(e) => failed.push((pair, e)),
+            }
+        }
+        (loaded, failed)
+    }
+
+    /// Every pair in the manifest as a collection; corrupt pairs are logged and skipped.
+    pub fn load_collection(&self) -> Result<TimeSeriesCollection> {
+        let (series_data, failed) = self.load_pairs(&self.pair_names());
+        for (pair, e) in &failed {
+            log::warn!("⚠️  Skipping cached {}: {:#}", pair, e);
+        }
+        if series_data.is_empty() {
+            bail!("No loadable pairs in cache {:?}", self.dir);
+        }
+
+        Ok(TimeSeriesCollection {
+            name: "Binance TimeSeries Collection".to_string(),
+            version: PERSISTENCE.kline.version,
+            series_data,
+        })
+    }
+
+    /// Writes one series, rewriting only months whose contents changed, then saves
+    /// the manifest. Returns the number of chunk files written.
+    pub fn write_series(&mut self, series: &OhlcvTimeSeries) -> Result<usize> {
+        let written = self.write_series_chunks(series)?;
+        self.save_manifest()?;
+        Ok(written)
+    }
+
+    /// `write_series` without saving the manifest, which only changes in memory.
+    fn write_series_chunks(&mut self, series: &OhlcvTimeSeries) -> Result<usize> {
+        let pair = series.pair_interval.name().to_string();
+        // Chunk dirs are keyed by pair within a cache of one interval
+        if series.pair_interval.interval_ms != self.manifest.interval_ms {
+            bail!(
+                "{} has {}ms klines, cache {:?} holds {}ms",
+                pair,
+                series.pair_interval.interval_ms,
+                self.dir,
+                self.manifest.interval_ms
+            );
+        }
+        let pair_dir = self.dir.join(Venue::path_safe(&pair));
+        std::fs::create_dir_all(&pair_dir)
+            .with_context(|| format!("Failed to create {:?}", pair_dir))?;
+
+        let previous: BTreeMap<String, String> = self
+            .entry(&pair)
+            .map(|e| {
+                e.chunks
+                    .iter()
+                    .map(|c| (c.month.clone(), c.checksum.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut chunks = Vec::new();
+        let mut written = 0;
+        for (month, start, end) in month_ranges(series) {
+            let part = series.sub_series(start, end);
+            let bytes = encode_chunk(&part)
+                .with_context(|| format!("Failed to serialize {} {}", pair, month))?;
+            let checksum = fnv1a_hex(&bytes);
+
+            let path = self.chunk_path(&pair, &month);
+            if previous.get(&month) != Some(&checksum) || !path.exists() {
+                write_atomically(&path, &bytes)?;
+                written += 1;
+            }
+
+            chunks.push(ChunkEntry {
+                month,
+                first_kline_timestamp_ms: part.first_kline_timestamp_ms,
+                candle_count: part.klines(),
+                checksum,
+            });
+        }
+
+        // Months that dropped out of the series (e.g. history trimmed) are removed.
+        for month in previous.keys() {
+            if !chunks.iter().any(|c| &c.month == month) {
+                let _ = std::fs::remove_file(self.chunk_path(&pair, month));
+            }
+        }
+
+        let now_ms = local_now_as_timestamp_ms();
+        self.manifest.pairs.insert(
+            pair,
+            PairManifestEntry {
+                pair_interval: series.pair_interval.clone(),
+                first_kline_timestamp_ms: series.first_kline_timestamp_ms,
+                candle_count: series.klines(),
+                pct_gaps: series.pct_gaps,
+                updated_at_ms: now_ms,
+                ingest: series.ingest,
+                chunks,
+            },
+        );
+        self.manifest.updated_at_ms = now_ms;
+
+        Ok(written)
+    }
+
+    /// Writes every series given, then the manifest once for the whole batch. Pairs
+    /// already cached but not given are kept, so a partial API load never shrinks the
+    /// cache. If a pair fails, the manifest is still saved for the pairs written before
+    /// it, whose new chunks are already in place.
+    pub fn write_collection<'a>(
+        &mut self,
+        series: impl IntoIterator<Item = &'a OhlcvTimeSeries>,
+    ) -> Result<usize> {
+        let mut written = 0;
+        let mut result = Ok(());
+        for series in series {
+            match self.write_series_chunks(series) {
+                Ok(chunks) => written += chunks,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.save_manifest()?;
+        result.map(|()| written)
+    }
+
+    /// Reads every chunk of every pair without loading the collection into memory at once.
+    pub fn verify(&self) -> Vec<PairVerification> {
+        self.pair_names()
+            .par_iter()
+            .map(|pair| {
+                let mut report = PairVerification {
+                    pair: pair.clone(),
+                    chunks: 0,
+                    candles: 0,
+                    outdated_chunks: 0,
+                    error: None,
+                };
+                match self.read_chunks(pair) {
+                    Ok((entry, chunks)) => {
+                        report.chunks = chunks.len();
+                        report.candles = entry.candle_count;
+                        report.outdated_chunks =
+                            chunks.iter().filter(|c| c.needs_migration()).count();
+                    }
+                    Err(e) => report.error = Some(format!("{:#}", e)),
+                }
+                report
+            })
+            .collect()
+    }
+
+    /// Rewrites every chunk stored in an older format as the current one. Timestamps are
+    /// left alone so a migrated cache is exactly as fresh as it was before.
+    pub fn migrate_chunks(&mut self, report: &mut MigrationReport) -> Result<()> {
+        for pair in self.pair_names() {
+            let rewritten = match self.read_chunks(&pair) {
+                Ok((_, chunks)) => {
+                    let mut rewritten = Vec::new();
+                    for (idx, chunk) in chunks.iter().enumerate() {
+                        if chunk.needs_migration() {
+                            rewritten.push((idx, encode_chunk(&chunk.series)?));
+                        }
+                    }
+                    rewritten
+                }
+                Err(e) => {
+                    report.failed.push((pair, format!("{:#}", e)));
+                    continue;
+                }
+            };
+            if rewritten.is_empty() {
+                continue;
+            }
+
+            let Some(entry) = self.manifest.pairs.get_mut(&pair) else {
+                continue;
+            };
+            for (idx, bytes) in rewritten {
+                let chunk = &mut entry.chunks[idx];
+                let path = self
+                    .dir
+                    .join(Venue::path_safe(&pair))
+                    .join(format!("{}.bin", chunk.month));
+                write_atomically(&path, &bytes)?;
+                chunk.checksum = fnv1a_hex(&bytes);
+                report.chunks_rewritten += 1;
+            }
+            report.pairs_migrated += 1;
+        }
+
+        if report.failed.is_empty() {
+            self.manifest.format_version = PERSISTENCE.kline.format_version;
+            self.manifest.schema_hash = format!("{:016x}", current_schema_hash());
+        }
+        self.save_manifest()
+    }
+
+    /// Brings the local cache for `interval_ms` up to the current format without touching
+    /// the network: moves a versioned `kline_30m_v4` directory to its unversioned name,
+    /// imports the legacy single-file cache if there is no chunked cache yet, and
+    /// migrates outdated chunks. Legacy files are left in place for the user to delete.
+    pub fn upgrade(interval_ms: i64) -> Result<MigrationReport> {
+        let mut report = MigrationReport::default();
+        let dir = Self::default_dir(interval_ms);
+        let manifest_path = dir.join(PERSISTENCE.kline.manifest_filename);
+
+        if !manifest_path.exists() {
+            let legacy_dir = PathBuf::from(PERSISTENCE.kline.directory)
+                .join(legacy_kline_cache_dirname(interval_ms));
+            if legacy_dir
+                .join(PERSISTENCE.kline.manifest_filename)
+                .exists()
+                && !dir.exists()
+            {
+                std::fs::rename(&legacy_dir, &dir)
+                    .with_context(|| format!("Failed to move {:?} to {:?}", legacy_dir, dir))?;
+                report.moved_legacy_dir = true;
+            }
+        }
+
+        if !manifest_path.exists() {
+            let legacy_path = CacheFile::default_cache_path(interval_ms);
+            if !legacy_path.exists() {
+                return Ok(report);
+            }
+            let legacy = CacheFile::load_from_path(&legacy_path)?;
+            if legacy.interval_ms != interval_ms {
+                bail!(
+                    "Legacy cache {:?} holds {}ms klines, expected {}ms",
+                    legacy_path,
+                    legacy.interval_ms,
+                    interval_ms
+                );
+            }
+
+            let mut cache = Self::open_or_create(&dir, interval_ms);
+            cache.write_collection(legacy.data.series_data.iter().map(Arc::as_ref))?;
+            // Keep the legacy file's age so the freshness check still means something.
+            for entry in cache.manifest.pairs.values_mut() {
+                entry.updated_at_ms = legacy.timestamp_ms;
+            }
+            cache.manifest.updated_at_ms = legacy.timestamp_ms;
+            cache.save_manifest()?;
+            report.imported_legacy_file = true;
+            return Ok(report);
+        }
+
+        let mut cache = Self::open(&dir)?;
+        if cache.manifest.format_version < PERSISTENCE.kline.format_version {
+            cache.migrate_chunks(&mut report)?;
+        }
+        Ok(report)
+    }
+
+    pub fn save_manifest(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {:?}", self.dir))?;
+        let json = serde_json::to_string_pretty(&self.manifest)?;
+        write_atomically(
+            &self.dir.join(PERSISTENCE.kline.manifest_filename),
+            json.as_bytes(),
+        )
+    }
+}
+
+/// (month label, start index, end index) for each UTC calendar month in the series.
+fn month_ranges(series: &OhlcvTimeSeries) -> Vec<(String, usize, usize)> {
+    let interval_ms = series.pair_interval.interval_ms;
+    let month_of = |idx: usize| {
+        let ts = series.first_kline_timestamp_ms + idx as i64 * interval_ms;
+        DateTime::<Utc>::from_timestamp_millis(ts)
+            .map(|dt| (dt.year(), dt.month()))
+            .unwrap_or_default()
+    };
+
+    let mut ranges: Vec<((i32, u32), usize, usize)> = Vec::new();
+    for idx in 0..series.klines() {
+        let month = month_of(idx);
+        match ranges.last_mut() {
+            Some((last_month, _, end)) if *last_month == month => *end = idx + 1,
+            _ => ranges.push((month, idx, idx + 1)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|((year, month), start, end)| (format!("{:04}-{:02}", year, month), start, end))
+        .collect()
+}
+
+/// Write to a temp file, flush it to disk, then rename, so a crash never leaves a
+/// half-written chunk or manifest: readers see the old file or the new one.
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file =
+        std::fs::File::create(&tmp).with_context(|| format!("Failed to create {:?}", tmp))?;
+    file.write_all(bytes)
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("Failed to write {:?}", tmp))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to move {:?} into place", tmp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::timeseries::cache_file::UnmaskedOhlcvTimeSeries;
+    use crate::utils::TimeUtils;
+
+    // 2025-01-31 00:00 UTC, so a few days of candles span two months
+    const START_MS: i64 = 1_738_281_600_000;
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "zone_sniper_chunked_cache_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn series(interval_ms: i64, klines: usize, first_close: f64) -> OhlcvTimeSeries {
+        named_series("BTCUSDT", interval_ms, klines, first_close)
+    }
+
+    fn named_series(
+        name: &str,
+        interval_ms: i64,
+        klines: usize,
+        first_close: f64,
+    ) -> OhlcvTimeSeries {
+        let candles: Vec<(f64, f64, f64, f64)> = (0..klines)
+            .map(|i| {
+                let close = first_close + i as f64;
+                (close, close + 1.0, close - 1.0, close)
+            })
+            .collect();
+        OhlcvTimeSeries::from_test_candles(name, interval_ms, START_MS, &candles)
+    }
+
+    #[test]
+    fn refuses_a_series_of_another_interval() {
+        let dir = temp_cache_dir("interval_guard");
+        let mut cache = ChunkedCache::open_or_create(&dir, TimeUtils::MS_IN_30_MIN);
+
+        let error = cache
+            .write_series(&series(TimeUtils::MS_IN_4_H, 12, 100.0))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("14400000ms klines"), "{}", error);
+        assert!(cache.entry("BTCUSDT").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn one_pair_at_two_intervals_keeps_both_caches() {
+        let root = temp_cache_dir("two_intervals");
+        let dir_30m = root.join(kline_cache_dirname(TimeUtils::MS_IN_30_MIN));
+        let dir_4h = root.join(kline_cache_dirname(TimeUtils::MS_IN_4_H));
+        let half_hourly = series(TimeUtils::MS_IN_30_MIN, 200, 100.0);
+        let four_hourly = series(TimeUtils::MS_IN_4_H, 30, 500.0);
+
+        let mut cache_30m = ChunkedCache::open_or_create(&dir_30m, TimeUtils::MS_IN_30_MIN);
+        assert_eq!(cache_30m.write_collection([&half_hourly]).unwrap(), 2);
+        let mut cache_4h = ChunkedCache::open_or_create(&dir_4h, TimeUtils::MS_IN_4_H);
+        assert_eq!(cache_4h.write_collection([&four_hourly]).unwrap(), 2);
+
+        let loaded_30m = ChunkedCache::open(&dir_30m)
+            .unwrap()
+            .load_pair("BTCUSDT")
+            .unwrap();
+        let loaded_4h = ChunkedCache::open(&dir_4h)
+            .unwrap()
+            .load_pair("BTCUSDT")
+            .unwrap();
+        assert_eq!(loaded_30m.close_prices, half_hourly.close_prices);
+        assert_eq!(
+            loaded_30m.pair_interval.interval_ms,
+            TimeUtils::MS_IN_30_MIN
+        );
+        assert_eq!(loaded_4h.close_prices, four_hourly.close_prices);
+        assert_eq!(loaded_4h.pair_interval.interval_ms, TimeUtils::MS_IN_4_H);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn a_batch_saves_the_manifest_once_even_when_a_pair_fails() {
+        let dir = temp_cache_dir("batch");
+        let mut cache = ChunkedCache::open_or_create(&dir, TimeUtils::MS_IN_30_MIN);
+        let good = named_series("ETHUSDT", TimeUtils::MS_IN_30_MIN, 100, 10.0);
+        let wrong_interval = named_series("SOLUSDT", TimeUtils::MS_IN_H, 100, 10.0);
+
+        assert!(cache.write_collection([&good, &wrong_interval]).is_err());
+
+        // What was written before the failure is in the saved manifest, no temp file left
+        let reopened = ChunkedCache::open(&dir).unwrap();
+        assert_eq!(reopened.pair_names(), vec!["ETHUSDT".to_string()]);
+        assert!(!dir.join("manifest.tmp").exists());
+        assert_eq!(
+            reopened.load_pair("ETHUSDT").unwrap().close_prices,
+            good.close_prices
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn freshness_is_kept_per_pair() {
+        let dir = temp_cache_dir("freshness");
+        let mut cache = ChunkedCache::open_or_create(&dir, TimeUtils::MS_IN_30_MIN);
+        let btc = named_series("BTCUSDT", TimeUtils::MS_IN_30_MIN, 50, 100.0);
+        let eth = named_series("ETHUSDT", TimeUtils::MS_IN_30_MIN, 50, 10.0);
+        cache.write_collection([&btc, &eth]).unwrap();
+        cache
+            .manifest
+            .pairs
+            .get_mut("ETHUSDT")
+            .unwrap()
+            .updated_at_ms = 1_000;
+        cache.save_manifest().unwrap();
+
+        // Topping up BTC leaves ETH as old as it was
+        let mut cache = ChunkedCache::open(&dir).unwrap();
+        cache.write_collection([&btc]).unwrap();
+        let reopened = ChunkedCache::open(&dir).unwrap();
+        assert_eq!(reopened.entry("ETHUSDT").unwrap().updated_at_ms, 1_000);
+        assert!(reopened.entry("BTCUSDT").unwrap().updated_at_ms > 1_000);
+        assert_eq!(
+            reopened.manifest.updated_at_ms,
+            reopened.entry("BTCUSDT").unwrap().updated_at_ms
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn pairs_are_handed_over_as_they_decode_and_a_corrupt_one_fails_alone() {
+        let dir = temp_cache_dir("streamed");
+        let mut cache = ChunkedCache::open_or_create(&dir, TimeUtils::MS_IN_30_MIN);
+        let btc = named_series("BTCUSDT", TimeUtils::MS_IN_30_MIN, 50, 100.0);
+        let eth = named_series("ETHUSDT", TimeUtils::MS_IN_30_MIN, 50, 10.0);
+        cache.write_collection([&btc, &eth]).unwrap();
+        let month = &cache.entry("ETHUSDT").unwrap().chunks[0].month;
+        std::fs::write(cache.chunk_path("ETHUSDT", month), b"not a chunk").unwrap();
+
+        let handed_over = std::sync::Mutex::new(Vec::new());
+        let (loaded, failed) = cache.load_pairs_with(&cache.pair_names(), |series| {
+            handed_over
+                .lock()
+                .unwrap()
+                .push(series.pair_interval.name().to_string())
+        });
+
+        assert_eq!(*handed_over.lock().unwrap(), vec!["BTCUSDT".to_string()]);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].close_prices, btc.close_prices);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "ETHUSDT");
+        assert!(failed[0].1.to_string().contains("Checksum mismatch"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn migrate_rewrites_legacy_chunks_in_place() {
+        let dir = temp_cache_dir("migrate");
+        let mut cache = ChunkedCache::open_or_create(&dir, TimeUtils::MS_IN_30_MIN);
+        let original = series(TimeUtils::MS_IN_30_MIN, 200, 100.0);
+        cache.write_series(&original).unwrap();
+
+        // Turn every chunk back into headerless v4 bincode, as an old build left them
+        let entry = cache.manifest.pairs.get_mut("BTCUSDT").unwrap();
+        let updated_at_ms = entry.updated_at_ms;
+        for chunk in &mut entry.chunks {
+            let path = dir.join("BTCUSDT").join(format!("{}.bin", chunk.month));
+            let decoded = decode_chunk(&std::fs::read(&path).unwrap()).unwrap();
+            let legacy =
+                bincode::serialize(&UnmaskedOhlcvTimeSeries::from(decoded.series)).unwrap();
+            chunk.checksum = fnv1a_hex(&legacy);
+            std::fs::write(&path, legacy).unwrap();
+        }
+        cache.manifest.format_version = CacheFormatVersion::LEGACY;
+        cache.save_manifest().unwrap();
+        let chunks = cache.entry("BTCUSDT").unwrap().chunks.len();
+        assert_eq!(cache.verify()[0].outdated_chunks, chunks);
+
+        let mut report = MigrationReport::default();
+        cache.migrate_chunks(&mut report).unwrap();
+
+        assert_eq!(report.chunks_rewritten, chunks);
+        assert_eq!(report.pairs_migrated, 1);
+        let reopened = ChunkedCache::open(&dir).unwrap();
+        assert_eq!(
+            reopened.manifest.format_version,
+            PERSISTENCE.kline.format_version
+        );
+        let entry = reopened.entry("BTCUSDT").unwrap();
+        assert_eq!(entry.updated_at_ms, updated_at_ms);
+        for chunk in &entry.chunks {
+            let bytes = std::fs::read(reopened.chunk_path("BTCUSDT", &chunk.month)).unwrap();
+            assert_eq!(fnv1a_hex(&bytes), chunk.checksum);
+        }
+        assert_eq!(reopened.verify()[0].outdated_chunks, 0);
+        assert_eq!(
+            reopened.load_pair("BTCUSDT").unwrap().close_prices,
+            original.close_prices
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ingest_issues_survive_the_cache() {
+        let dir = temp_cache_dir("ingest");
+        let mut cache = ChunkedCache::open_or_create(&dir, TimeUtils::MS_IN_30_MIN);
+        let mut original = series(TimeUtils::MS_IN_30_MIN, 100, 100.0);
+        original.ingest = IngestIssues {
+            duplicate_timestamps: 3,
+            misaligned_timestamps: 2,
+            truncated_candles: 40,
+        };
+        cache.write_series(&original).unwrap();
+
+        let reopened = ChunkedCache::open(&dir).unwrap();
+        assert_eq!(
+            reopened.load_pair("BTCUSDT").unwrap().ingest,
+            original.ingest
+        );
+
+        // Manifests from before the counters were kept still open, with none recorded
+        let manifest_path = dir.join(PERSISTENCE.kline.manifest_filename);
+        let mut json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+        json["pairs"]["BTCUSDT"]
+            .as_object_mut()
+            .unwrap()
+            .remove("ingest");
+        std::fs::write(&manifest_path, json.to_string()).unwrap();
+        let older = ChunkedCache::open(&dir).unwrap();
+        assert_eq!(
+            older.load_pair("BTCUSDT").unwrap().ingest,
+            IngestIssues::default()
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
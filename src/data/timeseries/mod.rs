@@ -2,7 +2,13 @@
 pub mod bnapi_version;
 pub mod cache_file;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod cache_format;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod chunked_cache;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod serde_version;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tabular_version;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm_demo;
 use crate::models::OhlcvTimeSeries;
@@ -10,6 +16,7 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeSet;
+use std::sync::Arc;
 
 #[async_trait]
 pub trait CreateTimeSeriesData {
@@ -23,6 +30,7 @@ pub trait CreateTimeSeriesData {
 pub async fn get_timeseries_data_async(
     implementations: &[Box<dyn CreateTimeSeriesData>],
 ) -> Result<(TimeSeriesCollection, &'static str)> {
+    let mut failures = Vec::new();
     for imp in implementations {
         match imp.create_timeseries_data().await {
             Ok(data) => {
@@ -31,18 +39,24 @@ pub async fn get_timeseries_data_async(
             }
             Err(e) => {
                 log::info!("Error with an async implementation: {}", e);
+                failures.push(format!("{}: {}", imp.signature(), e));
                 // Continue to the next implementation
             }
         }
     }
-    Err(anyhow!("All async implementations failed to create data"))
+    Err(anyhow!(
+        "All async implementations failed to create data ({})",
+        failures.join("; ")
+    ))
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct TimeSeriesCollection {
     pub name: String, // Metadata e.g. "Binance TimeSeries Collection".
     pub version: f64, // Half-hearted attempt to add versioning to Serialization (probably unncessary)
-    pub series_data: Vec<OhlcvTimeSeries>,
+    /// Each series behind its own `Arc`, so a copy of the collection shares the candles
+    /// and changing one pair (`Arc::make_mut`) copies only that pair.
+    pub series_data: Vec<Arc<OhlcvTimeSeries>>,
 }
 
 impl TimeSeriesCollection {
@@ -1,78 +1,152 @@
-use std::path::PathBuf;
-
 use anyhow::{Context, Result, bail};
 use async_trait::async_trait;
 
-use crate::config::{PERSISTENCE, kline_cache_filename};
+use std::collections::{BTreeMap, BTreeSet};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::config::{ANALYSIS, AnalysisConfig, BINANCE, PERSISTENCE, PairSource};
+use crate::data::pair_loading::PairLoadEvent;
+use crate::data::timeseries::chunked_cache::ChunkedCache;
 use crate::data::timeseries::{CreateTimeSeriesData, TimeSeriesCollection, cache_file::CacheFile};
+use crate::domain::watchlist::Watchlist;
+use crate::models::OhlcvTimeSeries;
+use crate::utils::TimeUtils;
 use crate::utils::time_utils::how_many_seconds_ago;
 
 #[cfg(debug_assertions)]
 use crate::config::DEBUG_FLAGS;
 
+/// Upgrades the local cache to the current format if needed (moving, importing or
+/// migrating it on disk rather than refetching), then checks its manifest. Freshness is
+/// per pair: every cached pair a load would serve (`pairs_in_use`, at any of the
+/// watchlist's intervals) must have been written within `recency_required_secs`.
 pub fn check_local_data_validity(
     recency_required_secs: i64,
-    version_required: f64,
     interval_ms: i64,
+    watchlist: &Watchlist,
 ) -> Result<()> {
-    let filename = kline_cache_filename(interval_ms);
-    let full_path = PathBuf::from(PERSISTENCE.kline.directory).join(&filename);
+    let chunked_dir = ChunkedCache::default_dir(interval_ms);
 
     #[cfg(debug_assertions)]
     if DEBUG_FLAGS.print_serde {
-        log::info!("Checking validity of local cache at {:?}...", full_path);
-        log::info!("Fetching data from local disk...");
+        log::info!("Checking validity of local cache at {:?}...", chunked_dir);
+    }
+
+    let report = ChunkedCache::upgrade(interval_ms).context("Local cache upgrade failed")?;
+    if report.changed_anything() {
+        log::info!(
+            "Upgraded local cache to format v{}: moved dir {}, imported legacy file {}, {} chunks rewritten across {} pairs",
+            PERSISTENCE.kline.format_version,
+            report.moved_legacy_dir,
+            report.imported_legacy_file,
+            report.chunks_rewritten,
+            report.pairs_migrated
+        );
+    }
+    for (pair, e) in &report.failed {
+        log::warn!("⚠️  Could not migrate cached {}: {}", pair, e);
     }
-    let cache = CacheFile::load_from_path(&full_path)?;
 
-    // Check version
-    if cache.version != version_required {
+    let cache = ChunkedCache::open(&chunked_dir)?;
+    if pairs_in_use(&cache, watchlist, interval_ms).is_empty() {
         bail!(
-            "Cache version mismatch: file v{} vs required v{}",
-            cache.version,
-            version_required
+            "Cache manifest {:?} lists none of the pairs in use",
+            chunked_dir
+        );
+    }
+    let version = cache.manifest.format_version;
+    let cached_interval_ms = cache.manifest.interval_ms;
+
+    // Check version (older formats were migrated above; a newer major is unreadable)
+    if version.major > PERSISTENCE.kline.format_version.major {
+        bail!(
+            "Cache format v{} is newer than this build supports (v{})",
+            version,
+            PERSISTENCE.kline.format_version
         );
     }
 
     // Check interval matches
-    if cache.interval_ms != interval_ms {
+    if cached_interval_ms != interval_ms {
         bail!(
             "Cache interval mismatch: file has {}ms intervals, expected {}ms",
-            cache.interval_ms,
+            cached_interval_ms,
             interval_ms
         );
     }
 
-    // Check recency
-    let seconds_ago = how_many_seconds_ago(cache.timestamp_ms);
-    if seconds_ago > recency_required_secs {
-        bail!(
-            "Cache too old: created {} seconds ago (limit: {} seconds)",
-            seconds_ago,
-            recency_required_secs
-        );
+    // Check recency of each pair in use, here and in the override intervals' caches
+    let mut seconds_ago = 0;
+    for other_interval_ms in intervals_in_use(watchlist, interval_ms) {
+        let other = if other_interval_ms == interval_ms {
+            cache.clone()
+        } else {
+            match ChunkedCache::open(&ChunkedCache::default_dir(other_interval_ms)) {
+                Ok(other) => other,
+                // Its pairs just won't come from the cache
+                Err(_) => continue,
+            }
+        };
+        for pair in pairs_in_use(&other, watchlist, interval_ms) {
+            let Some(entry) = other.entry(&pair) else {
+                continue;
+            };
+            let pair_seconds_ago = how_many_seconds_ago(entry.updated_at_ms);
+            if pair_seconds_ago > recency_required_secs {
+                bail!(
+                    "Cache too old: {} ({}) written {} seconds ago (limit: {} seconds)",
+                    pair,
+                    TimeUtils::interval_to_string(other_interval_ms),
+                    pair_seconds_ago,
+                    recency_required_secs
+                );
+            }
+            seconds_ago = seconds_ago.max(pair_seconds_ago);
+        }
     }
 
     #[cfg(debug_assertions)]
     if DEBUG_FLAGS.print_serde {
         log::info!(
-            "✅ Cache valid: v{}, {}s old (limit {}s), interval {}ms",
-            cache.version,
+            "✅ Cache valid: v{}, oldest pair {}s old (limit {}s), interval {}ms",
+            version,
             seconds_ago,
             recency_required_secs,
-            cache.interval_ms
+            cached_interval_ms
         );
     }
 
     Ok(())
 }
 
-/// Write timeseries data to binary cache file
+/// Loads the local kline cache: the chunked cache if present, else the legacy single file.
+pub fn load_local_collection(interval_ms: i64) -> Result<TimeSeriesCollection> {
+    let chunked_dir = ChunkedCache::default_dir(interval_ms);
+    match ChunkedCache::open(&chunked_dir).and_then(|cache| cache.load_collection()) {
+        Ok(collection) => Ok(collection),
+        Err(chunked_err) => {
+            let legacy_path = CacheFile::default_cache_path(interval_ms);
+            CacheFile::load_from_path(&legacy_path)
+                .map(|cache| cache.data)
+                .with_context(|| {
+                    format!(
+                        "No usable chunked cache ({:#}) or legacy cache {:?}",
+                        chunked_err, legacy_path
+                    )
+                })
+        }
+    }
+}
+
+/// Write timeseries data to the chunked caches (one bincode file per pair per month)
 /// Uses bincode for ~10-20x faster serialization vs JSON
+///
+/// Each pair goes in the cache of its own interval (`watchlist.config_for`), so a pair
+/// overridden to 4h never lands in, or overwrites, the 30m cache.
 pub fn write_timeseries_data_locally(
     timeseries_signature: &'static str,
     timeseries_collection: &TimeSeriesCollection,
-    interval_ms: i64,
+    watchlist: &Watchlist,
 ) -> Result<()> {
     if timeseries_signature != "Binance API" {
         #[cfg(debug_assertions)]
@@ -82,34 +156,44 @@ pub fn write_timeseries_data_locally(
         return Ok(());
     }
 
-    let filename = kline_cache_filename(interval_ms);
-    let dir_path = PathBuf::from(PERSISTENCE.kline.directory);
-    let full_path = dir_path.join(&filename);
+    let mut by_interval: BTreeMap<i64, Vec<&OhlcvTimeSeries>> = BTreeMap::new();
+    for series in &timeseries_collection.series_data {
+        let pair = series.pair_interval.name();
+        let interval_ms = watchlist.config_for(pair, &ANALYSIS).interval_width_ms;
+        if series.pair_interval.interval_ms != interval_ms {
+            log::warn!(
+                "⚠️  Not caching {}: loaded at {}ms, the watchlist says {}ms",
+                pair,
+                series.pair_interval.interval_ms,
+                interval_ms
+            );
+            continue;
+        }
+        by_interval.entry(interval_ms).or_default().push(series);
+    }
 
-    #[cfg(debug_assertions)]
-    let start_time = DEBUG_FLAGS.print_serde.then(|| {
-        log::info!("Writing cache to disk: {:?}...", full_path);
-        std::time::Instant::now()
-    });
-
-    let cache = CacheFile::new(
-        interval_ms,
-        timeseries_collection.clone(),
-        PERSISTENCE.kline.version,
-    );
-    cache.save_to_path(&full_path)?;
+    for (interval_ms, series) in by_interval {
+        let cache_dir = ChunkedCache::default_dir(interval_ms);
 
-    #[cfg(debug_assertions)]
-    if let Some(start) = start_time {
-        let elapsed = start.elapsed();
-        let file_size = std::fs::metadata(&full_path)?.len();
-        log::info!(
-            "✅ Cache written: {} ({:.1} MB in {:.2}s = {:.1} MB/s)",
-            filename,
-            file_size as f64 / 1_048_576.0,
-            elapsed.as_secs_f64(),
-            (file_size as f64 / 1_048_576.0) / elapsed.as_secs_f64()
-        );
+        #[cfg(debug_assertions)]
+        let start_time = DEBUG_FLAGS.print_serde.then(|| {
+            log::info!("Writing cache to disk: {:?}...", cache_dir);
+            std::time::Instant::now()
+        });
+
+        let mut cache = ChunkedCache::open_or_create(&cache_dir, interval_ms);
+        #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+        let chunks_written = cache.write_collection(series.iter().copied())?;
+
+        #[cfg(debug_assertions)]
+        if let Some(start) = start_time {
+            log::info!(
+                "✅ Cache written: {} pairs, {} changed chunks in {:.2}s",
+                series.len(),
+                chunks_written,
+                start.elapsed().as_secs_f64()
+            );
+        }
     }
 
     Ok(())
@@ -120,17 +204,107 @@ pub fn write_timeseries_data_locally(
 pub async fn write_timeseries_data_async(
     timeseries_signature: &'static str,
     timeseries_collection: TimeSeriesCollection,
-    interval_ms: i64,
+    watchlist: Watchlist,
 ) -> Result<()> {
     tokio::task::spawn_blocking(move || {
-        write_timeseries_data_locally(timeseries_signature, &timeseries_collection, interval_ms)
+        write_timeseries_data_locally(timeseries_signature, &timeseries_collection, &watchlist)
     })
     .await
     .context("Cache write task panicked")?
 }
 
+/// Every interval pairs are analysed at: `default_interval_ms` and each watchlist override
+fn intervals_in_use(watchlist: &Watchlist, default_interval_ms: i64) -> BTreeSet<i64> {
+    std::iter::once(default_interval_ms)
+        .chain(
+            watchlist
+                .entries
+                .iter()
+                .filter_map(|entry| entry.overrides.interval_ms),
+        )
+        .collect()
+}
+
+/// The pairs of `cache` a load serves: those the watchlist analyses at the cache's
+/// interval and, when pairs come from the watchlist file, only the ones it enables.
+/// Anything else in the cache (a pair since moved to another interval or dropped from
+/// the watchlist) is never read.
+fn pairs_in_use(
+    cache: &ChunkedCache,
+    watchlist: &Watchlist,
+    default_interval_ms: i64,
+) -> Vec<String> {
+    let base = AnalysisConfig {
+        interval_width_ms: default_interval_ms,
+        ..ANALYSIS.clone()
+    };
+    let from_file = BINANCE.pairs.source == PairSource::File && !watchlist.entries.is_empty();
+    cache
+        .pair_names()
+        .into_iter()
+        .filter(|pair| {
+            watchlist.config_for(pair, &base).interval_width_ms == cache.manifest.interval_ms
+                && (!from_file || watchlist.get(pair).is_some_and(|entry| entry.is_enabled()))
+        })
+        .collect()
+}
+
 pub struct SerdeVersion {
+    /// Interval of pairs without a watchlist override
     pub interval_ms: i64,
+    /// Pairs with an interval override are read from that interval's cache
+    pub watchlist: Watchlist,
+    /// Each pair is also sent here as soon as it's decoded (or has failed)
+    pub streamed: Option<UnboundedSender<PairLoadEvent>>,
+}
+
+impl SerdeVersion {
+    /// The pairs in use from the default interval's cache, then from each override
+    /// interval's cache. Only the default cache has to exist. Pairs are decoded one by
+    /// one (in parallel) and streamed as they're ready, never all before the first.
+    fn load(&self) -> Result<TimeSeriesCollection> {
+        let mut series_data = Vec::new();
+        for interval_ms in intervals_in_use(&self.watchlist, self.interval_ms) {
+            let cache = match ChunkedCache::open(&ChunkedCache::default_dir(interval_ms)) {
+                Ok(cache) => cache,
+                Err(e) if interval_ms == self.interval_ms => return Err(e),
+                Err(e) => {
+                    log::warn!(
+                        "⚠️  No {} cache: {:#}",
+                        TimeUtils::interval_to_string(interval_ms),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let pairs = pairs_in_use(&cache, &self.watchlist, self.interval_ms);
+            let (loaded, failed) = cache.load_pairs_with(&pairs, |series| {
+                if let Some(streamed) = &self.streamed {
+                    let _ = streamed.send(PairLoadEvent::Loaded(series.clone()));
+                }
+            });
+            for (pair, e) in failed {
+                log::warn!("⚠️  Skipping cached {}: {:#}", pair, e);
+                if let Some(streamed) = &self.streamed {
+                    let _ = streamed.send(PairLoadEvent::Failed {
+                        pair,
+                        reason: format!("Cached klines unreadable: {:#}", e),
+                    });
+                }
+            }
+            series_data.extend(loaded);
+        }
+
+        if series_data.is_empty() {
+            bail!("No loadable pairs in the local cache");
+        }
+        Ok(TimeSeriesCollection {
+            name: "Binance TimeSeries Collection".to_string(),
+            version: PERSISTENCE.kline.version,
+            series_data,
+        })
+    }
 }
 
 #[async_trait]
@@ -140,23 +314,29 @@ impl CreateTimeSeriesData for SerdeVersion {
     }
 
     async fn create_timeseries_data(&self) -> Result<TimeSeriesCollection> {
-        let filename = kline_cache_filename(self.interval_ms);
-        let full_path = PathBuf::from(PERSISTENCE.kline.directory).join(&filename);
+        let interval_ms = self.interval_ms;
+        let loader = SerdeVersion {
+            interval_ms,
+            watchlist: self.watchlist.clone(),
+            streamed: self.streamed.clone(),
+        };
 
         // 1. Declare the timer as an Option BEFORE the task
         // We use .then() which runs the closure only if PRINT_SERDE is true
         #[cfg(debug_assertions)]
         let start_time = DEBUG_FLAGS.print_serde.then(|| {
-            log::info!("Reading cache from: {:?}...", full_path);
+            log::info!(
+                "Reading cache from: {:?}...",
+                ChunkedCache::default_dir(interval_ms)
+            );
             std::time::Instant::now()
         });
 
-        // 2. Perform the task (Variable scope is unaffected here)
-        // Read file content
-        let cache = tokio::task::spawn_blocking(move || CacheFile::load_from_path(&full_path))
+        // 2. Perform the task (pairs are deserialized in parallel inside, and streamed)
+        let collection = tokio::task::spawn_blocking(move || loader.load())
             .await
             .context("Deserialization task panicked")?
-            .context("Failed to load cache file")?;
+            .context("Failed to load cache")?;
 
         // 3. Check if we have a start_time and log the result
         #[cfg(debug_assertions)]
@@ -164,11 +344,56 @@ impl CreateTimeSeriesData for SerdeVersion {
             let elapsed = start.elapsed();
             log::info!(
                 "✅ Cache loaded: {} pairs in {:.2}s",
-                cache.data.series_data.len(),
+                collection.series_data.len(),
                 elapsed.as_secs_f64()
             );
         }
 
-        Ok(cache.data)
+        Ok(collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TimeUtils;
+
+    #[test]
+    fn only_enabled_pairs_at_the_cache_interval_are_in_use() {
+        let dir = std::env::temp_dir().join(format!(
+            "zone_sniper_serde_version_in_use_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut cache = ChunkedCache::open_or_create(&dir, TimeUtils::MS_IN_30_MIN);
+        let candles = [(1.0, 2.0, 0.5, 1.5); 4];
+        let series: Vec<OhlcvTimeSeries> = ["BTCUSDT", "ETHUSDT", "SOLUSDT", "DOGEUSDT"]
+            .iter()
+            .map(|name| {
+                OhlcvTimeSeries::from_test_candles(
+                    name,
+                    TimeUtils::MS_IN_30_MIN,
+                    1_738_281_600_000,
+                    &candles,
+                )
+            })
+            .collect();
+        cache.write_collection(&series).unwrap();
+        let watchlist = Watchlist::parse(
+            "BTCUSDT\nETHUSDT interval=1h\n!SOLUSDT\n",
+            "watchlists/test.txt",
+        )
+        .unwrap();
+
+        // ETH moved to 1h, SOL disabled, DOGE dropped from the watchlist
+        assert_eq!(
+            pairs_in_use(&cache, &watchlist, TimeUtils::MS_IN_30_MIN),
+            vec!["BTCUSDT".to_string()]
+        );
+        assert_eq!(
+            intervals_in_use(&watchlist, TimeUtils::MS_IN_30_MIN),
+            BTreeSet::from([TimeUtils::MS_IN_30_MIN, TimeUtils::MS_IN_H])
+        );
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -0,0 +1,784 @@
+//! Candle data from CSV / Parquet files, and the matching exporters.
+//!
+//! Files are read into a `RawTable`, mapped onto candle fields with a `TabularFormat`
+//! (see `config::TABULAR`), then sent through the same gap handling as Binance klines
+//! (`OhlcvTimeSeriesTemp`), so imported series look exactly like fetched ones.
+//!
+//! The pair comes from the `symbol` column if mapped, else the file's metadata, else the
+//! file name (`BTCUSDT.csv`, `BTCUSDT_30m.csv`, `BTCUSDT-1h-2025-01.csv`). The interval
+//! comes from metadata, the file name, or the most common spacing between candles.
+//! Files for the same pair and interval (e.g. monthly dumps) are merged.
+//!
+//! Exports leave out filled gap candles (see `GapMask`), so re-importing one marks the
+//! same candles as gaps instead of mistaking the fill for real trading.
+
+pub mod csv_io;
+#[cfg(feature = "parquet")]
+pub mod parquet_io;
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use rayon::prelude::*;
+
+use crate::config::{ColumnRef, PERSISTENCE, TABULAR, TabularFormat, TimestampUnit};
+use crate::data::timeseries::bnapi_version::OhlcvTimeSeriesTemp;
+use crate::data::timeseries::bnapi_version::bn_kline::{AllValidKlines4Pair, BNKline};
+use crate::data::timeseries::{CreateTimeSeriesData, TimeSeriesCollection};
+use crate::domain::pair_interval::PairInterval;
+use crate::domain::venue::Venue;
+use crate::models::OhlcvTimeSeries;
+use crate::utils::TimeUtils;
+
+/// Metadata keys the exporters write (CSV `# key=value` header, Parquet key-value
+/// metadata) and the importer reads back.
+pub const META_PAIR: &str = "pair";
+pub const META_INTERVAL_MS: &str = "interval_ms";
+pub const META_PCT_GAPS: &str = "pct_gaps";
+
+/// A single value as it came out of a file, before column mapping.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    /// Already a UTC millisecond timestamp (typed Parquet timestamp columns)
+    TimestampMs(i64),
+    Null,
+}
+
+impl Cell {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Cell::Int(v) | Cell::TimestampMs(v) => Some(*v as f64),
+            Cell::Float(v) => Some(*v),
+            Cell::Text(s) => s.trim().parse().ok(),
+            Cell::Null => None,
+        }
+        .filter(|v: &f64| v.is_finite())
+    }
+
+    fn as_text(&self) -> Option<String> {
+        match self {
+            Cell::Text(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            Cell::Int(v) => Some(v.to_string()),
+            _ => None,
+        }
+    }
+
+    fn as_timestamp_ms(&self, unit: TimestampUnit) -> Option<i64> {
+        match self {
+            Cell::TimestampMs(v) => Some(*v),
+            Cell::Int(v) => scale_to_ms(*v, unit),
+            Cell::Float(v) => scale_to_ms(*v as i64, unit),
+            Cell::Text(s) => {
+                let s = s.trim();
+                match s.parse::<i64>() {
+                    Ok(v) => scale_to_ms(v, unit),
+                    Err(_) if matches!(unit, TimestampUnit::DateTime | TimestampUnit::Auto) => {
+                        parse_datetime_ms(s)
+                    }
+                    Err(_) => None,
+                }
+            }
+            Cell::Null => None,
+        }
+    }
+}
+
+fn scale_to_ms(value: i64, unit: TimestampUnit) -> Option<i64> {
+    let unit = match unit {
+        TimestampUnit::Auto => match value.unsigned_abs() {
+            0..100_000_000_000 => TimestampUnit::Seconds,
+            100_000_000_000..100_000_000_000_000 => TimestampUnit::Milliseconds,
+            100_000_000_000_000..100_000_000_000_000_000 => TimestampUnit::Microseconds,
+            _ => TimestampUnit::Nanoseconds,
+        },
+        other => other,
+    };
+    match unit {
+        TimestampUnit::Seconds => value.checked_mul(1000),
+        TimestampUnit::Milliseconds => Some(value),
+        TimestampUnit::Microseconds => Some(value / 1000),
+        TimestampUnit::Nanoseconds => Some(value / 1_000_000),
+        TimestampUnit::DateTime | TimestampUnit::Auto => None,
+    }
+}
+
+fn parse_datetime_ms(text: &str) -> Option<i64> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.timestamp_millis());
+    }
+    for pattern in [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(text, pattern) {
+            return Some(dt.and_utc().timestamp_millis());
+        }
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
+
+/// A file read into memory, before column mapping.
+#[derive(Debug, Clone, Default)]
+pub struct RawTable {
+    /// None for headerless CSVs (columns can then only be mapped by index)
+    pub headers: Option<Vec<String>>,
+    pub rows: Vec<Vec<Cell>>,
+    pub metadata: HashMap<String, String>,
+}
+
+impl RawTable {
+    fn resolve(&self, column: ColumnRef) -> Result<usize> {
+        match column {
+            ColumnRef::Index(idx) => Ok(idx),
+            ColumnRef::Name(name) => self
+                .headers
+                .as_ref()
+                .ok_or_else(|| anyhow!("Column '{}' requested but the file has no header", name))?
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("No column named '{}'", name)),
+        }
+    }
+}
+
+/// Rows for one pair, gathered from one or more files.
+#[derive(Debug, Default)]
+struct PairRows {
+    interval_ms: Option<i64>,
+    pct_gaps: Option<f64>,
+    klines: Vec<BNKline>,
+}
+
+/// A pair name as written in a file, with its symbol uppercased and any venue prefix kept
+/// ("binance-futures:btcusdt" -> "binance-futures:BTCUSDT").
+fn pair_name(name: &str) -> String {
+    let (venue, symbol) = Venue::split(name.trim());
+    venue.qualify(&symbol.to_uppercase())
+}
+
+/// "BTCUSDT_30m" / "BTCUSDT-1h-2025-01" -> ("BTCUSDT", Some(30m)).
+/// "binance-futures@BTCUSDT_30m" (see `Venue::path_safe`) -> ("binance-futures:BTCUSDT", ..).
+fn pair_and_interval_from_stem(stem: &str) -> (String, Option<i64>) {
+    let (venue, rest) = match stem.split_once('@') {
+        Some((id, rest)) => (Venue::from_id(id), rest),
+        None => (None, stem),
+    };
+    let mut tokens = rest.split(['_', '-']);
+    let symbol = tokens.next().unwrap_or(rest).to_uppercase();
+    let interval_ms = tokens.next().and_then(TimeUtils::interval_from_string);
+    let pair = venue.unwrap_or(Venue::BinanceSpot).qualify(&symbol);
+    (pair, interval_ms)
+}
+
+fn read_table(path: &Path, format: &TabularFormat) -> Result<RawTable> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    match extension.as_str() {
+        "csv" | "txt" => csv_io::read_table(path, format),
+        #[cfg(feature = "parquet")]
+        "parquet" => parquet_io::read_table(path),
+        #[cfg(not(feature = "parquet"))]
+        "parquet" => bail!("Built without the `parquet` feature (cargo run --features parquet)"),
+        other => bail!("Unsupported file type '{}'", other),
+    }
+}
+
+fn is_importable(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .as_deref(),
+        Some("csv" | "txt" | "parquet")
+    )
+}
+
+/// Reads one file into per-pair rows keyed by pair name.
+fn read_file(path: &Path, format: &TabularFormat) -> Result<BTreeMap<String, PairRows>> {
+    let table = read_table(path, format)?;
+    let columns = &format.columns;
+    let ts_col = table.resolve(columns.timestamp)?;
+    let open_col = table.resolve(columns.open)?;
+    let high_col = table.resolve(columns.high)?;
+    let low_col = table.resolve(columns.low)?;
+    let close_col = table.resolve(columns.close)?;
+    let base_col = table.resolve(columns.base_volume)?;
+    let quote_col = columns.quote_volume.map(|c| table.resolve(c)).transpose()?;
+    let symbol_col = columns.symbol.map(|c| table.resolve(c)).transpose()?;
+
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("IMPORTED");
+    let (stem_pair, stem_interval) = pair_and_interval_from_stem(stem);
+    let file_pair = table
+        .metadata
+        .get(META_PAIR)
+        .map(|p| pair_name(p))
+        .unwrap_or(stem_pair);
+    let file_interval = table
+        .metadata
+        .get(META_INTERVAL_MS)
+        .and_then(|v| v.parse().ok())
+        .or(stem_interval);
+    let file_pct_gaps = table
+        .metadata
+        .get(META_PCT_GAPS)
+        .and_then(|v| v.parse().ok());
+
+    let mut pairs: BTreeMap<String, PairRows> = BTreeMap::new();
+    for (row_no, row) in table.rows.iter().enumerate() {
+        let cell = |idx: usize| row.get(idx).unwrap_or(&Cell::Null);
+        let price = |idx: usize| cell(idx).as_f64();
+
+        let open_timestamp_ms = cell(ts_col)
+            .as_timestamp_ms(format.timestamp_unit)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Row {}: unreadable timestamp {:?}",
+                    row_no + 1,
+                    cell(ts_col)
+                )
+            })?;
+        let close_price = price(close_col);
+        let base_asset_volume = price(base_col);
+        let quote_asset_volume = match quote_col {
+            Some(idx) => price(idx),
+            None => close_price.zip(base_asset_volume).map(|(c, v)| c * v),
+        };
+
+        let pair = symbol_col
+            .and_then(|idx| cell(idx).as_text())
+            .map(|s| pair_name(&s))
+            .unwrap_or_else(|| file_pair.clone());
+        let rows = pairs.entry(pair).or_insert_with(|| PairRows {
+            interval_ms: file_interval,
+            pct_gaps: file_pct_gaps,
+            klines: Vec::new(),
+        });
+        rows.klines.push(BNKline {
+            open_timestamp_ms,
+            open_price: price(open_col),
+            high_price: price(high_col),
+            low_price: price(low_col),
+            close_price,
+            base_asset_volume,
+            quote_asset_volume,
+        });
+    }
+    Ok(pairs)
+}
+
+/// The most common spacing between consecutive (sorted, distinct) candles.
+fn infer_interval_ms(klines: &[BNKline]) -> Option<i64> {
+    let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+    for pair in klines.windows(2) {
+        let delta = pair[1].open_timestamp_ms - pair[0].open_timestamp_ms;
+        if delta > 0 {
+            *counts.entry(delta).or_default() += 1;
+        }
+    }
+    // max_by_key keeps the last maximum; iterate in reverse so ties pick the smaller delta.
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(delta, _)| delta)
+}
+
+fn build_series(pair: String, mut rows: PairRows) -> Result<OhlcvTimeSeries> {
+    rows.klines.sort_by_key(|k| k.open_timestamp_ms);
+    rows.klines.dedup_by_key(|k| k.open_timestamp_ms);
+
+    let interval_ms = rows
+        .interval_ms
+        .or_else(|| infer_interval_ms(&rows.klines))
+        .ok_or_else(|| {
+            anyhow!(
+                "{}: can't infer the interval from {} candle(s); name the file PAIR_INTERVAL.csv",
+                pair,
+                rows.klines.len()
+            )
+        })?;
+
+    let first_ms = rows.klines[0].open_timestamp_ms;
+    if let Some(off_grid) = rows
+        .klines
+        .iter()
+        .find(|k| (k.open_timestamp_ms - first_ms) % interval_ms != 0)
+    {
+        bail!(
+            "{}: candle at {} is not on the {} grid starting {}",
+            pair,
+            off_grid.open_timestamp_ms,
+            TimeUtils::interval_to_string(interval_ms),
+            first_ms
+        );
+    }
+
+    let klines = AllValidKlines4Pair::new(
+        rows.klines,
+        PairInterval {
+            name: pair.clone(),
+            interval_ms,
+        },
+    );
+    let temp = OhlcvTimeSeriesTemp::try_from(klines).map_err(|e| anyhow!("{}", e))?;
+    let mut series: OhlcvTimeSeries = temp.into();
+
+    // A file with no holes of its own keeps the gap stat recorded when it was exported.
+    if series.pct_gaps == 0.0 {
+        if let Some(pct_gaps) = rows.pct_gaps {
+            series.pct_gaps = pct_gaps;
+        }
+    }
+    Ok(series)
+}
+
+/// Imports a file, or every .csv / .parquet file in a directory.
+pub fn import_path(path: &Path, format: &TabularFormat) -> Result<Vec<OhlcvTimeSeries>> {
+    let files: Vec<PathBuf> = if path.is_dir() {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read {:?}", path))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| is_importable(p))
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let per_file: Vec<(PathBuf, Result<BTreeMap<String, PairRows>>)> = files
+        .into_par_iter()
+        .map(|file| {
+            let rows = read_file(&file, format);
+            (file, rows)
+        })
+        .collect();
+
+    let mut merged: BTreeMap<String, PairRows> = BTreeMap::new();
+    for (file, result) in per_file {
+        match result {
+            Ok(pairs) => {
+                for (pair, rows) in pairs {
+                    let entry = merged.entry(pair).or_default();
+                    entry.interval_ms = entry.interval_ms.or(rows.interval_ms);
+                    entry.pct_gaps = entry.pct_gaps.or(rows.pct_gaps);
+                    entry.klines.extend(rows.klines);
+                }
+            }
+            Err(e) => log::warn!("⚠️  Skipping {:?}: {:#}", file, e),
+        }
+    }
+
+    let mut series_data = Vec::new();
+    for (pair, rows) in merged {
+        match build_series(pair.clone(), rows) {
+            Ok(series) => series_data.push(series),
+            Err(e) => log::warn!("⚠️  Skipping imported {}: {:#}", pair, e),
+        }
+    }
+    if series_data.is_empty() {
+        bail!("No usable candle data in {:?}", path);
+    }
+    Ok(series_data)
+}
+
+/// `{export dir}/BTCUSDT_30m.csv`
+pub fn export_path(series: &OhlcvTimeSeries, extension: &str) -> PathBuf {
+    PathBuf::from(TABULAR.export.directory).join(format!(
+        "{}_{}.{}",
+        Venue::path_safe(series.pair_interval.name()),
+        TimeUtils::interval_to_string(series.pair_interval.interval_ms),
+        extension
+    ))
+}
+
+/// Writes a series as CSV or Parquet, chosen by the path's extension.
+pub fn export_series(series: &OhlcvTimeSeries, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("csv") => csv_io::write_series(series, path),
+        #[cfg(feature = "parquet")]
+        Some("parquet") => parquet_io::write_series(series, path),
+        #[cfg(not(feature = "parquet"))]
+        Some("parquet") => {
+            bail!("Built without the `parquet` feature (cargo run --features parquet)")
+        }
+        other => bail!("Unsupported export type {:?}", other),
+    }
+}
+
+fn metadata_for(series: &OhlcvTimeSeries) -> Vec<(&'static str, String)> {
+    vec![
+        (META_PAIR, series.pair_interval.name().to_string()),
+        (
+            META_INTERVAL_MS,
+            series.pair_interval.interval_ms.to_string(),
+        ),
+        (META_PCT_GAPS, series.pct_gaps.to_string()),
+    ]
+}
+
+/// Column names the exporters write, in order (matches `TABULAR.import.default_format`).
+pub const EXPORT_COLUMNS: [&str; 7] = [
+    "open_time_ms",
+    "open",
+    "high",
+    "low",
+    "close",
+    "base_volume",
+    "quote_volume",
+];
+
+pub struct TabularVersion {
+    pub path: PathBuf,
+    pub format: TabularFormat,
+}
+
+#[async_trait]
+impl CreateTimeSeriesData for TabularVersion {
+    fn signature(&self) -> &'static str {
+        "File Import"
+    }
+
+    async fn create_timeseries_data(&self) -> Result<TimeSeriesCollection> {
+        let path = self.path.clone();
+        let format = self.format;
+        let series_data = tokio::task::spawn_blocking(move || import_path(&path, &format))
+            .await
+            .context("Import task panicked")??;
+
+        log::info!("Imported {} pairs from {:?}", series_data.len(), self.path);
+        Ok(TimeSeriesCollection {
+            name: "Imported TimeSeries Collection".to_string(),
+            version: PERSISTENCE.kline.version,
+            series_data: series_data.into_iter().map(Arc::new).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ColumnMapping;
+    use crate::models::gap_mask::GapRun;
+
+    // Monday 2025-01-06 00:00 UTC
+    const START_MS: i64 = 1_736_121_600_000;
+    const HALF_HOUR_MS: i64 = 30 * 60 * 1000;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "zone_sniper_tabular_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn kline_at(open_timestamp_ms: i64) -> BNKline {
+        BNKline {
+            open_timestamp_ms,
+            open_price: Some(1.0),
+            high_price: Some(1.0),
+            low_price: Some(1.0),
+            close_price: Some(1.0),
+            base_asset_volume: Some(1.0),
+            quote_asset_volume: Some(1.0),
+        }
+    }
+
+    #[test]
+    fn auto_timestamps_are_scaled_by_magnitude() {
+        let ms = 1_736_121_600_000;
+        assert_eq!(scale_to_ms(ms / 1000, TimestampUnit::Auto), Some(ms));
+        assert_eq!(scale_to_ms(ms, TimestampUnit::Auto), Some(ms));
+        assert_eq!(scale_to_ms(ms * 1000, TimestampUnit::Auto), Some(ms));
+        assert_eq!(scale_to_ms(ms * 1_000_000, TimestampUnit::Auto), Some(ms));
+        // An explicit unit is taken at its word
+        assert_eq!(
+            scale_to_ms(ms, TimestampUnit::Seconds),
+            ms.checked_mul(1000)
+        );
+        assert_eq!(scale_to_ms(i64::MAX, TimestampUnit::Seconds), None);
+        assert_eq!(scale_to_ms(ms, TimestampUnit::DateTime), None);
+
+        assert_eq!(
+            Cell::Text(" 1736121600 ".to_string()).as_timestamp_ms(TimestampUnit::Auto),
+            Some(ms)
+        );
+        assert_eq!(
+            Cell::Float(1_736_121_600_000.0).as_timestamp_ms(TimestampUnit::Auto),
+            Some(ms)
+        );
+        assert_eq!(
+            Cell::TimestampMs(ms).as_timestamp_ms(TimestampUnit::Seconds),
+            Some(ms)
+        );
+        assert_eq!(Cell::Null.as_timestamp_ms(TimestampUnit::Auto), None);
+    }
+
+    #[test]
+    fn date_times_are_read_as_utc() {
+        let midnight = Some(START_MS);
+        assert_eq!(parse_datetime_ms("2025-01-06T00:00:00Z"), midnight);
+        assert_eq!(
+            parse_datetime_ms("2025-01-06T01:00:00+01:00"),
+            midnight,
+            "offsets are honoured"
+        );
+        assert_eq!(parse_datetime_ms("2025-01-06 00:00:00"), midnight);
+        assert_eq!(
+            parse_datetime_ms("2025-01-06T00:00:00.250"),
+            Some(START_MS + 250)
+        );
+        assert_eq!(
+            parse_datetime_ms("2025-01-06 00:30"),
+            Some(START_MS + HALF_HOUR_MS)
+        );
+        assert_eq!(parse_datetime_ms("2025-01-06"), midnight);
+        assert_eq!(parse_datetime_ms("06/01/2025"), None);
+        assert_eq!(parse_datetime_ms("soon"), None);
+
+        // Only as a fallback for text that isn't a number
+        assert_eq!(
+            Cell::Text("2025-01-06".to_string()).as_timestamp_ms(TimestampUnit::Milliseconds),
+            None
+        );
+        assert_eq!(
+            Cell::Text("2025-01-06".to_string()).as_timestamp_ms(TimestampUnit::DateTime),
+            midnight
+        );
+    }
+
+    #[test]
+    fn the_interval_is_the_most_common_spacing() {
+        let at = |offsets: &[i64]| -> Vec<BNKline> {
+            offsets
+                .iter()
+                .map(|&i| kline_at(START_MS + i * HALF_HOUR_MS))
+                .collect()
+        };
+        assert_eq!(infer_interval_ms(&at(&[0, 1, 2, 4, 5])), Some(HALF_HOUR_MS));
+        // A repeated candle is no spacing at all
+        assert_eq!(
+            infer_interval_ms(&at(&[0, 2, 2, 4])),
+            Some(2 * HALF_HOUR_MS)
+        );
+        // Ties go to the smaller spacing
+        assert_eq!(infer_interval_ms(&at(&[0, 1, 3])), Some(HALF_HOUR_MS));
+        assert_eq!(infer_interval_ms(&at(&[0])), None);
+        assert_eq!(infer_interval_ms(&[]), None);
+    }
+
+    #[test]
+    fn pair_names_keep_their_venue_prefix() {
+        assert_eq!(pair_name("btcusdt"), "BTCUSDT");
+        assert_eq!(
+            pair_name("binance-futures:btcusdt"),
+            "binance-futures:BTCUSDT"
+        );
+        assert_eq!(pair_name("Generic:SolUsdt"), "generic:SOLUSDT");
+        assert_eq!(
+            pair_and_interval_from_stem("ethusdt-1h-2025-01"),
+            ("ETHUSDT".to_string(), Some(2 * HALF_HOUR_MS))
+        );
+        assert_eq!(
+            pair_and_interval_from_stem("binance-futures@ethusdt_30m"),
+            ("binance-futures:ETHUSDT".to_string(), Some(HALF_HOUR_MS))
+        );
+        assert_eq!(
+            pair_and_interval_from_stem("ETHUSDT"),
+            ("ETHUSDT".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn column_presets_map_their_files() {
+        let dir = temp_dir("presets");
+
+        // data.binance.vision: headerless, microsecond open times, quote volume in column 7
+        let vision = dir.join("BTCUSDT-30m-2025-01.csv");
+        let mut text = String::new();
+        for i in 0..3 {
+            let open_us = (START_MS + i * HALF_HOUR_MS) * 1000;
+            let close = 100.0 + i as f64;
+            text.push_str(&format!(
+                "{},{},{},{},{},2,{},{},10,1,1,0\n",
+                open_us,
+                close - 0.5,
+                close + 1.0,
+                close - 1.0,
+                close,
+                open_us + HALF_HOUR_MS * 1000 - 1,
+                close * 2.0
+            ));
+        }
+        std::fs::write(&vision, text).unwrap();
+        let [series] = &import_path(&vision, &TABULAR.import.binance_vision_format).unwrap()[..]
+        else {
+            panic!("one pair");
+        };
+        assert_eq!(series.pair_interval.name(), "BTCUSDT");
+        assert_eq!(series.pair_interval.interval_ms, HALF_HOUR_MS);
+        assert_eq!(series.first_kline_timestamp_ms, START_MS);
+        assert_eq!(series.open_prices, vec![99.5, 100.5, 101.5]);
+        assert_eq!(series.high_prices, vec![101.0, 102.0, 103.0]);
+        assert_eq!(series.close_prices, vec![100.0, 101.0, 102.0]);
+        assert_eq!(series.base_asset_volumes, vec![2.0; 3]);
+        assert_eq!(series.quote_asset_volumes, vec![200.0, 202.0, 204.0]);
+
+        // The default preset finds its columns by name, in any order or case; without a
+        // quote volume column it is close * base volume
+        let named = TabularFormat {
+            columns: ColumnMapping {
+                quote_volume: None,
+                ..TABULAR.import.default_format.columns
+            },
+            ..TABULAR.import.default_format
+        };
+        let shuffled = dir.join("ETHUSDT_30m.csv");
+        std::fs::write(
+            &shuffled,
+            "CLOSE,open_time_ms,Base_Volume,low,high,open\n\
+             10,2025-01-06T00:00:00Z,3,9,11,9.5\n\
+             12,2025-01-06T00:30:00Z,4,10,13,10\n",
+        )
+        .unwrap();
+        let [series] = &import_path(&shuffled, &named).unwrap()[..] else {
+            panic!("one pair");
+        };
+        assert_eq!(series.pair_interval.name(), "ETHUSDT");
+        assert_eq!(series.first_kline_timestamp_ms, START_MS);
+        assert_eq!(series.open_prices, vec![9.5, 10.0]);
+        assert_eq!(series.low_prices, vec![9.0, 10.0]);
+        assert_eq!(series.quote_asset_volumes, vec![30.0, 48.0]);
+
+        // A mapped column the file lacks
+        assert!(read_file(&shuffled, &TABULAR.import.default_format).is_err());
+        // Names can't be looked up without a header
+        assert!(read_file(&vision, &TABULAR.import.default_format).is_err());
+
+        // A symbol column splits one file into pairs, venue prefixes and all
+        let by_symbol = TabularFormat {
+            columns: ColumnMapping {
+                symbol: Some(ColumnRef::Name("symbol")),
+                ..named.columns
+            },
+            ..named
+        };
+        let mixed = dir.join("mixed.csv");
+        std::fs::write(
+            &mixed,
+            "symbol,open_time_ms,open,high,low,close,base_volume\n\
+             btcusdt,1736121600000,1,1,1,1,1\n\
+             binance-futures:btcusdt,1736121600000,2,2,2,2,1\n\
+             btcusdt,1736123400000,1,1,1,1,1\n\
+             binance-futures:btcusdt,1736123400000,2,2,2,2,1\n",
+        )
+        .unwrap();
+        let pairs = read_file(&mixed, &by_symbol).unwrap();
+        let names: Vec<&String> = pairs.keys().collect();
+        assert_eq!(names, ["BTCUSDT", "binance-futures:BTCUSDT"]);
+        assert!(pairs.values().all(|rows| rows.klines.len() == 2));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn exports_round_trip_with_their_gaps_and_venue() {
+        let dir = temp_dir("round_trip");
+        // Candles 10 and 11 are missing: few enough to be filled rather than cut off
+        let mut text = "# pair=binance-futures:btcusdt interval_ms=1800000\n".to_string();
+        text.push_str(&EXPORT_COLUMNS.join(","));
+        text.push('\n');
+        for i in (0..32).filter(|i| !(10..12).contains(i)) {
+            let close = 100.0 + i as f64;
+            text.push_str(&format!(
+                "{},{},{},{},{},1,{}\n",
+                START_MS + i * HALF_HOUR_MS,
+                close,
+                close + 1.0,
+                close - 1.0,
+                close,
+                close
+            ));
+        }
+        let written = dir.join("written.csv");
+        std::fs::write(&written, text).unwrap();
+
+        let imported = import_path(&written, &TABULAR.import.default_format).unwrap();
+        let [series] = &imported[..] else {
+            panic!("one pair");
+        };
+        assert_eq!(series.pair_interval.name(), "binance-futures:BTCUSDT");
+        assert_eq!(series.klines(), 32);
+        assert_eq!(series.gaps.runs(), &[GapRun { start: 10, len: 2 }]);
+        assert!(series.pct_gaps > 0.0);
+
+        // Named as `export_path` names it, but in the test's own directory
+        let exported = dir.join(format!(
+            "{}_30m.csv",
+            Venue::path_safe(series.pair_interval.name())
+        ));
+        export_series(series, &exported).unwrap();
+        // The filled candles are left out of the file
+        let rows = std::fs::read_to_string(&exported)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .count();
+        assert_eq!(rows, 1 + 30);
+
+        let [again] = &import_path(&exported, &TABULAR.import.default_format).unwrap()[..] else {
+            panic!("one pair");
+        };
+        assert_eq!(again.pair_interval, series.pair_interval);
+        assert_eq!(
+            again.first_kline_timestamp_ms,
+            series.first_kline_timestamp_ms
+        );
+        assert_eq!(again.gaps, series.gaps);
+        assert_eq!(again.pct_gaps, series.pct_gaps);
+        assert_eq!(again.open_prices, series.open_prices);
+        assert_eq!(again.close_prices, series.close_prices);
+        assert_eq!(again.base_asset_volumes, series.base_asset_volumes);
+        assert_eq!(again.quote_asset_volumes, series.quote_asset_volumes);
+
+        // Without its metadata the file name alone still says which pair it is
+        let text = std::fs::read_to_string(&exported).unwrap();
+        let headerless = text.lines().skip(1).collect::<Vec<_>>().join("\n");
+        let renamed = dir.join("binance-futures@btcusdt_30m.csv");
+        std::fs::remove_file(&exported).unwrap();
+        std::fs::write(&renamed, headerless).unwrap();
+        let [from_name] = &import_path(&renamed, &TABULAR.import.default_format).unwrap()[..]
+        else {
+            panic!("one pair");
+        };
+        assert_eq!(from_name.pair_interval, series.pair_interval);
+        assert_eq!(from_name.gaps, series.gaps);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,98 @@
+//! CSV candle files. Leading `# key=value ...` lines carry metadata (pair, interval,
+//! pct_gaps); pandas reads the rest with `pd.read_csv(path, comment="#")`.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use super::{Cell, EXPORT_COLUMNS, RawTable, metadata_for};
+use crate::config::TabularFormat;
+use crate::models::OhlcvTimeSeries;
+
+pub fn read_table(path: &Path, format: &TabularFormat) -> Result<RawTable> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+
+    let mut metadata = HashMap::new();
+    for line in text.lines().take_while(|l| l.trim_start().starts_with('#')) {
+        for token in line
+            .trim_start_matches(|c: char| c == '#' || c.is_whitespace())
+            .split_whitespace()
+        {
+            if let Some((key, value)) = token.split_once('=') {
+                metadata.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(format.has_headers)
+        .delimiter(format.delimiter)
+        .comment(Some(b'#'))
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let headers = if format.has_headers {
+        Some(
+            reader
+                .headers()
+                .with_context(|| format!("Failed to read the header of {:?}", path))?
+                .iter()
+                .map(str::to_string)
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    let mut rows = Vec::new();
+    for (row_no, record) in reader.records().enumerate() {
+        let record = record.with_context(|| format!("{:?} row {}", path, row_no + 1))?;
+        rows.push(
+            record
+                .iter()
+                .map(|field| match field {
+                    "" => Cell::Null,
+                    text => Cell::Text(text.to_string()),
+                })
+                .collect(),
+        );
+    }
+
+    Ok(RawTable {
+        headers,
+        rows,
+        metadata,
+    })
+}
+
+pub fn write_series(series: &OhlcvTimeSeries, path: &Path) -> Result<()> {
+    let mut out = String::with_capacity(series.klines() * 64);
+
+    let metadata: Vec<String> = metadata_for(series)
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    let _ = writeln!(out, "# {}", metadata.join(" "));
+    let _ = writeln!(out, "{}", EXPORT_COLUMNS.join(","));
+
+    let interval_ms = series.pair_interval.interval_ms;
+    for i in (0..series.klines()).filter(|&i| !series.is_filled(i)) {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            series.first_kline_timestamp_ms + i as i64 * interval_ms,
+            series.open_prices[i],
+            series.high_prices[i],
+            series.low_prices[i],
+            series.close_prices[i],
+            series.base_asset_volumes[i],
+            series.quote_asset_volumes[i],
+        );
+    }
+
+    std::fs::write(path, out).with_context(|| format!("Failed to write {}", path.display()))
+}
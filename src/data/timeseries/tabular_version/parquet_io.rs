@@ -0,0 +1,149 @@
+//! Parquet candle files (behind the `parquet` feature). Pair, interval and pct_gaps
+//! are stored as file key-value metadata.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use parquet::basic::Compression;
+use parquet::data_type::{DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::format::KeyValue;
+use parquet::record::Field;
+use parquet::schema::parser::parse_message_type;
+
+use super::{Cell, EXPORT_COLUMNS, RawTable, metadata_for};
+use crate::models::OhlcvTimeSeries;
+
+const MS_IN_DAY: i64 = 86_400_000;
+
+fn to_cell(field: &Field) -> Cell {
+    match field {
+        Field::Byte(v) => Cell::Int(*v as i64),
+        Field::Short(v) => Cell::Int(*v as i64),
+        Field::Int(v) => Cell::Int(*v as i64),
+        Field::Long(v) => Cell::Int(*v),
+        Field::UByte(v) => Cell::Int(*v as i64),
+        Field::UShort(v) => Cell::Int(*v as i64),
+        Field::UInt(v) => Cell::Int(*v as i64),
+        Field::ULong(v) => Cell::Int(*v as i64),
+        Field::Float(v) => Cell::Float(*v as f64),
+        Field::Double(v) => Cell::Float(*v),
+        Field::Str(v) => Cell::Text(v.clone()),
+        Field::TimestampMillis(v) => Cell::TimestampMs(*v),
+        Field::TimestampMicros(v) => Cell::TimestampMs(*v / 1000),
+        Field::Date(days) => Cell::TimestampMs(*days as i64 * MS_IN_DAY),
+        _ => Cell::Null,
+    }
+}
+
+pub fn read_table(path: &Path) -> Result<RawTable> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let reader = SerializedFileReader::new(file)
+        .with_context(|| format!("{:?} is not a readable Parquet file", path))?;
+
+    let file_metadata = reader.metadata().file_metadata();
+    let metadata: HashMap<String, String> = file_metadata
+        .key_value_metadata()
+        .map(|kvs| {
+            kvs.iter()
+                .filter_map(|kv| Some((kv.key.clone(), kv.value.clone()?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let headers: Vec<String> = file_metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+
+    let mut rows = Vec::with_capacity(file_metadata.num_rows().max(0) as usize);
+    for row in reader.get_row_iter(None)? {
+        let row = row.with_context(|| format!("Failed to read a row of {:?}", path))?;
+        rows.push(
+            row.get_column_iter()
+                .map(|(_, field)| to_cell(field))
+                .collect(),
+        );
+    }
+
+    Ok(RawTable {
+        headers: Some(headers),
+        rows,
+        metadata,
+    })
+}
+
+pub fn write_series(series: &OhlcvTimeSeries, path: &Path) -> Result<()> {
+    let schema = format!(
+        "message ohlcv {{ REQUIRED INT64 {}; {} }}",
+        EXPORT_COLUMNS[0],
+        EXPORT_COLUMNS[1..]
+            .iter()
+            .map(|name| format!("REQUIRED DOUBLE {};", name))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    let schema = Arc::new(parse_message_type(&schema)?);
+
+    let key_values = metadata_for(series)
+        .into_iter()
+        .map(|(key, value)| KeyValue::new(key.to_string(), value))
+        .collect();
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_key_value_metadata(Some(key_values))
+            .build(),
+    );
+
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = SerializedFileWriter::new(file, schema, props)?;
+
+    let interval_ms = series.pair_interval.interval_ms;
+    let traded: Vec<usize> = (0..series.klines())
+        .filter(|&i| !series.is_filled(i))
+        .collect();
+    let timestamps: Vec<i64> = traded
+        .iter()
+        .map(|&i| series.first_kline_timestamp_ms + i as i64 * interval_ms)
+        .collect();
+    let value_columns: Vec<Vec<f64>> = [
+        &series.open_prices,
+        &series.high_prices,
+        &series.low_prices,
+        &series.close_prices,
+        &series.base_asset_volumes,
+        &series.quote_asset_volumes,
+    ]
+    .iter()
+    .map(|column| traded.iter().map(|&i| column[i]).collect())
+    .collect();
+
+    let mut row_group = writer.next_row_group()?;
+    let mut column_idx = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        if column_idx == 0 {
+            column
+                .typed::<Int64Type>()
+                .write_batch(&timestamps, None, None)?;
+        } else {
+            column
+                .typed::<DoubleType>()
+                .write_batch(&value_columns[column_idx - 1], None, None)?;
+        }
+        column.close()?;
+        column_idx += 1;
+    }
+    row_group.close()?;
+    writer
+        .close()
+        .with_context(|| format!("Failed to finish {}", path.display()))?;
+    Ok(())
+}
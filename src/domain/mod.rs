@@ -1,8 +1,16 @@
 // Domain types and value objects
 pub mod price_horizon;
 pub mod candle;
+pub mod order_book;
 pub mod pair_interval;
+pub mod symbol_registry;
+pub mod venue;
+pub mod watchlist;
 
 // Re-export commonly used types
 pub use candle::Candle;
+pub use order_book::{BookLevel, BookTicker, DepthSnapshot, Side};
 pub use pair_interval::PairInterval;
+pub use symbol_registry::{PairRejection, SymbolInfo, SymbolRegistry};
+pub use venue::Venue;
+pub use watchlist::{PairOverrides, Watchlist, WatchlistEntry};
@@ -0,0 +1,95 @@
+//! Top of book and a short depth snapshot for one pair, from the `@bookTicker` and
+//! `@depth<N>` streams. The last trade price says where the market was; these say what
+//! an order placed now would actually be filled at.
+
+use serde::{Deserialize, Serialize};
+
+/// Which side of the book an order takes liquidity from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Lifts the ask (long entry, short exit)
+    Buy,
+    /// Hits the bid (long exit, short entry)
+    Sell,
+}
+
+/// Resting quantity (base asset) at one price
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// Best bid and ask
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookTicker {
+    pub bid: BookLevel,
+    pub ask: BookLevel,
+}
+
+impl BookTicker {
+    pub fn mid(&self) -> f64 {
+        (self.bid.price + self.ask.price) / 2.0
+    }
+
+    pub fn spread(&self) -> f64 {
+        self.ask.price - self.bid.price
+    }
+
+    /// Spread as a percentage of the mid price
+    pub fn spread_pct(&self) -> f64 {
+        let mid = self.mid();
+        if mid > 0.0 {
+            self.spread() / mid * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Price a market order on `side` would get for the quantity shown at the top
+    pub fn executable_price(&self, side: Side) -> f64 {
+        match side {
+            Side::Buy => self.ask.price,
+            Side::Sell => self.bid.price,
+        }
+    }
+}
+
+/// The best few levels each side, as Binance sends them: bids falling, asks rising
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DepthSnapshot {
+    pub last_update_id: i64,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+impl DepthSnapshot {
+    /// Largest resting quantity on either side (for scaling a ladder)
+    pub fn max_quantity(&self) -> f64 {
+        self.bids
+            .iter()
+            .chain(&self.asks)
+            .map(|level| level.quantity)
+            .fold(0.0, f64::max)
+    }
+
+    /// Average fill price for `quantity` walked through the side an order on `side`
+    /// takes from. `None` if the snapshot doesn't hold that much.
+    pub fn average_fill_price(&self, side: Side, quantity: f64) -> Option<f64> {
+        let levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
+        };
+        let mut remaining = quantity;
+        let mut cost = 0.0;
+        for level in levels {
+            let take = remaining.min(level.quantity);
+            cost += take * level.price;
+            remaining -= take;
+            if remaining <= 0.0 {
+                return (quantity > 0.0).then(|| cost / quantity);
+            }
+        }
+        None
+    }
+}
@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::symbol_registry::SymbolRegistry;
+use crate::domain::venue::Venue;
 use crate::utils::TimeUtils;
 
+/// Quote assets tried, in order, on symbols the exchangeInfo registry doesn't list
+const FALLBACK_QUOTES: &[&str] = &["USDT", "USDC", "FDUSD", "BTC", "ETH"];
+
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq)]
 pub struct PairInterval {
     pub name: String,
@@ -9,22 +14,33 @@ pub struct PairInterval {
 }
 
 impl PairInterval {
-    pub fn get_base(text: &str) -> Option<&str> {
-        let quote = Self::get_quote(text)?;
+    pub fn get_base<'a>(text: &'a str, registry: &SymbolRegistry) -> Option<&'a str> {
+        let quote = Self::get_quote(text, registry)?;
         // `strip_suffix` returns `None` if the suffix is not found.
         // If get_quote returned Some(quote), strip_suffix can still return None
         // if the quote is not at the end (e.g., malformed pair name).
-        text.strip_suffix(quote)
+        // The venue prefix (`binance-futures:`) is not part of the base.
+        Venue::split(text).1.strip_suffix(quote)
     }
 
     // Finds the trading quote at the end of the pair name and returns it.
-    // Returns None if no matching quote is found.
-    pub fn get_quote(text: &str) -> Option<&str> {
-        static PAIR_QUOTES: &[&str] = &["USDT", "USDC", "FDUSD", "BTC", "ETH"];
-        PAIR_QUOTES
-            .iter()
-            .find(|&&ext| text.ends_with(ext))
-            .copied()
+    // `registry` (exchangeInfo) knows where the base ends. Symbols it doesn't list (all of
+    // them without a cached exchangeInfo, as in the WASM demo) fall back to the common
+    // quote suffixes; returns None if none matches.
+    pub fn get_quote<'a>(text: &'a str, registry: &SymbolRegistry) -> Option<&'a str> {
+        let quote_asset = match registry.get(text) {
+            Some(info) => info.quote_asset.as_str(),
+            None => {
+                let symbol = Venue::split(text).1.to_ascii_uppercase();
+                FALLBACK_QUOTES
+                    .iter()
+                    .find(|&&quote| symbol.len() > quote.len() && symbol.ends_with(quote))?
+            }
+        };
+        text.len()
+            .checked_sub(quote_asset.len())
+            .and_then(|start| text.get(start..))
+            .filter(|quote| quote.eq_ignore_ascii_case(quote_asset))
     }
 
     /* # Where we use base_asset and quote_asset in the app:
@@ -34,23 +50,33 @@ impl PairInterval {
         base_asset_volumes:
         quote_asset_volumes:
       So use get_base_and_quote() to split a string up into its constituent parts  */
-    pub fn get_base_and_quote(text: &str) -> Option<(&str, &str)> {
-        let base = Self::get_base(text)?;
-        let quote = Self::get_quote(text)?;
+    pub fn get_base_and_quote<'a>(
+        text: &'a str,
+        registry: &SymbolRegistry,
+    ) -> Option<(&'a str, &'a str)> {
+        let base = Self::get_base(text, registry)?;
+        let quote = Self::get_quote(text, registry)?;
         Some((base, quote))
     }
 
     // Split the name into base and quote assets.
-    pub fn split_pair_name(pair_name: &str) -> (&str, &str) {
-        match Self::get_base_and_quote(pair_name) {
+    pub fn split_pair_name<'a>(
+        pair_name: &'a str,
+        registry: &SymbolRegistry,
+    ) -> (&'a str, &'a str) {
+        match Self::get_base_and_quote(pair_name, registry) {
             Some((base, quote)) => (base, quote),
             None => ("Invalid", "Name"),
         }
     }
 
-    // The name we pass into the Binance API (not necessarily display name)
-    pub fn bn_name(&self) -> &str {
-        &self.name
+    pub fn venue(&self) -> Venue {
+        Venue::split(&self.name).0
+    }
+
+    // The name we pass into the venue's API (the pair name without its venue prefix)
+    pub fn venue_symbol(&self) -> &str {
+        Venue::split(&self.name).1
     }
 
     pub fn name(&self) -> &str {
@@ -61,16 +87,84 @@ impl PairInterval {
 // This appears to be unused.... hard to show in Egui coz selected_pair is Option<String>. It does not include the Interval at all..... yet.
 impl std::fmt::Display for PairInterval {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let base = Self::get_base(&self.name).unwrap_or("UNKNOWN_BASE");
-        let quote = Self::get_quote(&self.name).unwrap_or("UNKNOWN_QUOTE");
+        // Base and quote need the exchangeInfo registry (`get_base_and_quote`)
         write!(
             f,
-            "Base: {}, Quote: {}, full: {}, Interval: {}ms (or {}) ",
-            base,
-            quote,
+            "full: {}, Interval: {}ms (or {}) ",
             self.name(),
             self.interval_ms,
             TimeUtils::interval_to_string(self.interval_ms)
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::symbol_registry::SymbolInfo;
+
+    fn info(symbol: &str, base: &str, quote: &str) -> SymbolInfo {
+        SymbolInfo {
+            symbol: symbol.to_string(),
+            base_asset: base.to_string(),
+            quote_asset: quote.to_string(),
+            status: "TRADING".to_string(),
+            tick_size: None,
+            step_size: None,
+            min_qty: None,
+        }
+    }
+
+    #[test]
+    fn splits_by_the_registry_quote_asset() {
+        let registry = SymbolRegistry::new(
+            0,
+            [
+                info("BTCUSDT", "BTC", "USDT"),
+                // Suffix matching would take this as ...USD / T
+                info("USDTUSD", "USDT", "USD"),
+                info("binance-futures:ETHUSDC", "ETH", "USDC"),
+            ],
+        );
+
+        assert_eq!(
+            PairInterval::get_base_and_quote("BTCUSDT", &registry),
+            Some(("BTC", "USDT"))
+        );
+        assert_eq!(
+            PairInterval::get_base_and_quote("USDTUSD", &registry),
+            Some(("USDT", "USD"))
+        );
+        assert_eq!(
+            PairInterval::get_base_and_quote("binance-futures:ETHUSDC", &registry),
+            Some(("ETH", "USDC"))
+        );
+    }
+
+    #[test]
+    fn symbols_the_registry_does_not_list_fall_back_to_common_quotes() {
+        let registry = SymbolRegistry::new(0, [info("BTCUSDT", "BTC", "USDT")]);
+
+        assert_eq!(PairInterval::get_quote("ETHUSDT", &registry), Some("USDT"));
+        assert_eq!(PairInterval::get_base("ETHUSDT", &registry), Some("ETH"));
+        // No registry at all, as in the WASM demo
+        let empty = SymbolRegistry::default();
+        assert_eq!(
+            PairInterval::split_pair_name("SOLFDUSD", &empty),
+            ("SOL", "FDUSD")
+        );
+        assert_eq!(
+            PairInterval::split_pair_name("binance-futures:ETHBTC", &empty),
+            ("ETH", "BTC")
+        );
+        assert_eq!(
+            PairInterval::split_pair_name("XBTEUR", &empty),
+            ("Invalid", "Name")
+        );
+        // A bare quote has no base
+        assert_eq!(
+            PairInterval::split_pair_name("USDT", &empty),
+            ("Invalid", "Name")
+        );
+    }
+}
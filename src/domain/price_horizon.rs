@@ -13,16 +13,18 @@ pub struct PriceHorizonConfig {
 
 /// Automatically select discontinuous slice ranges based on price relevancy.
 /// Returns a tuple: (Vector of ranges [(start, end)], (price_min, price_max)).
+/// Unless `include_filled`, candles filled over exchange gaps never count as relevant.
 pub fn auto_select_ranges(
     timeseries: &OhlcvTimeSeries,
     current_price: f64,
     config: &PriceHorizonConfig,
+    include_filled: bool,
 ) -> (Vec<(usize, usize)>, (f64, f64)) {
     // 1. Calculate the user-defined price range
     let (price_min, price_max) = calculate_price_range(current_price, config.threshold_pct);
 
     // 2. Find all ranges where price is relevant
-    let mut ranges = find_relevant_ranges(timeseries, price_min, price_max);
+    let mut ranges = find_relevant_ranges(timeseries, price_min, price_max, include_filled);
 
     // 3. Apply minimum lookback constraint
     ranges = apply_min_lookback_constraint(ranges, timeseries, config.min_lookback_days);
@@ -42,6 +44,7 @@ fn find_relevant_ranges(
     timeseries: &OhlcvTimeSeries,
     price_min: f64,
     price_max: f64,
+    include_filled: bool,
 ) -> Vec<(usize, usize)> {
     let mut ranges: Vec<(usize, usize)> = Vec::new();
     let mut range_start: Option<usize> = None;
@@ -52,7 +55,10 @@ fn find_relevant_ranges(
         
         // Check if candle overlaps with relevant price range.
         // Overlap exists if candle_low <= range_max AND candle_high >= range_min.
-        let is_relevant = candle.low_price <= price_max && candle.high_price >= price_min;
+        // A filled gap candle only repeats the last real one, so it says nothing new.
+        let is_relevant = candle.low_price <= price_max
+            && candle.high_price >= price_min
+            && (include_filled || !timeseries.is_filled(i));
 
         if is_relevant {
             // Start a new range if we're not in one
@@ -123,8 +129,9 @@ pub fn calculate_relevant_start_timestamp(
     timeseries: &OhlcvTimeSeries,
     current_price: f64,
     config: &PriceHorizonConfig,
+    include_filled: bool,
 ) -> i64 {
-    let (ranges, _) = auto_select_ranges(timeseries, current_price, config);
+    let (ranges, _) = auto_select_ranges(timeseries, current_price, config, include_filled);
     
     if let Some((start_idx, _)) = ranges.first() {
         // Calculate timestamp based on index and interval
@@ -133,4 +140,23 @@ pub fn calculate_relevant_start_timestamp(
     } else {
         0
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GapMask;
+
+    #[test]
+    fn filled_candles_split_relevant_ranges_unless_included() {
+        let mut series =
+            OhlcvTimeSeries::from_test_candles("BTCUSDT", 60_000, 0, &[(10.0, 11.0, 9.0, 10.0); 6]);
+        series.gaps = GapMask::from_flags([false, false, true, false, false, true]);
+
+        assert_eq!(
+            find_relevant_ranges(&series, 9.5, 10.5, false),
+            vec![(0, 2), (3, 5)]
+        );
+        assert_eq!(find_relevant_ranges(&series, 9.5, 10.5, true), vec![(0, 6)]);
+    }
+}
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::domain::venue::Venue;
+
+/// Trading rules for one exchange symbol, as reported by Binance `exchangeInfo`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    /// e.g. "TRADING", "BREAK", "HALT", "END_OF_DAY"
+    pub status: String,
+    /// PRICE_FILTER tickSize (minimum price increment)
+    pub tick_size: Option<f64>,
+    /// LOT_SIZE stepSize (minimum quantity increment)
+    pub step_size: Option<f64>,
+    /// LOT_SIZE minQty
+    pub min_qty: Option<f64>,
+}
+
+impl SymbolInfo {
+    pub fn is_trading(&self) -> bool {
+        self.status == "TRADING"
+    }
+}
+
+/// Why a requested pair was dropped during validation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PairRejection {
+    /// Not listed on the exchange at all (typo or delisted).
+    Unknown(String),
+    /// Listed, but not currently tradable.
+    NotTrading { symbol: String, status: String },
+}
+
+impl PairRejection {
+    pub fn symbol(&self) -> &str {
+        match self {
+            PairRejection::Unknown(symbol) | PairRejection::NotTrading { symbol, .. } => symbol,
+        }
+    }
+}
+
+impl std::fmt::Display for PairRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PairRejection::Unknown(symbol) => write!(f, "{} is not listed", symbol),
+            PairRejection::NotTrading { symbol, status } => {
+                write!(f, "{} is not trading (status {})", symbol, status)
+            }
+        }
+    }
+}
+
+/// All symbols known to the loaded venues, keyed by upper-case pair name (the venue
+/// symbol with its venue prefix, see `domain::venue`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SymbolRegistry {
+    pub fetched_at_ms: i64,
+    pub symbols: HashMap<String, SymbolInfo>,
+}
+
+impl SymbolRegistry {
+    pub fn new(fetched_at_ms: i64, symbols: impl IntoIterator<Item = SymbolInfo>) -> Self {
+        Self {
+            fetched_at_ms,
+            symbols: symbols
+                .into_iter()
+                .map(|info| (info.symbol.to_uppercase(), info))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols.get(&symbol.to_uppercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn is_stale(&self, now_ms: i64, max_age_sec: i64) -> bool {
+        now_ms - self.fetched_at_ms > max_age_sec * 1000
+    }
+
+    /// Adds another venue's symbols. The result is as old as the older of the two.
+    pub fn merge(&mut self, other: SymbolRegistry) {
+        self.fetched_at_ms = self.fetched_at_ms.min(other.fetched_at_ms);
+        self.symbols.extend(other.symbols);
+    }
+
+    /// Whether any symbol of `venue` is known, i.e. its pairs can be validated here
+    pub fn has_venue(&self, venue: Venue) -> bool {
+        self.symbols
+            .values()
+            .any(|info| Venue::split(&info.symbol).0 == venue)
+    }
+
+    /// Splits requested pairs into tradable symbols (order preserved) and rejections.
+    pub fn validate(&self, pairs: &[String]) -> (Vec<String>, Vec<PairRejection>) {
+        let mut valid = Vec::new();
+        let mut rejected = Vec::new();
+
+        for pair in pairs {
+            match self.get(pair) {
+                Some(info) if info.is_trading() => valid.push(info.symbol.clone()),
+                Some(info) => rejected.push(PairRejection::NotTrading {
+                    symbol: info.symbol.clone(),
+                    status: info.status.clone(),
+                }),
+                None => rejected.push(PairRejection::Unknown(pair.clone())),
+            }
+        }
+
+        (valid, rejected)
+    }
+
+    /// Tradable symbols quoted in `quote_asset`, sorted by name.
+    pub fn trading_symbols_for_quote(&self, quote_asset: &str) -> Vec<String> {
+        let mut symbols: Vec<String> = self
+            .symbols
+            .values()
+            .filter(|info| info.is_trading() && info.quote_asset.eq_ignore_ascii_case(quote_asset))
+            .map(|info| info.symbol.clone())
+            .collect();
+        symbols.sort();
+        symbols
+    }
+}
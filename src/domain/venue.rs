@@ -0,0 +1,88 @@
+//! Where a symbol trades. Pair names carry their venue as a prefix, so the same symbol on
+//! two venues is two pairs (`BTCUSDT` and `binance-futures:BTCUSDT`) with their own
+//! candles, zones and live prices.
+//!
+//! A name without a known prefix is Binance spot, which keeps caches and watchlists
+//! written before venues existed valid.
+
+use serde::{Deserialize, Serialize};
+
+/// Separates the venue id from the symbol in a pair name
+pub const VENUE_SEPARATOR: char = ':';
+
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, Hash, Eq, PartialEq, Ord, PartialOrd,
+)]
+pub enum Venue {
+    #[default]
+    BinanceSpot,
+    /// Binance USDⓈ-M futures
+    BinanceFutures,
+    /// Any exchange speaking the small REST/WS protocol in `data::exchange::generic`
+    Generic,
+}
+
+impl Venue {
+    pub const ALL: [Venue; 3] = [Venue::BinanceSpot, Venue::BinanceFutures, Venue::Generic];
+
+    /// Prefix used in pair names and file names
+    pub fn id(&self) -> &'static str {
+        match self {
+            Venue::BinanceSpot => "binance",
+            Venue::BinanceFutures => "binance-futures",
+            Venue::Generic => "generic",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Venue::BinanceSpot => "Binance spot",
+            Venue::BinanceFutures => "Binance USDⓈ-M futures",
+            Venue::Generic => "Generic exchange",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Venue> {
+        Venue::ALL
+            .into_iter()
+            .find(|venue| venue.id().eq_ignore_ascii_case(id))
+    }
+
+    /// Splits a pair name into its venue and the symbol as the venue knows it.
+    /// Unprefixed names (and unknown prefixes) are Binance spot, returned whole.
+    pub fn split(name: &str) -> (Venue, &str) {
+        name.split_once(VENUE_SEPARATOR)
+            .and_then(|(id, symbol)| Some((Venue::from_id(id)?, symbol)))
+            .unwrap_or((Venue::BinanceSpot, name))
+    }
+
+    /// The pair name for `symbol` on this venue. Binance spot names stay bare.
+    pub fn qualify(&self, symbol: &str) -> String {
+        match self {
+            Venue::BinanceSpot => symbol.to_string(),
+            _ => format!("{}{}{}", self.id(), VENUE_SEPARATOR, symbol),
+        }
+    }
+
+    /// `name` with the separator replaced, for use in file and directory names
+    pub fn path_safe(name: &str) -> String {
+        name.replace(VENUE_SEPARATOR, "@")
+    }
+}
+
+impl std::str::FromStr for Venue {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Venue::from_id(text.trim()).ok_or_else(|| {
+            let ids: Vec<&str> = Venue::ALL.iter().map(Venue::id).collect();
+            format!("'{}' is not a venue (use one of {})", text, ids.join(", "))
+        })
+    }
+}
+
+impl std::fmt::Display for Venue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
@@ -0,0 +1,395 @@
+//! Watchlist file format (`BINANCE.pairs.watchlist_path`, `pairs.txt` by default).
+//!
+//! ```text
+//! # Comments start with '#', on their own line or after an entry.
+//! BTCUSDT                          # pairs before any header go in the default group
+//!
+//! [majors]                         # group header
+//! ETHUSDT interval=1h horizon=0.10 # per-pair overrides
+//! !LUNAUSDT                        # '!' (or enabled=false) keeps the line but skips the pair
+//!
+//! [new listings] enabled=false sticky.threshold=0.3   # header keys are group defaults
+//! ZKUSDT
+//!
+//! [futures]
+//! binance-futures:BTCUSDT          # '<venue>:' picks another venue (default: Binance spot)
+//! ```
+//!
+//! Venues: `binance`, `binance-futures`, `generic` (see `domain::venue`).
+//!
+//! Override keys: `enabled`, `interval` (Binance shorthand, e.g. `15m`, `1h`),
+//! `horizon` (price horizon threshold as a fraction, e.g. `0.15` = ±15%),
+//! `lookback_days`, `stale_after` (seconds without a live update before the pair's price
+//! counts as stale), and `sticky.*` / `reversal.*` with `smooth`, `gap`, `threshold`
+//! (see `ZoneParams`). A plain one-symbol-per-line file is still valid.
+
+use anyhow::{Result, anyhow, bail};
+
+use crate::config::{AnalysisConfig, ZoneParams};
+use crate::domain::venue::{VENUE_SEPARATOR, Venue};
+use crate::utils::TimeUtils;
+
+pub const DEFAULT_GROUP: &str = "Watchlist";
+
+/// Optional replacements for individual `ZoneParams` fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ZoneParamsOverride {
+    pub smooth_pct: Option<f64>,
+    pub gap_pct: Option<f64>,
+    pub threshold: Option<f64>,
+}
+
+impl ZoneParamsOverride {
+    pub fn apply(&self, base: ZoneParams) -> ZoneParams {
+        ZoneParams {
+            smooth_pct: self.smooth_pct.unwrap_or(base.smooth_pct),
+            gap_pct: self.gap_pct.unwrap_or(base.gap_pct),
+            threshold: self.threshold.unwrap_or(base.threshold),
+        }
+    }
+
+    fn merged_over(&self, defaults: &ZoneParamsOverride) -> ZoneParamsOverride {
+        ZoneParamsOverride {
+            smooth_pct: self.smooth_pct.or(defaults.smooth_pct),
+            gap_pct: self.gap_pct.or(defaults.gap_pct),
+            threshold: self.threshold.or(defaults.threshold),
+        }
+    }
+}
+
+/// Per-pair (or per-group) settings that replace the global `AnalysisConfig` values.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PairOverrides {
+    pub enabled: Option<bool>,
+    pub interval_ms: Option<i64>,
+    pub price_horizon_pct: Option<f64>,
+    pub min_lookback_days: Option<usize>,
+    pub stale_after_sec: Option<u64>,
+    pub sticky: ZoneParamsOverride,
+    pub reversal: ZoneParamsOverride,
+}
+
+impl PairOverrides {
+    /// The config a pair should be analysed with. Overrides win over `base`,
+    /// including values the user changes at runtime (e.g. the price horizon slider).
+    pub fn apply(&self, base: &AnalysisConfig) -> AnalysisConfig {
+        let mut config = base.clone();
+        if let Some(interval_ms) = self.interval_ms {
+            config.interval_width_ms = interval_ms;
+        }
+        if let Some(threshold_pct) = self.price_horizon_pct {
+            config.price_horizon.threshold_pct = threshold_pct;
+        }
+        if let Some(days) = self.min_lookback_days {
+            config.price_horizon.min_lookback_days = days;
+        }
+        config.zones.sticky = self.sticky.apply(config.zones.sticky);
+        config.zones.reversal = self.reversal.apply(config.zones.reversal);
+        config
+    }
+
+    fn merged_over(&self, defaults: &PairOverrides) -> PairOverrides {
+        PairOverrides {
+            enabled: self.enabled.or(defaults.enabled),
+            interval_ms: self.interval_ms.or(defaults.interval_ms),
+            price_horizon_pct: self.price_horizon_pct.or(defaults.price_horizon_pct),
+            min_lookback_days: self.min_lookback_days.or(defaults.min_lookback_days),
+            stale_after_sec: self.stale_after_sec.or(defaults.stale_after_sec),
+            sticky: self.sticky.merged_over(&defaults.sticky),
+            reversal: self.reversal.merged_over(&defaults.reversal),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let float = || {
+            value
+                .parse::<f64>()
+                .map_err(|_| anyhow!("'{}' expects a number, got '{}'", key, value))
+        };
+
+        match key {
+            "enabled" => {
+                self.enabled = Some(
+                    value
+                        .parse::<bool>()
+                        .map_err(|_| anyhow!("'enabled' expects true/false, got '{}'", value))?,
+                )
+            }
+            "interval" => {
+                self.interval_ms = Some(
+                    TimeUtils::interval_from_string(value)
+                        .ok_or_else(|| anyhow!("Unknown interval '{}'", value))?,
+                )
+            }
+            "horizon" => self.price_horizon_pct = Some(float()?),
+            "lookback_days" => {
+                self.min_lookback_days = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| anyhow!("'lookback_days' expects a whole number"))?,
+                )
+            }
+            "stale_after" => {
+                self.stale_after_sec = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| anyhow!("'stale_after' expects whole seconds"))?,
+                )
+            }
+            _ => {
+                let (layer, field) = key
+                    .split_once('.')
+                    .ok_or_else(|| anyhow!("Unknown key '{}'", key))?;
+                let target = match layer {
+                    "sticky" => &mut self.sticky,
+                    "reversal" => &mut self.reversal,
+                    _ => bail!("Unknown zone layer '{}' in '{}'", layer, key),
+                };
+                match field {
+                    "smooth" => target.smooth_pct = Some(float()?),
+                    "gap" => target.gap_pct = Some(float()?),
+                    "threshold" => target.threshold = Some(float()?),
+                    _ => bail!("Unknown zone parameter '{}' in '{}'", field, key),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchlistEntry {
+    pub symbol: String,
+    pub group: String,
+    /// Group defaults already merged in.
+    pub overrides: PairOverrides,
+}
+
+impl WatchlistEntry {
+    pub fn is_enabled(&self) -> bool {
+        self.overrides.enabled.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Watchlist {
+    pub entries: Vec<WatchlistEntry>,
+}
+
+impl Watchlist {
+    /// Parses a watchlist; `path` is the file it came from, for error messages.
+    pub fn parse(text: &str, path: &str) -> Result<Self> {
+        let mut entries: Vec<WatchlistEntry> = Vec::new();
+        let mut group = DEFAULT_GROUP.to_string();
+        let mut group_defaults = PairOverrides::default();
+
+        for (line_no, raw_line) in text.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let context = |e: anyhow::Error| anyhow!("{} line {}: {}", path, line_no + 1, e);
+
+            if let Some(rest) = line.strip_prefix('[') {
+                let (name, settings) = rest
+                    .split_once(']')
+                    .ok_or_else(|| context(anyhow!("Unclosed group header")))?;
+                group = name.trim().to_string();
+                group_defaults = parse_settings(settings).map_err(context)?;
+                continue;
+            }
+
+            let mut tokens = line.splitn(2, char::is_whitespace);
+            let symbol_token = tokens.next().unwrap_or_default();
+            let mut overrides = parse_settings(tokens.next().unwrap_or("")).map_err(context)?;
+
+            let name = match symbol_token.strip_prefix('!') {
+                Some(name) => {
+                    overrides.enabled = Some(false);
+                    name
+                }
+                None => symbol_token,
+            };
+            let (venue, local) = match name.split_once(VENUE_SEPARATOR) {
+                Some((id, local)) => (
+                    Venue::from_id(id).ok_or_else(|| context(anyhow!("Unknown venue '{}'", id)))?,
+                    local,
+                ),
+                None => (Venue::BinanceSpot, name),
+            };
+
+            if local.is_empty() || !local.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(context(anyhow!("'{}' is not a symbol", symbol_token)));
+            }
+            let symbol = venue.qualify(&local.to_uppercase());
+            // First occurrence wins so a pair can't be loaded twice.
+            if entries.iter().any(|e| e.symbol == symbol) {
+                log::warn!(
+                    "{}: {} listed more than once, keeping the first",
+                    path,
+                    symbol
+                );
+                continue;
+            }
+
+            entries.push(WatchlistEntry {
+                symbol,
+                group: group.clone(),
+                overrides: overrides.merged_over(&group_defaults),
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Reads and parses a watchlist file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read watchlist {}: {}", path, e))?;
+        Self::parse(&text, path)
+    }
+
+    pub fn enabled(&self) -> impl Iterator<Item = &WatchlistEntry> {
+        self.entries.iter().filter(|e| e.is_enabled())
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&WatchlistEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Analysis config for `symbol`; pairs not on the watchlist get `base` unchanged.
+    pub fn config_for(&self, symbol: &str, base: &AnalysisConfig) -> AnalysisConfig {
+        match self.get(symbol) {
+            Some(entry) => entry.overrides.apply(base),
+            None => base.clone(),
+        }
+    }
+
+    /// Groups `pairs` by watchlist group, in file order. Pairs that are not on the
+    /// watchlist (e.g. from an older cache) are collected in `DEFAULT_GROUP`.
+    pub fn group_pairs(&self, pairs: &[String]) -> Vec<(String, Vec<String>)> {
+        let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+        let mut push = |group: &str, pair: &str| match groups.iter_mut().find(|(g, _)| g == group) {
+            Some((_, members)) => members.push(pair.to_string()),
+            None => groups.push((group.to_string(), vec![pair.to_string()])),
+        };
+
+        for entry in &self.entries {
+            if pairs.contains(&entry.symbol) {
+                push(&entry.group, &entry.symbol);
+            }
+        }
+        let mut ungrouped: Vec<&String> = pairs.iter().filter(|p| self.get(p).is_none()).collect();
+        ungrouped.sort();
+        for pair in ungrouped {
+            push(DEFAULT_GROUP, pair);
+        }
+
+        groups
+    }
+}
+
+fn parse_settings(settings: &str) -> Result<PairOverrides> {
+    let mut overrides = PairOverrides::default();
+    for token in settings.split_whitespace() {
+        let (key, value) = token
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected key=value, got '{}'", token))?;
+        overrides.set(key, value)?;
+    }
+    Ok(overrides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "watchlists/majors.txt";
+
+    fn parse_error(text: &str) -> String {
+        format!("{:#}", Watchlist::parse(text, PATH).unwrap_err())
+    }
+
+    #[test]
+    fn parses_groups_overrides_and_venues() {
+        let watchlist = Watchlist::parse(
+            "BTCUSDT\n\
+             \n\
+             [majors] horizon=0.2   # group default\n\
+             ethusdt interval=1h sticky.threshold=0.3\n\
+             !SOLUSDT\n\
+             binance-futures:BTCUSDT\n\
+             ETHUSDT interval=4h    # repeat, ignored\n",
+            PATH,
+        )
+        .unwrap();
+
+        let symbols: Vec<&str> = watchlist
+            .entries
+            .iter()
+            .map(|e| e.symbol.as_str())
+            .collect();
+        assert_eq!(
+            symbols,
+            vec!["BTCUSDT", "ETHUSDT", "SOLUSDT", "binance-futures:BTCUSDT"]
+        );
+        assert_eq!(watchlist.entries[0].group, DEFAULT_GROUP);
+
+        let eth = watchlist.get("ETHUSDT").unwrap();
+        assert_eq!(eth.group, "majors");
+        assert_eq!(eth.overrides.interval_ms, Some(TimeUtils::MS_IN_H));
+        assert_eq!(eth.overrides.price_horizon_pct, Some(0.2));
+        assert_eq!(eth.overrides.sticky.threshold, Some(0.3));
+        assert!(!watchlist.get("SOLUSDT").unwrap().is_enabled());
+        assert_eq!(watchlist.enabled().count(), 3);
+    }
+
+    #[test]
+    fn errors_name_the_file_and_line() {
+        let error = parse_error("BTCUSDT\n# comment\n\nETHUSDT interval=7x\n");
+        assert!(
+            error.starts_with("watchlists/majors.txt line 4:"),
+            "{}",
+            error
+        );
+        assert!(error.contains("Unknown interval '7x'"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for (text, expected) in [
+            ("[majors", "Unclosed group header"),
+            ("[majors] horizon", "Expected key=value, got 'horizon'"),
+            ("BTCUSDT colour=red", "Unknown key 'colour'"),
+            (
+                "BTCUSDT horizon=wide",
+                "'horizon' expects a number, got 'wide'",
+            ),
+            ("BTCUSDT enabled=maybe", "'enabled' expects true/false"),
+            (
+                "BTCUSDT lookback_days=1.5",
+                "'lookback_days' expects a whole number",
+            ),
+            (
+                "BTCUSDT stale_after=-1",
+                "'stale_after' expects whole seconds",
+            ),
+            ("BTCUSDT wick.gap=0.1", "Unknown zone layer 'wick'"),
+            ("BTCUSDT sticky.width=0.1", "Unknown zone parameter 'width'"),
+            ("kraken:BTCUSD", "Unknown venue 'kraken'"),
+            ("BTC-USDT", "'BTC-USDT' is not a symbol"),
+            ("!", "'!' is not a symbol"),
+        ] {
+            let error = parse_error(text);
+            assert!(
+                error.starts_with("watchlists/majors.txt line 1:"),
+                "{}",
+                error
+            );
+            assert!(error.contains(expected), "{:?}: {}", text, error);
+        }
+    }
+}
@@ -1,11 +1,24 @@
-use std::collections::{HashMap, VecDeque};
-use std::sync::mpsc::{Receiver, Sender, channel};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::mpsc::{Receiver, Sender, TryRecvError, channel};
 use std::sync::Arc;
 
 use crate::analysis::MultiPairMonitor;
-use crate::config::{ANALYSIS, AnalysisConfig};
-use crate::data::price_stream::PriceStreamManager;
+use crate::analysis::data_quality::{DataQualityReport, assess_collection};
+use crate::config::{ANALYSIS, AnalysisConfig, BINANCE};
+use crate::data::pair_loading::{PairLoadEvent, PairLoadStatus};
+use crate::data::price_stream::{
+    ClosedKline, PriceStreamManager, PriceUpdate, PriceUpdates, StreamSelection, UpdateNotifier,
+};
 use crate::data::timeseries::TimeSeriesCollection;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::data::timeseries::bnapi_version::exchange_info::load_cached_symbol_registry;
+use crate::domain::candle::Candle;
+use crate::domain::order_book::{BookTicker, DepthSnapshot, Side};
+use crate::domain::symbol_registry::SymbolRegistry;
+use crate::domain::venue::Venue;
+use crate::domain::watchlist::Watchlist;
+use crate::journeys::PairJourneys;
+use crate::models::timeseries::{OhlcvTimeSeries, find_matching_ohlcv};
 use crate::models::trading_view::TradingModel;
 
 use super::messages::{JobRequest, JobResult};
@@ -16,12 +29,16 @@ pub struct SniperEngine {
     /// Registry of all pairs
     pub pairs: HashMap<String, PairState>,
 
-    /// Shared immutable data
+    /// Shared data. Jobs hold their own `Arc`, so live candles are appended to a copy of
+    /// the series they change, never of the whole collection (see `apply_closed_klines`).
     pub timeseries: Arc<TimeSeriesCollection>,
     
     /// Live Data Feed
     pub price_stream: Arc<PriceStreamManager>,
 
+    /// What the feed changed since the last `update`
+    price_updates: PriceUpdates,
+
     /// Owned monitor
     pub multi_pair_monitor: MultiPairMonitor,
 
@@ -34,13 +51,39 @@ pub struct SniperEngine {
     
     /// The Live Configuration State
     pub current_config: AnalysisConfig,
+
+    /// Groups and per-pair overrides from the watchlist file
+    pub watchlist: Watchlist,
+
+    /// exchangeInfo of the loaded venues, which splits pair names into base and quote.
+    /// The cached copy until an API load sends a fresh one.
+    pub symbols: Arc<SymbolRegistry>,
+
+    /// Data-quality report per pair, built once from the loaded data
+    pub data_quality: HashMap<String, DataQualityReport>,
+
+    /// Pairs still arriving from the background load (see `data::pair_loading`)
+    pair_loads: Option<Receiver<PairLoadEvent>>,
+
+    /// How that load is going
+    pub load_status: PairLoadStatus,
 }
 
 impl SniperEngine {
     /// Initialize the engine, spawn workers, and start the price stream.
     pub fn new(timeseries: TimeSeriesCollection) -> Self {
+        Self::with_price_stream(timeseries, PriceStreamManager::new())
+    }
+
+    /// As `new`, with prices from `price_stream` (e.g. `PriceStreamManager::replaying`).
+    pub fn with_price_stream(
+        timeseries: TimeSeriesCollection,
+        price_stream: PriceStreamManager,
+    ) -> Self {
         let timeseries_arc = Arc::new(timeseries);
-        let price_stream = Arc::new(PriceStreamManager::new());
+        let price_stream = Arc::new(price_stream);
+        // Subscribe before streaming starts so the warm-up prices aren't missed
+        let price_updates = price_stream.subscribe_updates();
         
         let (job_tx, job_rx) = channel::<JobRequest>();
         let (result_tx, result_rx) = channel::<JobResult>();
@@ -52,33 +95,155 @@ impl SniperEngine {
             pairs.insert(pair, PairState::new());
         }
         
-        let all_names: Vec<String> = pairs.keys().cloned().collect();
-        price_stream.subscribe_all(all_names);
+        let data_quality = assess_collection(&timeseries_arc)
+            .into_iter()
+            .map(|report| (report.pair.clone(), report))
+            .collect();
+        let load_status = PairLoadStatus::complete(timeseries_arc.series_data.len());
 
-        Self {
+        let engine = Self {
             pairs,
             timeseries: timeseries_arc,
             price_stream,
+            price_updates,
             multi_pair_monitor: MultiPairMonitor::new(),
             job_tx,
             result_rx,
             queue: VecDeque::new(),
             current_config: ANALYSIS.clone(), 
+            watchlist: load_watchlist(),
+            symbols: Arc::new(load_symbols()),
+            data_quality,
+            pair_loads: None,
+            load_status,
+        };
+        engine.subscribe_price_streams();
+        engine
+    }
+
+    /// Starts with no pairs and takes them in from `pair_loads` as they arrive, so the
+    /// UI can be up while they load (see `data::pair_loading::spawn_pair_loading`).
+    pub fn loading(pair_loads: Receiver<PairLoadEvent>, price_stream: PriceStreamManager) -> Self {
+        let mut engine = Self::with_price_stream(TimeSeriesCollection::default(), price_stream);
+        engine.pair_loads = Some(pair_loads);
+        engine.load_status = PairLoadStatus::default();
+        engine
+    }
+
+    /// Streams every loaded pair the watchlist doesn't disable, with the watchlist's
+    /// staleness thresholds. Safe to call again: the price stream only (un)subscribes
+    /// what changed.
+    fn subscribe_price_streams(&self) {
+        self.price_stream.set_stale_thresholds(
+            self.watchlist
+                .entries
+                .iter()
+                .filter_map(|entry| {
+                    Some((entry.symbol.clone(), entry.overrides.stale_after_sec?))
+                })
+                .collect(),
+        );
+
+        // Each pair's closed candles only at the interval(s) it is loaded at
+        let mut intervals: HashMap<&str, BTreeSet<i64>> = HashMap::new();
+        for series in &self.timeseries.series_data {
+            intervals
+                .entry(series.pair_interval.name())
+                .or_default()
+                .insert(series.pair_interval.interval_ms);
         }
+        let streamed: Vec<(String, StreamSelection)> = self
+            .get_all_pair_names()
+            .into_iter()
+            .filter(|pair| self.watchlist.get(pair).is_none_or(|entry| entry.is_enabled()))
+            .map(|pair| {
+                let selection = StreamSelection {
+                    kline_intervals: intervals
+                        .get(pair.as_str())
+                        .map(|intervals| intervals.iter().copied().collect())
+                        .unwrap_or_default(),
+                    book_ticker: BINANCE.ws.book_ticker,
+                    depth_levels: BINANCE.ws.depth_levels,
+                };
+                (pair, selection)
+            })
+            .collect();
+        self.price_stream.subscribe_each(streamed);
+    }
+
+    /// Re-reads the watchlist file: new overrides apply from the next recalc, and pairs
+    /// enabled or disabled there start or stop streaming without a restart.
+    pub fn reload_watchlist(&mut self) {
+        self.watchlist = load_watchlist();
+        self.subscribe_price_streams();
+        log::info!("Watchlist reloaded ({} entries).", self.watchlist.entries.len());
     }
 
-    /// THE GAME LOOP.
-    pub fn update(&mut self) {
+    /// THE GAME LOOP. Only does work for what changed since the last call, so it can be
+    /// driven by a render loop or by `set_update_notifier` wake-ups. Returns true if
+    /// anything changed (a model, a live value or the queue).
+    pub fn update(&mut self) -> bool {
+        // 0. Take In Pairs Loaded in the Background
+        let mut changed = self.receive_loaded_pairs();
+
         // 1. Process Results (Swap Buffers)
         while let Ok(result) = self.result_rx.try_recv() {
             self.handle_job_result(result);
+            changed = true;
         }
 
-        // 2. Check Triggers (Price Movement)
-        self.check_automatic_triggers();
+        // 2. Drain the price stream's updates
+        let mut moved: BTreeSet<String> = BTreeSet::new();
+        let mut recheck_all = false;
+        let mut new_klines = false;
+        while let Some(update) = self.price_updates.try_next() {
+            changed = true;
+            match update {
+                PriceUpdate::Price { symbol, .. } => {
+                    // Streams name pairs in lowercase; only the symbol is uppercase in ours
+                    let (venue, symbol) = Venue::split(&symbol);
+                    moved.insert(venue.qualify(&symbol.to_uppercase()));
+                }
+                PriceUpdate::ClosedKline { .. } => new_klines = true,
+                PriceUpdate::Book { .. } | PriceUpdate::Depth { .. } => {}
+                PriceUpdate::Lagged => {
+                    recheck_all = true;
+                    new_klines = true;
+                }
+            }
+        }
+
+        // 3. Append Live Candles
+        if new_klines {
+            self.apply_closed_klines(self.price_stream.take_closed_klines());
+        }
 
-        // 3. Dispatch Jobs
-        self.process_queue();
+        // 4. Check Triggers (Price Movement) for the pairs whose price changed
+        let pairs: Vec<String> = if recheck_all {
+            self.pairs.keys().cloned().collect()
+        } else {
+            moved.into_iter().filter(|pair| self.pairs.contains_key(pair)).collect()
+        };
+        self.check_automatic_triggers(pairs);
+
+        // 5. Dispatch Jobs
+        changed |= self.process_queue();
+
+        changed
+    }
+
+    /// Runs `notify` whenever the price stream publishes an update (from its own
+    /// threads), e.g. to wake a UI that repaints only on change
+    pub fn set_update_notifier(&self, notify: UpdateNotifier) {
+        self.price_stream.set_update_notifier(notify);
+    }
+
+    /// True while jobs are queued or running, or pairs are still loading; none of that
+    /// is announced, so the caller should keep calling `update` until this clears
+    pub fn is_busy(&self) -> bool {
+        !self.queue.is_empty()
+            || self.pairs.values().any(|state| state.is_calculating)
+            || self.load_status.is_loading()
     }
 
     /// Accessor for UI
@@ -86,12 +251,55 @@ impl SniperEngine {
         self.pairs.get(pair).and_then(|state| state.model.clone())
     }
     
+    /// Journey results computed with the pair's current model
+    pub fn get_journeys(&self, pair: &str) -> Option<&Result<Arc<PairJourneys>, String>> {
+        self.pairs.get(pair).and_then(|state| state.journeys.as_ref())
+    }
+
     pub fn get_price(&self, pair: &str) -> Option<f64> {
         self.price_stream.get_price(pair)
     }
 
+    /// The candles the pair is analysed on (at its watchlist interval, if overridden)
+    pub fn get_series(&self, pair: &str) -> Option<&OhlcvTimeSeries> {
+        let interval_ms = self
+            .watchlist
+            .config_for(pair, &self.current_config)
+            .interval_width_ms;
+        find_matching_ohlcv(&self.timeseries.series_data, pair, interval_ms).ok()
+    }
+
+    /// Best bid/ask, when the book ticker is streamed
+    pub fn get_book(&self, pair: &str) -> Option<BookTicker> {
+        self.price_stream.get_book(pair)
+    }
+
+    /// Short order-book snapshot, when depth is streamed
+    pub fn get_depth(&self, pair: &str) -> Option<DepthSnapshot> {
+        self.price_stream.get_depth(pair)
+    }
+
+    /// What an order on `side` would fill at now: the touch on that side of the book,
+    /// or the last trade price if the book isn't streamed.
+    pub fn get_executable_price(&self, pair: &str, side: Side) -> Option<f64> {
+        self.price_stream
+            .get_book(pair)
+            .map(|book| book.executable_price(side))
+            .or_else(|| self.price_stream.get_price(pair))
+    }
+
+    /// True if the pair's live price is too old to act on (see `live_price`)
+    pub fn is_price_stale(&self, pair: &str) -> bool {
+        !self.price_stream.is_suspended() && self.price_stream.is_price_stale(pair)
+    }
+
+    /// Signals for pairs whose price is live; a frozen feed would keep stale ones showing
     pub fn get_signals(&self) -> Vec<&crate::models::pair_context::PairContext> {
-        self.multi_pair_monitor.get_signals()
+        self.multi_pair_monitor
+            .get_signals()
+            .into_iter()
+            .filter(|ctx| !self.is_price_stale(&ctx.pair_name))
+            .collect()
     }
 
     pub fn set_stream_suspended(&self, suspended: bool) {
@@ -106,6 +314,15 @@ impl SniperEngine {
         self.timeseries.unique_pair_names()
     }
 
+    pub fn get_data_quality(&self, pair: &str) -> Option<&DataQualityReport> {
+        self.data_quality.get(pair)
+    }
+
+    /// Loaded pairs grouped as in the watchlist file: (group name, pairs).
+    pub fn get_pair_groups(&self) -> Vec<(String, Vec<String>)> {
+        self.watchlist.group_pairs(&self.get_all_pair_names())
+    }
+
     // --- TELEMETRY ---
     
     pub fn get_queue_len(&self) -> usize {
@@ -181,11 +398,123 @@ impl SniperEngine {
 
     // --- INTERNAL LOGIC ---
 
+    /// The price jobs and triggers may use: `None` while the pair's feed is stale.
+    /// While the stream is suspended (simulation) the frozen last price is used as-is.
+    fn live_price(&self, pair: &str) -> Option<f64> {
+        if self.price_stream.is_suspended() {
+            self.price_stream.get_price(pair)
+        } else {
+            self.price_stream.get_fresh_price(pair)
+        }
+    }
+
+    /// Adds the pairs the background load sent since the last call and streams their
+    /// prices; each one's first price then queues its first recalc. True if the load
+    /// reported anything.
+    fn receive_loaded_pairs(&mut self) -> bool {
+        let Some(pair_loads) = &self.pair_loads else {
+            return false;
+        };
+
+        let mut reported = false;
+        let mut arrived = Vec::new();
+        let mut loader_gone = false;
+        loop {
+            let event = match pair_loads.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    loader_gone = true;
+                    break;
+                }
+            };
+            reported = true;
+            self.load_status.record(&event);
+            match event {
+                PairLoadEvent::Loaded(series) => arrived.push(series),
+                PairLoadEvent::Failed { pair, reason } => {
+                    log::warn!("[{}] Not loaded: {}", pair, reason)
+                }
+                PairLoadEvent::Symbols(symbols) => self.symbols = symbols,
+                PairLoadEvent::Progress(_) | PairLoadEvent::Finished(_) => {}
+            }
+        }
+        if loader_gone {
+            self.pair_loads = None;
+            if self.load_status.is_loading() {
+                self.load_status.record(&PairLoadEvent::Finished(Err(
+                    "The pair loader stopped unexpectedly".to_string(),
+                )));
+                reported = true;
+            }
+        }
+
+        if !arrived.is_empty() {
+            let collection = Arc::make_mut(&mut self.timeseries);
+            for series in arrived {
+                let pair = series.pair_interval.name().to_string();
+                self.data_quality
+                    .insert(pair.clone(), DataQualityReport::assess(&series));
+                self.pairs.entry(pair).or_insert_with(PairState::new);
+                collection.series_data.push(series);
+            }
+            self.subscribe_price_streams();
+        }
+        reported
+    }
+
+    /// Appends closed candles to their series and re-queues the pairs that changed.
+    /// While a running job holds the old collection, `make_mut` copies its list of
+    /// `Arc`s and then only the series a candle is appended to; the rest stay shared.
+    fn apply_closed_klines(&mut self, klines: Vec<ClosedKline>) {
+        if klines.is_empty() {
+            return;
+        }
+
+        let collection = Arc::make_mut(&mut self.timeseries);
+        let mut changed: Vec<String> = Vec::new();
+        for kline in klines {
+            let Some(series) = collection.series_data.iter_mut().find(|series| {
+                series.pair_interval.interval_ms == kline.interval_ms
+                    && series.pair_interval.name().eq_ignore_ascii_case(&kline.symbol)
+            }) else {
+                continue;
+            };
+            // Checked first so a repeated kline doesn't copy a series a job still holds
+            if !series.accepts_candle_at(kline.open_time_ms) {
+                continue;
+            }
+            let candle = Candle {
+                open_price: kline.open_price,
+                high_price: kline.high_price,
+                low_price: kline.low_price,
+                close_price: kline.close_price,
+                base_volume: kline.base_volume,
+                quote_volume: kline.quote_volume,
+            };
+            if Arc::make_mut(series).append_candle(kline.open_time_ms, &candle) > 0 {
+                let pair = series.pair_interval.name().to_string();
+                if !changed.contains(&pair) {
+                    changed.push(pair);
+                }
+            }
+        }
+
+        for pair in changed {
+            // A pair mid-calculation waits at the back until its current job returns
+            if !self.queue.iter().any(|(p, _)| p == &pair) {
+                log::info!("[{}] Trigger: New closed candle", pair);
+                self.queue.push_back((pair, None));
+            }
+        }
+    }
+
     fn handle_job_result(&mut self, result: JobResult) {
         if let Some(state) = self.pairs.get_mut(&result.pair_name) {
             match result.result {
                 Ok(model) => {
                     state.update_buffer(model.clone());
+                    state.journeys = result.journeys;
                     
                     let ctx = crate::models::pair_context::PairContext::new(
                         (*model).clone(), 
@@ -202,11 +531,9 @@ impl SniperEngine {
         }
     }
 
-    fn check_automatic_triggers(&mut self) {
-        let pairs: Vec<String> = self.pairs.keys().cloned().collect();
-
+    fn check_automatic_triggers(&mut self, pairs: Vec<String>) {
         for pair in pairs {
-            if let Some(current_price) = self.price_stream.get_price(&pair) {
+            if let Some(current_price) = self.live_price(&pair) {
                 if let Some(state) = self.pairs.get_mut(&pair) {
                     
                     // Don't queue if already busy or already queued (Check name only)
@@ -233,8 +560,9 @@ impl SniperEngine {
         }
     }
 
-    fn process_queue(&mut self) {
-        if self.queue.is_empty() { return; }
+    /// Dispatches the front of the queue unless that pair is busy. True if the queue moved.
+    fn process_queue(&mut self) -> bool {
+        if self.queue.is_empty() { return false; }
 
         // Peek at front
         if let Some((pair, _)) = self.queue.front() {
@@ -242,7 +570,7 @@ impl SniperEngine {
              if let Some(state) = self.pairs.get(pair) {
                  if state.is_calculating {
                      // It's busy. Wait.
-                     return;
+                     return false;
                  }
              }
         }
@@ -250,33 +578,195 @@ impl SniperEngine {
         // Pop the tuple
         if let Some((pair, price_opt)) = self.queue.pop_front() {
             self.dispatch_job(pair, price_opt);
+            return true;
         }
+        false
     }
 
+    /// Without a usable price the pair is dropped from the queue until its next price
+    /// update re-triggers it.
     fn dispatch_job(&mut self, pair: String, price_override: Option<f64>) {
+        // Priority: Override -> Live Stream -> Fail
+        let price = if let Some(p) = price_override {
+            p
+        } else if let Some(p) = self.live_price(&pair) {
+            p
+        } else {
+            // No price, or a stale one. Do nothing.
+            if self.price_stream.get_price(&pair).is_some() {
+                log::warn!("[{}] Skipped recalc: live price is stale", pair);
+            }
+            return;
+        };
+
         if let Some(state) = self.pairs.get_mut(&pair) {
-            
-            // Priority: Override -> Live Stream -> Fail
-            let price = if let Some(p) = price_override {
-                p
-            } else if let Some(p) = self.price_stream.get_price(&pair) {
-                p
-            } else {
-                // No price. Do nothing.
-                return;
-            };
-            
             state.is_calculating = true;
             state.last_update_price = price; 
 
+            let config = self.watchlist.config_for(&pair, &self.current_config);
             let req = JobRequest {
                 pair_name: pair,
                 current_price: price,
-                config: self.current_config.clone(), 
+                config,
                 timeseries: self.timeseries.clone(),
             };
 
             let _ = self.job_tx.send(req);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_watchlist() -> Watchlist {
+    Watchlist::load(BINANCE.pairs.watchlist_path).unwrap_or_else(|e| {
+        log::warn!("Watchlist unavailable, using global settings for every pair: {:#}", e);
+        Watchlist::default()
+    })
+}
+
+// The web demo has no watchlist file.
+#[cfg(target_arch = "wasm32")]
+fn load_watchlist() -> Watchlist {
+    Watchlist::default()
+}
+
+/// The exchangeInfo cached by the last API load, so pair names split even when klines
+/// come from the cache and exchangeInfo is never fetched
+#[cfg(not(target_arch = "wasm32"))]
+fn load_symbols() -> SymbolRegistry {
+    load_cached_symbol_registry().unwrap_or_else(|e| {
+        log::warn!(
+            "No cached exchangeInfo, pair names split by common quote suffixes: {:#}",
+            e
+        );
+        SymbolRegistry::default()
+    })
+}
+
+// Nor any exchangeInfo: pair names split by common quote suffixes.
+#[cfg(target_arch = "wasm32")]
+fn load_symbols() -> SymbolRegistry {
+    SymbolRegistry::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::tick_tape::{ReplaySpeed, TickTape, tape_header};
+
+    const INTERVAL_MS: i64 = 30 * 60 * 1000;
+
+    fn series(name: &str) -> Arc<OhlcvTimeSeries> {
+        Arc::new(OhlcvTimeSeries::from_test_candles(
+            name,
+            INTERVAL_MS,
+            0,
+            &[(10.0, 11.0, 9.0, 10.5); 4],
+        ))
+    }
+
+    /// An engine fed from an empty tape, so nothing connects to Binance
+    fn offline_engine(series_data: Vec<Arc<OhlcvTimeSeries>>) -> SniperEngine {
+        let tape = TickTape::from_bytes(&tape_header()).unwrap();
+        SniperEngine::with_price_stream(
+            TimeSeriesCollection {
+                series_data,
+                ..Default::default()
+            },
+            PriceStreamManager::replaying(tape, ReplaySpeed::Max),
+        )
+    }
+
+    fn closed_kline(symbol: &str, open_time_ms: i64) -> ClosedKline {
+        ClosedKline {
+            symbol: symbol.to_string(),
+            interval_ms: INTERVAL_MS,
+            open_time_ms,
+            open_price: 10.5,
+            high_price: 12.0,
+            low_price: 10.0,
+            close_price: 11.5,
+            base_volume: 1.0,
+            quote_volume: 11.5,
+        }
+    }
+
+    #[test]
+    fn closed_klines_copy_only_the_series_they_change() {
+        let mut engine = offline_engine(vec![series("BTCUSDT"), series("ETHUSDT")]);
+        // What a running job holds
+        let job = engine.timeseries.clone();
+
+        engine.apply_closed_klines(vec![closed_kline("btcusdt", 4 * INTERVAL_MS)]);
+
+        let [btc, eth] = &engine.timeseries.series_data[..] else {
+            panic!("two series");
+        };
+        assert_eq!(btc.klines(), 5);
+        assert_eq!(btc.close_prices[4], 11.5);
+        assert_eq!(job.series_data[0].klines(), 4);
+        assert!(!Arc::ptr_eq(btc, &job.series_data[0]));
+        assert!(Arc::ptr_eq(eth, &job.series_data[1]));
+        assert!(engine.queue.iter().any(|(pair, _)| pair == "BTCUSDT"));
+
+        // Nothing new (already have that candle): nothing copied, nothing queued
+        engine.queue.clear();
+        let job = engine.timeseries.clone();
+        engine.apply_closed_klines(vec![closed_kline("ethusdt", 2 * INTERVAL_MS)]);
+        assert!(Arc::ptr_eq(
+            &engine.timeseries.series_data[1],
+            &job.series_data[1]
+        ));
+        assert!(engine.queue.is_empty());
+    }
+
+    #[test]
+    fn a_price_move_on_a_venue_prefixed_pair_triggers_its_recalc() {
+        let futures = "binance-futures:BTCUSDT";
+        let prices = std::collections::BTreeMap::from([(futures.to_string(), 100.0)]);
+        let mut engine = SniperEngine::with_price_stream(
+            TimeSeriesCollection {
+                series_data: vec![series(futures), series("BTCUSDT")],
+                ..Default::default()
+            },
+            PriceStreamManager::replaying(
+                TickTape::synthetic(&prices, 1000, 1000, 0.0),
+                ReplaySpeed::Max,
+            ),
+        );
+
+        let triggered = |engine: &SniperEngine, pair: &str| {
+            engine.pairs[pair].is_calculating || engine.queue.iter().any(|(p, _)| p == pair)
+        };
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !triggered(&engine, futures) {
+            assert!(std::time::Instant::now() < deadline, "no recalc for {}", futures);
+            engine.update();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        // The spot pair of the same symbol had no price
+        assert!(!triggered(&engine, "BTCUSDT"));
+    }
+
+    #[test]
+    fn loaded_pairs_join_without_copying_the_others() {
+        let (tx, rx) = channel();
+        let mut engine = offline_engine(Vec::new());
+        engine.pair_loads = Some(rx);
+        let btc = series("BTCUSDT");
+        tx.send(PairLoadEvent::Loaded(btc.clone())).unwrap();
+        assert!(engine.receive_loaded_pairs());
+        let job = engine.timeseries.clone();
+
+        let eth = series("ETHUSDT");
+        tx.send(PairLoadEvent::Loaded(eth.clone())).unwrap();
+        assert!(engine.receive_loaded_pairs());
+
+        let loaded = &engine.timeseries.series_data;
+        assert!(Arc::ptr_eq(&loaded[0], &btc));
+        assert!(Arc::ptr_eq(&loaded[0], &job.series_data[0]));
+        assert!(Arc::ptr_eq(&loaded[1], &eth));
+        assert_eq!(job.series_data.len(), 1);
+        assert!(engine.pairs.contains_key("ETHUSDT"));
+    }
+}
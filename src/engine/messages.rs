@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use crate::config::AnalysisConfig;
 use crate::data::timeseries::TimeSeriesCollection;
+use crate::journeys::PairJourneys;
 use crate::models::cva::CVACore;
 use crate::models::trading_view::TradingModel;
 
@@ -27,4 +28,7 @@ pub struct JobResult {
     // We pass back the CVACore too if needed for debugging/plots, 
     // though TradingModel usually wraps it.
     pub cva: Option<Arc<CVACore>>,
+
+    // Journeys to the new model's sticky zones (None when the model itself failed)
+    pub journeys: Option<Result<Arc<PairJourneys>, String>>,
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use crate::journeys::PairJourneys;
 use crate::models::trading_view::TradingModel;
 use std::sync::Arc;
 use std::time::Instant;
@@ -19,6 +20,9 @@ pub struct PairState {
 
     /// Last error (if any) to show in UI
     pub last_error: Option<String>,
+
+    /// Journeys to the current model's sticky zones, or why they couldn't be run
+    pub journeys: Option<Result<Arc<PairJourneys>, String>>,
 }
 
 impl PairState {
@@ -29,6 +33,7 @@ impl PairState {
             last_update_time: Instant::now(),
             is_calculating: false,
             last_error: None,
+            journeys: None,
         }
     }
 
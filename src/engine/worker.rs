@@ -4,6 +4,7 @@ use std::thread;
 use std::time::Instant;
 
 use crate::analysis::pair_analysis;
+use crate::journeys::analyze_pair_journeys;
 use crate::models::trading_view::TradingModel;
 use super::messages::{JobRequest, JobResult};
 
@@ -23,6 +24,7 @@ pub fn spawn_worker_thread(
             let result_cva = pair_analysis::pair_analysis_pure(
                 req.pair_name.clone(),
                 &req.timeseries,
+                req.config.interval_width_ms,
                 // We need to calculate slice ranges here or inside pair_analysis
                 req.current_price,
                 &req.config.price_horizon,
@@ -34,13 +36,23 @@ pub fn spawn_worker_thread(
                 Ok(cva) => {
                     let cva_arc = Arc::new(cva);
                     // The worker builds the data (the model)
-                    let model = TradingModel::from_cva(cva_arc.clone());
+                    let model = TradingModel::from_cva_with_zones(cva_arc.clone(), &req.config.zones);
+                    // Journeys run against the same zones and price the chart is drawing
+                    let journeys = analyze_pair_journeys(
+                        &req.timeseries,
+                        &model,
+                        req.current_price,
+                        &req.config,
+                    )
+                    .map(Arc::new)
+                    .map_err(|e| e.to_string());
                     // The worker wraps it in Arc::new() and sends it down the channel (tx)
                     tx.send(JobResult {
                         pair_name: req.pair_name,
                         duration_ms: elapsed,
                         result: Ok(Arc::new(model)), // <- Sneding the Arc
                         cva: Some(cva_arc),
+                        journeys: Some(journeys),
                     }).unwrap();
                 }
                 Err(e) => {
@@ -49,6 +61,7 @@ pub fn spawn_worker_thread(
                         duration_ms: elapsed,
                         result: Err(e.to_string()),
                         cva: None,
+                        journeys: None,
                     }).unwrap();
                 }
             }
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+use crate::journeys::journey::JourneyOutcome;
+
+/// Strategy used to thin out clustered historical start-price matches.
+///
+/// A sideways week around the start price produces hundreds of near-identical
+/// attempts whose journeys overlap almost completely. Counting each of them as an
+/// independent sample inflates `total_attempts` and makes the Wilson interval far
+/// too narrow, so one of these strategies is applied before (or after) evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum DeclusterStrategy {
+    /// Every candle within tolerance counts as an attempt (legacy behaviour).
+    #[default]
+    AllMatches,
+    /// Accept a match only if at least `min_spacing_ms` of wall-clock time has passed
+    /// since the previously accepted match (converted to candles via the pair's interval).
+    MinSpacing { min_spacing_ms: i64 },
+    /// Accept a match only once price has moved at least `excursion_pct` percent away
+    /// from the start price since the previously accepted match.
+    FirstTouchAfterExcursion { excursion_pct: f64 },
+    /// Keep every match, but shrink the sample size used for confidence intervals
+    /// according to the autocorrelation of consecutive outcomes.
+    EffectiveSampleSize,
+}
+
+impl std::fmt::Display for DeclusterStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeclusterStrategy::AllMatches => write!(f, "all matches"),
+            DeclusterStrategy::MinSpacing { min_spacing_ms } => {
+                write!(
+                    f,
+                    "min spacing ({:.1}h)",
+                    *min_spacing_ms as f64 / 3_600_000.0
+                )
+            }
+            DeclusterStrategy::FirstTouchAfterExcursion { excursion_pct } => {
+                write!(f, "first touch after {:.2}% excursion", excursion_pct)
+            }
+            DeclusterStrategy::EffectiveSampleSize => write!(f, "effective sample size"),
+        }
+    }
+}
+
+/// Filters candidate candle indices (sorted ascending) according to `strategy`.
+/// Strategies that only correct the sample size keep every candidate.
+/// `interval_ms` is the candle width used to turn time-based spacing into candles.
+pub fn decluster_indices(
+    close_prices: &[f64],
+    candidates: &[usize],
+    start_price: f64,
+    interval_ms: i64,
+    strategy: DeclusterStrategy,
+) -> Vec<usize> {
+    match strategy {
+        DeclusterStrategy::AllMatches | DeclusterStrategy::EffectiveSampleSize => {
+            candidates.to_vec()
+        }
+        DeclusterStrategy::MinSpacing { min_spacing_ms } => {
+            let min_candles = if interval_ms > 0 {
+                (min_spacing_ms.max(0) as u64).div_ceil(interval_ms as u64) as usize
+            } else {
+                1
+            };
+            let mut kept: Vec<usize> = Vec::with_capacity(candidates.len());
+            for &idx in candidates {
+                let far_enough = kept
+                    .last()
+                    .map(|&prev| idx - prev >= min_candles.max(1))
+                    .unwrap_or(true);
+                if far_enough {
+                    kept.push(idx);
+                }
+            }
+            kept
+        }
+        DeclusterStrategy::FirstTouchAfterExcursion { excursion_pct } => {
+            if start_price <= 0.0 {
+                return candidates.to_vec();
+            }
+
+            let excursion_fraction = (excursion_pct / 100.0).max(0.0);
+            let mut kept: Vec<usize> = Vec::new();
+
+            for &idx in candidates {
+                let Some(&prev) = kept.last() else {
+                    kept.push(idx);
+                    continue;
+                };
+
+                // Has price left the band since the previously accepted touch?
+                let excursed = close_prices[prev + 1..idx]
+                    .iter()
+                    .any(|close| (close - start_price).abs() / start_price >= excursion_fraction);
+
+                if excursed {
+                    kept.push(idx);
+                }
+            }
+            kept
+        }
+    }
+}
+
+/// Effective number of independent attempts behind a set of outcomes.
+///
+/// Uses the lag-1 autocorrelation of the success indicator across consecutive
+/// attempts (AR(1) variance inflation): `n_eff = n * (1 - rho) / (1 + rho)`.
+/// Negative correlation is ignored so the result never exceeds `n`.
+pub fn effective_sample_size(outcomes: &[JourneyOutcome]) -> f64 {
+    let n = outcomes.len();
+    if n < 3 {
+        return n as f64;
+    }
+
+    let series: Vec<f64> = outcomes
+        .iter()
+        .map(|o| if o.is_success() { 1.0 } else { 0.0 })
+        .collect();
+    let mean = series.iter().sum::<f64>() / n as f64;
+    let variance = series.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+
+    // All outcomes identical: correlation is undefined, so fall back to the raw count.
+    if variance <= f64::EPSILON {
+        return n as f64;
+    }
+
+    let covariance = series
+        .windows(2)
+        .map(|w| (w[0] - mean) * (w[1] - mean))
+        .sum::<f64>();
+    let rho = (covariance / variance).clamp(0.0, 0.999);
+
+    (n as f64 * (1.0 - rho) / (1.0 + rho)).clamp(1.0, n as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journeys::journey::Outcome;
+
+    const HALF_HOUR_MS: i64 = 30 * 60 * 1000;
+
+    fn outcomes(successes: &[bool]) -> Vec<JourneyOutcome> {
+        successes
+            .iter()
+            .map(|&success| JourneyOutcome {
+                start_timestamp_ms: 0,
+                end_timestamp_ms: 0,
+                start_price: 100.0,
+                outcome: if success {
+                    Outcome::Success { days_elapsed: 1 }
+                } else {
+                    Outcome::TimedOut { final_price: 100.0 }
+                },
+                days_to_target: success.then_some(1),
+                elapsed_days: 1.0,
+                max_drawdown_pct: 0.0,
+                final_price: 100.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn all_matches_keeps_every_candidate() {
+        let candidates = [1, 2, 3, 10];
+        let kept = decluster_indices(
+            &[100.0; 12],
+            &candidates,
+            100.0,
+            HALF_HOUR_MS,
+            DeclusterStrategy::AllMatches,
+        );
+        assert_eq!(kept, candidates);
+    }
+
+    #[test]
+    fn min_spacing_is_converted_through_the_interval() {
+        let candidates = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        let two_hours = DeclusterStrategy::MinSpacing {
+            min_spacing_ms: 2 * 60 * 60 * 1000,
+        };
+
+        // 2h of 30m candles is 4 candles; of 1h candles, 2 candles.
+        let on_30m = decluster_indices(&[100.0; 9], &candidates, 100.0, HALF_HOUR_MS, two_hours);
+        assert_eq!(on_30m, vec![0, 4, 8]);
+        let on_1h = decluster_indices(&[100.0; 9], &candidates, 100.0, 2 * HALF_HOUR_MS, two_hours);
+        assert_eq!(on_1h, vec![0, 2, 4, 6, 8]);
+
+        // Spacing shorter than one candle still keeps distinct candles only.
+        let tiny = DeclusterStrategy::MinSpacing { min_spacing_ms: 1 };
+        let kept = decluster_indices(&[100.0; 9], &[3, 4, 4], 100.0, HALF_HOUR_MS, tiny);
+        assert_eq!(kept, vec![3, 4]);
+    }
+
+    #[test]
+    fn first_touch_requires_leaving_the_band() {
+        // Price wanders off by 2% between index 2 and 5 only.
+        let closes = [100.0, 100.1, 100.0, 102.0, 100.0, 100.0, 100.1, 100.0];
+        let candidates = [0, 1, 2, 4, 5, 6, 7];
+        let kept = decluster_indices(
+            &closes,
+            &candidates,
+            100.0,
+            HALF_HOUR_MS,
+            DeclusterStrategy::FirstTouchAfterExcursion { excursion_pct: 1.0 },
+        );
+        assert_eq!(kept, vec![0, 4]);
+    }
+
+    #[test]
+    fn effective_sample_size_matches_ar1_formula() {
+        // rho = 0.125 -> 8 * 0.875 / 1.125
+        let ess = effective_sample_size(&outcomes(&[
+            true, true, false, false, true, true, false, false,
+        ]));
+        assert!((ess - 56.0 / 9.0).abs() < 1e-9, "{ess}");
+
+        // rho = 0.625 -> 8 * 0.375 / 1.625
+        let ess = effective_sample_size(&outcomes(&[
+            true, true, true, true, false, false, false, false,
+        ]));
+        assert!((ess - 24.0 / 13.0).abs() < 1e-9, "{ess}");
+    }
+
+    #[test]
+    fn effective_sample_size_falls_back_to_raw_count() {
+        // Negative correlation is ignored
+        let alternating = outcomes(&[true, false, true, false, true, false]);
+        assert_eq!(effective_sample_size(&alternating), 6.0);
+        // Identical outcomes have no defined correlation
+        assert_eq!(effective_sample_size(&outcomes(&[true; 5])), 5.0);
+        // Too few samples to estimate anything
+        assert_eq!(effective_sample_size(&outcomes(&[true, false])), 2.0);
+        assert_eq!(effective_sample_size(&[]), 0.0);
+    }
+}
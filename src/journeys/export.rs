@@ -0,0 +1,339 @@
+//! CSV / JSON export of journey results, with enough context to audit them.
+//!
+//! Every `JourneyAnalysisResult` carries a `JourneyAudit`: the exact `JourneyParams`
+//! that produced it plus a fingerprint of the OHLCV series it ran over. When a stat
+//! looks too good, export it and check each attempt against the chart.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+use crate::config::PERSISTENCE;
+use crate::domain::venue::Venue;
+use crate::journeys::journey::{
+    JourneyAnalysisResult, JourneyExecution, JourneyOutcome, JourneyParams, Outcome,
+};
+use crate::models::timeseries::OhlcvTimeSeries;
+use crate::utils::checksum::Fnv1a64;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
+
+/// Identifies the exact candle data a journey run was evaluated against.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DataFingerprint {
+    pub pair: String,
+    pub interval_ms: i64,
+    pub first_kline_timestamp_ms: i64,
+    pub last_kline_timestamp_ms: i64,
+    pub candle_count: usize,
+    pub pct_gaps: f64,
+    /// FNV-1a hash over the OHLC price bits, as 16 hex digits.
+    pub checksum: String,
+}
+
+impl DataFingerprint {
+    pub fn from_series(timeseries: &OhlcvTimeSeries) -> Self {
+        let mut hasher = Fnv1a64::new();
+        let columns = [
+            &timeseries.open_prices,
+            &timeseries.high_prices,
+            &timeseries.low_prices,
+            &timeseries.close_prices,
+        ];
+        for column in columns {
+            for value in column.iter() {
+                hasher.update(&value.to_bits().to_le_bytes());
+            }
+        }
+
+        let candle_count = timeseries.close_prices.len();
+        let interval_ms = timeseries.pair_interval.interval_ms;
+
+        Self {
+            pair: timeseries.pair_interval.name.clone(),
+            interval_ms,
+            first_kline_timestamp_ms: timeseries.first_kline_timestamp_ms,
+            last_kline_timestamp_ms: timeseries.first_kline_timestamp_ms
+                + candle_count.saturating_sub(1) as i64 * interval_ms,
+            candle_count,
+            pct_gaps: timeseries.pct_gaps,
+            checksum: hasher.hex(),
+        }
+    }
+}
+
+/// Provenance of a journey result: what was asked, over which data, and when.
+#[derive(Debug, Clone, Serialize)]
+pub struct JourneyAudit {
+    pub params: JourneyParams,
+    pub data: DataFingerprint,
+    pub generated_at_ms: i64,
+}
+
+impl JourneyAudit {
+    pub fn new(params: &JourneyParams, data: DataFingerprint) -> Self {
+        Self {
+            params: params.clone(),
+            data,
+            generated_at_ms: local_now_as_timestamp_ms(),
+        }
+    }
+}
+
+/// Supported export encodings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+const CSV_HEADER: &str = "attempt,start_timestamp_ms,end_timestamp_ms,start_price,outcome,days_to_target,elapsed_days,max_drawdown_pct,final_price";
+
+impl JourneyAnalysisResult {
+    /// Full result (audit, every attempt and the summary stats) as pretty JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize journey result")
+    }
+
+    /// One row per attempt. The audit trail is written as leading `#` comment lines
+    /// so the file still loads as plain CSV in most tools.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+
+        if let Some(audit) = &self.audit {
+            let params = &audit.params;
+            let data = &audit.data;
+            let _ = writeln!(out, "# generated_at_ms: {}", audit.generated_at_ms);
+            let _ = writeln!(out, "# pair: {}", params.pair);
+            let _ = writeln!(out, "# interval_ms: {}", params.interval_ms);
+            let _ = writeln!(out, "# start_price: {}", params.start_price);
+            let _ = writeln!(out, "# end_price: {}", params.end_price);
+            let _ = writeln!(
+                out,
+                "# max_journey_time_secs: {}",
+                params.max_journey_time.as_secs()
+            );
+            let _ = writeln!(
+                out,
+                "# start_price_tolerance_pct: {}",
+                params.start_price_tolerance_pct
+            );
+            let _ = writeln!(out, "# stop_loss_pct: {}", params.stop_loss_pct);
+            let _ = writeln!(out, "# compute_kelly: {}", params.compute_kelly);
+            let _ = writeln!(out, "# declustering: {}", params.declustering);
+            let _ = writeln!(
+                out,
+                "# data: {} candles from {} to {} (gaps {:.2}%), checksum {}",
+                data.candle_count,
+                data.first_kline_timestamp_ms,
+                data.last_kline_timestamp_ms,
+                data.pct_gaps,
+                data.checksum
+            );
+        }
+
+        let _ = writeln!(out, "{}", CSV_HEADER);
+        for (attempt, outcome) in self.outcomes.iter().enumerate() {
+            let _ = writeln!(out, "{}", csv_row(attempt, outcome));
+        }
+
+        out
+    }
+
+    /// Encodes the result in the requested format.
+    pub fn export(&self, format: ExportFormat) -> Result<String> {
+        match format {
+            ExportFormat::Csv => Ok(self.to_csv()),
+            ExportFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Writes the result to `path`, creating parent directories as needed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_to_file(&self, path: &std::path::Path, format: ExportFormat) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let contents = self.export(format)?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write journey export to {}", path.display()))
+    }
+}
+
+impl JourneyExecution {
+    /// Default export location, e.g. `journey_exports/BTCUSDT_zone12_1731000000000.csv`.
+    pub fn export_path(&self, format: ExportFormat) -> std::path::PathBuf {
+        let (pair, generated_at_ms) = self
+            .analysis
+            .audit
+            .as_ref()
+            .map(|a| (a.params.pair.as_str(), a.generated_at_ms))
+            .unwrap_or(("unknown", 0));
+
+        std::path::Path::new(PERSISTENCE.journey_export.directory).join(format!(
+            "{}_zone{}_{}.{}",
+            Venue::path_safe(pair),
+            self.zone_index,
+            generated_at_ms,
+            format.extension()
+        ))
+    }
+}
+
+fn csv_row(attempt: usize, outcome: &JourneyOutcome) -> String {
+    let label = match outcome.outcome {
+        Outcome::Success { .. } => "success",
+        Outcome::TimedOut { .. } => "timed_out",
+        Outcome::StoppedOut { .. } => "stopped_out",
+    };
+    let days_to_target = outcome
+        .days_to_target
+        .map(|d| d.to_string())
+        .unwrap_or_default();
+
+    format!(
+        "{},{},{},{},{},{},{:.4},{:.4},{}",
+        attempt,
+        outcome.start_timestamp_ms,
+        outcome.end_timestamp_ms,
+        outcome.start_price,
+        label,
+        days_to_target,
+        outcome.elapsed_days,
+        outcome.max_drawdown_pct,
+        outcome.final_price,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::timeseries::TimeSeriesCollection;
+    use crate::journeys::declustering::DeclusterStrategy;
+    use crate::journeys::journey::{JourneyAnalyzer, JourneyRequest, ZoneTarget};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const PAIR: &str = "binance-futures:BTCUSDT";
+    const HOUR_MS: i64 = 3_600_000;
+
+    /// Saw-tooth between 100 and 103 every four hours, for 4 days.
+    fn collection() -> TimeSeriesCollection {
+        let candles: Vec<_> = (0..96)
+            .map(|i| match i % 4 {
+                0 => (100.0, 100.5, 99.8, 100.0),
+                1 => (100.0, 101.5, 100.0, 101.0),
+                2 => (101.0, 103.0, 101.0, 102.5),
+                _ => (102.5, 102.6, 100.2, 100.4),
+            })
+            .collect();
+        TimeSeriesCollection {
+            name: "test".to_string(),
+            version: 1.0,
+            series_data: vec![Arc::new(OhlcvTimeSeries::from_test_candles(
+                PAIR, HOUR_MS, 0, &candles,
+            ))],
+        }
+    }
+
+    fn execution(collection: &TimeSeriesCollection) -> JourneyExecution {
+        let target = ZoneTarget {
+            index: 7,
+            price_bottom: 102.0,
+            price_top: 104.0,
+        };
+        JourneyAnalyzer::new(collection)
+            .analyze_zone(JourneyRequest {
+                pair: PAIR,
+                interval_ms: HOUR_MS,
+                current_price: 100.0,
+                target: &target,
+                start_price_tolerance_pct: 0.1,
+                max_journey_time: Duration::from_secs(86_400),
+                stop_loss_pct: 5.0,
+                compute_kelly: true,
+                declustering: DeclusterStrategy::AllMatches,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn fingerprint_tracks_the_prices() {
+        let collection = collection();
+        let series = &collection.series_data[0];
+        let fingerprint = DataFingerprint::from_series(series);
+        assert_eq!(fingerprint.candle_count, 96);
+        assert_eq!(fingerprint.last_kline_timestamp_ms, 95 * HOUR_MS);
+        assert_eq!(fingerprint.checksum.len(), 16);
+        assert_eq!(fingerprint, DataFingerprint::from_series(series));
+
+        let mut changed = OhlcvTimeSeries::clone(series);
+        changed.close_prices[10] += 0.01;
+        assert_ne!(
+            DataFingerprint::from_series(&changed).checksum,
+            fingerprint.checksum
+        );
+    }
+
+    #[test]
+    fn csv_has_audit_header_and_one_row_per_attempt() {
+        let collection = collection();
+        let execution = execution(&collection);
+        let analysis = &execution.analysis;
+        // Every open at 100 (the last one has no time left, but still counts)
+        assert_eq!(analysis.outcomes.len(), 24);
+
+        let csv = analysis.to_csv();
+        assert!(csv.contains(&format!("# pair: {}\n", PAIR)));
+        assert!(csv.contains("# declustering: all matches\n"));
+        let rows: Vec<&str> = csv
+            .lines()
+            .skip_while(|line| line.starts_with('#'))
+            .collect();
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(rows.len(), analysis.outcomes.len() + 1);
+        assert!(rows[1].starts_with("0,0,"));
+        assert!(rows[1].contains(",success,"));
+
+        let json: serde_json::Value = serde_json::from_str(&analysis.to_json().unwrap()).unwrap();
+        assert_eq!(json["audit"]["params"]["pair"], PAIR);
+        assert_eq!(json["outcomes"].as_array().unwrap().len(), 24);
+    }
+
+    #[test]
+    fn export_path_is_file_name_safe() {
+        let collection = collection();
+        let execution = execution(&collection);
+        let path = execution.export_path(ExportFormat::Csv);
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        assert!(file_name.starts_with("binance-futures@BTCUSDT_zone7_"));
+        assert!(file_name.ends_with(".csv"));
+        assert!(path.starts_with(PERSISTENCE.journey_export.directory));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn write_to_file_creates_directories() {
+        let collection = collection();
+        let execution = execution(&collection);
+        let dir = std::env::temp_dir().join(format!("journey_export_test_{}", std::process::id()));
+        let path = dir.join("nested").join("journey.json");
+
+        execution
+            .analysis
+            .write_to_file(&path, ExportFormat::Json)
+            .unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, execution.analysis.to_json().unwrap());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,16 +1,23 @@
 use anyhow::{Result, anyhow};
+use serde::Serialize;
 use std::time::Duration;
 
-use crate::config::DEBUG_FLAGS;
-
+use crate::config::ANALYSIS;
 use crate::data::timeseries::TimeSeriesCollection;
+use crate::journeys::declustering::{DeclusterStrategy, decluster_indices, effective_sample_size};
+use crate::journeys::export::{DataFingerprint, JourneyAudit};
+use crate::journeys::monte_carlo::MonteCarloSummary;
+use crate::journeys::obstruction::{
+    ObstructionBreakdown, ObstructionSettings, obstruction_breakdown,
+};
 use crate::models::timeseries::{OhlcvTimeSeries, find_matching_ohlcv};
+use crate::models::trading_view::ClassifiedZones;
 use crate::utils::app_time::now;
 
 const MILLIS_PER_DAY: f64 = 86_400_000.0;
 
 /// Outcome classification for a historical journey attempt.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Outcome {
     /// Target reached within the journey time budget.
     Success { days_elapsed: u16 },
@@ -21,9 +28,11 @@ pub enum Outcome {
 }
 
 /// Captures the result of replaying a historical journey from a start price.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct JourneyOutcome {
     pub start_timestamp_ms: i64,
+    /// Open time of the last candle evaluated (where the journey resolved or ran out).
+    pub end_timestamp_ms: i64,
     pub start_price: f64,
     pub outcome: Outcome,
     /// Time in days to reach the target (present for successful journeys).
@@ -44,7 +53,7 @@ impl JourneyOutcome {
 }
 
 /// Summary metrics describing loss characteristics for failed journeys.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct RiskMetrics {
     pub avg_loss_on_failure: f64,
     pub median_loss: f64,
@@ -53,14 +62,14 @@ pub struct RiskMetrics {
 }
 
 /// Expected value metrics derived from historical journeys.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ExpectedValue {
     pub expected_annualized_return: f64,
     pub kelly_criterion: Option<f64>,
 }
 
 /// Aggregate metrics derived from a set of journey outcomes.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct JourneyStats {
     pub total_attempts: usize,
     pub success_count: usize,
@@ -69,6 +78,12 @@ pub struct JourneyStats {
     pub probability_success: f64,
     pub probability_failure: f64,
     pub confidence_interval_success: (f64, f64),
+    /// De-clustering strategy applied to the raw start-price matches.
+    pub declustering: DeclusterStrategy,
+    /// Number of candles within tolerance before de-clustering.
+    pub raw_match_count: usize,
+    /// Effective number of independent attempts (drives the confidence interval).
+    pub effective_sample_size: f64,
     pub avg_success_roi: f64,
     pub avg_failure_roi: f64,
     pub avg_success_annualized_roi: f64,
@@ -79,14 +94,16 @@ pub struct JourneyStats {
 }
 
 /// Result wrapper combining raw outcomes with summary statistics.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct JourneyAnalysisResult {
+    /// Parameters and data fingerprint that produced this result (see `export`).
+    pub audit: Option<JourneyAudit>,
     pub outcomes: Vec<JourneyOutcome>,
     pub stats: JourneyStats,
 }
 
 /// Parameter bundle describing the evaluation context for a set of journeys.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct JourneyParams {
     pub pair: String,
     pub interval_ms: i64,
@@ -97,6 +114,10 @@ pub struct JourneyParams {
     pub start_price_tolerance_pct: f64,
     pub stop_loss_pct: f64,
     pub compute_kelly: bool,
+    /// How overlapping historical attempts are thinned out or down-weighted.
+    pub declustering: DeclusterStrategy,
+    /// Whether candles filled over exchange gaps can start a journey or hit its target / stop.
+    pub include_filled_candles: bool,
 }
 
 /// Historical snapshot where price matched the requested start conditions.
@@ -126,6 +147,7 @@ pub struct JourneyRequest<'a> {
     pub max_journey_time: Duration,
     pub stop_loss_pct: f64,
     pub compute_kelly: bool,
+    pub declustering: DeclusterStrategy,
 }
 
 /// Execution result for a single journey analysis run.
@@ -137,6 +159,10 @@ pub struct JourneyExecution {
     pub target_price: f64,
     pub direction_up: bool,
     pub analysis: JourneyAnalysisResult,
+    /// Reversal-zone split of the outcomes; filled by `analyze_obstructions`.
+    pub obstruction: Option<ObstructionBreakdown>,
+    /// Block-bootstrap of the outcomes; filled from `monte_carlo`.
+    pub simulation: Option<MonteCarloSummary>,
     pub elapsed: Duration,
 }
 
@@ -161,16 +187,27 @@ impl<'a> JourneyAnalyzer<'a> {
         )
         .map_err(|e| anyhow!("Failed to locate OHLCV data: {e}"))?;
 
+        let audit = JourneyAudit::new(params, DataFingerprint::from_series(timeseries));
         let price_matches = self.match_start_prices(timeseries, params)?;
+        let raw_match_count = price_matches.len();
+        let price_matches = self.decluster_matches(timeseries, price_matches, params);
 
         if price_matches.is_empty() {
-            return Ok(JourneyAnalysisResult::default());
+            return Ok(JourneyAnalysisResult {
+                audit: Some(audit),
+                ..Default::default()
+            });
         }
 
         let outcomes = self.evaluate_price_matches(timeseries, &price_matches, params);
-        let stats = self.compute_stats(&outcomes, params);
+        let mut stats = self.compute_stats(&outcomes, params);
+        stats.raw_match_count = raw_match_count;
 
-        Ok(JourneyAnalysisResult { outcomes, stats })
+        Ok(JourneyAnalysisResult {
+            audit: Some(audit),
+            outcomes,
+            stats,
+        })
     }
 
     fn match_start_prices(
@@ -198,6 +235,9 @@ impl<'a> JourneyAnalyzer<'a> {
         let mut matches = Vec::new();
 
         for (idx, close_price) in timeseries.close_prices.iter().enumerate() {
+            if !params.include_filled_candles && timeseries.is_filled(idx) {
+                continue;
+            }
             let price_delta = (close_price - params.start_price).abs() / params.start_price;
             if price_delta <= tolerance_fraction {
                 let timestamp_ms = timeseries.first_kline_timestamp_ms
@@ -213,6 +253,40 @@ impl<'a> JourneyAnalyzer<'a> {
         Ok(matches)
     }
 
+    fn decluster_matches(
+        &self,
+        timeseries: &OhlcvTimeSeries,
+        price_matches: Vec<PriceMatch>,
+        params: &JourneyParams,
+    ) -> Vec<PriceMatch> {
+        let candidates: Vec<usize> = price_matches.iter().map(|m| m.candle_index).collect();
+        let kept = decluster_indices(
+            &timeseries.close_prices,
+            &candidates,
+            params.start_price,
+            timeseries.pair_interval.interval_ms,
+            params.declustering,
+        );
+
+        if kept.len() == price_matches.len() {
+            return price_matches;
+        }
+
+        // Both lists are sorted by candle index, so a single merge pass is enough.
+        let mut kept_iter = kept.into_iter().peekable();
+        price_matches
+            .into_iter()
+            .filter(|m| {
+                if kept_iter.peek() == Some(&m.candle_index) {
+                    kept_iter.next();
+                    true
+                } else {
+                    false
+                }
+            })
+            .collect()
+    }
+
     fn evaluate_price_matches(
         &self,
         timeseries: &OhlcvTimeSeries,
@@ -234,7 +308,7 @@ impl<'a> JourneyAnalyzer<'a> {
         let mut outcomes = Vec::with_capacity(price_matches.len());
         let stop_loss_fraction = (params.stop_loss_pct / 100.0).max(0.0);
 
-        for (attempt_index, price_match) in price_matches.iter().enumerate() {
+        for price_match in price_matches.iter() {
             let start_idx = price_match.candle_index;
             if start_idx >= timeseries.close_prices.len() {
                 continue;
@@ -260,28 +334,6 @@ impl<'a> JourneyAnalyzer<'a> {
                 None
             };
 
-            let debug_this_attempt = cfg!(debug_assertions)
-                && !DEBUG_FLAGS.print_journey_for_pair.is_empty()
-                && DEBUG_FLAGS.print_journey_for_pair == params.pair
-                && DEBUG_FLAGS.print_trigger_updates
-                && DEBUG_FLAGS.debug_journey_attempt_index >= 0
-                && attempt_index == DEBUG_FLAGS.debug_journey_attempt_index as usize;
-
-            if debug_this_attempt {
-                log::info!(
-                    "\n=== Debug journey attempt #{:03} for pair {} ===\nstart_timestamp_ms: {}\nstart_price: {:.4}\ntarget_price: {:.4} (target_is_above = {})\nstop_loss_pct: {:.2}%\nmax_journey_time: {:?}\nmax_steps: {}\n",
-                    attempt_index,
-                    params.pair,
-                    price_match.timestamp_ms,
-                    price_match.close_price,
-                    params.end_price,
-                    target_is_above,
-                    params.stop_loss_pct,
-                    params.max_journey_time,
-                    max_steps,
-                );
-            }
-
             for step in 1..=max_steps {
                 let idx = start_idx + step;
                 if idx >= timeseries.close_prices.len() {
@@ -290,22 +342,14 @@ impl<'a> JourneyAnalyzer<'a> {
 
                 steps_taken = step;
 
+                // Time still passes over a gap, but nothing traded so nothing can be hit.
+                if !params.include_filled_candles && timeseries.is_filled(idx) {
+                    continue;
+                }
+
                 let high = timeseries.high_prices[idx];
                 let low = timeseries.low_prices[idx];
 
-                if debug_this_attempt {
-                    let elapsed_ms = (step as u64 * interval_ms) as f64;
-                    let elapsed_days_debug = (elapsed_ms / MILLIS_PER_DAY).max(1.0);
-                    log::info!(
-                        "step {:04} | idx {} | elapsed_days ~ {:.2} | high {:.4} | low {:.4}",
-                        step,
-                        idx,
-                        elapsed_days_debug,
-                        high,
-                        low,
-                    );
-                }
-
                 if target_is_above {
                     if low < worst_adverse_price {
                         worst_adverse_price = low;
@@ -318,14 +362,6 @@ impl<'a> JourneyAnalyzer<'a> {
                             adverse_price: stop_price,
                         };
                         final_price = stop_price;
-
-                        if debug_this_attempt {
-                            log::info!(
-                                "  -> STOPPED OUT at price {:.4} after {:.2} days (bull journey)",
-                                stop_price,
-                                elapsed_days
-                            );
-                        }
                         break;
                     }
 
@@ -339,14 +375,6 @@ impl<'a> JourneyAnalyzer<'a> {
                         };
                         days_to_target = Some(elapsed_days_rounded);
                         final_price = params.end_price;
-
-                        if debug_this_attempt {
-                            log::info!(
-                                "  -> SUCCESS: target {:.4} reached after {:.2} days (bull journey)",
-                                params.end_price,
-                                elapsed_days
-                            );
-                        }
                         break;
                     }
                 } else {
@@ -361,14 +389,6 @@ impl<'a> JourneyAnalyzer<'a> {
                             adverse_price: stop_price,
                         };
                         final_price = stop_price;
-
-                        if debug_this_attempt {
-                            log::info!(
-                                "  -> STOPPED OUT at price {:.4} after {:.2} days (bear journey)",
-                                stop_price,
-                                elapsed_days
-                            );
-                        }
                         break;
                     }
 
@@ -382,14 +402,6 @@ impl<'a> JourneyAnalyzer<'a> {
                         };
                         days_to_target = Some(elapsed_days_rounded);
                         final_price = params.end_price;
-
-                        if debug_this_attempt {
-                            log::info!(
-                                "  -> SUCCESS: target {:.4} reached after {:.2} days (bear journey)",
-                                params.end_price,
-                                elapsed_days
-                            );
-                        }
                         break;
                     }
                 }
@@ -421,19 +433,10 @@ impl<'a> JourneyAnalyzer<'a> {
                 0.0
             };
 
-            if debug_this_attempt {
-                log::info!(
-                    "--- Attempt summary ---\nsteps_taken: {}\nelapsed_days: {:.2}\nmax_drawdown_pct: {:.2}%\nfinal_price: {:.4}\noutcome: {:?}\n========================\n",
-                    steps_taken,
-                    elapsed_days,
-                    max_drawdown_pct,
-                    final_price,
-                    outcome,
-                );
-            }
-
             outcomes.push(JourneyOutcome {
                 start_timestamp_ms: price_match.timestamp_ms,
+                end_timestamp_ms: price_match.timestamp_ms
+                    + steps_taken as i64 * timeseries.pair_interval.interval_ms,
                 start_price: price_match.close_price,
                 outcome,
                 days_to_target,
@@ -500,7 +503,11 @@ impl<'a> JourneyAnalyzer<'a> {
             }
         }
 
-        let (ci_lower, ci_upper) = wilson_interval(success_count, total_attempts);
+        let effective_n = match params.declustering {
+            DeclusterStrategy::EffectiveSampleSize => effective_sample_size(outcomes),
+            _ => total_attempts as f64,
+        };
+        let (ci_lower, ci_upper) = wilson_interval(success_rate, effective_n);
         let probability_success = success_rate;
         let probability_failure = 1.0 - probability_success;
 
@@ -551,6 +558,9 @@ impl<'a> JourneyAnalyzer<'a> {
             probability_success,
             probability_failure,
             confidence_interval_success: (ci_lower, ci_upper),
+            declustering: params.declustering,
+            raw_match_count: total_attempts,
+            effective_sample_size: effective_n,
             avg_success_roi,
             avg_failure_roi,
             avg_success_annualized_roi,
@@ -578,6 +588,8 @@ impl<'a> JourneyAnalyzer<'a> {
             start_price_tolerance_pct: request.start_price_tolerance_pct,
             stop_loss_pct: request.stop_loss_pct,
             compute_kelly: request.compute_kelly,
+            declustering: request.declustering,
+            include_filled_candles: ANALYSIS.gaps.journeys_include_filled,
         };
 
         let start_time = now();
@@ -593,6 +605,8 @@ impl<'a> JourneyAnalyzer<'a> {
             target_price,
             direction_up,
             analysis,
+            obstruction: None,
+            simulation: None,
             elapsed,
         })
     }
@@ -609,6 +623,7 @@ impl<'a> JourneyAnalyzer<'a> {
         max_journey_time: Duration,
         compute_kelly: bool,
         stop_loss_pct: f64,
+        declustering: DeclusterStrategy,
     ) -> Result<Vec<JourneyExecution>> {
         let mut executions = Vec::new();
 
@@ -622,6 +637,7 @@ impl<'a> JourneyAnalyzer<'a> {
                 max_journey_time,
                 compute_kelly,
                 stop_loss_pct,
+                declustering,
             };
 
             let execution = self.analyze_zone(request)?;
@@ -630,6 +646,34 @@ impl<'a> JourneyAnalyzer<'a> {
 
         Ok(executions)
     }
+
+    /// Splits each execution's outcomes by whether a reversal zone stood on each
+    /// attempt's own path, and records the probability for today's (obstructed or
+    /// clear) path.
+    pub fn analyze_obstructions(
+        &self,
+        pair: &str,
+        interval_ms: i64,
+        current_price: f64,
+        zones: &ClassifiedZones,
+        settings: &ObstructionSettings,
+        executions: &mut [JourneyExecution],
+    ) -> Result<()> {
+        let timeseries = find_matching_ohlcv(&self.timeseries.series_data, pair, interval_ms)
+            .map_err(|e| anyhow!("Failed to locate OHLCV data: {e}"))?;
+
+        for execution in executions.iter_mut() {
+            execution.obstruction = Some(obstruction_breakdown(
+                timeseries,
+                execution,
+                current_price,
+                zones,
+                settings,
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 fn annualized_roi(roi: f64, days: f64) -> f64 {
@@ -680,14 +724,14 @@ fn percentile(sorted_values: &[f64], fraction: f64) -> f64 {
     sorted_values.get(idx).copied().unwrap_or(0.0)
 }
 
-fn wilson_interval(successes: usize, total: usize) -> (f64, f64) {
-    if total == 0 {
+/// Wilson score interval for a success proportion `p` observed over `n` samples.
+/// `n` may be fractional when it is an effective (de-correlated) sample size.
+fn wilson_interval(p: f64, n: f64) -> (f64, f64) {
+    if n <= 0.0 {
         return (0.0, 0.0);
     }
 
     let z = 1.96_f64;
-    let n = total as f64;
-    let p = successes as f64 / n;
 
     let denominator = 1.0 + (z * z / n);
     let center = (p + (z * z) / (2.0 * n)) / denominator;
@@ -745,3 +789,29 @@ fn nearest_zone_edge(current_price: f64, price_bottom: f64, price_top: f64) -> f
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wilson_interval_matches_reference_values() {
+        // p = 0.5, n = 100: 0.5 ± 0.0961 (z = 1.96)
+        let (low, high) = wilson_interval(0.5, 100.0);
+        assert!((low - 0.4038).abs() < 1e-4, "{low}");
+        assert!((high - 0.5962).abs() < 1e-4, "{high}");
+
+        // A perfect record still leaves room below 100%
+        let (low, high) = wilson_interval(1.0, 10.0);
+        assert!((low - 0.7225).abs() < 1e-4, "{low}");
+        assert!((high - 1.0).abs() < 1e-9, "{high}");
+    }
+
+    #[test]
+    fn wilson_interval_widens_as_samples_shrink() {
+        let (low_big, high_big) = wilson_interval(0.6, 400.0);
+        let (low_small, high_small) = wilson_interval(0.6, 20.0);
+        assert!(high_small - low_small > high_big - low_big);
+        assert_eq!(wilson_interval(0.6, 0.0), (0.0, 0.0));
+    }
+}
@@ -1,6 +1,18 @@
+pub mod declustering;
+pub mod export;
 pub mod journey;
+pub mod monte_carlo;
+pub mod obstruction;
+pub mod pair_journeys;
+pub mod validation;
 
+pub use declustering::DeclusterStrategy;
+pub use export::{DataFingerprint, ExportFormat, JourneyAudit};
 pub use journey::{
     ExpectedValue, JourneyAnalysisResult, JourneyAnalyzer, JourneyExecution, JourneyOutcome,
     JourneyParams, JourneyRequest, JourneyStats, Outcome, RiskMetrics, ZoneTarget,
 };
+pub use monte_carlo::{DistributionSummary, MonteCarloSettings, MonteCarloSummary, PositionSizing};
+pub use obstruction::{ObstructingZone, ObstructionBreakdown, ObstructionSettings};
+pub use pair_journeys::{PairJourneys, analyze_pair_journeys};
+pub use validation::{ValidationReport, WalkForwardSettings};
@@ -0,0 +1,342 @@
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::journeys::journey::{JourneyExecution, JourneyOutcome};
+
+/// Position-sizing rule applied to every simulated trade.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PositionSizing {
+    /// Risk a fixed fraction of current equity on every trade (0.0 to 1.0).
+    FixedFraction(f64),
+    /// Use the Kelly fraction from `JourneyStats`, scaled by `multiplier`
+    /// (e.g. 0.5 for "half Kelly"). Requires `compute_kelly` to have been enabled.
+    Kelly { multiplier: f64 },
+}
+
+/// Tunables for the block-bootstrap Monte Carlo.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MonteCarloSettings {
+    /// Number of simulated equity paths.
+    pub paths: usize,
+    /// Number of trades in each simulated path.
+    pub trades_per_path: usize,
+    /// Length of each resampled block of consecutive historical outcomes.
+    /// Blocks preserve the serial correlation between neighbouring attempts.
+    pub block_len: usize,
+    /// A path is ruined once equity falls this many percent below its starting value.
+    pub ruin_drawdown_pct: f64,
+    /// Seed for the resampler, so runs are reproducible.
+    pub seed: u64,
+}
+
+/// Percentile summary of a simulated distribution (all values in percent).
+#[derive(Debug, Default, Clone)]
+pub struct DistributionSummary {
+    pub mean: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+/// Result of a Monte Carlo run over one set of journey outcomes.
+#[derive(Debug, Default, Clone)]
+pub struct MonteCarloSummary {
+    /// Fraction of equity committed per trade after resolving the sizing rule.
+    pub position_fraction: f64,
+    pub paths: usize,
+    pub trades_per_path: usize,
+    /// Cumulative return over each path, in percent.
+    pub cumulative_return: DistributionSummary,
+    /// Peak-to-trough drawdown over each path, in percent.
+    pub max_drawdown: DistributionSummary,
+    /// Share of paths that breached the ruin threshold (0.0 to 1.0).
+    pub probability_of_ruin: f64,
+}
+
+impl JourneyExecution {
+    /// Resamples this execution's historical outcomes into simulated trade sequences.
+    pub fn monte_carlo(
+        &self,
+        sizing: PositionSizing,
+        settings: &MonteCarloSettings,
+    ) -> Result<MonteCarloSummary> {
+        let kelly = self.analysis.stats.expected_value.kelly_criterion;
+        simulate_outcomes(
+            &self.analysis.outcomes,
+            self.direction_up,
+            sizing,
+            kelly,
+            settings,
+        )
+    }
+}
+
+/// Block-bootstraps `outcomes` (in chronological order) into `settings.paths`
+/// sequences of `settings.trades_per_path` trades and summarises the equity paths.
+pub fn simulate_outcomes(
+    outcomes: &[JourneyOutcome],
+    direction_up: bool,
+    sizing: PositionSizing,
+    kelly_criterion: Option<f64>,
+    settings: &MonteCarloSettings,
+) -> Result<MonteCarloSummary> {
+    if outcomes.is_empty() {
+        bail!("Monte Carlo needs at least one historical outcome");
+    }
+    if settings.paths == 0 || settings.trades_per_path == 0 {
+        bail!("Monte Carlo needs at least one path and one trade per path");
+    }
+
+    let position_fraction = match sizing {
+        PositionSizing::FixedFraction(fraction) => fraction,
+        PositionSizing::Kelly { multiplier } => {
+            kelly_criterion.ok_or_else(|| {
+                anyhow!("Kelly sizing requested but no Kelly fraction is available")
+            })? * multiplier
+        }
+    }
+    .clamp(0.0, 1.0);
+
+    let trade_returns: Vec<f64> = outcomes
+        .iter()
+        .map(|o| trade_return(o, direction_up))
+        .collect();
+
+    let block_len = settings.block_len.clamp(1, trade_returns.len());
+    let ruin_equity = 1.0 - (settings.ruin_drawdown_pct / 100.0).clamp(0.0, 1.0);
+    let mut rng = SplitMix64::new(settings.seed);
+
+    let mut final_returns = Vec::with_capacity(settings.paths);
+    let mut drawdowns = Vec::with_capacity(settings.paths);
+    let mut ruined_paths = 0usize;
+
+    for _ in 0..settings.paths {
+        let mut equity = 1.0_f64;
+        let mut peak = 1.0_f64;
+        let mut worst_drawdown = 0.0_f64;
+        let mut ruined = false;
+        let mut trades = 0usize;
+
+        while trades < settings.trades_per_path {
+            // Circular block bootstrap: blocks may wrap around the end of history.
+            let block_start = rng.next_index(trade_returns.len());
+            for offset in 0..block_len {
+                if trades == settings.trades_per_path {
+                    break;
+                }
+                let r = trade_returns[(block_start + offset) % trade_returns.len()];
+                equity = (equity * (1.0 + position_fraction * r)).max(0.0);
+                peak = peak.max(equity);
+                if peak > 0.0 {
+                    worst_drawdown = worst_drawdown.max((peak - equity) / peak);
+                }
+                if equity <= ruin_equity {
+                    ruined = true;
+                }
+                trades += 1;
+            }
+        }
+
+        if ruined {
+            ruined_paths += 1;
+        }
+        final_returns.push((equity - 1.0) * 100.0);
+        drawdowns.push(worst_drawdown * 100.0);
+    }
+
+    Ok(MonteCarloSummary {
+        position_fraction,
+        paths: settings.paths,
+        trades_per_path: settings.trades_per_path,
+        cumulative_return: summarize(&mut final_returns),
+        max_drawdown: summarize(&mut drawdowns),
+        probability_of_ruin: ruined_paths as f64 / settings.paths as f64,
+    })
+}
+
+/// Direction-aware fractional return of a single historical attempt.
+fn trade_return(outcome: &JourneyOutcome, direction_up: bool) -> f64 {
+    if outcome.start_price <= 0.0 {
+        return 0.0;
+    }
+    if direction_up {
+        (outcome.final_price - outcome.start_price) / outcome.start_price
+    } else {
+        (outcome.start_price - outcome.final_price) / outcome.start_price
+    }
+}
+
+fn summarize(values: &mut [f64]) -> DistributionSummary {
+    if values.is_empty() {
+        return DistributionSummary::default();
+    }
+
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let at = |fraction: f64| {
+        let idx = ((values.len() - 1) as f64 * fraction).round() as usize;
+        values[idx]
+    };
+
+    DistributionSummary {
+        mean: values.iter().sum::<f64>() / values.len() as f64,
+        p5: at(0.05),
+        p25: at(0.25),
+        median: at(0.5),
+        p75: at(0.75),
+        p95: at(0.95),
+    }
+}
+
+/// Small deterministic PRNG (SplitMix64) so simulations are reproducible from a seed.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journeys::journey::Outcome;
+
+    fn outcome(final_price: f64) -> JourneyOutcome {
+        JourneyOutcome {
+            start_timestamp_ms: 0,
+            end_timestamp_ms: 0,
+            start_price: 100.0,
+            outcome: Outcome::TimedOut { final_price },
+            days_to_target: None,
+            elapsed_days: 1.0,
+            max_drawdown_pct: 0.0,
+            final_price,
+        }
+    }
+
+    fn settings(paths: usize, trades_per_path: usize, block_len: usize) -> MonteCarloSettings {
+        MonteCarloSettings {
+            paths,
+            trades_per_path,
+            block_len,
+            ruin_drawdown_pct: 50.0,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn splitmix_matches_reference_sequence() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+    }
+
+    #[test]
+    fn same_seed_gives_same_summary() {
+        let outcomes: Vec<_> = [104.0, 97.0, 110.0, 92.0, 101.0]
+            .into_iter()
+            .map(outcome)
+            .collect();
+        let run = |seed| {
+            let settings = MonteCarloSettings {
+                seed,
+                ..settings(500, 20, 2)
+            };
+            simulate_outcomes(
+                &outcomes,
+                true,
+                PositionSizing::FixedFraction(0.5),
+                None,
+                &settings,
+            )
+            .unwrap()
+        };
+
+        let (a, b) = (run(7), run(7));
+        assert_eq!(a.cumulative_return.median, b.cumulative_return.median);
+        assert_eq!(a.max_drawdown.p95, b.max_drawdown.p95);
+        assert_eq!(a.probability_of_ruin, b.probability_of_ruin);
+        assert_ne!(a.cumulative_return.mean, run(8).cumulative_return.mean);
+    }
+
+    #[test]
+    fn circular_blocks_wrap_around_history() {
+        // +10% then -50%. A block of two starting at the last outcome wraps to the first,
+        // so every path holds both trades; only the order (and so ruin) depends on the draw.
+        let outcomes = [outcome(110.0), outcome(50.0)];
+        let settings = settings(1_000, 2, 2);
+        let summary = simulate_outcomes(
+            &outcomes,
+            true,
+            PositionSizing::FixedFraction(1.0),
+            None,
+            &settings,
+        )
+        .unwrap();
+
+        for value in [
+            summary.cumulative_return.p5,
+            summary.cumulative_return.median,
+            summary.cumulative_return.p95,
+        ] {
+            assert!((value - -45.0).abs() < 1e-9, "{value}");
+        }
+        assert!((summary.max_drawdown.median - 50.0).abs() < 1e-9);
+
+        // Paths whose block starts on the -50% trade hit the ruin threshold at once
+        let mut rng = SplitMix64::new(settings.seed);
+        let expected_ruined = (0..settings.paths)
+            .filter(|_| rng.next_index(outcomes.len()) == 1)
+            .count();
+        assert_eq!(
+            summary.probability_of_ruin,
+            expected_ruined as f64 / settings.paths as f64
+        );
+        assert!((0.4..0.6).contains(&summary.probability_of_ruin));
+    }
+
+    #[test]
+    fn direction_flips_trade_returns() {
+        let outcomes = [outcome(90.0)];
+        let settings = settings(10, 3, 1);
+        let short = simulate_outcomes(
+            &outcomes,
+            false,
+            PositionSizing::FixedFraction(1.0),
+            None,
+            &settings,
+        )
+        .unwrap();
+        // Three +10% trades: 1.1^3 - 1
+        assert!((short.cumulative_return.median - 33.1).abs() < 1e-9);
+        assert_eq!(short.max_drawdown.p95, 0.0);
+    }
+
+    #[test]
+    fn kelly_sizing_needs_a_kelly_fraction() {
+        let outcomes = [outcome(110.0)];
+        let settings = settings(10, 3, 1);
+        let sizing = PositionSizing::Kelly { multiplier: 0.5 };
+        assert!(simulate_outcomes(&outcomes, true, sizing, None, &settings).is_err());
+
+        let summary = simulate_outcomes(&outcomes, true, sizing, Some(0.4), &settings).unwrap();
+        assert!((summary.position_fraction - 0.2).abs() < 1e-12);
+        assert!(simulate_outcomes(&[], true, sizing, Some(0.4), &settings).is_err());
+    }
+}
@@ -0,0 +1,398 @@
+use serde::{Deserialize, Serialize};
+
+use crate::journeys::journey::{JourneyExecution, JourneyOutcome};
+use crate::models::timeseries::OhlcvTimeSeries;
+use crate::models::trading_view::{ClassifiedZones, SuperZone};
+
+/// Tunables for reversal-zone obstruction analysis (spec item 2.5).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ObstructionSettings {
+    /// History before each attempt scanned for wick rejections, in milliseconds
+    /// (converted to candles through the pair's interval).
+    pub lookback_ms: i64,
+    /// Width of a reversal zone, in percent of the attempt's start price.
+    pub zone_width_pct: f64,
+    /// Rejections within one zone width needed to call it a reversal zone.
+    pub min_rejections: usize,
+    /// Minimum attempts in a group before its success rate replaces the overall rate.
+    pub min_group_samples: usize,
+}
+
+/// Price range of a reversal superzone lying between the live price and a target.
+#[derive(Debug, Clone)]
+pub struct ObstructingZone {
+    pub id: usize,
+    pub price_bottom: f64,
+    pub price_top: f64,
+}
+
+/// Success rates for historical attempts split by whether a reversal zone stood
+/// on each attempt's own path to its target when it started.
+#[derive(Debug, Default, Clone)]
+pub struct ObstructionBreakdown {
+    /// Reversal zones between the live price and the target (empty = clear path).
+    pub obstructing_zones: Vec<ObstructingZone>,
+    pub obstructed_attempts: usize,
+    pub obstructed_successes: usize,
+    pub obstructed_success_rate: Option<f64>,
+    pub clear_attempts: usize,
+    pub clear_successes: usize,
+    pub clear_success_rate: Option<f64>,
+    /// Success probability for the live journey: the rate of the group matching
+    /// today's path, or the unconditional rate when that group is too small.
+    pub adjusted_probability: f64,
+    /// True if `adjusted_probability` came from the matching group.
+    pub uses_group_rate: bool,
+}
+
+impl ObstructionBreakdown {
+    pub fn is_obstructed(&self) -> bool {
+        !self.obstructing_zones.is_empty()
+    }
+}
+
+/// Reversal superzones that sit between `live_price` and `target_price`.
+///
+/// Bullish journeys are obstructed by high-wick zones (sellers rejecting higher
+/// prices); bearish journeys by low-wick zones (buyers rejecting lower prices).
+pub fn find_obstructing_zones(
+    zones: &ClassifiedZones,
+    live_price: f64,
+    target_price: f64,
+) -> Vec<ObstructingZone> {
+    let direction_up = target_price >= live_price;
+    let (lower, upper) = if direction_up {
+        (live_price, target_price)
+    } else {
+        (target_price, live_price)
+    };
+
+    let candidates: &[SuperZone] = if direction_up {
+        &zones.high_wicks_superzones
+    } else {
+        &zones.low_wicks_superzones
+    };
+
+    candidates
+        .iter()
+        .filter(|sz| sz.price_top > lower && sz.price_bottom < upper)
+        .map(|sz| ObstructingZone {
+            id: sz.id,
+            price_bottom: sz.price_bottom,
+            price_top: sz.price_top,
+        })
+        .collect()
+}
+
+/// Splits the execution's historical outcomes into obstructed and clear groups.
+///
+/// Each attempt is judged on its own path: the move from its start price by the same
+/// relative distance as today's journey. It counts as obstructed when, in the
+/// `lookback_ms` before it started, at least `min_rejections` candles poked a wick
+/// into that path and closed back on the start side, with their wick tips within
+/// `zone_width_pct` of each other (a reversal zone as it stood at the time).
+pub fn obstruction_breakdown(
+    timeseries: &OhlcvTimeSeries,
+    execution: &JourneyExecution,
+    live_price: f64,
+    zones: &ClassifiedZones,
+    settings: &ObstructionSettings,
+) -> ObstructionBreakdown {
+    let obstructing_zones = find_obstructing_zones(zones, live_price, execution.target_price);
+    let interval_ms = timeseries.pair_interval.interval_ms;
+    let lookback_candles = if interval_ms > 0 {
+        (settings.lookback_ms.max(0) / interval_ms) as usize
+    } else {
+        0
+    };
+    let target_ratio = if live_price > 0.0 {
+        execution.target_price / live_price
+    } else {
+        1.0
+    };
+
+    let mut breakdown = ObstructionBreakdown::default();
+    for outcome in &execution.analysis.outcomes {
+        let obstructed = start_index(timeseries, outcome).is_some_and(|idx| {
+            path_obstructed(
+                timeseries,
+                idx,
+                outcome.start_price,
+                outcome.start_price * target_ratio,
+                lookback_candles,
+                settings,
+            )
+        });
+
+        if obstructed {
+            breakdown.obstructed_attempts += 1;
+            if outcome.is_success() {
+                breakdown.obstructed_successes += 1;
+            }
+        } else {
+            breakdown.clear_attempts += 1;
+            if outcome.is_success() {
+                breakdown.clear_successes += 1;
+            }
+        }
+    }
+
+    breakdown.obstructed_success_rate = rate(
+        breakdown.obstructed_successes,
+        breakdown.obstructed_attempts,
+    );
+    breakdown.clear_success_rate = rate(breakdown.clear_successes, breakdown.clear_attempts);
+
+    // Today's path decides which group the live journey belongs to
+    let (group_rate, group_attempts) = if obstructing_zones.is_empty() {
+        (breakdown.clear_success_rate, breakdown.clear_attempts)
+    } else {
+        (
+            breakdown.obstructed_success_rate,
+            breakdown.obstructed_attempts,
+        )
+    };
+    match group_rate.filter(|_| group_attempts >= settings.min_group_samples.max(1)) {
+        Some(group_rate) => {
+            breakdown.adjusted_probability = group_rate;
+            breakdown.uses_group_rate = true;
+        }
+        None => breakdown.adjusted_probability = execution.analysis.stats.success_rate,
+    }
+    breakdown.obstructing_zones = obstructing_zones;
+
+    breakdown
+}
+
+fn start_index(timeseries: &OhlcvTimeSeries, outcome: &JourneyOutcome) -> Option<usize> {
+    let interval_ms = timeseries.pair_interval.interval_ms;
+    if interval_ms <= 0 || outcome.start_timestamp_ms < timeseries.first_kline_timestamp_ms {
+        return None;
+    }
+    let idx =
+        ((outcome.start_timestamp_ms - timeseries.first_kline_timestamp_ms) / interval_ms) as usize;
+    (idx < timeseries.close_prices.len()).then_some(idx)
+}
+
+/// Did wick rejections in the `lookback_candles` before `start_idx` cluster into a
+/// reversal zone strictly between `start_price` and `target_price`?
+fn path_obstructed(
+    timeseries: &OhlcvTimeSeries,
+    start_idx: usize,
+    start_price: f64,
+    target_price: f64,
+    lookback_candles: usize,
+    settings: &ObstructionSettings,
+) -> bool {
+    if start_price <= 0.0 {
+        return false;
+    }
+    let direction_up = target_price >= start_price;
+    let (lower, upper) = if direction_up {
+        (start_price, target_price)
+    } else {
+        (target_price, start_price)
+    };
+    let zone_width = start_price * (settings.zone_width_pct / 100.0).max(0.0);
+    let from = start_idx.saturating_sub(lookback_candles);
+
+    // Wick tips inside the path whose candle closed back at least a zone width away
+    let mut tips: Vec<f64> = (from..start_idx)
+        .filter_map(|i| {
+            let close = timeseries.close_prices[i];
+            let (tip, rejected) = if direction_up {
+                let high = timeseries.high_prices[i];
+                (high, close <= high - zone_width)
+            } else {
+                let low = timeseries.low_prices[i];
+                (low, close >= low + zone_width)
+            };
+            (rejected && tip > lower && tip < upper).then_some(tip)
+        })
+        .collect();
+
+    let needed = settings.min_rejections.max(1);
+    if tips.len() < needed {
+        return false;
+    }
+    tips.sort_by(|a, b| a.total_cmp(b));
+    tips.windows(needed)
+        .any(|window| window[needed - 1] - window[0] <= zone_width)
+}
+
+fn rate(successes: usize, attempts: usize) -> Option<f64> {
+    (attempts > 0).then(|| successes as f64 / attempts as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journeys::journey::{JourneyAnalysisResult, JourneyStats, Outcome};
+    use std::time::Duration;
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    fn settings(min_group_samples: usize) -> ObstructionSettings {
+        ObstructionSettings {
+            lookback_ms: 10 * HOUR_MS,
+            zone_width_pct: 0.5,
+            min_rejections: 2,
+            min_group_samples,
+        }
+    }
+
+    /// Flat at 100, except for rejected wicks at the given (index, high, low) candles.
+    fn series(wicks: &[(usize, f64, f64)]) -> OhlcvTimeSeries {
+        let mut candles = vec![(100.0, 100.0, 100.0, 100.0); 40];
+        for &(idx, high, low) in wicks {
+            candles[idx] = (100.0, high, low, 100.0);
+        }
+        OhlcvTimeSeries::from_test_candles("TESTUSDT", HOUR_MS, 0, &candles)
+    }
+
+    fn attempt(start_idx: usize, success: bool) -> JourneyOutcome {
+        JourneyOutcome {
+            start_timestamp_ms: start_idx as i64 * HOUR_MS,
+            end_timestamp_ms: start_idx as i64 * HOUR_MS,
+            start_price: 100.0,
+            outcome: if success {
+                Outcome::Success { days_elapsed: 1 }
+            } else {
+                Outcome::TimedOut { final_price: 100.0 }
+            },
+            days_to_target: success.then_some(1),
+            elapsed_days: 1.0,
+            max_drawdown_pct: 0.0,
+            final_price: 100.0,
+        }
+    }
+
+    fn execution(target_price: f64, outcomes: Vec<JourneyOutcome>) -> JourneyExecution {
+        let successes = outcomes.iter().filter(|o| o.is_success()).count();
+        JourneyExecution {
+            zone_index: 0,
+            zone_bottom: target_price,
+            zone_top: target_price,
+            target_price,
+            direction_up: target_price >= 100.0,
+            analysis: JourneyAnalysisResult {
+                audit: None,
+                stats: JourneyStats {
+                    total_attempts: outcomes.len(),
+                    success_rate: successes as f64 / outcomes.len() as f64,
+                    ..Default::default()
+                },
+                outcomes,
+            },
+            obstruction: None,
+            simulation: None,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    fn superzone(price_bottom: f64, price_top: f64) -> SuperZone {
+        SuperZone {
+            id: 1,
+            index_range: (0, 0),
+            price_bottom,
+            price_top,
+            price_center: (price_bottom + price_top) / 2.0,
+            constituent_zones: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn attempts_are_judged_on_their_own_path() {
+        // Two rejections at 103 before the attempt at 10; the ones at 110 before the
+        // attempt at 20 overshoot its 105 target; nothing before the attempt at 35.
+        let timeseries = series(&[
+            (5, 103.0, 100.0),
+            (7, 103.2, 100.0),
+            (13, 110.0, 100.0),
+            (15, 110.0, 100.0),
+        ]);
+        let execution = execution(
+            105.0,
+            vec![attempt(10, true), attempt(20, false), attempt(35, false)],
+        );
+        let zones = ClassifiedZones::default();
+
+        let breakdown = obstruction_breakdown(&timeseries, &execution, 100.0, &zones, &settings(1));
+        assert_eq!(
+            (
+                breakdown.obstructed_attempts,
+                breakdown.obstructed_successes
+            ),
+            (1, 1)
+        );
+        assert_eq!(
+            (breakdown.clear_attempts, breakdown.clear_successes),
+            (2, 0)
+        );
+        assert_eq!(breakdown.obstructed_success_rate, Some(1.0));
+        assert_eq!(breakdown.clear_success_rate, Some(0.0));
+
+        // Today's path is clear, so the clear group's rate applies
+        assert!(!breakdown.is_obstructed());
+        assert!(breakdown.uses_group_rate);
+        assert_eq!(breakdown.adjusted_probability, 0.0);
+    }
+
+    #[test]
+    fn todays_obstruction_picks_the_group() {
+        let timeseries = series(&[(5, 103.0, 100.0), (7, 103.2, 100.0)]);
+        let execution = execution(105.0, vec![attempt(10, true), attempt(35, false)]);
+        let zones = ClassifiedZones {
+            high_wicks_superzones: vec![superzone(102.0, 104.0)],
+            ..Default::default()
+        };
+
+        let breakdown = obstruction_breakdown(&timeseries, &execution, 100.0, &zones, &settings(1));
+        assert!(breakdown.is_obstructed());
+        assert_eq!(breakdown.adjusted_probability, 1.0);
+
+        // Too few obstructed samples: fall back to the overall rate, both groups still reported
+        let breakdown = obstruction_breakdown(&timeseries, &execution, 100.0, &zones, &settings(5));
+        assert!(!breakdown.uses_group_rate);
+        assert_eq!(breakdown.adjusted_probability, 0.5);
+        assert_eq!(breakdown.obstructed_attempts, 1);
+        assert_eq!(breakdown.clear_attempts, 1);
+    }
+
+    #[test]
+    fn scattered_or_unrejected_wicks_do_not_form_a_zone() {
+        // Downward journey to 95: lows at 96 and 98 are 2% apart (wider than a zone),
+        // and the low at 97 closed back inside its own zone width.
+        let mut candles = vec![(100.0, 100.0, 100.0, 100.0); 20];
+        candles[3] = (100.0, 100.0, 96.0, 100.0);
+        candles[5] = (100.0, 100.0, 98.0, 100.0);
+        candles[7] = (97.0, 97.0, 97.0, 97.2);
+        let timeseries = OhlcvTimeSeries::from_test_candles("TESTUSDT", HOUR_MS, 0, &candles);
+        let execution = execution(95.0, vec![attempt(10, false)]);
+
+        let breakdown = obstruction_breakdown(
+            &timeseries,
+            &execution,
+            100.0,
+            &ClassifiedZones::default(),
+            &settings(1),
+        );
+        assert_eq!(breakdown.clear_attempts, 1);
+        assert_eq!(breakdown.obstructed_attempts, 0);
+    }
+
+    #[test]
+    fn obstructing_zones_depend_on_direction() {
+        let zones = ClassifiedZones {
+            high_wicks_superzones: vec![superzone(102.0, 104.0)],
+            low_wicks_superzones: vec![superzone(96.0, 97.0)],
+            ..Default::default()
+        };
+        assert_eq!(find_obstructing_zones(&zones, 100.0, 105.0).len(), 1);
+        assert!(find_obstructing_zones(&zones, 100.0, 101.0).is_empty());
+        assert_eq!(
+            find_obstructing_zones(&zones, 100.0, 95.0)[0].price_top,
+            97.0
+        );
+    }
+}
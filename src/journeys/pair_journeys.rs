@@ -0,0 +1,94 @@
+//! Journeys from the live price to each sticky zone of a pair's current model.
+//!
+//! This is what the journey panel shows: run by the worker right after the model,
+//! so the zones, the price and the candles all match what the chart is drawing.
+
+use anyhow::Result;
+use std::time::Duration;
+
+use crate::config::AnalysisConfig;
+use crate::data::timeseries::TimeSeriesCollection;
+use crate::journeys::journey::{JourneyAnalyzer, JourneyExecution, ZoneTarget};
+use crate::models::trading_view::TradingModel;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Journey results for one pair against the sticky zones of its current model.
+#[derive(Debug, Clone)]
+pub struct PairJourneys {
+    pub pair: String,
+    /// Live price every journey starts from.
+    pub current_price: f64,
+    pub time_horizon_days: u64,
+    /// One execution per sticky superzone the price is not already inside.
+    pub executions: Vec<JourneyExecution>,
+}
+
+impl PairJourneys {
+    pub fn total_attempts(&self) -> usize {
+        self.executions
+            .iter()
+            .map(|execution| execution.analysis.stats.total_attempts)
+            .sum()
+    }
+}
+
+/// Runs a journey to the nearest edge of every sticky superzone in `model`, splits
+/// each execution's outcomes by reversal-zone obstruction, then block-bootstraps them
+/// with the configured position sizing.
+pub fn analyze_pair_journeys(
+    timeseries: &TimeSeriesCollection,
+    model: &TradingModel,
+    current_price: f64,
+    config: &AnalysisConfig,
+) -> Result<PairJourneys> {
+    let settings = &config.journey;
+    let targets: Vec<ZoneTarget> = model
+        .zones
+        .sticky_superzones
+        .iter()
+        .filter(|zone| !zone.contains(current_price))
+        .map(|zone| ZoneTarget {
+            index: zone.id,
+            price_bottom: zone.price_bottom,
+            price_top: zone.price_top,
+        })
+        .collect();
+
+    let time_horizon_days = config.time_horizon.default_days;
+    let analyzer = JourneyAnalyzer::new(timeseries);
+    let mut executions = analyzer.analyze_zones(
+        &model.pair_name,
+        config.interval_width_ms,
+        current_price,
+        &targets,
+        settings.start_price_tolerance_pct,
+        Duration::from_secs(time_horizon_days * SECONDS_PER_DAY),
+        true,
+        settings.stop_loss_pct,
+        settings.declustering,
+    )?;
+
+    analyzer.analyze_obstructions(
+        &model.pair_name,
+        config.interval_width_ms,
+        current_price,
+        &model.zones,
+        &settings.obstruction,
+        &mut executions,
+    )?;
+
+    for execution in &mut executions {
+        // No outcomes (or no Kelly fraction to size by) just leaves the simulation out
+        execution.simulation = execution
+            .monte_carlo(settings.position_sizing, &settings.monte_carlo)
+            .ok();
+    }
+
+    Ok(PairJourneys {
+        pair: model.pair_name.clone(),
+        current_price,
+        time_horizon_days,
+        executions,
+    })
+}
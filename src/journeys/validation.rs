@@ -0,0 +1,441 @@
+//! Walk-forward validation of Stage-0 (live price -> sticky zone) hit probabilities.
+//!
+//! At each historical cutoff we pretend "now" is that candle: the `TradingModel` is
+//! built from data up to the cutoff only, every sticky superzone gets a predicted
+//! probability of being reached within the horizon, and the candles after the cutoff
+//! say whether it actually was. Pooling those (prediction, outcome) pairs gives a
+//! Brier score and a calibration curve.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::analysis::pair_analysis::cva_from_series;
+use crate::domain::price_horizon::PriceHorizonConfig;
+use crate::journeys::declustering::{DeclusterStrategy, decluster_indices};
+use crate::models::timeseries::OhlcvTimeSeries;
+use crate::models::trading_view::TradingModel;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// Tunables for the walk-forward harness.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WalkForwardSettings {
+    /// Number of evenly spaced cutoffs across the usable history.
+    pub cutoffs: usize,
+    /// History required before the first cutoff.
+    pub min_history_days: u64,
+    /// Window after each cutoff in which the zone must be reached.
+    pub horizon_days: u64,
+    /// Tolerance when matching historical candles to the cutoff price (percentage).
+    pub start_price_tolerance_pct: f64,
+    /// Zones with fewer historical samples than this are not scored.
+    pub min_samples: usize,
+    /// Number of equal-width probability bins in the calibration curve.
+    pub calibration_bins: usize,
+}
+
+/// One scored prediction: a sticky zone at a cutoff, and what happened next.
+#[derive(Debug, Clone, Serialize)]
+pub struct ZonePrediction {
+    pub cutoff_timestamp_ms: i64,
+    pub live_price: f64,
+    pub zone_id: usize,
+    pub zone_bottom: f64,
+    pub zone_top: f64,
+    /// Predicted Stage-0 probability of reaching the zone within the horizon.
+    pub predicted: f64,
+    /// Historical start candles behind the prediction (after de-clustering).
+    pub samples: usize,
+    /// Whether price actually entered the zone within the horizon.
+    pub hit: bool,
+}
+
+/// One point on the calibration curve.
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_predicted: f64,
+    pub observed_rate: f64,
+}
+
+/// Pooled walk-forward results for one pair.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationReport {
+    pub pair: String,
+    pub cutoffs_evaluated: usize,
+    pub predictions: Vec<ZonePrediction>,
+    /// Mean squared error of the predicted probabilities (lower is better).
+    pub brier_score: f64,
+    /// Brier score of always predicting the observed base rate.
+    pub reference_brier_score: f64,
+    /// `1 - brier / reference`; positive means the model beats the base rate.
+    pub brier_skill_score: f64,
+    pub calibration: Vec<CalibrationBin>,
+}
+
+/// Runs the walk-forward harness over a single pair's history.
+pub fn walk_forward_validate(
+    timeseries: &OhlcvTimeSeries,
+    price_horizon: &PriceHorizonConfig,
+    declustering: DeclusterStrategy,
+    settings: &WalkForwardSettings,
+) -> Result<ValidationReport> {
+    let interval_ms = timeseries.pair_interval.interval_ms;
+    if interval_ms <= 0 {
+        bail!("Invalid interval ({} ms)", interval_ms);
+    }
+
+    let candles = timeseries.klines();
+    let horizon = (settings.horizon_days as i64 * MILLIS_PER_DAY / interval_ms) as usize;
+    let min_history = (settings.min_history_days as i64 * MILLIS_PER_DAY / interval_ms) as usize;
+
+    if horizon == 0 || candles <= min_history + horizon {
+        bail!(
+            "{} has {} candles; walk-forward needs more than {} (history) + {} (horizon)",
+            timeseries.pair_interval.name,
+            candles,
+            min_history,
+            horizon
+        );
+    }
+
+    let first_cutoff = min_history;
+    let last_cutoff = candles - horizon - 1;
+    let cutoff_count = settings.cutoffs.max(1);
+    let step = ((last_cutoff - first_cutoff) / cutoff_count).max(1);
+
+    let mut predictions = Vec::new();
+    let mut cutoffs_evaluated = 0usize;
+
+    for cutoff in (first_cutoff..=last_cutoff)
+        .step_by(step)
+        .take(cutoff_count)
+    {
+        let live_price = timeseries.close_prices[cutoff];
+        if live_price <= 0.0 {
+            continue;
+        }
+
+        // Only candles up to and including the cutoff are visible to the model.
+        let history = timeseries.truncated(cutoff + 1);
+        let Ok(cva) = cva_from_series(
+            timeseries.pair_interval.name.clone(),
+            &history,
+            live_price,
+            price_horizon,
+        ) else {
+            continue;
+        };
+        let model = TradingModel::from_cva(Arc::new(cva));
+        cutoffs_evaluated += 1;
+
+        let start_indices = matching_starts(
+            &history,
+            live_price,
+            horizon,
+            settings.start_price_tolerance_pct,
+            declustering,
+        );
+
+        for zone in &model.zones.sticky_superzones {
+            // Already inside the zone: nothing to predict.
+            if zone.contains(live_price) {
+                continue;
+            }
+
+            let samples = start_indices.len();
+            if samples < settings.min_samples.max(1) {
+                continue;
+            }
+
+            let hits = start_indices
+                .iter()
+                .filter(|&&idx| {
+                    enters_zone(&history, idx, horizon, zone.price_bottom, zone.price_top)
+                })
+                .count();
+
+            predictions.push(ZonePrediction {
+                cutoff_timestamp_ms: timeseries.first_kline_timestamp_ms
+                    + cutoff as i64 * interval_ms,
+                live_price,
+                zone_id: zone.id,
+                zone_bottom: zone.price_bottom,
+                zone_top: zone.price_top,
+                predicted: hits as f64 / samples as f64,
+                samples,
+                hit: enters_zone(
+                    timeseries,
+                    cutoff,
+                    horizon,
+                    zone.price_bottom,
+                    zone.price_top,
+                ),
+            });
+        }
+    }
+
+    let (brier_score, reference_brier_score) = brier_scores(&predictions);
+    let brier_skill_score = if reference_brier_score > 0.0 {
+        1.0 - brier_score / reference_brier_score
+    } else {
+        0.0
+    };
+    let calibration = calibration_curve(&predictions, settings.calibration_bins.max(1));
+
+    Ok(ValidationReport {
+        pair: timeseries.pair_interval.name.clone(),
+        cutoffs_evaluated,
+        predictions,
+        brier_score,
+        reference_brier_score,
+        brier_skill_score,
+        calibration,
+    })
+}
+
+/// Historical candles whose close is near `live_price` and whose full horizon
+/// still lies inside `history` (so no outcome peeks past the cutoff).
+fn matching_starts(
+    history: &OhlcvTimeSeries,
+    live_price: f64,
+    horizon: usize,
+    tolerance_pct: f64,
+    declustering: DeclusterStrategy,
+) -> Vec<usize> {
+    let tolerance_fraction = (tolerance_pct / 100.0).max(0.0);
+    let usable = history.klines().saturating_sub(horizon);
+
+    let candidates: Vec<usize> = (0..usable)
+        .filter(|&idx| {
+            (history.close_prices[idx] - live_price).abs() / live_price <= tolerance_fraction
+        })
+        .collect();
+
+    decluster_indices(
+        &history.close_prices,
+        &candidates,
+        live_price,
+        history.pair_interval.interval_ms,
+        declustering,
+    )
+}
+
+/// First-passage check: does any candle in `start + 1 ..= start + horizon` touch the zone?
+fn enters_zone(
+    timeseries: &OhlcvTimeSeries,
+    start: usize,
+    horizon: usize,
+    zone_bottom: f64,
+    zone_top: f64,
+) -> bool {
+    let end = (start + horizon).min(timeseries.klines().saturating_sub(1));
+    (start + 1..=end).any(|idx| {
+        timeseries.low_prices[idx] <= zone_top && timeseries.high_prices[idx] >= zone_bottom
+    })
+}
+
+fn brier_scores(predictions: &[ZonePrediction]) -> (f64, f64) {
+    if predictions.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let n = predictions.len() as f64;
+    let outcome = |p: &ZonePrediction| if p.hit { 1.0 } else { 0.0 };
+    let base_rate = predictions.iter().map(outcome).sum::<f64>() / n;
+
+    let brier = predictions
+        .iter()
+        .map(|p| (p.predicted - outcome(p)).powi(2))
+        .sum::<f64>()
+        / n;
+    let reference = predictions
+        .iter()
+        .map(|p| (base_rate - outcome(p)).powi(2))
+        .sum::<f64>()
+        / n;
+
+    (brier, reference)
+}
+
+fn calibration_curve(predictions: &[ZonePrediction], bins: usize) -> Vec<CalibrationBin> {
+    let width = 1.0 / bins as f64;
+    let mut predicted_sums = vec![0.0; bins];
+    let mut hit_counts = vec![0usize; bins];
+    let mut counts = vec![0usize; bins];
+
+    for p in predictions {
+        let bin = ((p.predicted / width) as usize).min(bins - 1);
+        counts[bin] += 1;
+        predicted_sums[bin] += p.predicted;
+        if p.hit {
+            hit_counts[bin] += 1;
+        }
+    }
+
+    (0..bins)
+        .map(|bin| {
+            let count = counts[bin];
+            let (mean_predicted, observed_rate) = if count > 0 {
+                (
+                    predicted_sums[bin] / count as f64,
+                    hit_counts[bin] as f64 / count as f64,
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            CalibrationBin {
+                lower: bin as f64 * width,
+                upper: (bin + 1) as f64 * width,
+                count,
+                mean_predicted,
+                observed_rate,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ANALYSIS;
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    /// Hourly candles oscillating between roughly 90 and 110 with a ~3 day period.
+    fn oscillating(candles: usize) -> Vec<(f64, f64, f64, f64)> {
+        (0..candles)
+            .map(|i| {
+                let close = 100.0 + 10.0 * (i as f64 / 12.0).sin();
+                let open = 100.0 + 10.0 * ((i as f64 - 1.0) / 12.0).sin();
+                (open, open.max(close) + 0.3, open.min(close) - 0.3, close)
+            })
+            .collect()
+    }
+
+    fn settings() -> WalkForwardSettings {
+        WalkForwardSettings {
+            cutoffs: 12,
+            min_history_days: 20,
+            horizon_days: 1,
+            start_price_tolerance_pct: 1.0,
+            min_samples: 1,
+            calibration_bins: 5,
+        }
+    }
+
+    fn validate(candles: &[(f64, f64, f64, f64)]) -> ValidationReport {
+        let series = OhlcvTimeSeries::from_test_candles("TESTUSDT", HOUR_MS, 0, candles);
+        walk_forward_validate(
+            &series,
+            &ANALYSIS.price_horizon,
+            DeclusterStrategy::AllMatches,
+            &settings(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn predictions_never_see_past_their_cutoff() {
+        let original = oscillating(1_200);
+        // Rewrite everything from candle 900 on: a crash to a quarter of the price
+        let change_at = 900;
+        let mut rewritten = original.clone();
+        for candle in &mut rewritten[change_at..] {
+            *candle = (
+                candle.0 / 4.0,
+                candle.1 / 4.0,
+                candle.2 / 4.0,
+                candle.3 / 4.0,
+            );
+        }
+
+        let before = validate(&original);
+        let after = validate(&rewritten);
+        assert!(!before.predictions.is_empty());
+
+        let horizon_ms = settings().horizon_days as i64 * MILLIS_PER_DAY;
+        let change_ms = change_at as i64 * HOUR_MS;
+        let mut compared = 0;
+        for (a, b) in before.predictions.iter().zip(&after.predictions) {
+            if a.cutoff_timestamp_ms >= change_ms {
+                break;
+            }
+            // Same history up to the cutoff: the same zones and probabilities
+            assert_eq!(a.cutoff_timestamp_ms, b.cutoff_timestamp_ms);
+            assert_eq!(
+                (a.zone_id, a.predicted, a.samples),
+                (b.zone_id, b.predicted, b.samples)
+            );
+            // The outcome may only differ once the horizon reaches the rewritten candles
+            if a.cutoff_timestamp_ms + horizon_ms < change_ms {
+                assert_eq!(a.hit, b.hit);
+            }
+            compared += 1;
+        }
+        assert!(compared > 0);
+        // ...while cutoffs after the rewrite do see it
+        assert!(
+            before
+                .predictions
+                .iter()
+                .zip(&after.predictions)
+                .any(|(a, b)| a.zone_bottom != b.zone_bottom)
+        );
+    }
+
+    #[test]
+    fn matching_starts_leave_room_for_the_horizon() {
+        let candles = vec![(100.0, 100.0, 100.0, 100.0); 50];
+        let history = OhlcvTimeSeries::from_test_candles("TESTUSDT", HOUR_MS, 0, &candles);
+
+        let starts = matching_starts(&history, 100.0, 10, 0.5, DeclusterStrategy::AllMatches);
+        assert_eq!(starts, (0..40).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn zone_entry_is_first_passage_within_the_horizon() {
+        let mut candles = vec![(100.0, 100.5, 99.5, 100.0); 10];
+        candles[0] = (104.0, 105.0, 104.0, 104.0); // The start candle itself doesn't count
+        candles[5] = (100.0, 104.5, 100.0, 100.0);
+        let series = OhlcvTimeSeries::from_test_candles("TESTUSDT", HOUR_MS, 0, &candles);
+
+        assert!(!enters_zone(&series, 0, 4, 104.0, 106.0));
+        assert!(enters_zone(&series, 0, 5, 104.0, 106.0));
+        // The horizon is clamped to the end of the series
+        assert!(!enters_zone(&series, 6, 100, 104.0, 106.0));
+    }
+
+    #[test]
+    fn brier_and_calibration_match_hand_computed_values() {
+        let prediction = |predicted: f64, hit: bool| ZonePrediction {
+            cutoff_timestamp_ms: 0,
+            live_price: 100.0,
+            zone_id: 0,
+            zone_bottom: 0.0,
+            zone_top: 0.0,
+            predicted,
+            samples: 10,
+            hit,
+        };
+        let predictions = [
+            prediction(0.9, true),
+            prediction(0.8, false),
+            prediction(0.1, false),
+            prediction(0.3, true),
+        ];
+
+        // (0.01 + 0.64 + 0.01 + 0.49) / 4; the base rate 0.5 scores 0.25
+        let (brier, reference) = brier_scores(&predictions);
+        assert!((brier - 0.2875).abs() < 1e-12);
+        assert!((reference - 0.25).abs() < 1e-12);
+
+        let curve = calibration_curve(&predictions, 2);
+        assert_eq!((curve[0].count, curve[1].count), (2, 2));
+        assert!((curve[0].mean_predicted - 0.2).abs() < 1e-12);
+        assert!((curve[1].observed_rate - 0.5).abs() < 1e-12);
+    }
+}
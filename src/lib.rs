@@ -32,6 +32,52 @@ pub struct Cli {
     /// Use API as primary source instead of the local cache
     #[arg(long, default_value_t = false)]
     pub prefer_api: bool,
+
+    /// Analyse candles from a CSV / Parquet file (or a directory of them) instead of Binance
+    #[arg(long, value_name = "PATH")]
+    pub import: Option<std::path::PathBuf>,
+
+    /// Column layout of the files given to --import
+    #[arg(long, value_enum, default_value_t = ImportPreset::Default)]
+    pub import_format: ImportPreset,
+
+    /// Drop pairs whose data-quality report is "bad" (see `config::DATA_QUALITY`)
+    #[arg(long, default_value_t = false)]
+    pub exclude_bad_data: bool,
+
+    /// Fetch recent aggTrades so the CVA uses exact volume-at-price (see `ANALYSIS.trades`)
+    #[arg(long, default_value_t = false)]
+    pub agg_trades: bool,
+
+    /// Append live prices and best bid/ask to this tick tape (see `data::tick_tape`)
+    #[arg(long, value_name = "FILE")]
+    pub record_ticks: Option<std::path::PathBuf>,
+
+    /// Play a recorded tick tape instead of streaming prices from Binance
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Speed for --replay: 1x (as recorded), 10x, ... or max
+    #[arg(long, default_value = "1x")]
+    pub replay_speed: data::tick_tape::ReplaySpeed,
+}
+
+/// Named column layouts for --import (see `config::TABULAR`)
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportPreset {
+    /// The columns `export_klines` writes (open_time_ms, open, ..., quote_volume)
+    Default,
+    /// Headerless Binance public data dumps (data.binance.vision)
+    BinanceVision,
+}
+
+impl ImportPreset {
+    pub fn format(self) -> config::TabularFormat {
+        match self {
+            ImportPreset::Default => config::TABULAR.import.default_format,
+            ImportPreset::BinanceVision => config::TABULAR.import.binance_vision_format,
+        }
+    }
 }
 
 /// Main application entry point - creates the GUI app
@@ -39,11 +85,24 @@ pub struct Cli {
 pub fn run_app(
     cc: &eframe::CreationContext,
     timeseries_data: TimeSeriesCollection,
+    price_stream: PriceStreamManager,
 ) -> Box<dyn eframe::App> {
 
     // 1. Initialize the Engine (The Brain)
-    let engine = crate::engine::SniperEngine::new(timeseries_data);
+    let engine = crate::engine::SniperEngine::with_price_stream(timeseries_data, price_stream);
 
     let app = ui::ZoneSniperApp::new(cc, engine);
     Box::new(app)
 }
+
+/// As `run_app`, with the pairs still loading: they arrive on `pair_loads`
+/// (see `data::pair_loading::spawn_pair_loading`) while the UI is already up
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_app_loading(
+    cc: &eframe::CreationContext,
+    pair_loads: std::sync::mpsc::Receiver<data::pair_loading::PairLoadEvent>,
+    price_stream: PriceStreamManager,
+) -> Box<dyn eframe::App> {
+    let engine = crate::engine::SniperEngine::loading(pair_loads, price_stream);
+    Box::new(ui::ZoneSniperApp::new(cc, engine))
+}
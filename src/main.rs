@@ -7,17 +7,18 @@ use clap::Parser;
 use eframe::NativeOptions;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
+use zone_sniper::config::BINANCE;
 #[cfg(not(target_arch = "wasm32"))]
-use tokio::runtime::Runtime;
+use zone_sniper::data::pair_loading::spawn_pair_loading;
 #[cfg(not(target_arch = "wasm32"))]
-use zone_sniper::config::ANALYSIS;
-use zone_sniper::config::BINANCE;
+use zone_sniper::data::tick_tape::{TickRecorder, TickTape, spawn_recording};
 #[cfg(not(target_arch = "wasm32"))]
-use zone_sniper::{config::PERSISTENCE, data::write_timeseries_data_async};
+use zone_sniper::{config::PERSISTENCE, run_app_loading};
 
 #[allow(unused_imports)]
 use zone_sniper::{
     Cli,                  // re-export lib.rs
+    PriceStreamManager,   // re-export from lib.rs
     TimeSeriesCollection, // re-export from lib.rs
     fetch_pair_data,      // The re-export from lib.rs
     run_app,              // The function from lib.rs
@@ -85,9 +86,20 @@ pub async fn start() -> Result<(), wasm_bindgen::JsValue> {
 
     // C. Load demo timeseries data for WASM using the bundled cache
     //    This calls into fetch_pair_data(), which under wasm uses WasmDemoData.
-    let args = Cli { prefer_api: false };
+    let args = Cli {
+        prefer_api: false,
+        import: None,
+        import_format: zone_sniper::ImportPreset::Default,
+        exclude_bad_data: false,
+        agg_trades: false,
+        record_ticks: None,
+        replay: None,
+        replay_speed: zone_sniper::data::tick_tape::ReplaySpeed::REAL_TIME,
+    };
     let (timeseries_data, timeseries_signature) =
-        fetch_pair_data(BINANCE.limits.kline_acceptable_age_sec, &args).await;
+        fetch_pair_data(BINANCE.limits.kline_acceptable_age_sec, &args)
+            .await
+            .map_err(|e| format!("Failed to load the demo data: {:#}", e))?;
 
     log::info!(
         "WASM startup loaded timeseries via provider: {} (series_count={})",
@@ -112,7 +124,7 @@ pub async fn start() -> Result<(), wasm_bindgen::JsValue> {
         .start(
             canvas,
             web_options,
-            Box::new(|cc| Ok(run_app(cc, timeseries_data))),
+            Box::new(|cc| Ok(run_app(cc, timeseries_data, PriceStreamManager::new()))),
         )
         .await
 }
@@ -149,26 +161,30 @@ fn main() -> eframe::Result {
     if PRINT_CLI {
         log::info!("Parsed arguments: {:?}", args);
     }
-    // C. Data Loading (Blocking)
-    let rt = Runtime::new().expect("Failed to create Tokio runtime");
-    let (timeseries_data, timeseries_signature) = rt.block_on(fetch_pair_data(
-        BINANCE.limits.kline_acceptable_age_sec,
-        &args,
-    ));
-
-    // D. Background Cache Write
-    let cache_data = timeseries_data.clone();
-    rt.spawn(async move {
-        if let Err(e) = write_timeseries_data_async(
-            timeseries_signature,
-            cache_data,
-            ANALYSIS.interval_width_ms,
-        )
-        .await
-        {
-            log::warn!("⚠️  Failed to write cache: {}", e);
+    // C. Price Source: a recorded tape, or Binance (optionally recorded)
+    let price_stream = match &args.replay {
+        Some(path) => match TickTape::load(path) {
+            Ok(tape) => PriceStreamManager::replaying(tape, args.replay_speed),
+            Err(e) => {
+                eprintln!("Cannot replay: {:#}", e);
+                std::process::exit(1);
+            }
+        },
+        None => PriceStreamManager::new(),
+    };
+    if let Some(path) = &args.record_ticks {
+        match TickRecorder::open(path) {
+            Ok(recorder) => {
+                spawn_recording(recorder, price_stream.subscribe_updates());
+                log::info!("Recording ticks to {}", path.display());
+            }
+            Err(e) => log::error!("Not recording ticks: {:#}", e),
         }
-    });
+    }
+
+    // D. Data Loading (in the background; pairs show up in the UI as they arrive, and
+    // the cache is written once they're all in)
+    let pair_loads = spawn_pair_loading(args, BINANCE.limits.kline_acceptable_age_sec);
 
     // E. Run Native App
     let options = NativeOptions {
@@ -179,6 +195,6 @@ fn main() -> eframe::Result {
     eframe::run_native(
         "Zone Sniper - Scope. Lock. Snipe.",
         options,
-        Box::new(move |cc| Ok(run_app(cc, timeseries_data))),
+        Box::new(move |cc| Ok(run_app_loading(cc, pair_loads, price_stream))),
     )
 }
@@ -25,6 +25,9 @@ pub struct CVACore {
     // Metadata fields required by pair_analysis.rs and ui_plot_view.rs
     pub start_timestamp_ms: i64,
     pub end_timestamp_ms: i64,
+    /// Open-time ranges (end exclusive) of the candle slices the histogram was built from
+    #[serde(default)]
+    pub slice_ranges_ms: Vec<(i64, i64)>,
     pub time_decay_factor: f64, 
 
 }
@@ -72,7 +75,6 @@ impl CVACore {
     }
 
     // Updated helper to use new enum variants
-    #[allow(dead_code)]
     pub fn increase_score_one_zone_weighted(&mut self, st: ScoreType, price: f64, weight: f64) {
         let range_copy = self.price_range.clone();
         let index = range_copy.chunk_index(price);
@@ -144,6 +146,7 @@ impl CVACore {
             total_candles,
             start_timestamp_ms: 0,
             end_timestamp_ms: 0,
+            slice_ranges_ms: Vec::new(),
             time_decay_factor,
         }
     }
@@ -0,0 +1,193 @@
+//! Which candles of an `OhlcvTimeSeries` were synthesized to fill a gap in the exchange's
+//! data rather than traded. Stored as sorted, non-overlapping runs because gaps are rare
+//! and clustered (exchange outages, delistings), so a 100k-candle series with a handful of
+//! outages costs a handful of entries instead of 100k flags.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::OhlcvTimeSeries;
+
+/// `len` consecutive filled candles starting at candle index `start`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GapRun {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl GapRun {
+    pub fn end(&self) -> usize {
+        self.start + self.len
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct GapMask {
+    runs: Vec<GapRun>,
+}
+
+impl GapMask {
+    /// Builds a mask from one flag per candle (true = filled).
+    pub fn from_flags(flags: impl IntoIterator<Item = bool>) -> Self {
+        let mut mask = GapMask::default();
+        for (idx, filled) in flags.into_iter().enumerate() {
+            if filled {
+                mask.push_run(idx, 1);
+            }
+        }
+        mask
+    }
+
+    /// Best guess for data stored before masks existed: a candle that is an exact copy of
+    /// the one before it is what `OhlcvTimeSeriesTemp`'s forward fill produces.
+    pub fn detect_forward_filled(series: &OhlcvTimeSeries) -> Self {
+        Self::from_flags((0..series.klines()).map(|idx| {
+            idx > 0
+                && series.open_prices[idx] == series.open_prices[idx - 1]
+                && series.high_prices[idx] == series.high_prices[idx - 1]
+                && series.low_prices[idx] == series.low_prices[idx - 1]
+                && series.close_prices[idx] == series.close_prices[idx - 1]
+                && series.base_asset_volumes[idx] == series.base_asset_volumes[idx - 1]
+                && series.quote_asset_volumes[idx] == series.quote_asset_volumes[idx - 1]
+        }))
+    }
+
+    /// Marks `start..start + len` as filled. Runs must be pushed in candle order;
+    /// a run touching the previous one is merged into it.
+    pub fn push_run(&mut self, start: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(last) if last.end() >= start => {
+                debug_assert!(last.start <= start, "GapMask runs pushed out of order");
+                last.len = last.len.max(start + len - last.start);
+            }
+            _ => self.runs.push(GapRun { start, len }),
+        }
+    }
+
+    pub fn is_filled(&self, idx: usize) -> bool {
+        // First run starting after idx; the one before it is the only candidate.
+        let after = self.runs.partition_point(|run| run.start <= idx);
+        after > 0 && idx < self.runs[after - 1].end()
+    }
+
+    pub fn filled_count(&self) -> usize {
+        self.runs.iter().map(|run| run.len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn runs(&self) -> &[GapRun] {
+        &self.runs
+    }
+
+    /// The part of the mask covering candles `start..end`, re-indexed to start at 0.
+    pub fn slice(&self, start: usize, end: usize) -> GapMask {
+        let mut out = GapMask::default();
+        for run in &self.runs {
+            let from = run.start.max(start);
+            let to = run.end().min(end);
+            if from < to {
+                out.push_run(from - start, to - from);
+            }
+        }
+        out
+    }
+
+    /// Appends `other`, whose candle 0 is candle `offset` of this mask's series.
+    pub fn append(&mut self, other: &GapMask, offset: usize) {
+        for run in &other.runs {
+            self.push_run(run.start + offset, run.len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(mask: &GapMask, candles: usize) -> Vec<usize> {
+        (0..candles).filter(|&idx| mask.is_filled(idx)).collect()
+    }
+
+    #[test]
+    fn flags_become_merged_runs() {
+        let mask = GapMask::from_flags([false, true, true, false, true, false, false, true]);
+        assert_eq!(
+            mask.runs(),
+            &[
+                GapRun { start: 1, len: 2 },
+                GapRun { start: 4, len: 1 },
+                GapRun { start: 7, len: 1 }
+            ]
+        );
+        assert_eq!(filled(&mask, 10), vec![1, 2, 4, 7]);
+        assert_eq!(mask.filled_count(), 4);
+        assert!(GapMask::from_flags([false; 5]).is_empty());
+    }
+
+    #[test]
+    fn touching_and_overlapping_runs_merge() {
+        let mut mask = GapMask::default();
+        mask.push_run(2, 3);
+        mask.push_run(5, 2); // touches 2..5
+        mask.push_run(6, 1); // inside 2..7
+        mask.push_run(9, 0); // empty, ignored
+        mask.push_run(10, 1);
+        assert_eq!(
+            mask.runs(),
+            &[GapRun { start: 2, len: 5 }, GapRun { start: 10, len: 1 }]
+        );
+        assert!(!mask.is_filled(1));
+        assert!(mask.is_filled(2));
+        assert!(mask.is_filled(6));
+        assert!(!mask.is_filled(7));
+        assert!(!mask.is_filled(11));
+    }
+
+    #[test]
+    fn slices_reindex_and_append_restores() {
+        let mask = GapMask::from_flags([true, false, true, true, true, false, true]);
+
+        let middle = mask.slice(3, 6);
+        assert_eq!(middle.runs(), &[GapRun { start: 0, len: 2 }]);
+        assert!(mask.slice(5, 6).is_empty());
+
+        // Splitting at any point and appending the halves gives the mask back
+        for split in 0..=7 {
+            let mut rebuilt = mask.slice(0, split);
+            rebuilt.append(&mask.slice(split, 7), split);
+            assert_eq!(rebuilt, mask, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn forward_filled_copies_are_detected() {
+        let mut series = OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            60_000,
+            0,
+            &[
+                (1.0, 2.0, 0.5, 1.5),
+                (1.0, 2.0, 0.5, 1.5),
+                (1.5, 2.5, 1.0, 2.0),
+                (1.5, 2.5, 1.0, 2.0),
+                (1.5, 2.5, 1.0, 2.0),
+            ],
+        );
+        assert_eq!(
+            GapMask::detect_forward_filled(&series).runs(),
+            &[GapRun { start: 1, len: 1 }, GapRun { start: 3, len: 2 }]
+        );
+
+        // Same prices but another volume traded: a real candle
+        series.base_asset_volumes[4] = 2.0;
+        assert_eq!(
+            GapMask::detect_forward_filled(&series).runs(),
+            &[GapRun { start: 1, len: 1 }, GapRun { start: 3, len: 1 }]
+        );
+    }
+}
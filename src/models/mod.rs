@@ -2,12 +2,20 @@
 // These modules contain pure business logic independent of UI/visualization
 
 pub mod cva;
+pub mod gap_mask;
 pub mod pair_context;
+pub mod resample;
 pub mod timeseries;
+pub mod trade_profile;
 pub mod trading_view;
 
 // Re-export key types for convenience
 pub use cva::CVACore;
+pub use gap_mask::{GapMask, GapRun};
 pub use pair_context::{PairContext, TradingSignal};
-pub use timeseries::{MostRecentIntervals, OhlcvTimeSeries, TimeSeriesSlice, find_matching_ohlcv};
+pub use resample::EdgeBuckets;
+pub use timeseries::{
+    IngestIssues, MostRecentIntervals, OhlcvTimeSeries, TimeSeriesSlice, find_matching_ohlcv,
+};
+pub use trade_profile::{PriceLevel, TradeProfile};
 pub use trading_view::{SuperZone, TradingModel, Zone, ZoneType};
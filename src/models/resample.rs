@@ -0,0 +1,327 @@
+//! Aggregating an `OhlcvTimeSeries` to a coarser interval (30m -> 1h, 4h, 1d, 1w, ...).
+//!
+//! Buckets are aligned the way Binance aligns its own klines: to the UTC epoch, except
+//! weeks, which start on Monday 00:00 UTC. Filled gap candles (see `GapMask`) count as
+//! flat candles at the previous close with no volume, so a gap never double-counts volume.
+//! An output candle is itself marked filled only if every candle in its bucket was.
+
+use anyhow::{Result, bail};
+use std::sync::Arc;
+
+use crate::domain::pair_interval::PairInterval;
+use crate::models::{GapMask, OhlcvTimeSeries};
+use crate::utils::TimeUtils;
+
+/// 1970-01-01 was a Thursday; Binance weeks start on Monday 1970-01-05.
+pub(crate) const WEEK_ORIGIN_MS: i64 = 4 * TimeUtils::MS_IN_D;
+
+/// What to do with the first / last bucket when the series starts or ends mid-bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeBuckets {
+    /// Keep them, aggregated from whatever candles they have (e.g. today's unfinished 1d candle)
+    Keep,
+    /// Drop them so every output candle covers a full bucket
+    Drop,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start_ms: i64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    base_volume: f64,
+    quote_volume: f64,
+    candles: i64,
+    filled: i64,
+}
+
+impl OhlcvTimeSeries {
+    /// Aggregates to `target_interval_ms`, which must be a whole multiple of this series'
+    /// interval. The result has the same pair and `pct_gaps`, and the new interval.
+    pub fn resample(&self, target_interval_ms: i64, edges: EdgeBuckets) -> Result<OhlcvTimeSeries> {
+        let source_ms = self.pair_interval.interval_ms;
+        if target_interval_ms == source_ms {
+            return Ok(self.clone());
+        }
+        if target_interval_ms < source_ms || target_interval_ms % source_ms != 0 {
+            bail!(
+                "Can't resample {}ms candles to {}ms: the target must be a whole multiple of the source interval",
+                source_ms,
+                target_interval_ms
+            );
+        }
+        if target_interval_ms == TimeUtils::MS_IN_1_M {
+            bail!("Calendar months aren't fixed-width buckets; resample to 1w or 1d instead");
+        }
+
+        let origin_ms = if target_interval_ms % TimeUtils::MS_IN_W == 0 {
+            WEEK_ORIGIN_MS
+        } else {
+            0
+        };
+        let bucket_start = |ts: i64| ts - (ts - origin_ms).rem_euclid(target_interval_ms);
+        if (bucket_start(self.first_kline_timestamp_ms) - self.first_kline_timestamp_ms) % source_ms
+            != 0
+        {
+            bail!(
+                "{}: candles don't line up with {} bucket boundaries",
+                self.pair_interval,
+                TimeUtils::interval_to_string(target_interval_ms)
+            );
+        }
+
+        let mut buckets: Vec<Bucket> = Vec::new();
+        for idx in 0..self.klines() {
+            let ts = self.first_kline_timestamp_ms + idx as i64 * source_ms;
+            let filled = self.is_filled(idx);
+            let (open, high, low, close, base_volume, quote_volume) = if filled && idx > 0 {
+                let prev_close = self.close_prices[idx - 1];
+                (prev_close, prev_close, prev_close, prev_close, 0.0, 0.0)
+            } else {
+                (
+                    self.open_prices[idx],
+                    self.high_prices[idx],
+                    self.low_prices[idx],
+                    self.close_prices[idx],
+                    self.base_asset_volumes[idx],
+                    self.quote_asset_volumes[idx],
+                )
+            };
+
+            let start_ms = bucket_start(ts);
+            match buckets.last_mut() {
+                Some(bucket) if bucket.start_ms == start_ms => {
+                    bucket.high = bucket.high.max(high);
+                    bucket.low = bucket.low.min(low);
+                    bucket.close = close;
+                    bucket.base_volume += base_volume;
+                    bucket.quote_volume += quote_volume;
+                    bucket.candles += 1;
+                    bucket.filled += filled as i64;
+                }
+                _ => buckets.push(Bucket {
+                    start_ms,
+                    open,
+                    high,
+                    low,
+                    close,
+                    base_volume,
+                    quote_volume,
+                    candles: 1,
+                    filled: filled as i64,
+                }),
+            }
+        }
+
+        if edges == EdgeBuckets::Drop {
+            let full = target_interval_ms / source_ms;
+            if buckets.last().is_some_and(|b| b.candles < full) {
+                buckets.pop();
+            }
+            if buckets.first().is_some_and(|b| b.candles < full) {
+                buckets.remove(0);
+            }
+        }
+        if buckets.is_empty() {
+            bail!(
+                "{}: not enough candles for a single full {} bucket",
+                self.pair_interval,
+                TimeUtils::interval_to_string(target_interval_ms)
+            );
+        }
+
+        Ok(OhlcvTimeSeries {
+            pair_interval: PairInterval {
+                name: self.pair_interval.name.clone(),
+                interval_ms: target_interval_ms,
+            },
+            first_kline_timestamp_ms: buckets[0].start_ms,
+            open_prices: buckets.iter().map(|b| b.open).collect(),
+            high_prices: buckets.iter().map(|b| b.high).collect(),
+            low_prices: buckets.iter().map(|b| b.low).collect(),
+            close_prices: buckets.iter().map(|b| b.close).collect(),
+            base_asset_volumes: buckets.iter().map(|b| b.base_volume).collect(),
+            quote_asset_volumes: buckets.iter().map(|b| b.quote_volume).collect(),
+            pct_gaps: self.pct_gaps,
+            gaps: GapMask::from_flags(buckets.iter().map(|b| b.filled == b.candles)),
+            ingest: self.ingest,
+            trades: self
+                .trades
+                .as_ref()
+                .map(|profile| Arc::new(profile.resample(target_interval_ms))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GapRun;
+
+    // 2025-01-06 00:00 UTC, a Monday
+    const MONDAY_MS: i64 = 1_736_121_600_000;
+
+    /// Six 30m candles from Monday 00:30, so both 1h edge buckets hold a single candle.
+    /// Base volume is 1 per candle, quote volume the close.
+    fn half_hours() -> OhlcvTimeSeries {
+        OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            TimeUtils::MS_IN_30_MIN,
+            MONDAY_MS + TimeUtils::MS_IN_30_MIN,
+            &[
+                (10.0, 12.0, 9.0, 11.0),
+                (11.0, 15.0, 10.0, 14.0),
+                (14.0, 16.0, 13.0, 13.0),
+                (13.0, 14.0, 8.0, 9.0),
+                (9.0, 10.0, 7.0, 10.0),
+                (10.0, 11.0, 9.0, 10.5),
+            ],
+        )
+    }
+
+    #[test]
+    fn partial_edge_buckets_are_kept_or_dropped() {
+        let kept = half_hours()
+            .resample(TimeUtils::MS_IN_H, EdgeBuckets::Keep)
+            .unwrap();
+        assert_eq!(kept.pair_interval.interval_ms, TimeUtils::MS_IN_H);
+        assert_eq!(kept.first_kline_timestamp_ms, MONDAY_MS);
+        assert_eq!(kept.open_prices, vec![10.0, 11.0, 13.0, 10.0]);
+        assert_eq!(kept.high_prices, vec![12.0, 16.0, 14.0, 11.0]);
+        assert_eq!(kept.low_prices, vec![9.0, 10.0, 7.0, 9.0]);
+        assert_eq!(kept.close_prices, vec![11.0, 13.0, 10.0, 10.5]);
+        assert_eq!(kept.base_asset_volumes, vec![1.0, 2.0, 2.0, 1.0]);
+        assert_eq!(kept.quote_asset_volumes, vec![11.0, 27.0, 19.0, 10.5]);
+        assert!(kept.gaps.is_empty());
+
+        let dropped = half_hours()
+            .resample(TimeUtils::MS_IN_H, EdgeBuckets::Drop)
+            .unwrap();
+        assert_eq!(
+            dropped.first_kline_timestamp_ms,
+            MONDAY_MS + TimeUtils::MS_IN_H
+        );
+        assert_eq!(dropped.open_prices, kept.open_prices[1..3]);
+        assert_eq!(dropped.high_prices, kept.high_prices[1..3]);
+        assert_eq!(dropped.low_prices, kept.low_prices[1..3]);
+        assert_eq!(dropped.close_prices, kept.close_prices[1..3]);
+        assert_eq!(dropped.base_asset_volumes, kept.base_asset_volumes[1..3]);
+        assert_eq!(dropped.quote_asset_volumes, kept.quote_asset_volumes[1..3]);
+    }
+
+    #[test]
+    fn filled_candles_add_no_volume_and_mark_all_filled_buckets() {
+        // Candles 2 and 3 forward-fill candle 1
+        let mut series = OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            TimeUtils::MS_IN_30_MIN,
+            MONDAY_MS,
+            &[
+                (10.0, 12.0, 9.0, 11.0),
+                (11.0, 13.0, 10.0, 12.0),
+                (11.0, 13.0, 10.0, 12.0),
+                (11.0, 13.0, 10.0, 12.0),
+            ],
+        );
+        series.gaps = GapMask::from_flags([false, false, true, true]);
+
+        let hourly = series
+            .resample(TimeUtils::MS_IN_H, EdgeBuckets::Keep)
+            .unwrap();
+        assert_eq!(hourly.open_prices, vec![10.0, 12.0]);
+        assert_eq!(hourly.high_prices, vec![13.0, 12.0]);
+        assert_eq!(hourly.low_prices, vec![9.0, 12.0]);
+        assert_eq!(hourly.close_prices, vec![12.0, 12.0]);
+        assert_eq!(hourly.base_asset_volumes, vec![2.0, 0.0]);
+        assert_eq!(hourly.quote_asset_volumes, vec![23.0, 0.0]);
+        assert_eq!(hourly.gaps.runs(), &[GapRun { start: 1, len: 1 }]);
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // Saturday, Sunday, then a full Monday-to-Sunday week
+        let candles: Vec<(f64, f64, f64, f64)> = (0..9)
+            .map(|day| {
+                let price = 100.0 + day as f64;
+                (price, price + 1.0, price - 1.0, price)
+            })
+            .collect();
+        let daily = OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            TimeUtils::MS_IN_D,
+            MONDAY_MS - 2 * TimeUtils::MS_IN_D,
+            &candles,
+        );
+
+        let kept = daily
+            .resample(TimeUtils::MS_IN_W, EdgeBuckets::Keep)
+            .unwrap();
+        assert_eq!(
+            kept.first_kline_timestamp_ms,
+            MONDAY_MS - TimeUtils::MS_IN_W
+        );
+        assert_eq!(kept.base_asset_volumes, vec![2.0, 7.0]);
+        assert_eq!(kept.open_prices, vec![100.0, 102.0]);
+        assert_eq!(kept.close_prices, vec![101.0, 108.0]);
+        assert_eq!(kept.high_prices, vec![102.0, 109.0]);
+        assert_eq!(kept.low_prices, vec![99.0, 101.0]);
+
+        let dropped = daily
+            .resample(TimeUtils::MS_IN_W, EdgeBuckets::Drop)
+            .unwrap();
+        assert_eq!(dropped.first_kline_timestamp_ms, MONDAY_MS);
+        assert_eq!(dropped.klines(), 1);
+    }
+
+    #[test]
+    fn impossible_targets_are_refused() {
+        let series = half_hours();
+        assert!(
+            series
+                .resample(TimeUtils::MS_IN_15_MIN, EdgeBuckets::Keep)
+                .is_err()
+        );
+        assert!(
+            series
+                .resample(45 * TimeUtils::MS_IN_MIN, EdgeBuckets::Keep)
+                .is_err()
+        );
+        assert!(
+            series
+                .resample(TimeUtils::MS_IN_1_M, EdgeBuckets::Keep)
+                .is_err()
+        );
+
+        let off_grid = OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            TimeUtils::MS_IN_30_MIN,
+            MONDAY_MS + TimeUtils::MS_IN_15_MIN,
+            &[(1.0, 1.0, 1.0, 1.0); 4],
+        );
+        assert!(
+            off_grid
+                .resample(TimeUtils::MS_IN_H, EdgeBuckets::Keep)
+                .is_err()
+        );
+
+        // One lone half hour: nothing left once the partial bucket goes
+        let lone = OhlcvTimeSeries::from_test_candles(
+            "BTCUSDT",
+            TimeUtils::MS_IN_30_MIN,
+            MONDAY_MS,
+            &[(1.0, 1.0, 1.0, 1.0)],
+        );
+        assert!(
+            lone.resample(TimeUtils::MS_IN_H, EdgeBuckets::Drop)
+                .is_err()
+        );
+        assert_eq!(
+            lone.resample(TimeUtils::MS_IN_H, EdgeBuckets::Keep)
+                .unwrap()
+                .klines(),
+            1
+        );
+    }
+}
@@ -4,12 +4,15 @@ use chrono::{DateTime, Duration, Utc};
 use crate::domain::candle::Candle;
 use crate::domain::pair_interval::PairInterval;
 use crate::models::cva::{CVACore, ScoreType};
+use crate::models::gap_mask::GapMask;
+use crate::models::trade_profile::{PriceLevel, TradeProfile};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::data::timeseries::bnapi_version::OhlcvTimeSeriesTemp;
 #[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 // ============================================================================
 // OhlcvTimeSeries: Raw time series data for a trading pair
@@ -32,6 +35,27 @@ pub struct OhlcvTimeSeries {
 
     // Stats
     pub pct_gaps: f64,
+    /// Candles forward-filled over gaps in the exchange data (not real trading)
+    pub gaps: GapMask,
+    /// What building this series from raw klines had to paper over. Not in the
+    /// serialized series: the chunked cache keeps it per pair in its manifest.
+    #[serde(skip)]
+    pub ingest: IngestIssues,
+    /// Exact volume-at-price for the most recent candles, when aggTrades were loaded.
+    /// Shared, since walk-forward and resampling copy the series many times.
+    #[serde(skip)]
+    pub trades: Option<Arc<TradeProfile>>,
+}
+
+/// Raw kline problems that were resolved while building an `OhlcvTimeSeries`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestIssues {
+    /// Klines sharing an open time with an earlier one (the later one wins)
+    pub duplicate_timestamps: usize,
+    /// Klines whose open time is off the interval grid (dropped)
+    pub misaligned_timestamps: usize,
+    /// Leading candles cut off because the history had too many gaps to fill
+    pub truncated_candles: usize,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -58,12 +82,15 @@ impl From<OhlcvTimeSeriesTemp> for OhlcvTimeSeries {
             pair_interval: old_struct.pair_interval,
             first_kline_timestamp_ms: old_struct.first_kline_timestamp_ms,
             pct_gaps: old_struct.pct_gaps.unwrap_or(0.0),
+            gaps: old_struct.gaps,
+            ingest: old_struct.ingest,
+            trades: None,
         }
     }
 }
 
 pub fn find_matching_ohlcv<'a>(
-    timeseries_data: &'a [OhlcvTimeSeries],
+    timeseries_data: &'a [Arc<OhlcvTimeSeries>],
     pair_name: &str,
     interval_ms: i64,
 ) -> Result<&'a OhlcvTimeSeries> {
@@ -72,6 +99,7 @@ pub fn find_matching_ohlcv<'a>(
         .find(|ohlcv| {
             ohlcv.pair_interval.name == pair_name && ohlcv.pair_interval.interval_ms == interval_ms
         })
+        .map(|ohlcv| ohlcv.as_ref())
         .ok_or_else(|| {
             anyhow!(
                 "No matching OHLCV data found for pair {} with interval {} ms",
@@ -135,6 +163,84 @@ impl OhlcvTimeSeries {
         Some((start_index, end_index))
     }
 
+    /// Copy of this series holding only candles `0..end_idx` (walk-forward cutoffs).
+    pub fn truncated(&self, end_idx: usize) -> OhlcvTimeSeries {
+        self.sub_series(0, end_idx)
+    }
+
+    /// Copy of candles `start_idx..end_idx`, with the start timestamp moved to match.
+    pub fn sub_series(&self, start_idx: usize, end_idx: usize) -> OhlcvTimeSeries {
+        let end_idx = end_idx.min(self.klines());
+        let start_idx = start_idx.min(end_idx);
+        OhlcvTimeSeries {
+            pair_interval: self.pair_interval.clone(),
+            first_kline_timestamp_ms: self.first_kline_timestamp_ms
+                + start_idx as i64 * self.pair_interval.interval_ms,
+            open_prices: self.open_prices[start_idx..end_idx].to_vec(),
+            high_prices: self.high_prices[start_idx..end_idx].to_vec(),
+            low_prices: self.low_prices[start_idx..end_idx].to_vec(),
+            close_prices: self.close_prices[start_idx..end_idx].to_vec(),
+            base_asset_volumes: self.base_asset_volumes[start_idx..end_idx].to_vec(),
+            quote_asset_volumes: self.quote_asset_volumes[start_idx..end_idx].to_vec(),
+            pct_gaps: self.pct_gaps,
+            gaps: self.gaps.slice(start_idx, end_idx),
+            ingest: self.ingest,
+            trades: self.trades.clone(),
+        }
+    }
+
+    /// Whether `append_candle` would add the candle opening at `open_time_ms`: it is
+    /// after the current last one and on the interval grid.
+    pub fn accepts_candle_at(&self, open_time_ms: i64) -> bool {
+        if self.klines() == 0 {
+            return true;
+        }
+        let next_open_ms = self.last_kline_timestamp_ms() + self.pair_interval.interval_ms;
+        open_time_ms >= next_open_ms
+            && (open_time_ms - next_open_ms) % self.pair_interval.interval_ms == 0
+    }
+
+    /// Appends the candle opening at `open_time_ms` (a closed live kline). Missed candles
+    /// in between are forward-filled and marked in `gaps`, as at load time. Candles at or
+    /// before the current last one, or off the interval grid, are ignored.
+    /// Returns how many candles were added, fills included.
+    pub fn append_candle(&mut self, open_time_ms: i64, candle: &Candle) -> usize {
+        if !self.accepts_candle_at(open_time_ms) {
+            return 0;
+        }
+        let interval_ms = self.pair_interval.interval_ms;
+        let mut missed = 0;
+        if self.klines() == 0 {
+            self.first_kline_timestamp_ms = open_time_ms;
+        } else {
+            let next_open_ms = self.last_kline_timestamp_ms() + interval_ms;
+            missed = ((open_time_ms - next_open_ms) / interval_ms) as usize;
+            self.gaps.push_run(self.klines(), missed);
+            let last = self.klines() - 1;
+            for _ in 0..missed {
+                self.open_prices.push(self.open_prices[last]);
+                self.high_prices.push(self.high_prices[last]);
+                self.low_prices.push(self.low_prices[last]);
+                self.close_prices.push(self.close_prices[last]);
+                self.base_asset_volumes.push(self.base_asset_volumes[last]);
+                self.quote_asset_volumes
+                    .push(self.quote_asset_volumes[last]);
+            }
+        }
+        self.open_prices.push(candle.open_price);
+        self.high_prices.push(candle.high_price);
+        self.low_prices.push(candle.low_price);
+        self.close_prices.push(candle.close_price);
+        self.base_asset_volumes.push(candle.base_volume);
+        self.quote_asset_volumes.push(candle.quote_volume);
+        missed + 1
+    }
+
+    /// True if candle `idx` was forward-filled over a gap rather than traded.
+    pub fn is_filled(&self, idx: usize) -> bool {
+        self.gaps.is_filled(idx)
+    }
+
     pub fn get_all_indices(&self) -> (usize, usize) {
         (0, self.open_prices.len())
     }
@@ -178,13 +284,18 @@ pub struct TimeSeriesSlice<'a> {
 }
 
 impl TimeSeriesSlice<'_> {
-    /// Generate CVA results from this time slice (potentially discontinuous ranges)
+    /// Generate CVA results from this time slice (potentially discontinuous ranges).
+    /// Candles filled over gaps count `filled_candle_weight` times as much as traded ones.
+    /// Where the series has a `TradeProfile`, `exact_volume_weight` (0.0 to 1.0) of each
+    /// candle's volume goes to the prices it actually traded at instead of being spread.
     pub fn generate_cva_results(
         &self,
         n_chunks: usize,
         pair_name: String,
         time_decay_factor: f64,
         price_range: (f64, f64), // User-defined price range
+        filled_candle_weight: f64,
+        exact_volume_weight: f64,
     ) -> CVACore {
         let (min_price, max_price) = price_range;
 
@@ -204,7 +315,26 @@ impl TimeSeriesSlice<'_> {
         let mut position = 0;
         for (start_idx, end_idx) in &self.ranges {
             for idx in *start_idx..*end_idx {
+                let fill_weight = if self.series_data.is_filled(idx) {
+                    filled_candle_weight
+                } else {
+                    1.0
+                };
+                if fill_weight <= 0.0 {
+                    // Still advances `position`: decay follows time, not traded candles
+                    position += 1;
+                    continue;
+                }
                 let candle = self.series_data.get_candle(idx);
+                let traded_levels = match &self.series_data.trades {
+                    Some(profile) if exact_volume_weight > 0.0 && fill_weight == 1.0 => profile
+                        .levels(
+                            self.series_data.first_kline_timestamp_ms
+                                + idx as i64 * self.series_data.pair_interval.interval_ms,
+                        )
+                        .map(|levels| (levels, exact_volume_weight.min(1.0))),
+                    _ => None,
+                };
 
                 // Exponential temporal decay based on position within relevant candles
                 let progress = if total_candles > 1 {
@@ -236,7 +366,13 @@ impl TimeSeriesSlice<'_> {
                 //         temporal_weight, time_decay_factor
                 //     )
                 // );
-                self.process_candle_scores(&mut cva_core, &candle, temporal_weight);
+                self.process_candle_scores(
+                    &mut cva_core,
+                    &candle,
+                    temporal_weight,
+                    fill_weight,
+                    traded_levels,
+                );
                 position += 1;
             }
         }
@@ -244,10 +380,25 @@ impl TimeSeriesSlice<'_> {
         cva_core
     }
 
-    fn process_candle_scores(&self, cva_core: &mut CVACore, candle: &Candle, temporal_weight: f64) {
+    fn process_candle_scores(
+        &self,
+        cva_core: &mut CVACore,
+        candle: &Candle,
+        temporal_weight: f64,
+        fill_weight: f64,
+        traded_levels: Option<(&[PriceLevel], f64)>,
+    ) {
         let (price_min, price_max) = cva_core.price_range.min_max();
+        // Filled gap candles are discounted on every score, including the undecayed one
+        let temporal_weight = temporal_weight * fill_weight;
         let clamp = |price: f64| price.max(price_min).min(price_max);
 
+        // 0. EXACT VOLUME (aggTrades) - the share of the volume we know the prices of
+        let exact_share = traded_levels.map_or(0.0, |(levels, share)| {
+            Self::add_traded_levels(cva_core, candle, levels, share, temporal_weight, clamp)
+        });
+        let spread_share = 1.0 - exact_share;
+
         // 1. FULL CANDLE (Sticky Zones) - Keep Volume Weighting
         let candle_low = clamp(candle.low_price);
         let candle_high = clamp(candle.high_price);
@@ -255,7 +406,7 @@ impl TimeSeriesSlice<'_> {
             ScoreType::FullCandleTVW,
             candle_low,
             candle_high,
-            candle.base_volume * temporal_weight,
+            candle.base_volume * temporal_weight * spread_share,
         );
 
         // 2. LOW WICK (Reversal Support) - Count Only (Volume Removed)
@@ -286,9 +437,45 @@ impl TimeSeriesSlice<'_> {
             ScoreType::QuoteVolume,
             candle_low,
             candle_high,
-            candle.quote_volume,
+            candle.quote_volume * fill_weight * spread_share,
         );
     }
+
+    /// Adds `share` of the candle's volume at the prices it traded at. The levels are
+    /// rescaled to the candle's own volume, so trades only decide where it goes, not how
+    /// much there is (the profile can be a few trades ahead of or behind the kline).
+    /// Returns the share placed, which is 0.0 if the levels hold no volume.
+    fn add_traded_levels(
+        cva_core: &mut CVACore,
+        candle: &Candle,
+        levels: &[PriceLevel],
+        share: f64,
+        temporal_weight: f64,
+        clamp: impl Fn(f64) -> f64,
+    ) -> f64 {
+        let traded_base: f64 = levels.iter().map(|l| l.base_volume).sum();
+        let traded_quote: f64 = levels.iter().map(|l| l.price * l.base_volume).sum();
+        if traded_base <= 0.0 || traded_quote <= 0.0 {
+            return 0.0;
+        }
+        let base_scale = candle.base_volume / traded_base;
+        let quote_scale = candle.quote_volume / traded_quote;
+
+        for level in levels {
+            let price = clamp(level.price);
+            cva_core.increase_score_one_zone_weighted(
+                ScoreType::FullCandleTVW,
+                price,
+                level.base_volume * base_scale * share * temporal_weight,
+            );
+            cva_core.increase_score_one_zone_weighted(
+                ScoreType::QuoteVolume,
+                price,
+                level.price * level.base_volume * quote_scale * share, // Undecayed, like the spread
+            );
+        }
+        share
+    }
 }
 
 // ============================================================================
@@ -325,3 +512,116 @@ impl From<DateTime<Utc>> for DateTimeInput {
         DateTimeInput::ChronoDateTime(dt)
     }
 }
+
+#[cfg(test)]
+impl OhlcvTimeSeries {
+    /// Gap-free series of `(open, high, low, close)` candles with unit volumes, for tests.
+    pub(crate) fn from_test_candles(
+        name: &str,
+        interval_ms: i64,
+        first_kline_timestamp_ms: i64,
+        candles: &[(f64, f64, f64, f64)],
+    ) -> Self {
+        OhlcvTimeSeries {
+            pair_interval: PairInterval {
+                name: name.to_string(),
+                interval_ms,
+            },
+            first_kline_timestamp_ms,
+            open_prices: candles.iter().map(|c| c.0).collect(),
+            high_prices: candles.iter().map(|c| c.1).collect(),
+            low_prices: candles.iter().map(|c| c.2).collect(),
+            close_prices: candles.iter().map(|c| c.3).collect(),
+            base_asset_volumes: vec![1.0; candles.len()],
+            quote_asset_volumes: candles.iter().map(|c| c.3).collect(),
+            pct_gaps: 0.0,
+            gaps: GapMask::default(),
+            ingest: IngestIssues::default(),
+            trades: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GapMask;
+
+    /// Total `QuoteVolume` score over four identical candles, two of them filled
+    fn quote_volume_score(filled_candle_weight: f64) -> f64 {
+        let mut series =
+            OhlcvTimeSeries::from_test_candles("BTCUSDT", 60_000, 0, &[(10.0, 12.0, 8.0, 11.0); 4]);
+        series.gaps = GapMask::from_flags([false, true, false, true]);
+        let slice = TimeSeriesSlice {
+            series_data: &series,
+            ranges: vec![(0, 4)],
+        };
+        let cva = slice.generate_cva_results(
+            4,
+            "BTCUSDT".to_string(),
+            1.0,
+            (8.0, 12.0),
+            filled_candle_weight,
+            0.0,
+        );
+        cva.get_scores_ref(ScoreType::QuoteVolume).iter().sum()
+    }
+
+    #[test]
+    fn filled_candles_are_weighted_in_the_cva() {
+        // Quote volume is the close, 11 per candle
+        assert!((quote_volume_score(1.0) - 44.0).abs() < 1e-9);
+        assert!((quote_volume_score(0.5) - 33.0).abs() < 1e-9);
+        assert!((quote_volume_score(0.0) - 22.0).abs() < 1e-9);
+    }
+
+    /// Scores of one (10, 12, 8, 11) candle, base volume 1, over four zones of 8..12,
+    /// with 3 + 1 base traded at 9.5 and 11.5
+    fn exact_volume_scores(exact_volume_weight: f64) -> (Vec<f64>, Vec<f64>) {
+        let mut series =
+            OhlcvTimeSeries::from_test_candles("BTCUSDT", 60_000, 0, &[(10.0, 12.0, 8.0, 11.0)]);
+        let mut profile = TradeProfile::new("BTCUSDT".to_string(), 60_000, 0);
+        profile.add_trade(1, 10, 9.5, 3.0);
+        profile.add_trade(2, 20, 11.5, 1.0);
+        series.trades = Some(std::sync::Arc::new(profile));
+        let slice = TimeSeriesSlice {
+            series_data: &series,
+            ranges: vec![(0, 1)],
+        };
+        let cva = slice.generate_cva_results(
+            4,
+            "BTCUSDT".to_string(),
+            1.0,
+            (8.0, 12.0),
+            1.0,
+            exact_volume_weight,
+        );
+        (
+            cva.get_scores_ref(ScoreType::FullCandleTVW).clone(),
+            cva.get_scores_ref(ScoreType::QuoteVolume).clone(),
+        )
+    }
+
+    fn assert_scores(actual: &[f64], expected: [f64; 4]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn traded_levels_are_rescaled_to_the_candles_volume() {
+        // Four base traded against a kline volume of one: only the split is kept
+        let (bodies, quote) = exact_volume_scores(1.0);
+        assert_scores(&bodies, [0.0, 0.75, 0.0, 0.25]);
+        // Quote volume 11 (the close), split by 28.5 vs 11.5 traded at each price
+        assert_scores(&quote, [0.0, 28.5 * 11.0 / 40.0, 0.0, 11.5 * 11.0 / 40.0]);
+
+        // Half placed at the traded prices, half spread over the range
+        let (bodies, quote) = exact_volume_scores(0.5);
+        assert_scores(&bodies, [0.125, 0.5, 0.125, 0.25]);
+        assert!((quote.iter().sum::<f64>() - 11.0).abs() < 1e-9);
+
+        let (bodies, _) = exact_volume_scores(0.0);
+        assert_scores(&bodies, [0.25; 4]);
+    }
+}
@@ -0,0 +1,256 @@
+//! Exact volume-at-price from Binance aggregated trades, for the most recent candles.
+//!
+//! Kline-based CVA has to guess where inside a candle's range its volume traded, so it
+//! spreads it evenly from low to high. A `TradeProfile` keeps the real per-price volume of
+//! each candle instead. It is stored at exchange price precision rather than on a zone grid,
+//! so the CVA can bin it onto whatever `RangeF64` the price horizon picks.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::resample::WEEK_ORIGIN_MS;
+use crate::utils::TimeUtils;
+
+/// Base-asset volume traded at one exact price
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub price: f64,
+    pub base_volume: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeProfile {
+    pub pair_name: String,
+    pub interval_ms: i64,
+    /// Candles opening before this hold partial trades (or none) and are never used
+    pub complete_from_ms: i64,
+    /// Id and time of the newest trade added, so updates resume where the last fetch stopped
+    pub last_trade_id: Option<i64>,
+    pub last_trade_ms: i64,
+    /// Candle open time -> levels traded in that candle, sorted by price
+    candles: BTreeMap<i64, Vec<PriceLevel>>,
+}
+
+impl TradeProfile {
+    /// An empty profile whose first complete candle is the first one opening at or after
+    /// `start_ms`. Trades are to be fetched from that candle's open.
+    pub fn new(pair_name: String, interval_ms: i64, start_ms: i64) -> Self {
+        let open_ms = candle_open_ms(start_ms, interval_ms);
+        let complete_from_ms = if open_ms == start_ms {
+            open_ms
+        } else {
+            open_ms + interval_ms
+        };
+        TradeProfile {
+            pair_name,
+            interval_ms,
+            complete_from_ms,
+            last_trade_id: None,
+            last_trade_ms: complete_from_ms,
+            candles: BTreeMap::new(),
+        }
+    }
+
+    /// Adds one aggregated trade. Trades must arrive in id order.
+    pub fn add_trade(&mut self, id: i64, timestamp_ms: i64, price: f64, quantity: f64) {
+        if self.last_trade_id.is_some_and(|last| id <= last) {
+            return;
+        }
+        self.last_trade_id = Some(id);
+        self.last_trade_ms = self.last_trade_ms.max(timestamp_ms);
+        if timestamp_ms < self.complete_from_ms || quantity <= 0.0 {
+            return;
+        }
+        let open_ms = candle_open_ms(timestamp_ms, self.interval_ms);
+        add_level(self.candles.entry(open_ms).or_default(), price, quantity);
+    }
+
+    /// Levels traded in the candle opening at `open_ms`, if the profile covers all of it.
+    pub fn levels(&self, open_ms: i64) -> Option<&[PriceLevel]> {
+        if open_ms < self.complete_from_ms {
+            return None;
+        }
+        self.candles.get(&open_ms).map(Vec::as_slice)
+    }
+
+    /// Forgets candles opening before `cutoff_ms`; they count as uncovered from then on.
+    pub fn prune_before(&mut self, cutoff_ms: i64) {
+        let cutoff_ms = candle_open_ms(cutoff_ms, self.interval_ms);
+        if cutoff_ms <= self.complete_from_ms {
+            return;
+        }
+        self.candles = self.candles.split_off(&cutoff_ms);
+        self.complete_from_ms = cutoff_ms;
+    }
+
+    pub fn candle_count(&self) -> usize {
+        self.candles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candles.is_empty()
+    }
+
+    /// The same trades keyed by `target_interval_ms` candles, aligned like `resample`.
+    /// A coarse candle that starts before `complete_from_ms` is only partly covered, so
+    /// coverage moves up to the next coarse boundary.
+    pub fn resample(&self, target_interval_ms: i64) -> TradeProfile {
+        let origin_ms = if target_interval_ms % TimeUtils::MS_IN_W == 0 {
+            WEEK_ORIGIN_MS
+        } else {
+            0
+        };
+        let bucket_start = |ts: i64| ts - (ts - origin_ms).rem_euclid(target_interval_ms);
+        let first_bucket = bucket_start(self.complete_from_ms);
+        let complete_from_ms = if first_bucket == self.complete_from_ms {
+            first_bucket
+        } else {
+            first_bucket + target_interval_ms
+        };
+
+        let mut candles: BTreeMap<i64, Vec<PriceLevel>> = BTreeMap::new();
+        for (open_ms, levels) in self.candles.range(complete_from_ms..) {
+            let bucket = candles.entry(bucket_start(*open_ms)).or_default();
+            for level in levels {
+                add_level(bucket, level.price, level.base_volume);
+            }
+        }
+
+        TradeProfile {
+            pair_name: self.pair_name.clone(),
+            interval_ms: target_interval_ms,
+            complete_from_ms,
+            last_trade_id: self.last_trade_id,
+            last_trade_ms: self.last_trade_ms,
+            candles,
+        }
+    }
+}
+
+fn candle_open_ms(timestamp_ms: i64, interval_ms: i64) -> i64 {
+    timestamp_ms - timestamp_ms.rem_euclid(interval_ms)
+}
+
+fn add_level(levels: &mut Vec<PriceLevel>, price: f64, base_volume: f64) {
+    match levels.binary_search_by(|level| level.price.total_cmp(&price)) {
+        Ok(i) => levels[i].base_volume += base_volume,
+        Err(i) => levels.insert(i, PriceLevel { price, base_volume }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M30: i64 = TimeUtils::MS_IN_30_MIN;
+    const H1: i64 = TimeUtils::MS_IN_H;
+    /// A Monday, 00:00 UTC
+    const MONDAY_MS: i64 = WEEK_ORIGIN_MS + 2_800 * TimeUtils::MS_IN_W;
+
+    fn level(price: f64, base_volume: f64) -> PriceLevel {
+        PriceLevel { price, base_volume }
+    }
+
+    #[test]
+    fn coverage_starts_at_the_first_whole_candle() {
+        let on_boundary = TradeProfile::new("BTCUSDT".to_string(), M30, MONDAY_MS);
+        assert_eq!(on_boundary.complete_from_ms, MONDAY_MS);
+        let mid_candle = TradeProfile::new("BTCUSDT".to_string(), M30, MONDAY_MS + 1);
+        assert_eq!(mid_candle.complete_from_ms, MONDAY_MS + M30);
+        assert_eq!(mid_candle.last_trade_ms, MONDAY_MS + M30);
+        assert!(mid_candle.is_empty());
+    }
+
+    #[test]
+    fn trades_merge_by_price_and_replayed_ids_are_skipped() {
+        let mut profile = TradeProfile::new("BTCUSDT".to_string(), M30, MONDAY_MS);
+        profile.add_trade(1, MONDAY_MS + 10, 101.0, 1.0);
+        profile.add_trade(2, MONDAY_MS + 20, 100.0, 2.0);
+        profile.add_trade(3, MONDAY_MS + 30, 101.0, 0.5);
+        // Already added: a fetch that overlaps the last one
+        profile.add_trade(2, MONDAY_MS + 20, 100.0, 2.0);
+        profile.add_trade(3, MONDAY_MS + 30, 101.0, 0.5);
+        // Nothing traded
+        profile.add_trade(4, MONDAY_MS + 40, 102.0, 0.0);
+        profile.add_trade(5, MONDAY_MS + M30, 99.0, 1.0);
+
+        assert_eq!(profile.last_trade_id, Some(5));
+        assert_eq!(profile.last_trade_ms, MONDAY_MS + M30);
+        assert_eq!(profile.candle_count(), 2);
+        assert_eq!(
+            profile.levels(MONDAY_MS).unwrap(),
+            [level(100.0, 2.0), level(101.0, 1.5)]
+        );
+        assert_eq!(profile.levels(MONDAY_MS + M30).unwrap(), [level(99.0, 1.0)]);
+        assert_eq!(profile.levels(MONDAY_MS + 2 * M30), None);
+    }
+
+    #[test]
+    fn trades_before_coverage_only_move_the_resume_point() {
+        let mut profile = TradeProfile::new("BTCUSDT".to_string(), M30, MONDAY_MS + 1);
+        profile.add_trade(7, MONDAY_MS + 10, 100.0, 1.0);
+
+        assert_eq!(profile.last_trade_id, Some(7));
+        assert!(profile.is_empty());
+        assert_eq!(profile.levels(MONDAY_MS), None);
+    }
+
+    #[test]
+    fn pruning_uncovers_whole_candles_before_the_cutoff() {
+        let mut profile = TradeProfile::new("BTCUSDT".to_string(), M30, MONDAY_MS);
+        for (id, candle) in (0..4).enumerate() {
+            profile.add_trade(id as i64, MONDAY_MS + candle * M30, 100.0, 1.0);
+        }
+
+        // Mid-candle cutoffs keep the candle they fall in
+        profile.prune_before(MONDAY_MS + 2 * M30 + 1);
+        assert_eq!(profile.complete_from_ms, MONDAY_MS + 2 * M30);
+        assert_eq!(profile.candle_count(), 2);
+        assert_eq!(profile.levels(MONDAY_MS + M30), None);
+        assert!(profile.levels(MONDAY_MS + 2 * M30).is_some());
+
+        // Cutoffs before coverage change nothing
+        profile.prune_before(MONDAY_MS);
+        assert_eq!(profile.complete_from_ms, MONDAY_MS + 2 * M30);
+        assert_eq!(profile.candle_count(), 2);
+    }
+
+    #[test]
+    fn resampling_covers_only_whole_coarse_candles() {
+        // Covered from 00:30, so the 00:00 hour is only half known
+        let mut profile = TradeProfile::new("BTCUSDT".to_string(), M30, MONDAY_MS + M30);
+        for (id, candle) in (1..6).enumerate() {
+            profile.add_trade(
+                id as i64,
+                MONDAY_MS + candle * M30,
+                100.0 + candle as f64,
+                1.0,
+            );
+        }
+        profile.add_trade(10, MONDAY_MS + 2 * M30 + 5, 103.0, 1.0);
+
+        let hourly = profile.resample(H1);
+        assert_eq!(hourly.interval_ms, H1);
+        assert_eq!(hourly.complete_from_ms, MONDAY_MS + H1);
+        assert_eq!(hourly.last_trade_id, Some(10));
+        assert_eq!(hourly.levels(MONDAY_MS), None);
+        assert_eq!(
+            hourly.levels(MONDAY_MS + H1).unwrap(),
+            [level(102.0, 1.0), level(103.0, 2.0)]
+        );
+        assert_eq!(
+            hourly.levels(MONDAY_MS + 2 * H1).unwrap(),
+            [level(104.0, 1.0), level(105.0, 1.0)]
+        );
+
+        // Weeks start on Monday, so a profile covered from Monday resamples whole
+        let weekly = profile.resample(TimeUtils::MS_IN_W);
+        assert_eq!(weekly.complete_from_ms, MONDAY_MS + TimeUtils::MS_IN_W);
+        assert!(weekly.is_empty());
+        let mut from_monday = profile.clone();
+        from_monday.complete_from_ms = MONDAY_MS;
+        let weekly = from_monday.resample(TimeUtils::MS_IN_W);
+        assert_eq!(weekly.complete_from_ms, MONDAY_MS);
+        assert_eq!(weekly.levels(MONDAY_MS).unwrap().len(), 5);
+    }
+}
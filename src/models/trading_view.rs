@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::analysis::zone_scoring::find_target_zones;
 use crate::config::ANALYSIS; // Import Config
 use crate::config::ZoneParams; // Would love this to just use crate::config::ZoneParams
+use crate::config::ZoneClassificationConfig;
 use crate::models::cva::{CVACore, ScoreType};
 use crate::utils::maths_utils::{normalize_max, smooth_data};
 
@@ -168,7 +169,12 @@ pub struct ZoneCoverageStats {
 impl TradingModel {
     /// Create a new trading model from CVA results and optional current price
     pub fn from_cva(cva: Arc<CVACore>) -> Self {
-        let (zones, coverage) = Self::classify_zones(&cva);
+        Self::from_cva_with_zones(cva, &ANALYSIS.zones)
+    }
+
+    /// As `from_cva`, but with explicit zone parameters (e.g. per-pair watchlist overrides).
+    pub fn from_cva_with_zones(cva: Arc<CVACore>, zone_config: &ZoneClassificationConfig) -> Self {
+        let (zones, coverage) = Self::classify_zones(&cva, zone_config);
 
         Self {
             pair_name: cva.pair_name.clone(),
@@ -180,7 +186,10 @@ impl TradingModel {
 
     // src/models/trading_view.rs
 
-    fn classify_zones(cva: &CVACore) -> (ClassifiedZones, ZoneCoverageStats) {
+    fn classify_zones(
+        cva: &CVACore,
+        zone_config: &ZoneClassificationConfig,
+    ) -> (ClassifiedZones, ZoneCoverageStats) {
         let (price_min, price_max) = cva.price_range.min_max();
         let zone_count = cva.zone_count;
         let total_candles = cva.total_candles as f64;
@@ -224,7 +233,7 @@ impl TradingModel {
         // --- Sticky Zones ---
         let (sticky, sticky_superzones) = process_layer(
             cva.get_scores_ref(ScoreType::FullCandleTVW),
-            ANALYSIS.zones.sticky,
+            zone_config.sticky,
             None,
         );
 
@@ -232,14 +241,14 @@ impl TradingModel {
         // 1. Low Wicks
         let (low_wicks, low_wicks_superzones) = process_layer(
             cva.get_scores_ref(ScoreType::LowWickCount),
-            ANALYSIS.zones.reversal,
+            zone_config.reversal,
             Some(total_candles),
         );
 
         // 2. High Wicks
         let (high_wicks, high_wicks_superzones) = process_layer(
             cva.get_scores_ref(ScoreType::HighWickCount),
-            ANALYSIS.zones.reversal,
+            zone_config.reversal,
             Some(total_candles),
         );
 
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
-// use std::sync::Arc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use eframe::egui::Context;
 use eframe::{App, Frame, Storage};
@@ -8,17 +9,22 @@ use serde::{Deserialize, Serialize};
 use crate::config::ANALYSIS;
 use crate::config::AnalysisConfig;
 use crate::engine::SniperEngine;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::journeys::ExportFormat;
 use crate::models::cva::ScoreType;
 use crate::ui::app_simulation::{SimDirection, SimStepSize};
+use crate::ui::config::UI_CONFIG;
 use crate::ui::ui_plot_view::PlotView;
 use crate::ui::utils::setup_custom_visuals;
 
 /// Persistent visibility settings for the plot
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)] // Settings saved before a field existed keep the rest
 pub struct PlotVisibility {
     pub sticky: bool,
     pub low_wicks: bool,
     pub high_wicks: bool,
+    pub depth: bool,
 }
 
 impl Default for PlotVisibility {
@@ -27,6 +33,7 @@ impl Default for PlotVisibility {
             sticky: true,
             low_wicks: true,
             high_wicks: true,
+            depth: true,
         }
     }
 }
@@ -53,6 +60,10 @@ pub struct ZoneSniperApp {
     #[serde(skip)]
     pub show_debug_help: bool,
 
+    /// Where the last journey export went (or why it failed)
+    #[serde(skip)]
+    pub journey_export_status: Option<String>,
+
     // --- 3. Debug / Simulation State (Skipped) ---
     #[serde(skip)]
     pub debug_background_mode: ScoreType,
@@ -81,6 +92,7 @@ impl Default for ZoneSniperApp {
             engine: None, // Must be injected after creation
             plot_view: PlotView::new(),
             show_debug_help: false,
+            journey_export_status: None,
 
             debug_background_mode: ScoreType::FullCandleTVW,
             is_simulation_mode: false,
@@ -111,6 +123,13 @@ impl ZoneSniperApp {
         // But to be safe, we can trigger a global recalc here.
         engine.trigger_global_recalc(app.selected_pair.clone());
 
+        // Live updates wake the UI; between them it sleeps (see `update`)
+        let egui_ctx = cc.egui_ctx.clone();
+        engine.set_update_notifier(Arc::new(move || {
+            egui_ctx
+                .request_repaint_after(Duration::from_millis(UI_CONFIG.live_repaint_interval_ms));
+        }));
+
         // 2. Inject the Engine (The Brain)
         app.engine = Some(engine);
 
@@ -147,9 +166,41 @@ impl ZoneSniperApp {
         }
     }
 
-    /// Placeholder for Journey logic (Suspended for now).
-    pub fn mark_all_journeys_stale(&mut self, _reason: &str) {
-        // No-op until Journey system is ported to Engine architecture
+    /// Journeys are run by the worker alongside each model, so settings they depend on
+    /// (like the time horizon) take effect through a global recalc.
+    pub fn mark_all_journeys_stale(&mut self, reason: &str) {
+        self.invalidate_all_pairs_for_global_change(reason);
+    }
+
+    /// Writes the selected pair's journey to `zone_index` to the export directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export_journey(&mut self, zone_index: usize, format: ExportFormat) {
+        let journeys = match (&self.engine, &self.selected_pair) {
+            (Some(engine), Some(pair)) => match engine.get_journeys(pair) {
+                Some(Ok(journeys)) => Arc::clone(journeys),
+                _ => return,
+            },
+            _ => return,
+        };
+        let Some(execution) = journeys
+            .executions
+            .iter()
+            .find(|execution| execution.zone_index == zone_index)
+        else {
+            return;
+        };
+
+        let path = execution.export_path(format);
+        self.journey_export_status = Some(match execution.analysis.write_to_file(&path, format) {
+            Ok(()) => {
+                log::info!("Journey exported to {}", path.display());
+                format!("Exported to {}", path.display())
+            }
+            Err(e) => {
+                log::error!("Journey export failed: {:#}", e);
+                format!("Export failed: {:#}", e)
+            }
+        });
     }
 
     /// Proxy to get signals from the Engine's Monitor.
@@ -184,20 +235,11 @@ impl App for ZoneSniperApp {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
         setup_custom_visuals(ctx);
 
-        // 1. Update Engine (We will remove the 'bool' return in the next step)
-        if let Some(engine) = &mut self.engine {
-            engine.update();
-        }
-
-        // // 1. Update the Engine (The Game Loop)
-        // if let Some(engine) = &mut self.engine {
-        //     // FIX: If engine returns true (BUSY), we request another frame immediately.
-        //     // This prevents the queue from stalling when the mouse stops moving.
-        //     let is_busy = engine.update();
-        //     if is_busy {
-        //         ctx.request_repaint();
-        //     }
-        // }
+        // 1. Update Engine (drains whatever changed since the last frame)
+        let (changed, busy) = match &mut self.engine {
+            Some(engine) => (engine.update(), engine.is_busy()),
+            None => (false, false),
+        };
 
         // 2. Handle Inputs
         self.handle_global_shortcuts(ctx);
@@ -211,6 +253,13 @@ impl App for ZoneSniperApp {
             self.render_help_panel(ctx);
         }
 
-        ctx.request_repaint();
+        // 4. Schedule the next frame. Input and live updates (via the notifier) wake the
+        // UI on their own; otherwise only poll while the worker has jobs.
+        let next_repaint_ms = if changed || busy {
+            UI_CONFIG.busy_repaint_interval_ms
+        } else {
+            UI_CONFIG.idle_repaint_interval_ms
+        };
+        ctx.request_repaint_after(Duration::from_millis(next_repaint_ms));
     }
 }
@@ -19,6 +19,14 @@ pub struct UiColors {
 pub struct UiConfig {
     pub colors: UiColors,
     pub max_journey_zone_lines: usize,
+    /// Height of the scrollable list of historical attempts under each journey zone
+    pub journey_attempt_list_height: f32,
+    /// Soonest repaint after a live price/book update (caps the feed-driven frame rate)
+    pub live_repaint_interval_ms: u64,
+    /// Repaint interval while jobs are queued or running, to pick up their results
+    pub busy_repaint_interval_ms: u64,
+    /// Repaint interval when nothing happens (ages and staleness still tick)
+    pub idle_repaint_interval_ms: u64,
 }
 
 /// Global UI configuration instance
@@ -33,4 +41,8 @@ pub static UI_CONFIG: UiConfig = UiConfig {
         journey_bear: Color32::from_rgb(180, 160, 230),
     },
     max_journey_zone_lines: 10,
+    journey_attempt_list_height: 180.0,
+    live_repaint_interval_ms: 100,
+    busy_repaint_interval_ms: 50,
+    idle_repaint_interval_ms: 1000,
 };
@@ -3,15 +3,18 @@ use eframe::egui::{Color32, Id, LayerId, Order::Tooltip, RichText, Stroke, Ui};
 #[allow(deprecated)]
 use eframe::egui::show_tooltip_at_pointer;
 
-use egui_plot::{HLine, PlotPoints, PlotUi, Polygon};
+use egui_plot::{BoxElem, BoxPlot, BoxSpread, HLine, Line, PlotPoints, PlotUi, Polygon};
 
 use crate::config::plot::PLOT_CONFIG;
+use crate::domain::order_book::{BookLevel, DepthSnapshot};
 use crate::models::cva::ScoreType;
+use crate::models::timeseries::OhlcvTimeSeries;
 use crate::models::trading_view::{SuperZone, TradingModel};
 use crate::ui::app::PlotVisibility;
-use crate::ui::ui_plot_view::PlotCache;
+use crate::ui::ui_plot_view::{JourneyFocus, PlotCache};
 use crate::ui::ui_text::UI_TEXT;
-use crate::ui::utils::format_price;
+use crate::ui::utils::{format_price, format_timestamp_ms};
+use crate::utils::TimeUtils;
 
 /// Context passed to every layer during rendering.
 /// This prevents argument explosion.
@@ -23,6 +26,9 @@ pub struct LayerContext<'a> {
     pub x_min: f64,
     pub x_max: f64,
     pub current_price: Option<f64>, // Pass SIM-aware price so layers render correctly in SIM mode
+    pub depth: Option<&'a DepthSnapshot>, // Live order book (None in SIM mode or if not streamed)
+    pub candles: Option<&'a OhlcvTimeSeries>, // The pair's candles (candlestick chart only)
+    pub journey_focus: Option<&'a JourneyFocus>, // Attempt picked in the journey panel
 }
 
 /// A standardized layer in the plot stack.
@@ -122,63 +128,28 @@ pub struct ReversalZoneLayer;
 
 impl PlotLayer for ReversalZoneLayer {
     fn render(&self, plot_ui: &mut PlotUi, ctx: &LayerContext) {
-        let current_price = ctx.current_price;
+        draw_reversal_zones(
+            plot_ui,
+            ctx,
+            0.5,
+            ZoneShape::TriangleUp,
+            ZoneShape::TriangleDown,
+        );
+    }
+}
 
-        // A. Low Wicks (Support)
-        if ctx.visibility.low_wicks {
-            for superzone in &ctx.trading_model.zones.low_wicks_superzones {
-                let is_relevant = current_price
-                    .map(|p| superzone.contains(p) || superzone.price_center < p)
-                    .unwrap_or(false);
-
-                if is_relevant {
-                    let color = get_zone_status_color(superzone, current_price);
-                    let label = UI_TEXT.label_reversal_support;
-                    let stroke = get_stroke(superzone, current_price, color);
-
-                    draw_superzone(
-                        plot_ui,
-                        superzone,
-                        ctx.x_min,
-                        ctx.x_max,
-                        label,
-                        color,
-                        stroke,
-                        0.5,
-                        1.5,
-                        ZoneShape::TriangleUp,
-                    );
-                }
-            }
-        }
+/// The same zones as full-width bands, for the candlestick chart
+pub struct ReversalBandLayer;
 
-        // B. High Wicks (Resistance)
-        if ctx.visibility.high_wicks {
-            for superzone in &ctx.trading_model.zones.high_wicks_superzones {
-                let is_relevant = current_price
-                    .map(|p| superzone.contains(p) || superzone.price_center > p)
-                    .unwrap_or(false);
-
-                if is_relevant {
-                    let color = get_zone_status_color(superzone, current_price);
-                    let label = UI_TEXT.label_reversal_resistance;
-                    let stroke = get_stroke(superzone, current_price, color);
-
-                    draw_superzone(
-                        plot_ui,
-                        superzone,
-                        ctx.x_min,
-                        ctx.x_max,
-                        label,
-                        color,
-                        stroke,
-                        0.5,
-                        1.5,
-                        ZoneShape::TriangleDown,
-                    );
-                }
-            }
-        }
+impl PlotLayer for ReversalBandLayer {
+    fn render(&self, plot_ui: &mut PlotUi, ctx: &LayerContext) {
+        draw_reversal_zones(
+            plot_ui,
+            ctx,
+            1.0,
+            ZoneShape::Rectangle,
+            ZoneShape::Rectangle,
+        );
     }
 }
 
@@ -210,10 +181,282 @@ impl PlotLayer for PriceLineLayer {
     }
 }
 
+// ============================================================================
+// 5. DEPTH LADDER LAYER (Live Order Book)
+// ============================================================================
+pub struct DepthLadderLayer;
+
+impl PlotLayer for DepthLadderLayer {
+    fn render(&self, plot_ui: &mut PlotUi, ctx: &LayerContext) {
+        let Some(depth) = ctx.depth else {
+            return;
+        };
+        if !ctx.visibility.depth {
+            return;
+        }
+        let max_quantity = depth.max_quantity();
+        if max_quantity <= 0.0 {
+            return;
+        }
+
+        // Grows leftwards from the right edge, so it doesn't hide the histogram's base
+        let full_width = (ctx.x_max - ctx.x_min) * PLOT_CONFIG.depth_ladder_width_pct;
+        let mut draw_side = |levels: &[BookLevel], label: &str, color: Color32| {
+            for level in levels {
+                let length = full_width * level.quantity / max_quantity;
+                let points = PlotPoints::new(vec![
+                    [ctx.x_max - length, level.price],
+                    [ctx.x_max, level.price],
+                ]);
+                plot_ui.line(
+                    Line::new(label, points)
+                        .color(color)
+                        .width(PLOT_CONFIG.depth_line_width),
+                );
+            }
+        };
+        draw_side(&depth.bids, "Resting Bids", PLOT_CONFIG.depth_bid_color);
+        draw_side(&depth.asks, "Resting Asks", PLOT_CONFIG.depth_ask_color);
+    }
+}
+
+// ============================================================================
+// 6. CVA SLICE LAYER (Which candles fed the histogram)
+// ============================================================================
+pub struct CvaSliceLayer;
+
+impl PlotLayer for CvaSliceLayer {
+    fn render(&self, plot_ui: &mut PlotUi, ctx: &LayerContext) {
+        // Full height of the view, wherever it has been panned to
+        let bounds = plot_ui.plot_bounds();
+        let (y_min, y_max) = (bounds.min()[1], bounds.max()[1]);
+
+        for &(start_ms, end_ms) in &ctx.trading_model.cva.slice_ranges_ms {
+            let (x_min, x_max) = (start_ms as f64, end_ms as f64);
+            let points = PlotPoints::new(vec![
+                [x_min, y_min],
+                [x_max, y_min],
+                [x_max, y_max],
+                [x_min, y_max],
+            ]);
+            plot_ui.polygon(
+                Polygon::new(UI_TEXT.label_cva_slices, points)
+                    .fill_color(PLOT_CONFIG.cva_slice_color)
+                    .stroke(Stroke::NONE)
+                    .allow_hover(false),
+            );
+        }
+    }
+}
+
+// ============================================================================
+// 7. CANDLESTICK LAYER
+// ============================================================================
+pub struct CandlestickLayer;
+
+impl PlotLayer for CandlestickLayer {
+    fn render(&self, plot_ui: &mut PlotUi, ctx: &LayerContext) {
+        let Some(series) = ctx.candles else {
+            return;
+        };
+        let count = series.klines();
+        let interval_ms = series.pair_interval.interval_ms;
+        if count == 0 || interval_ms <= 0 {
+            return;
+        }
+
+        // Only the candles in view, merged into buckets once there are too many to draw.
+        // Buckets are aligned to the series, so panning doesn't reshuffle them.
+        let bounds = plot_ui.plot_bounds();
+        let first_ms = series.first_kline_timestamp_ms;
+        let index_at = |x: f64| {
+            ((x - first_ms as f64) / interval_ms as f64)
+                .floor()
+                .clamp(0.0, count as f64) as usize
+        };
+        let (first, last) = (index_at(bounds.min()[0]), index_at(bounds.max()[0]));
+        let bucket = ((last + 1).min(count).saturating_sub(first))
+            .div_ceil(PLOT_CONFIG.max_drawn_candles)
+            .max(1);
+        let first = first / bucket * bucket;
+        let end = (last + 1).min(count);
+        let bucket_ms = interval_ms * bucket as i64;
+        let with_time = bucket_ms < TimeUtils::MS_IN_D;
+
+        let candles: Vec<BoxElem> = (first..end)
+            .step_by(bucket)
+            .map(|start| {
+                let stop = (start + bucket).min(count);
+                let open = series.open_prices[start];
+                let close = series.close_prices[stop - 1];
+                let high = series.high_prices[start..stop]
+                    .iter()
+                    .copied()
+                    .fold(f64::MIN, f64::max);
+                let low = series.low_prices[start..stop]
+                    .iter()
+                    .copied()
+                    .fold(f64::MAX, f64::min);
+                let color = if close >= open {
+                    PLOT_CONFIG.candle_up_color
+                } else {
+                    PLOT_CONFIG.candle_down_color
+                };
+                let open_ms = first_ms + start as i64 * interval_ms;
+
+                // Body from open to close, wicks out to the low and high; the median
+                // line sits on the close, which is how the tooltip tells it from the open
+                BoxElem::new(
+                    (open_ms + bucket_ms / 2) as f64,
+                    BoxSpread::new(low, open.min(close), close, open.max(close), high),
+                )
+                .name(format_timestamp_ms(open_ms, with_time))
+                .box_width(bucket_ms as f64 * PLOT_CONFIG.candle_body_width_pct)
+                .whisker_width(0.0)
+                .fill(color)
+                .stroke(Stroke::new(1.0, color))
+            })
+            .collect();
+
+        plot_ui.box_plot(
+            BoxPlot::new(UI_TEXT.label_candles, candles)
+                .element_formatter(Box::new(|candle, _| format_candle(candle))),
+        );
+    }
+}
+
+// ============================================================================
+// 8. JOURNEY ATTEMPT LAYER
+// ============================================================================
+pub struct JourneyAttemptLayer;
+
+impl PlotLayer for JourneyAttemptLayer {
+    fn render(&self, plot_ui: &mut PlotUi, ctx: &LayerContext) {
+        let Some(focus) = ctx.journey_focus else {
+            return;
+        };
+
+        // The attempt's span over the full height of the view, start and target across it
+        let bounds = plot_ui.plot_bounds();
+        let (y_min, y_max) = (bounds.min()[1], bounds.max()[1]);
+        let (x_min, x_max) = (focus.start_ms as f64, focus.end_ms as f64);
+        plot_ui.polygon(
+            Polygon::new(
+                UI_TEXT.label_journey_attempt,
+                PlotPoints::new(vec![
+                    [x_min, y_min],
+                    [x_max, y_min],
+                    [x_max, y_max],
+                    [x_min, y_max],
+                ]),
+            )
+            .fill_color(PLOT_CONFIG.journey_attempt_color)
+            .stroke(Stroke::NONE)
+            .allow_hover(false),
+        );
+        for price in [focus.start_price, focus.target_price] {
+            plot_ui.line(
+                Line::new(
+                    UI_TEXT.label_journey_attempt,
+                    PlotPoints::new(vec![[x_min, price], [x_max, price]]),
+                )
+                .color(PLOT_CONFIG.journey_attempt_line_color)
+                .width(PLOT_CONFIG.zone_boundary_line_width)
+                .allow_hover(false),
+            );
+        }
+    }
+}
+
 // ============================================================================
 // HELPER FUNCTIONS (Private to this module)
 // ============================================================================
 
+fn format_candle(candle: &BoxElem) -> String {
+    let spread = &candle.spread;
+    let close = spread.median;
+    let open = if close == spread.quartile3 {
+        spread.quartile1
+    } else {
+        spread.quartile3
+    };
+    format!(
+        "{}\nO {}\nH {}\nL {}\nC {}",
+        candle.name,
+        format_price(open),
+        format_price(spread.upper_whisker),
+        format_price(spread.lower_whisker),
+        format_price(close)
+    )
+}
+
+fn draw_reversal_zones(
+    plot_ui: &mut PlotUi,
+    ctx: &LayerContext,
+    width_factor: f64,
+    support_shape: ZoneShape,
+    resistance_shape: ZoneShape,
+) {
+    let current_price = ctx.current_price;
+
+    // A. Low Wicks (Support)
+    if ctx.visibility.low_wicks {
+        for superzone in &ctx.trading_model.zones.low_wicks_superzones {
+            let is_relevant = current_price
+                .map(|p| superzone.contains(p) || superzone.price_center < p)
+                .unwrap_or(false);
+
+            if is_relevant {
+                let color = get_zone_status_color(superzone, current_price);
+                let label = UI_TEXT.label_reversal_support;
+                let stroke = get_stroke(superzone, current_price, color);
+
+                draw_superzone(
+                    plot_ui,
+                    superzone,
+                    ctx.x_min,
+                    ctx.x_max,
+                    label,
+                    color,
+                    stroke,
+                    width_factor,
+                    1.5,
+                    support_shape,
+                );
+            }
+        }
+    }
+
+    // B. High Wicks (Resistance)
+    if ctx.visibility.high_wicks {
+        for superzone in &ctx.trading_model.zones.high_wicks_superzones {
+            let is_relevant = current_price
+                .map(|p| superzone.contains(p) || superzone.price_center > p)
+                .unwrap_or(false);
+
+            if is_relevant {
+                let color = get_zone_status_color(superzone, current_price);
+                let label = UI_TEXT.label_reversal_resistance;
+                let stroke = get_stroke(superzone, current_price, color);
+
+                draw_superzone(
+                    plot_ui,
+                    superzone,
+                    ctx.x_min,
+                    ctx.x_max,
+                    label,
+                    color,
+                    stroke,
+                    width_factor,
+                    1.5,
+                    resistance_shape,
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 enum ZoneShape {
     Rectangle,
     TriangleUp,
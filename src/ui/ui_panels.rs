@@ -1,15 +1,29 @@
-use eframe::egui::{ComboBox, ScrollArea, Ui, Slider, RichText, Color32};
+use eframe::egui::{
+    CollapsingHeader, ComboBox, ProgressBar, ScrollArea, Ui, Slider, RichText, Color32,
+};
+use std::sync::Arc;
 use strum::IntoEnumIterator;
 
+use crate::analysis::{DataQualityReport, QualityVerdict};
 use crate::config::ANALYSIS;
 use crate::config::plot::PLOT_CONFIG;
+use crate::data::pair_loading::PairLoadStatus;
 use crate::domain::pair_interval::PairInterval;
+use crate::domain::symbol_registry::SymbolRegistry;
+use crate::journeys::{
+    ExportFormat, JourneyExecution, ObstructionBreakdown, Outcome, PairJourneys,
+};
 
 use crate::models::cva::ScoreType;
 use crate::models::{PairContext, ZoneType};
 use crate::domain::price_horizon::PriceHorizonConfig;
-use crate::ui::config::UI_TEXT;
-use crate::ui::utils::{colored_subsection_heading, section_heading, spaced_separator};
+use crate::ui::config::{UI_CONFIG, UI_TEXT};
+use crate::ui::ui_plot_view::JourneyFocus;
+use crate::ui::utils::{
+    colored_subsection_heading, format_price, format_timestamp_ms, section_heading,
+    spaced_separator,
+};
+use crate::utils::time_utils::epoch_ms_to_utc;
 
 #[cfg(debug_assertions)]
 use crate::config::DEBUG_FLAGS;
@@ -25,7 +39,10 @@ pub struct DataGenerationPanel<'a> {
     #[allow(dead_code)]
     zone_count: usize,
     selected_pair: Option<String>,
-    available_pairs: Vec<String>,
+    /// Pairs grouped as in the watchlist file: (group name, pairs)
+    pair_groups: Vec<(String, Vec<String>)>,
+    /// Splits the selected pair into base and quote
+    symbols: Arc<SymbolRegistry>,
     price_horizon_config: &'a PriceHorizonConfig,
     time_horizon_days: u64,
 }
@@ -34,14 +51,16 @@ impl<'a> DataGenerationPanel<'a> {
     pub fn new(
         zone_count: usize,
         selected_pair: Option<String>,
-        available_pairs: Vec<String>,
+        pair_groups: Vec<(String, Vec<String>)>,
+        symbols: Arc<SymbolRegistry>,
         price_horizon_config: &'a PriceHorizonConfig,
         time_horizon_days: u64,
     ) -> Self {
         Self {
             zone_count,
             selected_pair,
-            available_pairs,
+            pair_groups,
+            symbols,
             price_horizon_config,
             time_horizon_days,
         }
@@ -124,11 +143,25 @@ impl<'a> DataGenerationPanel<'a> {
             .max_height(160.)
             .id_salt("pair_selector")
             .show(ui, |ui| {
-                for item in &self.available_pairs {
-                    let is_selected = self.selected_pair.as_ref() == Some(item);
-                    if ui.selectable_label(is_selected, item).clicked() {
-                        self.selected_pair = Some(item.clone());
-                        changed = Some(item.clone());
+                let show_headers = self.pair_groups.len() > 1;
+                for (group, pairs) in &self.pair_groups {
+                    let mut render_pairs = |ui: &mut Ui| {
+                        for item in pairs {
+                            let is_selected = self.selected_pair.as_ref() == Some(item);
+                            if ui.selectable_label(is_selected, item).clicked() {
+                                self.selected_pair = Some(item.clone());
+                                changed = Some(item.clone());
+                            }
+                        }
+                    };
+
+                    if show_headers {
+                        CollapsingHeader::new(format!("{} ({})", group, pairs.len()))
+                            .id_salt(("pair_group", group))
+                            .default_open(true)
+                            .show(ui, render_pairs);
+                    } else {
+                        render_pairs(ui);
                     }
                 }
             });
@@ -177,7 +210,7 @@ impl<'a> Panel for DataGenerationPanel<'a> {
         if let Some(pair) = &self.selected_pair {
             ui.label(format!(
                 "Selected: {:?}",
-                PairInterval::split_pair_name(pair)
+                PairInterval::split_pair_name(pair, &self.symbols)
             ));
         }
         ui.add_space(20.0);
@@ -289,3 +322,502 @@ impl<'a> Panel for SignalsPanel<'a> {
         events
     }
 }
+
+/// Panel summarising the selected pair's data-quality report
+pub struct DataQualityPanel<'a> {
+    report: Option<&'a DataQualityReport>,
+}
+
+impl<'a> DataQualityPanel<'a> {
+    pub fn new(report: Option<&'a DataQualityReport>) -> Self {
+        Self { report }
+    }
+}
+
+impl<'a> Panel for DataQualityPanel<'a> {
+    type Event = ();
+
+    fn render(&mut self, ui: &mut Ui) -> Vec<Self::Event> {
+        section_heading(ui, UI_TEXT.data_quality_heading);
+
+        let Some(report) = self.report else {
+            ui.label(RichText::new("No report for this pair").small().color(Color32::GRAY));
+            return Vec::new();
+        };
+
+        let color = match report.verdict {
+            QualityVerdict::Good => Color32::from_rgb(100, 200, 100),
+            QualityVerdict::Warning => Color32::from_rgb(230, 180, 60),
+            QualityVerdict::Bad => Color32::from_rgb(230, 80, 80),
+        };
+        ui.label(RichText::new(format!("{}: {}", report.pair, report.verdict)).color(color));
+        for reason in &report.reasons {
+            ui.label(RichText::new(format!("• {}", reason)).small().color(color));
+        }
+
+        ui.label(
+            RichText::new(format!(
+                "{:.0} of {:.0} days real history, {:.2}% gaps",
+                report.effective_history_days, report.span_days, report.pct_gaps
+            ))
+            .small(),
+        );
+
+        CollapsingHeader::new("Details")
+            .id_salt("data_quality_details")
+            .show(ui, |ui| {
+                for gap in &report.largest_gaps {
+                    ui.label(
+                        RichText::new(format!(
+                            "Gap {} → {} ({} candles)",
+                            epoch_ms_to_utc(gap.start_ms),
+                            epoch_ms_to_utc(gap.end_ms),
+                            gap.candles
+                        ))
+                        .small(),
+                    );
+                }
+                ui.label(
+                    RichText::new(format!(
+                        "Timestamps: {} duplicate, {} misaligned{}",
+                        report.ingest.duplicate_timestamps,
+                        report.ingest.misaligned_timestamps,
+                        if report.misaligned_start { ", off-grid start" } else { "" }
+                    ))
+                    .small(),
+                );
+                if report.ingest.truncated_candles > 0 {
+                    ui.label(
+                        RichText::new(format!(
+                            "History cut by {} candles (too many gaps)",
+                            report.ingest.truncated_candles
+                        ))
+                        .small(),
+                    );
+                }
+                ui.label(
+                    RichText::new(format!(
+                        "Zero-volume streaks: {}",
+                        report.zero_volume_streaks
+                    ))
+                    .small(),
+                );
+                ui.label(
+                    RichText::new(format!(
+                        "Inconsistent candles: {}",
+                        report.ohlc_inconsistencies
+                    ))
+                    .small(),
+                );
+                ui.label(
+                    RichText::new(format!("Outlier wicks: {}", report.outlier_wicks)).small(),
+                );
+            });
+
+        ui.add_space(10.0);
+        Vec::new()
+    }
+}
+
+/// Panel for the background pair load: how far it's got, and which pairs failed and why
+pub struct PairLoadPanel<'a> {
+    status: &'a PairLoadStatus,
+}
+
+impl<'a> PairLoadPanel<'a> {
+    pub fn new(status: &'a PairLoadStatus) -> Self {
+        Self { status }
+    }
+}
+
+impl<'a> Panel for PairLoadPanel<'a> {
+    type Event = ();
+
+    fn render(&mut self, ui: &mut Ui) -> Vec<Self::Event> {
+        let status = self.status;
+        let failed_color = Color32::from_rgb(230, 80, 80);
+        section_heading(ui, UI_TEXT.pair_load_heading);
+
+        if status.is_loading() {
+            match status.progress {
+                Some(progress) if progress.total > 0 => {
+                    ui.add(ProgressBar::new(progress.fraction()).text(progress.to_string()));
+                }
+                _ => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(
+                            RichText::new(format!("{} pairs loaded", status.loaded)).small(),
+                        );
+                    });
+                }
+            }
+        } else if let Some(error) = &status.error {
+            ui.label(RichText::new(error).small().color(failed_color));
+        } else {
+            ui.label(RichText::new(format!("{} pairs loaded", status.loaded)).small());
+        }
+
+        if !status.failures.is_empty() {
+            CollapsingHeader::new(format!("{} not loaded", status.failures.len()))
+                .id_salt("pair_load_failures")
+                .show(ui, |ui| {
+                    for (pair, reason) in &status.failures {
+                        ui.label(RichText::new(pair).small().strong().color(failed_color));
+                        ui.label(RichText::new(reason).small());
+                    }
+                });
+        }
+
+        ui.add_space(10.0);
+        Vec::new()
+    }
+}
+
+/// What the journey panel asks the app to do
+pub enum JourneyPanelEvent {
+    /// Show a historical attempt on the candlestick chart
+    ShowAttempt(JourneyFocus),
+    /// Write a zone's journey result (audit and every attempt) to a file
+    Export {
+        zone_index: usize,
+        format: ExportFormat,
+    },
+}
+
+/// Panel with the selected pair's journeys to each sticky zone: stats, obstruction,
+/// Monte Carlo, and every historical attempt behind them
+pub struct JourneyPanel<'a> {
+    journeys: Option<&'a Result<Arc<PairJourneys>, String>>,
+    /// Attempt currently shown on the chart
+    focus: Option<&'a JourneyFocus>,
+    /// Outcome of the last export, if any
+    export_status: Option<&'a str>,
+}
+
+impl<'a> JourneyPanel<'a> {
+    pub fn new(
+        journeys: Option<&'a Result<Arc<PairJourneys>, String>>,
+        focus: Option<&'a JourneyFocus>,
+        export_status: Option<&'a str>,
+    ) -> Self {
+        Self {
+            journeys,
+            focus,
+            export_status,
+        }
+    }
+
+    /// One row per historical attempt, oldest first; clicking one shows it on the chart
+    fn render_attempts(
+        &self,
+        ui: &mut Ui,
+        pair: &str,
+        execution: &JourneyExecution,
+        events: &mut Vec<JourneyPanelEvent>,
+    ) {
+        let outcomes = &execution.analysis.outcomes;
+        CollapsingHeader::new(
+            RichText::new(format!(
+                "{} {}",
+                outcomes.len(),
+                UI_TEXT.journey_zone_label_attempts_short
+            ))
+            .small(),
+        )
+        .id_salt(("journey_attempts", execution.zone_index))
+        .show(ui, |ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            ui.horizontal(|ui| {
+                for format in [ExportFormat::Csv, ExportFormat::Json] {
+                    let label = format!("💾 {}", format.extension().to_uppercase());
+                    if ui.small_button(label).clicked() {
+                        events.push(JourneyPanelEvent::Export {
+                            zone_index: execution.zone_index,
+                            format,
+                        });
+                    }
+                }
+            });
+
+            let row_height = ui.text_style_height(&eframe::egui::TextStyle::Small) + 4.0;
+            ScrollArea::vertical()
+                .id_salt(("journey_attempt_rows", execution.zone_index))
+                .max_height(UI_CONFIG.journey_attempt_list_height)
+                .show_rows(ui, row_height, outcomes.len(), |ui, rows| {
+                    for outcome in &outcomes[rows] {
+                        let focus = JourneyFocus {
+                            pair: pair.to_string(),
+                            start_ms: outcome.start_timestamp_ms,
+                            end_ms: outcome.end_timestamp_ms,
+                            start_price: outcome.start_price,
+                            target_price: execution.target_price,
+                        };
+                        let (icon, result) = match outcome.outcome {
+                            Outcome::Success { days_elapsed } => {
+                                ("✅", format!("{}d", days_elapsed))
+                            }
+                            Outcome::TimedOut { final_price } => ("⌛", format_price(final_price)),
+                            Outcome::StoppedOut { adverse_price } => {
+                                ("🛑", format_price(adverse_price))
+                            }
+                        };
+                        let text = format!(
+                            "{} {} {} → {}, dd {:.1}%",
+                            icon,
+                            format_timestamp_ms(outcome.start_timestamp_ms, true),
+                            format_price(outcome.start_price),
+                            result,
+                            outcome.max_drawdown_pct
+                        );
+                        let selected = self.focus == Some(&focus);
+                        if ui
+                            .selectable_label(selected, RichText::new(text).small())
+                            .clicked()
+                        {
+                            events.push(JourneyPanelEvent::ShowAttempt(focus));
+                        }
+                    }
+                });
+        });
+    }
+
+    fn render_obstruction(ui: &mut Ui, obstruction: &ObstructionBreakdown) {
+        let path = if obstruction.is_obstructed() {
+            format!(
+                "{} {} reversal zone(s)",
+                UI_TEXT.journey_obstructed_label,
+                obstruction.obstructing_zones.len()
+            )
+        } else {
+            UI_TEXT.journey_clear_path_label.to_string()
+        };
+        ui.label(RichText::new(path).small().strong());
+
+        let group = |successes: usize, attempts: usize, rate: Option<f64>| match rate {
+            Some(rate) => format!("{}/{} ({:.0}%)", successes, attempts, rate * 100.0),
+            None => format!("{}/{}", successes, attempts),
+        };
+        ui.label(
+            RichText::new(format!(
+                "Obstructed then {} · clear {}",
+                group(
+                    obstruction.obstructed_successes,
+                    obstruction.obstructed_attempts,
+                    obstruction.obstructed_success_rate
+                ),
+                group(
+                    obstruction.clear_successes,
+                    obstruction.clear_attempts,
+                    obstruction.clear_success_rate
+                ),
+            ))
+            .small(),
+        );
+        ui.label(
+            RichText::new(format!(
+                "Adjusted {} {:.1}%{}",
+                UI_TEXT.journey_zone_label_success_rate_short,
+                obstruction.adjusted_probability * 100.0,
+                if obstruction.uses_group_rate {
+                    ""
+                } else {
+                    " (too few samples in today's group, overall rate)"
+                }
+            ))
+            .small(),
+        );
+    }
+
+    fn render_execution(
+        &self,
+        ui: &mut Ui,
+        pair: &str,
+        execution: &JourneyExecution,
+        events: &mut Vec<JourneyPanelEvent>,
+    ) {
+        let stats = &execution.analysis.stats;
+        let outcomes = &execution.analysis.outcomes;
+        let timeouts = outcomes
+            .iter()
+            .filter(|o| matches!(o.outcome, Outcome::TimedOut { .. }))
+            .count();
+        let stops = outcomes
+            .iter()
+            .filter(|o| matches!(o.outcome, Outcome::StoppedOut { .. }))
+            .count();
+        let color = if execution.direction_up {
+            UI_CONFIG.colors.journey_bull
+        } else {
+            UI_CONFIG.colors.journey_bear
+        };
+
+        let header = format!(
+            "{} Z{} {} {}  {} {:.0}%",
+            UI_TEXT.journey_zone_line_prefix,
+            execution.zone_index,
+            if execution.direction_up { "⬆" } else { "⬇" },
+            format_price(execution.target_price),
+            UI_TEXT.journey_zone_label_success_rate_short,
+            stats.success_rate * 100.0
+        );
+        CollapsingHeader::new(RichText::new(header).small().color(color))
+            .id_salt(("journey_zone", execution.zone_index))
+            .show(ui, |ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "{} {} ({} raw, n_eff {:.1}), {} {}, {} {}, {} {}",
+                        stats.total_attempts,
+                        UI_TEXT.journey_zone_label_attempts_short,
+                        stats.raw_match_count,
+                        stats.effective_sample_size,
+                        stats.success_count,
+                        UI_TEXT.journey_zone_label_successes_short,
+                        timeouts,
+                        UI_TEXT.journey_zone_label_timeouts_short,
+                        stops,
+                        UI_TEXT.journey_zone_label_stops_short,
+                    ))
+                    .small(),
+                );
+                ui.label(
+                    RichText::new(format!(
+                        "{} {:.1}% (95% CI {:.1}–{:.1}%), {}",
+                        UI_TEXT.journey_zone_label_success_rate_short,
+                        stats.success_rate * 100.0,
+                        stats.confidence_interval_success.0 * 100.0,
+                        stats.confidence_interval_success.1 * 100.0,
+                        stats.declustering
+                    ))
+                    .small(),
+                );
+                let kelly = stats
+                    .expected_value
+                    .kelly_criterion
+                    .map(|k| format!("{:.2}", k))
+                    .unwrap_or_else(|| "–".to_string());
+                ui.label(
+                    RichText::new(format!(
+                        "{} {:.1}%, {} {}, {} {:.1}%, {} {:.1}%",
+                        UI_TEXT.journey_zone_label_ev_annual_short,
+                        stats.expected_annualized_return,
+                        UI_TEXT.journey_zone_label_kelly_short,
+                        kelly,
+                        UI_TEXT.journey_zone_label_worst_loss_short,
+                        stats.risk_metrics.worst_case_loss,
+                        UI_TEXT.journey_zone_label_avg_drawdown_short,
+                        stats.risk_metrics.avg_max_drawdown
+                    ))
+                    .small(),
+                );
+
+                if let Some(obstruction) = &execution.obstruction {
+                    Self::render_obstruction(ui, obstruction);
+                }
+
+                if let Some(simulation) = &execution.simulation {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} ({:.0}% × {} trades, {} paths)",
+                            UI_TEXT.journey_monte_carlo_label,
+                            simulation.position_fraction * 100.0,
+                            simulation.trades_per_path,
+                            simulation.paths
+                        ))
+                        .small()
+                        .strong(),
+                    );
+                    let returns = &simulation.cumulative_return;
+                    ui.label(
+                        RichText::new(format!(
+                            "Return p5 {:+.1}% · median {:+.1}% · p95 {:+.1}%",
+                            returns.p5, returns.median, returns.p95
+                        ))
+                        .small(),
+                    );
+                    ui.label(
+                        RichText::new(format!(
+                            "Drawdown median {:.1}% · p95 {:.1}%, {} {:.1}%",
+                            simulation.max_drawdown.median,
+                            simulation.max_drawdown.p95,
+                            UI_TEXT.journey_monte_carlo_ruin_label,
+                            simulation.probability_of_ruin * 100.0
+                        ))
+                        .small(),
+                    );
+                }
+
+                self.render_attempts(ui, pair, execution, events);
+            });
+    }
+}
+
+impl<'a> Panel for JourneyPanel<'a> {
+    type Event = JourneyPanelEvent;
+
+    fn render(&mut self, ui: &mut Ui) -> Vec<Self::Event> {
+        let mut events = Vec::new();
+        section_heading(ui, UI_TEXT.journey_status_heading);
+        let subdued = |ui: &mut Ui, text: &str| {
+            ui.label(RichText::new(text).small().color(Color32::GRAY));
+        };
+
+        let journeys = match self.journeys {
+            None => {
+                subdued(ui, UI_TEXT.journey_status_waiting);
+                return Vec::new();
+            }
+            Some(Err(error)) => {
+                ui.label(
+                    RichText::new(format!(
+                        "{} ({}: {})",
+                        UI_TEXT.journey_status_failed, UI_TEXT.journey_status_error_prefix, error
+                    ))
+                    .small()
+                    .color(Color32::LIGHT_RED),
+                );
+                return Vec::new();
+            }
+            Some(Ok(journeys)) => journeys,
+        };
+
+        if journeys.executions.is_empty() {
+            subdued(ui, UI_TEXT.journey_status_no_zones);
+            ui.add_space(10.0);
+            return Vec::new();
+        }
+
+        ui.label(
+            RichText::new(format!(
+                "{}: {} {} from {} within {} days, {} {}",
+                UI_TEXT.journey_status_current_prefix,
+                journeys.executions.len(),
+                UI_TEXT.journey_status_zones_label,
+                format_price(journeys.current_price),
+                journeys.time_horizon_days,
+                journeys.total_attempts(),
+                UI_TEXT.journey_status_attempts_label
+            ))
+            .small(),
+        );
+
+        // Nearest zones first: they are the ones a trade would actually target
+        let mut executions: Vec<&JourneyExecution> = journeys.executions.iter().collect();
+        executions.sort_by(|a, b| {
+            let distance = |e: &JourneyExecution| (e.target_price - journeys.current_price).abs();
+            distance(a).total_cmp(&distance(b))
+        });
+        for execution in executions
+            .into_iter()
+            .take(UI_CONFIG.max_journey_zone_lines)
+        {
+            self.render_execution(ui, &journeys.pair, execution, &mut events);
+        }
+        if let Some(status) = self.export_status {
+            ui.label(RichText::new(status).small().color(Color32::GRAY));
+        }
+
+        ui.add_space(10.0);
+        events
+    }
+}
@@ -1,19 +1,26 @@
 use colorgrad::Gradient;
 use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
 
-use eframe::egui::{Color32, Ui};
-use egui_plot::{AxisHints, Corner, HPlacement, Legend, Plot};
+use eframe::egui::{Color32, Ui, Vec2, vec2};
+use egui_plot::{
+    AxisHints, Corner, GridInput, GridMark, HPlacement, Legend, Plot, log_grid_spacer,
+};
 
 use crate::config::plot::PLOT_CONFIG;
+use crate::domain::order_book::DepthSnapshot;
 use crate::models::cva::{CVACore, ScoreType};
+use crate::models::timeseries::OhlcvTimeSeries;
 use crate::models::trading_view::TradingModel;
 use crate::ui::ui_text::UI_TEXT;
 use crate::utils::maths_utils;
-use crate::ui::utils::format_price;
+use crate::utils::TimeUtils;
+use crate::ui::utils::{format_price, format_timestamp_ms};
 
 // Import the new Layer System
 use crate::ui::plot_layers::{
-    BackgroundLayer, LayerContext, PlotLayer, PriceLineLayer, ReversalZoneLayer, StickyZoneLayer,
+    BackgroundLayer, CandlestickLayer, CvaSliceLayer, DepthLadderLayer, JourneyAttemptLayer,
+    LayerContext, PlotLayer, PriceLineLayer, ReversalBandLayer, ReversalZoneLayer, StickyZoneLayer,
 };
 
 /// A lightweight representation of a background bar.
@@ -41,14 +48,51 @@ pub struct PlotCache {
     pub total_width: f64,
 }
 
+/// A historical journey attempt to show on the candlestick chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct JourneyFocus {
+    pub pair: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub start_price: f64,
+    pub target_price: f64,
+}
+
 #[derive(Default)]
 pub struct PlotView {
     cache: Option<PlotCache>,
+    /// Order book to draw over the next plot (set each frame; None hides the ladder)
+    depth: Option<DepthSnapshot>,
+    /// The analysis the current pan/zoom belongs to (see `view_key`)
+    view_key: Option<u64>,
+    /// Attempt picked in the journey panel, and whether the chart still has to jump to it
+    journey_focus: Option<JourneyFocus>,
+    jump_to_focus: bool,
 }
 
 impl PlotView {
     pub fn new() -> Self {
-        Self { cache: None }
+        Self {
+            cache: None,
+            depth: None,
+            view_key: None,
+            journey_focus: None,
+            jump_to_focus: false,
+        }
+    }
+
+    pub fn set_depth(&mut self, depth: Option<DepthSnapshot>) {
+        self.depth = depth;
+    }
+
+    /// Highlights `focus` on the candlestick chart and pans/zooms it there next frame
+    pub fn focus_journey(&mut self, focus: JourneyFocus) {
+        self.journey_focus = Some(focus);
+        self.jump_to_focus = true;
+    }
+
+    pub fn journey_focus(&self) -> Option<&JourneyFocus> {
+        self.journey_focus.as_ref()
     }
 
     pub fn cache_hits(&self) -> usize {
@@ -63,12 +107,14 @@ impl PlotView {
 
     pub fn clear_cache(&mut self) {
         self.cache = None;
+        self.view_key = None;
     }
 
     pub fn has_cache(&self) -> bool {
         self.cache.is_some()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn show_my_plot(
         &mut self,
         ui: &mut Ui,
@@ -77,108 +123,56 @@ impl PlotView {
         current_pair_price: Option<f64>,
         background_score_type: ScoreType,
         visibility: &crate::ui::app::PlotVisibility,
+        candles: Option<&OhlcvTimeSeries>,
     ) {
         // let trading_model =
         //     TradingModel::from_cva(Arc::new(cva_results.clone()), current_pair_price);
 
         let cache = self.calculate_plot_data(cva_results, background_score_type);
-        let pair_name = &cva_results.pair_name;
-        let (y_min, y_max) = cva_results.price_range.min_max();
-        let total_y_range = y_max - y_min;
-
-        let _legend = Legend::default().position(Corner::RightTop);
-
-        Plot::new("my_plot")
-            // .view_aspect(PLOT_CONFIG.plot_aspect_ratio)
-            .legend(_legend)
-            .custom_x_axes(vec![create_x_axis(&cache)])
-            .custom_y_axes(vec![create_y_axis(pair_name)])
-            // Suppress Defaults
-            .label_formatter(|_, _| String::new())
-            .x_grid_spacer(move |_input| {
-                let mut marks = Vec::new();
-                let (min, max) = _input.bounds;
-                let range = max - min;
-                let step_size = if range < 0.1 { 0.02 } else { 0.1 };
-                let start = (min / step_size).ceil() as i64;
-                let end = (max / step_size).floor() as i64;
-                for i in start..=end {
-                    let value = i as f64 * step_size;
-                    if value >= 0.0 && value <= 1.0 {
-                        marks.push(egui_plot::GridMark { value, step_size });
-                    }
-                }
-                marks
-            })
-            // NEW: Force Y-Axis Labels at Start/End
-            .y_grid_spacer(move |_input| {
-                let mut marks = Vec::new();
-
-                // 1. Mandatory Start (Min Price)
-                marks.push(egui_plot::GridMark {
-                    value: y_min,
-                    step_size: total_y_range,
-                });
-
-                // 2. Mandatory End (Max Price)
-                marks.push(egui_plot::GridMark {
-                    value: y_max,
-                    step_size: total_y_range,
-                });
 
-                // 3. Fill in the middle (e.g. 5 even steps) to keep it readable
-                // We use a slightly different step_size so egui knows they are secondary
-                let divisions = 5;
-                let step = total_y_range / divisions as f64;
-                for i in 1..divisions {
-                    let value = y_min + (step * i as f64);
-                    marks.push(egui_plot::GridMark {
-                        value,
-                        step_size: step,
-                    });
-                }
+        // A new analysis (another pair, or a recalculated range) starts from its default
+        // view; otherwise panning and zooming are left alone
+        let view_key = view_key(cva_results);
+        let reset_view = self.view_key != Some(view_key);
+        self.view_key = Some(view_key);
+
+        // 1. Create Context
+        let ctx = LayerContext {
+            trading_model,
+            cache: &cache,
+            visibility,
+            background_score_type,
+            x_min: cache.x_min,
+            x_max: cache.x_max,
+            current_price: current_pair_price,
+            depth: self.depth.as_ref(),
+            candles: None,
+            journey_focus: self
+                .journey_focus
+                .as_ref()
+                .filter(|focus| focus.pair == cva_results.pair_name),
+        };
 
-                marks
-            })
-            .allow_scroll(false)
-            .allow_zoom(false)
-            .allow_drag(false)
-            .allow_boxed_zoom(false)
-            .show(ui, |plot_ui| {
-                let (y_min, y_max) = cva_results.price_range.min_max();
-                let price = current_pair_price.unwrap_or(y_min);
-                let y_min_adjusted = y_min.min(price);
-                let y_max_adjusted = y_max.max(price);
-
-                plot_ui.set_plot_bounds_y(y_min_adjusted..=y_max_adjusted);
-                plot_ui.set_plot_bounds_x(cache.x_min..=cache.x_max);
-
-                // --- LAYER RENDERING SYSTEM ---
-
-                // 1. Create Context
-                let ctx = LayerContext {
-                    trading_model: &trading_model,
-                    cache: &cache,
-                    visibility,
-                    background_score_type,
-                    x_min: cache.x_min,
-                    x_max: cache.x_max,
-                    current_price: current_pair_price,
-                };
+        let Some(series) = candles.filter(|series| series.klines() > 0) else {
+            show_histogram(ui, &ctx, None, reset_view);
+            return;
+        };
 
-                // 2. Define Layer Stack (Back to Front)
-                let layers: Vec<Box<dyn PlotLayer>> = vec![
-                    Box::new(BackgroundLayer),
-                    Box::new(StickyZoneLayer),
-                    Box::new(ReversalZoneLayer),
-                    Box::new(PriceLineLayer),
-                ];
-
-                // 3. Render Loop
-                for layer in layers {
-                    layer.render(plot_ui, &ctx);
-                }
-            });
+        // Candles on the left, the histogram on the right, sharing the price axis
+        let size = ui.available_size();
+        let chart_width = size.x * PLOT_CONFIG.candle_chart_width_pct;
+        let histogram_width = size.x - chart_width - ui.spacing().item_spacing.x;
+        let candle_ctx = LayerContext {
+            x_min: series.first_kline_timestamp_ms as f64,
+            x_max: (series.last_kline_timestamp_ms() + series.pair_interval.interval_ms) as f64,
+            candles: Some(series),
+            ..ctx
+        };
+        let jump = candle_ctx.journey_focus.is_some() && std::mem::take(&mut self.jump_to_focus);
+        ui.horizontal(|ui| {
+            show_candle_chart(ui, &candle_ctx, vec2(chart_width, size.y), reset_view, jump);
+            show_histogram(ui, &ctx, Some(vec2(histogram_width, size.y)), reset_view);
+        });
     }
 
     fn calculate_plot_data(&mut self, cva_results: &CVACore, score_type: ScoreType) -> PlotCache {
@@ -279,6 +273,251 @@ fn to_egui_color(colorgrad_color: colorgrad::Color) -> Color32 {
     Color32::from_rgba_unmultiplied(rgba8[0], rgba8[1], rgba8[2], 255)
 }
 
+/// Plots sharing this group pan and zoom their price (y) axis together
+const PRICE_AXIS_LINK: &str = "price_axis";
+
+fn view_key(cva_results: &CVACore) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    cva_results.pair_name.hash(&mut hasher);
+    let (y_min, y_max) = cva_results.price_range.min_max();
+    y_min.to_bits().hash(&mut hasher);
+    y_max.to_bits().hash(&mut hasher);
+    cva_results.start_timestamp_ms.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The analysed price range, stretched to take in the current price
+fn default_price_bounds(ctx: &LayerContext) -> RangeInclusive<f64> {
+    let (y_min, y_max) = ctx.trading_model.cva.price_range.min_max();
+    let price = ctx.current_price.unwrap_or(y_min);
+    y_min.min(price)..=y_max.max(price)
+}
+
+/// The zone-strength histogram. Its x axis is fixed; its price axis pans and zooms
+/// (with the candlestick chart's, when there is one).
+fn show_histogram(ui: &mut Ui, ctx: &LayerContext, size: Option<Vec2>, reset_view: bool) {
+    let cache = ctx.cache;
+    let pair_name = &ctx.trading_model.cva.pair_name;
+    let (y_min, y_max) = ctx.trading_model.cva.price_range.min_max();
+    let total_y_range = y_max - y_min;
+    let default_y_spacer = log_grid_spacer(10);
+
+    let _legend = Legend::default().position(Corner::RightTop);
+
+    let mut plot = Plot::new("my_plot")
+        // .view_aspect(PLOT_CONFIG.plot_aspect_ratio)
+        .legend(_legend)
+        .custom_x_axes(vec![create_x_axis(cache)])
+        .custom_y_axes(vec![create_y_axis(pair_name)])
+        // Suppress Defaults
+        .label_formatter(|_, _| String::new())
+        .x_grid_spacer(move |_input| {
+            let mut marks = Vec::new();
+            let (min, max) = _input.bounds;
+            let range = max - min;
+            let step_size = if range < 0.1 { 0.02 } else { 0.1 };
+            let start = (min / step_size).ceil() as i64;
+            let end = (max / step_size).floor() as i64;
+            for i in start..=end {
+                let value = i as f64 * step_size;
+                if (0.0..=1.0).contains(&value) {
+                    marks.push(egui_plot::GridMark { value, step_size });
+                }
+            }
+            marks
+        })
+        // NEW: Force Y-Axis Labels at Start/End
+        .y_grid_spacer(move |_input| {
+            // Zoomed in past the analysed range: ordinary marks, or there'd be none
+            let (min, max) = _input.bounds;
+            if y_min < min || y_max > max {
+                return default_y_spacer(_input);
+            }
+
+            let mut marks = Vec::new();
+
+            // 1. Mandatory Start (Min Price)
+            marks.push(egui_plot::GridMark {
+                value: y_min,
+                step_size: total_y_range,
+            });
+
+            // 2. Mandatory End (Max Price)
+            marks.push(egui_plot::GridMark {
+                value: y_max,
+                step_size: total_y_range,
+            });
+
+            // 3. Fill in the middle (e.g. 5 even steps) to keep it readable
+            // We use a slightly different step_size so egui knows they are secondary
+            let divisions = 5;
+            let step = total_y_range / divisions as f64;
+            for i in 1..divisions {
+                let value = y_min + (step * i as f64);
+                marks.push(egui_plot::GridMark {
+                    value,
+                    step_size: step,
+                });
+            }
+
+            marks
+        })
+        .link_axis(PRICE_AXIS_LINK, [false, true])
+        .allow_scroll([false, true])
+        .allow_zoom([false, true])
+        .allow_drag([false, true])
+        .allow_boxed_zoom(false)
+        .allow_double_click_reset(false);
+    if let Some(size) = size {
+        plot = plot.width(size.x).height(size.y);
+    }
+
+    plot.show(ui, |plot_ui| {
+        if reset_view || plot_ui.response().double_clicked() {
+            plot_ui.set_plot_bounds_y(default_price_bounds(ctx));
+        }
+        plot_ui.set_plot_bounds_x(cache.x_min..=cache.x_max);
+
+        // --- LAYER RENDERING SYSTEM ---
+
+        // 2. Define Layer Stack (Back to Front)
+        let layers: Vec<Box<dyn PlotLayer>> = vec![
+            Box::new(BackgroundLayer),
+            Box::new(StickyZoneLayer),
+            Box::new(ReversalZoneLayer),
+            Box::new(DepthLadderLayer),
+            Box::new(PriceLineLayer),
+        ];
+
+        // 3. Render Loop
+        for layer in layers {
+            layer.render(plot_ui, ctx);
+        }
+    });
+}
+
+/// Candlesticks over time, with the zones as bands across them and the candle slices
+/// the histogram was built from shaded. Pans and zooms freely; double-click resets.
+/// `jump` frames the journey attempt in `ctx` instead.
+fn show_candle_chart(ui: &mut Ui, ctx: &LayerContext, size: Vec2, reset_view: bool, jump: bool) {
+    let cva = &ctx.trading_model.cva;
+
+    Plot::new("candle_chart")
+        .legend(Legend::default().position(Corner::LeftTop))
+        .custom_x_axes(vec![create_time_axis()])
+        .custom_y_axes(vec![create_y_axis(&cva.pair_name)])
+        .label_formatter(|_, _| String::new())
+        .x_grid_spacer(time_grid_marks)
+        .link_axis(PRICE_AXIS_LINK, [false, true])
+        .allow_double_click_reset(false)
+        .width(size.x)
+        .height(size.y)
+        .show(ui, |plot_ui| {
+            if reset_view || plot_ui.response().double_clicked() {
+                // From the first analysed candle to the latest one
+                let x_min = if cva.slice_ranges_ms.is_empty() {
+                    ctx.x_min
+                } else {
+                    cva.start_timestamp_ms as f64
+                };
+                let margin = (ctx.x_max - x_min) * 0.02;
+                plot_ui.set_plot_bounds_x(x_min - margin..=ctx.x_max + margin);
+                plot_ui.set_plot_bounds_y(default_price_bounds(ctx));
+            }
+            if let (true, Some(focus), Some(series)) = (jump, ctx.journey_focus, ctx.candles) {
+                let (x_range, y_range) = journey_focus_bounds(focus, series);
+                plot_ui.set_plot_bounds_x(x_range);
+                plot_ui.set_plot_bounds_y(y_range);
+            }
+
+            // Back to Front
+            let layers: Vec<Box<dyn PlotLayer>> = vec![
+                Box::new(CvaSliceLayer),
+                Box::new(StickyZoneLayer),
+                Box::new(ReversalBandLayer),
+                Box::new(JourneyAttemptLayer),
+                Box::new(CandlestickLayer),
+                Box::new(PriceLineLayer),
+            ];
+            for layer in layers {
+                layer.render(plot_ui, ctx);
+            }
+        });
+}
+
+/// The attempt with as much time again either side of it, and every price it, its
+/// start and its target touched in that window
+fn journey_focus_bounds(
+    focus: &JourneyFocus,
+    series: &OhlcvTimeSeries,
+) -> (RangeInclusive<f64>, RangeInclusive<f64>) {
+    let interval_ms = series.pair_interval.interval_ms.max(1);
+    let span_ms = (focus.end_ms - focus.start_ms).max(interval_ms);
+    let (x_min, x_max) = (focus.start_ms - span_ms, focus.end_ms + span_ms);
+
+    let index_at = |ms: i64| {
+        ((ms - series.first_kline_timestamp_ms).max(0) / interval_ms).min(series.klines() as i64)
+            as usize
+    };
+    let (first, end) = (index_at(x_min), index_at(x_max + interval_ms));
+    let (mut low, mut high) = (
+        focus.start_price.min(focus.target_price),
+        focus.start_price.max(focus.target_price),
+    );
+    for idx in first..end {
+        low = low.min(series.low_prices[idx]);
+        high = high.max(series.high_prices[idx]);
+    }
+    let margin = (high - low) * 0.05;
+
+    (x_min as f64..=x_max as f64, low - margin..=high + margin)
+}
+
+/// Marks on round UTC steps (an hour up to a year), no more than about eight in view
+fn time_grid_marks(input: GridInput) -> Vec<GridMark> {
+    const MAX_MARKS: f64 = 8.0;
+    const STEPS_MS: [i64; 8] = [
+        TimeUtils::MS_IN_H,
+        TimeUtils::MS_IN_4_H,
+        TimeUtils::MS_IN_12_H,
+        TimeUtils::MS_IN_D,
+        TimeUtils::MS_IN_W,
+        30 * TimeUtils::MS_IN_D,
+        91 * TimeUtils::MS_IN_D,
+        365 * TimeUtils::MS_IN_D,
+    ];
+
+    let (min, max) = input.bounds;
+    let span = max - min;
+    if !span.is_finite() || span <= 0.0 {
+        return Vec::new();
+    }
+    let year_ms = (365 * TimeUtils::MS_IN_D) as f64;
+    let step = STEPS_MS
+        .iter()
+        .map(|&step| step as f64)
+        .find(|&step| span / step <= MAX_MARKS)
+        .unwrap_or_else(|| (span / (MAX_MARKS * year_ms)).ceil() * year_ms);
+
+    let start = (min / step).ceil() as i64;
+    let end = (max / step).floor() as i64;
+    (start..=end)
+        .map(|i| GridMark {
+            value: i as f64 * step,
+            step_size: step,
+        })
+        .collect()
+}
+
+fn create_time_axis() -> AxisHints<'static> {
+    AxisHints::new_x()
+        .label(UI_TEXT.plot_time_axis)
+        .formatter(|grid_mark, _range| {
+            let with_time = grid_mark.step_size < TimeUtils::MS_IN_D as f64;
+            format_timestamp_ms(grid_mark.value as i64, with_time)
+        })
+}
+
 fn create_x_axis(_plot_cache: &PlotCache) -> AxisHints<'static> {
     AxisHints::new_x()
         .label(UI_TEXT.plot_x_axis)
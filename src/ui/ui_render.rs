@@ -4,14 +4,16 @@ use eframe::egui::{
 };
 
 use crate::config::ANALYSIS;
+use crate::data::pair_loading::PairLoadStatus;
 use crate::models::cva::ScoreType;
 use crate::ui::app_simulation::SimDirection;
 use crate::ui::config::{UI_CONFIG, UI_TEXT};
 use crate::ui::styles::UiStyleExt;
-use crate::ui::ui_panels::{DataGenerationEventChanged, Panel};
+use crate::ui::ui_panels::{DataGenerationEventChanged, JourneyPanelEvent, Panel};
 
 use super::app::ZoneSniperApp;
 use crate::ui::utils::format_price;
+use crate::utils::time_utils::local_now_as_timestamp_ms;
 
 impl ZoneSniperApp {
     pub(super) fn render_side_panel(&mut self, ctx: &Context) {
@@ -36,6 +38,10 @@ impl ZoneSniperApp {
                     }
                 }
 
+                self.pair_load_panel(ui);
+                self.data_quality_panel(ui);
+                self.journey_panel(ui);
+
                 for event in data_events {
                     match event {
                         DataGenerationEventChanged::Pair(new_pair) => {
@@ -83,7 +89,26 @@ impl ZoneSniperApp {
                     return;
                 };
 
-                // 2. Safety Check: Selected Pair
+                // 2. Pairs still loading (or none could be)
+                let load_status = &engine.load_status;
+                if engine.get_active_pair_count() == 0 {
+                    if load_status.is_loading() {
+                        render_fullscreen_message(
+                            ui,
+                            "Loading Pairs...",
+                            &loading_subtitle(load_status),
+                            false,
+                        );
+                    } else {
+                        let reason = load_status.error.as_deref().unwrap_or(
+                            "None of the pairs could be loaded (see the panel on the left).",
+                        );
+                        render_fullscreen_message(ui, "No Pair Data", reason, true);
+                    }
+                    return;
+                }
+
+                // 3. Safety Check: Selected Pair
                 let Some(pair) = self.selected_pair.clone() else {
                     render_fullscreen_message(
                         ui,
@@ -94,7 +119,34 @@ impl ZoneSniperApp {
                     return;
                 };
 
-                // 3. Get Price State (Do we have a live price?)
+                // 4. Selected Pair not (yet) loaded
+                if !engine.pairs.contains_key(&pair) {
+                    if let Some(reason) = load_status.failure(&pair) {
+                        render_fullscreen_message(
+                            ui,
+                            &format!("{} Not Loaded", pair),
+                            reason,
+                            true,
+                        );
+                    } else if load_status.is_loading() {
+                        render_fullscreen_message(
+                            ui,
+                            &format!("Loading {}...", pair),
+                            &loading_subtitle(load_status),
+                            false,
+                        );
+                    } else {
+                        render_fullscreen_message(
+                            ui,
+                            &format!("{} Not Loaded", pair),
+                            "Select a pair on the left.",
+                            true,
+                        );
+                    }
+                    return;
+                }
+
+                // 5. Get Price State (Do we have a live price?)
                 let current_price = self.get_display_price(&pair); // engine.get_price(&pair);
 
                 let (is_calculating, last_error) = engine.get_pair_status(&pair);
@@ -114,6 +166,13 @@ impl ZoneSniperApp {
                 // PRIORITY 2: VALID MODEL
                 // If no error, and we have data, draw it.
                 else if let Some(model) = engine.get_model(&pair) {
+                    // The live book means nothing against a simulated price
+                    let depth = if self.is_simulation_mode {
+                        None
+                    } else {
+                        engine.get_depth(&pair)
+                    };
+                    self.plot_view.set_depth(depth);
                     self.plot_view.show_my_plot(
                         ui,
                         &model.cva,
@@ -121,6 +180,7 @@ impl ZoneSniperApp {
                         current_price,
                         self.debug_background_mode,
                         &self.plot_visibility,
+                        engine.get_series(&pair),
                     );
 
                     // Optional: Small loading indicator overlay if updating in background
@@ -207,12 +267,31 @@ impl ZoneSniperApp {
                                 );
                                 ui.separator();
 
+                                let stale_age_sec = self.engine.as_ref().and_then(|engine| {
+                                    if !engine.is_price_stale(pair) {
+                                        return None;
+                                    }
+                                    let quote = engine.price_stream.get_quote(pair)?;
+                                    Some(quote.age_ms(local_now_as_timestamp_ms()) / 1000)
+                                });
                                 if let Some(price) = self.get_display_price(pair) {
+                                    let color = if stale_age_sec.is_some() {
+                                        Color32::from_rgb(255, 150, 0) // Orange
+                                    } else {
+                                        Color32::from_rgb(100, 200, 100) // Light Green
+                                    };
                                     ui.label(
                                         RichText::new(format!("💰 {}", format_price(price)))
                                             .strong()
-                                            .color(Color32::from_rgb(100, 200, 100)), // Light Green
+                                            .color(color),
                                     );
+                                    if let Some(age_sec) = stale_age_sec {
+                                        ui.label(
+                                            RichText::new(format!("⏸ stale ({}s)", age_sec))
+                                                .small()
+                                                .color(color),
+                                        );
+                                    }
                                 } else {
                                     ui.label("Connecting...");
                                 }
@@ -306,6 +385,19 @@ impl ZoneSniperApp {
                             let total_pairs = engine.get_active_pair_count();
                             ui.metric("📊 Pairs", &format!("{}", total_pairs), Color32::LIGHT_GRAY);
 
+                            // Pairs still loading in the background
+                            if engine.load_status.is_loading() {
+                                ui.separator();
+                                ui.label(
+                                    RichText::new(format!(
+                                        "⏳ Loading {}",
+                                        loading_subtitle(&engine.load_status)
+                                    ))
+                                    .small()
+                                    .color(Color32::from_rgb(255, 165, 0)), // Orange
+                                );
+                            }
+
                             // Worker Status
                             if let Some(msg) = engine.get_worker_status_msg() {
                                 ui.separator();
@@ -342,9 +434,42 @@ impl ZoneSniperApp {
                             };
                             ui.metric(
                                 &format!("{} Live Prices", icon),
-                                &format!("{:.0}% connected", health),
+                                &format!(
+                                    "{:.0}% connected ({} sockets)",
+                                    health,
+                                    engine.price_stream.connection_count()
+                                ),
                                 color,
                             );
+
+                            let stale = engine.price_stream.stale_symbols();
+                            if !stale.is_empty() {
+                                ui.label(
+                                    RichText::new(format!("⏸ {} stale", stale.len()))
+                                        .small()
+                                        .color(Color32::from_rgb(255, 150, 0)),
+                                )
+                                .on_hover_text(stale.join(", ").to_uppercase());
+                            }
+
+                            // Top of book for the selected pair
+                            let book = self
+                                .selected_pair
+                                .as_deref()
+                                .and_then(|pair| engine.get_book(pair));
+                            if let Some(book) = book {
+                                ui.separator();
+                                ui.metric(
+                                    "↔ Bid / Ask",
+                                    &format!(
+                                        "{} / {} ({:.3}%)",
+                                        format_price(book.bid.price),
+                                        format_price(book.ask.price),
+                                        book.spread_pct()
+                                    ),
+                                    Color32::LIGHT_GRAY,
+                                );
+                            }
                         }
                     });
                 });
@@ -385,6 +510,8 @@ impl ZoneSniperApp {
                         "3",
                         &("Toggle ".to_owned() + &UI_TEXT.label_upper_wick_zones),
                     ),
+                    ("L", UI_TEXT.label_help_depth_ladder),
+                    ("W", UI_TEXT.label_help_reload_watchlist),
                 ];
 
                 Grid::new("general_shortcuts_grid")
@@ -458,22 +585,68 @@ impl ZoneSniperApp {
         panel.render(ui)
     }
 
+    /// Shown while pairs load, and afterwards if any failed
+    fn pair_load_panel(&mut self, ui: &mut Ui) {
+        let Some(engine) = &self.engine else {
+            return;
+        };
+        let status = &engine.load_status;
+        if status.is_loading() || !status.failures.is_empty() || status.error.is_some() {
+            crate::ui::ui_panels::PairLoadPanel::new(status).render(ui);
+        }
+    }
+
+    fn data_quality_panel(&mut self, ui: &mut Ui) {
+        let report = match (&self.engine, &self.selected_pair) {
+            (Some(engine), Some(pair)) => engine.get_data_quality(pair),
+            _ => None,
+        };
+        let mut panel = crate::ui::ui_panels::DataQualityPanel::new(report);
+        panel.render(ui);
+    }
+
+    fn journey_panel(&mut self, ui: &mut Ui) {
+        let journeys = match (&self.engine, &self.selected_pair) {
+            (Some(engine), Some(pair)) => engine.get_journeys(pair),
+            _ => None,
+        };
+        let events = crate::ui::ui_panels::JourneyPanel::new(
+            journeys,
+            self.plot_view.journey_focus(),
+            self.journey_export_status.as_deref(),
+        )
+        .render(ui);
+
+        for event in events {
+            match event {
+                JourneyPanelEvent::ShowAttempt(focus) => self.plot_view.focus_journey(focus),
+                #[cfg(not(target_arch = "wasm32"))]
+                JourneyPanelEvent::Export { zone_index, format } => {
+                    self.export_journey(zone_index, format)
+                }
+                #[cfg(target_arch = "wasm32")]
+                JourneyPanelEvent::Export { .. } => {}
+            }
+        }
+    }
+
     fn data_generation_panel(
         &mut self,
         ui: &mut eframe::egui::Ui,
     ) -> Vec<crate::ui::ui_panels::DataGenerationEventChanged> {
-        // Use Engine or Config for available pairs
-        let available_pairs = if let Some(engine) = &self.engine {
-            engine.get_all_pair_names()
+        // Use Engine or Config for available pairs (grouped as in the watchlist)
+        let (pair_groups, symbols) = if let Some(engine) = &self.engine {
+            (engine.get_pair_groups(), engine.symbols.clone())
         } else {
-            Vec::new()
+            (Vec::new(), Default::default())
         };
 
         // Pass global constant zone_count from ANALYSIS
         let mut panel = crate::ui::ui_panels::DataGenerationPanel::new(
             ANALYSIS.zone_count,
             self.selected_pair.clone(),
-            available_pairs,
+            pair_groups,
+            symbols,
             &self.app_config.price_horizon,
             self.app_config.time_horizon.default_days,
         );
@@ -492,6 +665,17 @@ impl ZoneSniperApp {
             if i.key_pressed(Key::Num3) {
                 self.plot_visibility.high_wicks = !self.plot_visibility.high_wicks;
             }
+            if i.key_pressed(Key::L) {
+                self.plot_visibility.depth = !self.plot_visibility.depth;
+            }
+
+            // 'W'atchlist reload (overrides and enabled pairs)
+            if i.key_pressed(Key::W) {
+                if let Some(engine) = &mut self.engine {
+                    engine.reload_watchlist();
+                    engine.trigger_global_recalc(self.selected_pair.clone());
+                }
+            }
 
             if i.key_pressed(Key::H) {
                 self.show_debug_help = !self.show_debug_help;
@@ -554,6 +738,14 @@ impl ZoneSniperApp {
     }
 }
 
+/// "12/40 pairs, ETA 30s" once the download reports progress, a count until then
+fn loading_subtitle(status: &PairLoadStatus) -> String {
+    match status.progress {
+        Some(progress) if progress.total > 0 => progress.to_string(),
+        _ => format!("{} pairs loaded so far", status.loaded),
+    }
+}
+
 fn render_fullscreen_message(ui: &mut Ui, title: &str, subtitle: &str, is_error: bool) {
     ui.vertical_centered(|ui| {
         ui.add_space(40.0);
@@ -6,6 +6,8 @@ pub struct UiText {
     pub view_options_heading: &'static str,
     pub view_data_source_heading: &'static str,
     pub signals_heading: &'static str,
+    pub data_quality_heading: &'static str,
+    pub pair_load_heading: &'static str,
     pub price_horizon_helper_prefix: &'static str,
     pub price_horizon_helper_suffix: &'static str,
     pub time_horizon_helper_prefix: &'static str,
@@ -39,14 +41,22 @@ pub struct UiText {
     pub journey_zone_label_kelly_short: &'static str,
     pub journey_zone_label_worst_loss_short: &'static str,
     pub journey_zone_label_avg_drawdown_short: &'static str,
+    pub journey_monte_carlo_label: &'static str,
+    pub journey_monte_carlo_ruin_label: &'static str,
+    pub journey_obstructed_label: &'static str,
+    pub journey_clear_path_label: &'static str,
     pub plot_x_axis: &'static str,
     pub plot_y_axis: &'static str,
+    pub plot_time_axis: &'static str,
     pub plot_strongest_zone: &'static str,
     pub plot_this_zone_is: &'static str,
 
     pub label_volume: &'static str,
     pub label_reversal_support: &'static str,
     pub label_reversal_resistance: &'static str,
+    pub label_candles: &'static str,
+    pub label_cva_slices: &'static str,
+    pub label_journey_attempt: &'static str,
     pub label_lower_wick_count: &'static str,
     pub label_upper_wick_count:&'static str,
     pub label_hvz: &'static str,
@@ -56,6 +66,8 @@ pub struct UiText {
     pub label_hvz_beneath: &'static str,
     pub label_hvz_within: &'static str,
     pub label_help_background: &'static str,
+    pub label_help_depth_ladder: &'static str,
+    pub label_help_reload_watchlist: &'static str,
     pub label_help_sim_toggle_direction: &'static str,
     pub label_help_sim_step_size: &'static str,
     pub label_help_sim_activate_price_change: &'static str,
@@ -73,6 +85,8 @@ pub const UI_TEXT: UiText = UiText {
     view_options_heading: "View Options",
     view_data_source_heading: "Data Source",
     signals_heading: "🎯 Signals",
+    data_quality_heading: "🩺 Data Quality",
+    pair_load_heading: "⏳ Pair Loading",
     price_horizon_helper_prefix: "Focus on price action within ±",
     price_horizon_helper_suffix: "% of current price",
     time_horizon_helper_prefix: "Focus on trades that complete within ",
@@ -106,8 +120,13 @@ pub const UI_TEXT: UiText = UiText {
     journey_zone_label_kelly_short: "Kelly",
     journey_zone_label_worst_loss_short: "worst loss",
     journey_zone_label_avg_drawdown_short: "avg drawdown",
+    journey_monte_carlo_label: "Monte Carlo",
+    journey_monte_carlo_ruin_label: "ruin",
+    journey_obstructed_label: "Path blocked by",
+    journey_clear_path_label: "Path clear of reversal zones",
     plot_y_axis: "Price",
     plot_x_axis: "Key Zone Strength (0 % of the strongest zone)",
+    plot_time_axis: "Time (UTC)",
     plot_strongest_zone: "of strongest zone",
     plot_this_zone_is: "This zone is",
     
@@ -119,11 +138,16 @@ pub const UI_TEXT: UiText = UiText {
     label_upper_wick_zones: "Upper Wick Zones",
     label_reversal_support: "`High Lower Wick Count Zone` (HLWCZ)  (reversal likely) ",
     label_reversal_resistance: "`High Upper Wick Count Zone` (HUWCZ)  (reversal likely)",
+    label_candles: "Candles",
+    label_cva_slices: "Candles in the histogram",
+    label_journey_attempt: "Journey attempt",
     label_hvz_above: "`High Volume Zone` (HVZ) is above (if bullish, acts as future target price)",
     label_hvz_beneath: "`High Volume Zone` (HVZ) is below (if bearish, acts as future target price)",
     label_hvz_within: "Inside `High Volume Zone` (HVZ) now (consolidating...)",
 
     label_help_background: "Rotate Background Data Selection (between (1) Trading Volume, (2) Lower Wick Count ,(3) Upper Wick Count",
+    label_help_depth_ladder: "Toggle live order-book depth ladder",
+    label_help_reload_watchlist: "Reload the watchlist file (overrides, enabled pairs)",
     label_help_sim_toggle_direction: "Toggle direction (⬆️ UP / ⬇️ DOWN)",
     label_help_sim_step_size: "Cycle step size (0.1% → 1% → 5% → 10%)",
     label_help_sim_activate_price_change:"Activate price change in current direction",
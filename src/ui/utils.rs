@@ -1,6 +1,7 @@
 use eframe::egui::{Context, RichText, Ui, Visuals};
 
 use crate::ui::config::UI_CONFIG;
+use crate::utils::TimeUtils;
 
 /// Creates a colored heading with uppercase text and monospace font
 pub fn colored_heading(text: impl Into<String>) -> RichText {
@@ -73,4 +74,17 @@ pub fn format_price(price: f64) -> String {
         // Sub-penny / Meme coins: 8 decimals needed to see movement
         format!("${:.8}", price)
     }
-}
\ No newline at end of file
+}
+
+/// Formats a UTC timestamp for chart labels: the date, plus the time of day if `with_time`.
+/// Timestamps chrono can't represent (far outside the data) come back empty.
+pub fn format_timestamp_ms(timestamp_ms: i64, with_time: bool) -> String {
+    let format = if with_time {
+        "%Y-%m-%d %H:%M"
+    } else {
+        TimeUtils::STANDARD_TIME_FORMAT
+    };
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|datetime| datetime.format(format).to_string())
+        .unwrap_or_default()
+}
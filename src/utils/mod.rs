@@ -1,4 +1,5 @@
 pub mod app_time;
+pub mod checksum;
 pub mod maths_utils;
 pub mod time_utils;
 pub mod vec_utils;
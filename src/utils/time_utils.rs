@@ -44,6 +44,30 @@ impl TimeUtils {
             _ => "unknown",
         }
     }
+
+    /// Inverse of `interval_to_string`: parse a Binance-style shorthand into milliseconds.
+    pub fn interval_from_string(interval: &str) -> Option<i64> {
+        [
+            Self::MS_IN_S,
+            Self::MS_IN_MIN,
+            Self::MS_IN_3_MIN,
+            Self::MS_IN_5_MIN,
+            Self::MS_IN_15_MIN,
+            Self::MS_IN_30_MIN,
+            Self::MS_IN_H,
+            Self::MS_IN_2_H,
+            Self::MS_IN_4_H,
+            Self::MS_IN_6_H,
+            Self::MS_IN_8_H,
+            Self::MS_IN_12_H,
+            Self::MS_IN_D,
+            Self::MS_IN_3_D,
+            Self::MS_IN_W,
+            Self::MS_IN_1_M,
+        ]
+        .into_iter()
+        .find(|&ms| Self::interval_to_string(ms) == interval)
+    }
 }
 
 #[allow(dead_code)]
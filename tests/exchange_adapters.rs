@@ -0,0 +1,153 @@
+//! The exchange adapters (`data::exchange`) run against the mock Binance server: symbols,
+//! paged klines and prices from Binance spot, USDⓈ-M futures and the generic protocol,
+//! each reached through a venue-prefixed pair name.
+//!
+//! The adapters build their REST clients once per process from the base URL overrides, so
+//! everything that talks to the mock lives in one test.
+
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+use zone_sniper::data::exchange::exchange_for;
+use zone_sniper::data::mock_binance::{MockBinanceConfig, MockBinanceServer};
+use zone_sniper::domain::pair_interval::PairInterval;
+use zone_sniper::domain::venue::Venue;
+
+const INTERVAL_MS: i64 = 30 * 60 * 1000;
+const FIRST_OPEN_MS: i64 = 1_700_000_000_000 - 1_700_000_000_000 % INTERVAL_MS;
+const KLINES: usize = 2_500;
+
+/// A fixtures dir holding `KLINES` BTCUSDT 30m klines, closing at 100, 101, ...
+fn fixtures_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "zone_sniper_exchange_adapters_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rows: Vec<Value> = (0..KLINES)
+        .map(|i| {
+            let open_time = FIRST_OPEN_MS + i as i64 * INTERVAL_MS;
+            let close = 100.0 + i as f64;
+            json!([
+                open_time,
+                close.to_string(),
+                (close + 1.0).to_string(),
+                (close - 1.0).to_string(),
+                close.to_string(),
+                "1",
+                open_time + INTERVAL_MS - 1,
+                close.to_string(),
+                0,
+                "0",
+                "0",
+                "0"
+            ])
+        })
+        .collect();
+    std::fs::write(dir.join("BTCUSDT_30m.json"), json!(rows).to_string()).unwrap();
+    dir
+}
+
+/// Requests the server saw for `path` since the log was `seen` entries long
+fn requests_to(server: &MockBinanceServer, seen: usize, path: &str) -> usize {
+    server.request_log()[seen..]
+        .iter()
+        .filter(|line| line.contains(&format!(" {}", path)))
+        .count()
+}
+
+async fn check_adapter(server: &MockBinanceServer, pair_name: &str, klines_path: &str) {
+    let (venue, symbol) = Venue::split(pair_name);
+    assert_eq!(symbol, "BTCUSDT");
+    let exchange = exchange_for(venue);
+    assert_eq!(exchange.venue(), venue);
+
+    let symbols = exchange.fetch_symbols().await.unwrap();
+    let btc = symbols.iter().find(|s| s.symbol == "BTCUSDT").unwrap();
+    assert_eq!(
+        (btc.base_asset.as_str(), btc.quote_asset.as_str()),
+        ("BTC", "USDT")
+    );
+
+    let seen = server.request_log().len();
+    let pair_interval = PairInterval {
+        name: pair_name.to_string(),
+        interval_ms: INTERVAL_MS,
+    };
+    let loaded = exchange.fetch_klines(pair_interval.clone()).await.unwrap();
+
+    // Pages of 1000, 1000 and 502, each dropping its newest kline
+    assert_eq!(loaded.pair_interval, pair_interval, "{}", pair_name);
+    assert_eq!(loaded.klines.len(), KLINES - 1, "{}", pair_name);
+    assert_eq!(loaded.first_timestamp_ms(), FIRST_OPEN_MS);
+    assert_eq!(
+        loaded.last_timestamp_ms(),
+        FIRST_OPEN_MS + (KLINES as i64 - 2) * INTERVAL_MS
+    );
+    assert!(
+        loaded
+            .klines
+            .windows(2)
+            .all(|w| w[1].open_timestamp_ms - w[0].open_timestamp_ms == INTERVAL_MS)
+    );
+    assert_eq!(loaded.klines[0].close_price, Some(100.0));
+    assert_eq!(requests_to(server, seen, klines_path), 3, "{}", pair_name);
+
+    let prices = exchange.fetch_prices().await.unwrap();
+    assert_eq!(prices["BTCUSDT"], 100.0 + (KLINES - 1) as f64);
+}
+
+#[tokio::test]
+async fn every_adapter_loads_symbols_paged_klines_and_prices_from_the_mock() {
+    let server = MockBinanceServer::start(MockBinanceConfig::new(fixtures_dir()))
+        .await
+        .unwrap();
+    for (var, value) in server.env_overrides() {
+        // SAFETY: the only test in this binary that touches the environment, and it sets
+        // the overrides before any adapter reads them
+        unsafe { std::env::set_var(var, value) };
+    }
+
+    check_adapter(&server, "BTCUSDT", "/api/v3/klines").await;
+    check_adapter(&server, "binance:BTCUSDT", "/api/v3/klines").await;
+    check_adapter(&server, "binance-futures:BTCUSDT", "/fapi/v1/klines").await;
+    check_adapter(&server, "generic:BTCUSDT", "/v1/candles").await;
+}
+
+#[test]
+fn venue_prefixed_names_split_and_qualify() {
+    assert_eq!(Venue::split("BTCUSDT"), (Venue::BinanceSpot, "BTCUSDT"));
+    assert_eq!(
+        Venue::split("binance:BTCUSDT"),
+        (Venue::BinanceSpot, "BTCUSDT")
+    );
+    assert_eq!(
+        Venue::split("Binance-Futures:ETHUSDT"),
+        (Venue::BinanceFutures, "ETHUSDT")
+    );
+    assert_eq!(Venue::split("generic:SOLUSDT"), (Venue::Generic, "SOLUSDT"));
+    // Unknown prefixes are a spot symbol, returned whole
+    assert_eq!(
+        Venue::split("kraken:XBTUSD"),
+        (Venue::BinanceSpot, "kraken:XBTUSD")
+    );
+
+    for venue in Venue::ALL {
+        let name = venue.qualify("BTCUSDT");
+        assert_eq!(Venue::split(&name), (venue, "BTCUSDT"));
+        assert!(!Venue::path_safe(&name).contains(':'));
+    }
+    assert_eq!(Venue::BinanceSpot.qualify("BTCUSDT"), "BTCUSDT");
+    assert_eq!(
+        Venue::path_safe("binance-futures:BTCUSDT"),
+        "binance-futures@BTCUSDT"
+    );
+    assert_eq!(Venue::path_safe("BTCUSDT"), "BTCUSDT");
+
+    let futures = PairInterval {
+        name: "binance-futures:BTCUSDT".to_string(),
+        interval_ms: INTERVAL_MS,
+    };
+    assert_eq!(futures.venue(), Venue::BinanceFutures);
+    assert_eq!(futures.venue_symbol(), "BTCUSDT");
+}
@@ -0,0 +1,351 @@
+//! The mock Binance server (`data::mock_binance`) exercised over real sockets:
+//! kline pagination, injected 429 / 418 / 5xx / dropped responses, the weight limit,
+//! and per-connection WebSocket scripts across reconnects.
+
+use anyhow::{Result, anyhow};
+use futures::StreamExt;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+use zone_sniper::data::fetch_scheduler::{FetchScheduler, Throttled};
+use zone_sniper::data::mock_binance::{MockBinanceConfig, MockBinanceServer, RestFault, WsEvent};
+use zone_sniper::data::timeseries::bnapi_version::bn_kline::{
+    BNKline, convert_klines, load_klines_paged,
+};
+use zone_sniper::domain::pair_interval::PairInterval;
+use zone_sniper::domain::venue::Venue;
+
+const INTERVAL_MS: i64 = 30 * 60 * 1000;
+const FIRST_OPEN_MS: i64 = 1_700_000_000_000 - 1_700_000_000_000 % INTERVAL_MS;
+const KLINES: usize = 2_500;
+
+/// A fixtures dir of its own for each test, holding `KLINES` BTCUSDT 30m klines
+fn fixtures_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "zone_sniper_mock_binance_{}_{}",
+        test,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rows: Vec<Value> = (0..KLINES)
+        .map(|i| {
+            let open_time = FIRST_OPEN_MS + i as i64 * INTERVAL_MS;
+            let close = 100.0 + i as f64;
+            json!([
+                open_time,
+                close.to_string(),
+                (close + 1.0).to_string(),
+                (close - 1.0).to_string(),
+                close.to_string(),
+                "1",
+                open_time + INTERVAL_MS - 1,
+                close.to_string(),
+                0,
+                "0",
+                "0",
+                "0"
+            ])
+        })
+        .collect();
+    std::fs::write(dir.join("BTCUSDT_30m.json"), json!(rows).to_string()).unwrap();
+    dir
+}
+
+async fn start(test: &str) -> MockBinanceServer {
+    MockBinanceServer::start(MockBinanceConfig::new(fixtures_dir(test)))
+        .await
+        .unwrap()
+}
+
+/// GET `path_and_query`; 418 / 429 / 5xx come back as `Throttled`, like the adapters do
+async fn get(server: &MockBinanceServer, path_and_query: &str) -> Result<Value> {
+    let response = reqwest::get(format!("{}{}", server.rest_base_url(), path_and_query)).await?;
+    let status = response.status().as_u16();
+    let retry_after = response
+        .headers()
+        .get("Retry-After")
+        .and_then(|v| v.to_str().ok()?.parse::<u64>().ok())
+        .map(Duration::from_secs);
+    let body = response.text().await?;
+    match status {
+        200 => Ok(serde_json::from_str(&body)?),
+        418 | 429 | 500..=599 => Err(Throttled {
+            status,
+            retry_after,
+            message: body,
+        }
+        .into()),
+        _ => Err(anyhow!("HTTP {}: {}", status, body)),
+    }
+}
+
+fn throttled(error: &anyhow::Error) -> &Throttled {
+    error
+        .downcast_ref::<Throttled>()
+        .expect("a Throttled error")
+}
+
+async fn klines_page(server: &MockBinanceServer, end_time: Option<i64>) -> Result<Vec<BNKline>> {
+    let mut query = "/api/v3/klines?symbol=BTCUSDT&interval=30m&limit=1000".to_string();
+    if let Some(end_time) = end_time {
+        query.push_str(&format!("&endTime={}", end_time));
+    }
+    let rows: Vec<Vec<Value>> = serde_json::from_value(get(server, &query).await?)?;
+    Ok(convert_klines(&rows)?)
+}
+
+#[tokio::test]
+async fn paged_kline_load_reads_the_whole_fixture() {
+    let server = start("paging").await;
+    let pair_interval = PairInterval {
+        name: "BTCUSDT".to_string(),
+        interval_ms: INTERVAL_MS,
+    };
+
+    let loaded = load_klines_paged(pair_interval, 1000, |end_time| {
+        klines_page(&server, end_time)
+    })
+    .await
+    .unwrap();
+
+    // Every page drops its newest kline (the overlap with the page after it), the first
+    // page included: live, that one is the candle still forming
+    assert_eq!(loaded.klines.len(), KLINES - 1);
+    assert_eq!(loaded.first_timestamp_ms(), FIRST_OPEN_MS);
+    assert_eq!(
+        loaded.last_timestamp_ms(),
+        FIRST_OPEN_MS + (KLINES as i64 - 2) * INTERVAL_MS
+    );
+    assert!(
+        loaded
+            .klines
+            .windows(2)
+            .all(|w| w[1].open_timestamp_ms - w[0].open_timestamp_ms == INTERVAL_MS)
+    );
+    // Pages of 1000, 1000 and 502: each one after the first repeats its end_time
+    assert_eq!(server.request_log().len(), 3);
+}
+
+#[tokio::test]
+async fn klines_honour_start_time_end_time_and_limit() {
+    let server = start("range").await;
+    let open = |i: i64| FIRST_OPEN_MS + i * INTERVAL_MS;
+    let open_times = |rows: Value| -> Vec<i64> {
+        rows.as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row[0].as_i64().unwrap())
+            .collect()
+    };
+
+    // startTime: the oldest `limit` from there
+    let from_start = get(
+        &server,
+        &format!(
+            "/api/v3/klines?symbol=BTCUSDT&interval=30m&limit=3&startTime={}",
+            open(10)
+        ),
+    )
+    .await
+    .unwrap();
+    assert_eq!(open_times(from_start), vec![open(10), open(11), open(12)]);
+
+    // endTime only: the newest `limit` up to and including it
+    let to_end = get(
+        &server,
+        &format!(
+            "/api/v3/klines?symbol=BTCUSDT&interval=30m&limit=3&endTime={}",
+            open(10)
+        ),
+    )
+    .await
+    .unwrap();
+    assert_eq!(open_times(to_end), vec![open(8), open(9), open(10)]);
+
+    // limit is capped at 1000, like Binance
+    let capped = get(
+        &server,
+        "/api/v3/klines?symbol=BTCUSDT&interval=30m&limit=5000",
+    )
+    .await
+    .unwrap();
+    assert_eq!(capped.as_array().unwrap().len(), 1000);
+
+    let unknown = get(&server, "/api/v3/klines?symbol=NOPEUSDT&interval=30m")
+        .await
+        .unwrap_err();
+    assert!(unknown.to_string().contains("-1121"), "{}", unknown);
+}
+
+#[tokio::test]
+async fn injected_faults_are_served_in_order_then_requests_succeed() {
+    let server = start("faults").await;
+    server.inject_rest_fault(RestFault::TooManyRequests {
+        retry_after_secs: 7,
+    });
+    server.inject_rest_fault(RestFault::IpBan {
+        retry_after_secs: 120,
+    });
+    server.inject_rest_fault(RestFault::ServerError(503));
+    server.inject_rest_fault(RestFault::Disconnect);
+
+    let path = "/api/v3/ticker/price";
+    let too_many = get(&server, path).await.unwrap_err();
+    assert_eq!(throttled(&too_many).status, 429);
+    assert_eq!(
+        throttled(&too_many).retry_after,
+        Some(Duration::from_secs(7))
+    );
+
+    let banned = get(&server, path).await.unwrap_err();
+    assert_eq!(throttled(&banned).status, 418);
+    assert_eq!(
+        throttled(&banned).retry_after,
+        Some(Duration::from_secs(120))
+    );
+
+    let unavailable = get(&server, path).await.unwrap_err();
+    assert_eq!(throttled(&unavailable).status, 503);
+    assert_eq!(throttled(&unavailable).retry_after, None);
+
+    // No response at all: a transport error, not a status
+    let dropped = get(&server, path).await.unwrap_err();
+    assert!(dropped.downcast_ref::<Throttled>().is_none());
+    assert!(dropped.downcast_ref::<reqwest::Error>().is_some());
+
+    let tickers = get(&server, path).await.unwrap();
+    assert_eq!(tickers[0]["symbol"], "BTCUSDT");
+    assert_eq!(
+        tickers[0]["price"],
+        (100.0 + (KLINES - 1) as f64).to_string()
+    );
+    assert_eq!(server.request_log().len(), 5);
+}
+
+#[tokio::test]
+async fn scheduler_retries_through_429_and_5xx_but_stops_at_a_418() {
+    let server = start("scheduler").await;
+    server.inject_rest_fault(RestFault::TooManyRequests {
+        retry_after_secs: 1,
+    });
+    server.inject_rest_fault(RestFault::ServerError(502));
+
+    let started = std::time::Instant::now();
+    let tickers = FetchScheduler::shared()
+        .run(Venue::Generic, 1, || get(&server, "/v1/prices"))
+        .await
+        .unwrap();
+
+    assert_eq!(tickers[0]["symbol"], "BTCUSDT");
+    assert_eq!(server.request_log().len(), 3);
+    // The Retry-After pause of a second
+    assert!(started.elapsed() >= Duration::from_secs(1));
+
+    // A ban isn't retried, and nothing more is sent until it's over
+    server.inject_rest_fault(RestFault::IpBan {
+        retry_after_secs: 1,
+    });
+    let banned = FetchScheduler::shared()
+        .run(Venue::Generic, 1, || get(&server, "/v1/prices"))
+        .await
+        .unwrap_err();
+    let banned_at = std::time::Instant::now();
+    assert_eq!(throttled(&banned).status, 418);
+    let refused = FetchScheduler::shared()
+        .run(Venue::Generic, 1, || get(&server, "/v1/prices"))
+        .await
+        .unwrap_err();
+    assert!(refused.downcast_ref::<Throttled>().is_none());
+    assert_eq!(server.request_log().len(), 4);
+
+    tokio::time::sleep(Duration::from_millis(1100).saturating_sub(banned_at.elapsed())).await;
+    FetchScheduler::shared()
+        .run(Venue::Generic, 1, || get(&server, "/v1/prices"))
+        .await
+        .unwrap();
+    assert_eq!(server.request_log().len(), 5);
+}
+
+#[tokio::test]
+async fn exceeding_the_weight_limit_answers_429_until_the_next_minute() {
+    let config = MockBinanceConfig {
+        weight_limit_minute: 5,
+        ..MockBinanceConfig::new(fixtures_dir("weight"))
+    };
+    let server = MockBinanceServer::start(config).await.unwrap();
+    let path = "/api/v3/klines?symbol=BTCUSDT&interval=30m&limit=1";
+
+    // Klines weigh 2: 2 and 4 fit, 6 doesn't
+    get(&server, path).await.unwrap();
+    get(&server, path).await.unwrap();
+    let over = get(&server, path).await.unwrap_err();
+    let over = throttled(&over);
+    assert_eq!(over.status, 429);
+    assert!(
+        over.retry_after
+            .is_some_and(|wait| wait <= Duration::from_secs(60))
+    );
+    assert!(server.used_weight() >= 6);
+}
+
+/// Next text frame, as JSON; `None` once the connection is gone
+async fn next_frame<S>(read: &mut S) -> Option<Value>
+where
+    S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), read.next())
+            .await
+            .expect("a frame within 5s");
+        match message {
+            Some(Ok(Message::Text(text))) => return serde_json::from_str(&text).ok(),
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return None,
+            Some(Ok(_)) => {}
+        }
+    }
+}
+
+fn tick(price: f64) -> WsEvent {
+    WsEvent::Tick {
+        symbol: "BTCUSDT".to_string(),
+        price,
+    }
+}
+
+#[tokio::test]
+async fn each_ws_connection_plays_its_own_script_across_reconnects() {
+    let server = start("ws").await;
+    server.script_ws_connection([tick(1.0), tick(2.0), WsEvent::Disconnect]);
+    server.script_ws_connection([tick(3.0)]);
+    let url = format!("{}/stream?streams=btcusdt@miniTicker", server.ws_base_url());
+
+    let (first, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (_, mut read) = first.split();
+    let first_prices = [
+        next_frame(&mut read).await.unwrap(),
+        next_frame(&mut read).await.unwrap(),
+    ];
+    assert_eq!(first_prices[0]["stream"], "btcusdt@miniTicker");
+    assert_eq!(first_prices[0]["data"]["c"], "1");
+    assert_eq!(first_prices[1]["data"]["c"], "2");
+    // Dropped without a close frame
+    assert!(next_frame(&mut read).await.is_none());
+
+    // The reconnect gets the second script, not what's left of the first
+    let (second, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (_, mut read) = second.split();
+    assert_eq!(next_frame(&mut read).await.unwrap()["data"]["c"], "3");
+    assert_eq!(server.ws_connections(), 2);
+
+    // Pushed events reach every open connection
+    let (third, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (_, mut third_read) = third.split();
+    while server.open_ws_connections() < 2 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    server.push_ws_events([tick(4.0)]);
+    assert_eq!(next_frame(&mut read).await.unwrap()["data"]["c"], "4");
+    assert_eq!(next_frame(&mut third_read).await.unwrap()["data"]["c"], "4");
+}